@@ -13,9 +13,22 @@ pub struct Command {
     /// Whether to compile to bytecode (false) or to print out the generated ASM (true).
     #[structopt(long)]
     pub print_intermediate_asm: bool,
+    /// Whether to generate the ASM via the intermediate representation.
+    #[structopt(long)]
+    pub use_ir: bool,
+    /// Whether to print out the generated intermediate representation. Implies `--use-ir`.
+    #[structopt(long)]
+    pub print_ir: bool,
+    /// Whether to skip optimizing the generated ASM.
+    #[structopt(long)]
+    pub no_optimize: bool,
     /// If set, outputs a binary file representing the script bytes.
     #[structopt(short = "o")]
     pub binary_outfile: Option<String>,
+    /// If set, outputs a JSON file which lists where in the source the program reverts with each
+    /// of the codes it can revert with.
+    #[structopt(long)]
+    pub revert_codes_outfile: Option<String>,
     /// Offline mode, prevents Forc from using the network when managing dependencies.
     /// Meaning it will only try to use previously downloaded dependencies.
     #[structopt(long = "offline")]
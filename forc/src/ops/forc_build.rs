@@ -10,9 +10,12 @@ use std::fs::File;
 use std::io::Write;
 use std::sync::Arc;
 use sway_core::{FinalizedAsm, TreeType};
+use sway_types::RevertCode;
 use sway_utils::{constants, find_manifest_dir};
 
-use sway_core::{BuildConfig, BytecodeCompilationResult, CompilationResult, Namespace};
+use sway_core::{
+    BuildConfig, BytecodeCompilationResult, CompilationResult, Namespace, OptimizationPass,
+};
 
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
@@ -28,8 +31,12 @@ pub fn build(command: BuildCommand) -> Result<Vec<u8>, String> {
 
     let BuildCommand {
         binary_outfile,
+        revert_codes_outfile,
         print_finalized_asm,
         print_intermediate_asm,
+        use_ir,
+        print_ir,
+        no_optimize,
         offline_mode,
         silent_mode,
         ..
@@ -64,7 +71,14 @@ pub fn build(command: BuildCommand) -> Result<Vec<u8>, String> {
         manifest_dir.clone(),
     )
     .print_finalized_asm(print_finalized_asm)
-    .print_intermediate_asm(print_intermediate_asm);
+    .print_intermediate_asm(print_intermediate_asm)
+    .use_ir(use_ir || print_ir)
+    .print_ir(print_ir)
+    .optimization_passes(if no_optimize {
+        vec![]
+    } else {
+        OptimizationPass::all()
+    });
 
     let mut dependency_graph = HashMap::new();
 
@@ -86,7 +100,7 @@ pub fn build(command: BuildCommand) -> Result<Vec<u8>, String> {
     // now, compile this program with all of its dependencies
     let main_file = get_main_file(&manifest, &manifest_dir)?;
 
-    let main = compile(
+    let (main, revert_codes) = compile(
         main_file,
         &manifest.project.name,
         &namespace,
@@ -98,6 +112,10 @@ pub fn build(command: BuildCommand) -> Result<Vec<u8>, String> {
         let mut file = File::create(outfile).map_err(|e| e.to_string())?;
         file.write_all(main.as_slice()).map_err(|e| e.to_string())?;
     }
+    if let Some(outfile) = revert_codes_outfile {
+        let file = File::create(outfile).map_err(|e| e.to_string())?;
+        serde_json::to_writer(&file, &revert_codes).map_err(|e| e.to_string())?;
+    }
 
     println!("  Bytecode size is {} bytes.", main.len());
 
@@ -271,16 +289,20 @@ fn compile(
     build_config: BuildConfig,
     dependency_graph: &mut HashMap<String, HashSet<String>>,
     silent_mode: bool,
-) -> Result<Vec<u8>, String> {
+) -> Result<(Vec<u8>, Vec<RevertCode>), String> {
     let res = sway_core::compile_to_bytecode(source, namespace, build_config, dependency_graph);
     match res {
-        BytecodeCompilationResult::Success { bytes, warnings } => {
+        BytecodeCompilationResult::Success {
+            bytes,
+            warnings,
+            revert_codes,
+        } => {
             print_on_success(silent_mode, proj_name, warnings, TreeType::Script {});
-            Ok(bytes)
+            Ok((bytes, revert_codes))
         }
         BytecodeCompilationResult::Library { warnings } => {
             print_on_success_library(silent_mode, proj_name, warnings);
-            Ok(vec![])
+            Ok((vec![], vec![]))
         }
         BytecodeCompilationResult::Failure { errors, warnings } => {
             print_on_failure(silent_mode, warnings, errors);
@@ -299,7 +321,7 @@ fn compile_to_asm(
 ) -> Result<FinalizedAsm, String> {
     let res = sway_core::compile_to_asm(source, namespace, build_config, dependency_graph);
     match res {
-        CompilationResult::Success { asm, warnings } => {
+        CompilationResult::Success { asm, warnings, .. } => {
             print_on_success(silent_mode, proj_name, warnings, TreeType::Script {});
             Ok(asm)
         }
@@ -43,7 +43,11 @@ pub async fn deploy(command: DeployCommand) -> Result<(), CliError> {
                             path,
                             print_finalized_asm,
                             print_intermediate_asm,
+                            use_ir: false,
+                            print_ir: false,
+                            no_optimize: false,
                             binary_outfile,
+                            revert_codes_outfile: None,
                             offline_mode,
                             silent_mode,
                         };
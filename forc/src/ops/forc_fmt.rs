@@ -11,7 +11,11 @@ pub fn format(command: FormatCommand) -> Result<(), FormatError> {
         path: None,
         print_finalized_asm: false,
         print_intermediate_asm: false,
+        use_ir: false,
+        print_ir: false,
+        no_optimize: false,
         binary_outfile: None,
+        revert_codes_outfile: None,
         offline_mode: false,
         silent_mode: false,
     };
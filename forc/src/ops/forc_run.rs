@@ -41,7 +41,11 @@ pub async fn run(command: RunCommand) -> Result<(), CliError> {
                             path: command.path,
                             print_finalized_asm: command.print_finalized_asm,
                             print_intermediate_asm: command.print_intermediate_asm,
+                            use_ir: false,
+                            print_ir: false,
+                            no_optimize: false,
                             binary_outfile: command.binary_outfile,
+                            revert_codes_outfile: None,
                             offline_mode: false,
                             silent_mode: command.silent_mode,
                         };
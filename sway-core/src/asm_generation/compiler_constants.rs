@@ -14,3 +14,11 @@ const NUM_COMPILER_RESERVED_REGISTERS: u8 = 1;
 pub(crate) const DATA_SECTION_REGISTER: u8 = NUM_FREE_REGISTERS - 2;
 pub(crate) const NUM_ALLOCATABLE_REGISTERS: u8 =
     NUM_FREE_REGISTERS - NUM_COMPILER_RESERVED_REGISTERS;
+
+/// Functions which compile to at most this many ops are inlined at their call sites rather than
+/// being called, unless they are recursive.
+pub(crate) const MAX_INLINED_FUNCTION_SIZE: usize = 32;
+
+/// The optimization passes are run repeatedly until they stop changing anything, up to this many
+/// times.
+pub(crate) const MAX_OPTIMIZATION_ROUNDS: usize = 8;
@@ -10,6 +10,7 @@ pub(crate) fn convert_fn_decl_to_asm(
     _namespace: &mut AsmNamespace,
     _register_sequencer: &mut RegisterSequencer,
 ) -> CompileResult<Vec<Op>> {
-    // for now, we inline all functions as a shortcut.
+    // functions are compiled when they are first applied, either inline or out of line, so that
+    // functions which are never called generate no code.
     ok(vec![], vec![], vec![])
 }
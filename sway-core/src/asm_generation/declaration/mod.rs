@@ -1,4 +1,4 @@
-use super::{AsmNamespace, RegisterSequencer};
+use super::{storage::convert_storage_reassignment_to_asm, AsmNamespace, RegisterSequencer};
 use crate::{asm_lang::Op, error::*, TypedDeclaration};
 
 mod const_decl;
@@ -24,11 +24,13 @@ pub(crate) fn convert_decl_to_asm(
         }
         // a trait declaration also does not have any asm directly generated from it
         TypedDeclaration::TraitDeclaration(_) => ok(vec![], vec![], vec![]),
-        // since all functions are inlined (for now -- shortcut), we also don't need to do anything
-        // for this.
+        // methods, like functions, are compiled when they are applied, so we also don't need to
+        // do anything for this.
         TypedDeclaration::ImplTrait { .. } => ok(vec![], vec![], vec![]),
         // once again the declaration of a type has no inherent asm, only instantiations
         TypedDeclaration::StructDeclaration(_) => ok(vec![], vec![], vec![]),
+        // nor does a type alias, which is just another name for a type
+        TypedDeclaration::TypeAliasDeclaration(_) => ok(vec![], vec![], vec![]),
         TypedDeclaration::VariableDeclaration(var_decl) => {
             convert_variable_decl_to_asm(var_decl, namespace, register_sequencer)
         }
@@ -38,6 +40,12 @@ pub(crate) fn convert_decl_to_asm(
         TypedDeclaration::Reassignment(reassignment) => {
             convert_reassignment_to_asm(reassignment, namespace, register_sequencer)
         }
+        // storage is initialized when the contract is first called, rather than where it is
+        // declared
+        TypedDeclaration::StorageDeclaration(_) => ok(vec![], vec![], vec![]),
+        TypedDeclaration::StorageReassignment(reassignment) => {
+            convert_storage_reassignment_to_asm(reassignment, namespace, register_sequencer)
+        }
         _ => err(
             vec![],
             vec![CompileError::Unimplemented(
@@ -8,6 +8,7 @@ use crate::asm_lang::{
 use crate::{
     error::*,
     semantic_analysis::{ast_node::TypedEnumDeclaration, TypedExpression},
+    span::Span,
     type_engine::resolve_type,
     CompileResult, Ident, Literal,
 };
@@ -129,3 +130,39 @@ pub(crate) fn convert_enum_instantiation_to_asm(
 
     ok(asm_buf, warnings, errors)
 }
+
+/// Reads the word at `offset` into the enum `prefix` evaluates to. An enum is laid out as its tag
+/// followed by its contents, as [convert_enum_instantiation_to_asm] writes them, so the tag is
+/// the word at offset 0 and the contents are the word at offset 1.
+pub(crate) fn convert_enum_word_to_asm(
+    prefix: &TypedExpression,
+    offset: u64,
+    span: &Span,
+    return_register: &VirtualRegister,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let prefix_register = register_sequencer.next();
+    let mut asm_buf = check!(
+        convert_expression_to_asm(prefix, namespace, &prefix_register, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    asm_buf.push(Op::new_with_comment(
+        VirtualOp::LW(
+            return_register.clone(),
+            prefix_register,
+            VirtualImmediate12::new_unchecked(offset, "the offset is 0 or 1; infallible"),
+        ),
+        span.clone(),
+        if offset == 0 {
+            "enum tag"
+        } else {
+            "enum contents"
+        },
+    ));
+    ok(asm_buf, warnings, errors)
+}
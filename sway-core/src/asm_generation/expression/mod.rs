@@ -1,3 +1,4 @@
+use super::functions::{compile_function_out_of_line, convert_fn_call_to_asm};
 use super::*;
 use crate::span::Span;
 use crate::{
@@ -7,7 +8,7 @@ use crate::{
         ast_node::{TypedAsmRegisterDeclaration, TypedCodeBlock, TypedExpressionVariant},
         TypedExpression,
     },
-    type_engine::look_up_type_id,
+    type_engine::{look_up_type_id, TypeId},
 };
 
 mod array;
@@ -15,10 +16,11 @@ mod contract_call;
 mod enums;
 mod if_exp;
 mod lazy_op;
+mod signed_integer;
 mod structs;
 mod subfield;
 use contract_call::convert_contract_call_to_asm;
-use enums::convert_enum_instantiation_to_asm;
+use enums::{convert_enum_instantiation_to_asm, convert_enum_word_to_asm};
 use if_exp::convert_if_exp_to_asm;
 pub(crate) use structs::{
     convert_struct_expression_to_asm, convert_tuple_expression_to_asm, get_contiguous_memory_layout,
@@ -52,6 +54,7 @@ pub(crate) fn convert_expression_to_asm(
             arguments,
             function_body,
             selector,
+            ..
         } => {
             if let Some(metadata) = selector {
                 assert_eq!(
@@ -79,6 +82,7 @@ pub(crate) fn convert_expression_to_asm(
                     name,
                     arguments,
                     function_body,
+                    exp.return_type,
                     namespace,
                     return_register,
                     register_sequencer,
@@ -296,23 +300,22 @@ pub(crate) fn convert_expression_to_asm(
             register_sequencer,
             return_register,
         ),
-        /*
-        TypedExpressionVariant::EnumArgAccess {
+        TypedExpressionVariant::EnumArgAccess { prefix, .. } => convert_enum_word_to_asm(
             prefix,
-            variant_to_access,
-            arg_num_to_access,
-            resolved_type_of_parent,
-        } => convert_enum_arg_expression_to_asm(
+            1,
             &exp.span,
-            prefix,
-            variant_to_access,
-            arg_num_to_access.to_owned(),
-            *resolved_type_of_parent,
+            return_register,
             namespace,
             register_sequencer,
+        ),
+        TypedExpressionVariant::EnumTag { exp: prefix } => convert_enum_word_to_asm(
+            prefix,
+            0,
+            &exp.span,
             return_register,
+            namespace,
+            register_sequencer,
         ),
-        */
         TypedExpressionVariant::EnumInstantiation {
             enum_decl,
             variant_name,
@@ -361,6 +364,51 @@ pub(crate) fn convert_expression_to_asm(
         }
         // ABI casts are purely compile-time constructs and generate no corresponding bytecode
         TypedExpressionVariant::AbiCast { .. } => ok(vec![], warnings, errors),
+        TypedExpressionVariant::StorageAccess { field_name } => {
+            super::storage::convert_storage_access_to_asm(
+                field_name,
+                exp.return_type,
+                &exp.span,
+                namespace,
+                return_register,
+                register_sequencer,
+            )
+        }
+        TypedExpressionVariant::StorageMapMethod(method) => {
+            super::storage::convert_storage_map_method_to_asm(
+                method,
+                exp.return_type,
+                &exp.span,
+                namespace,
+                return_register,
+                register_sequencer,
+            )
+        }
+        TypedExpressionVariant::SignedIntegerOp(op) => {
+            signed_integer::convert_signed_integer_op_to_asm(
+                op,
+                &exp.span,
+                namespace,
+                return_register,
+                register_sequencer,
+            )
+        }
+        TypedExpressionVariant::Revert { code } => {
+            let code_register = register_sequencer.next();
+            let mut asm_buf = convert_literal_to_asm(
+                &Literal::U64(*code),
+                namespace,
+                &code_register,
+                register_sequencer,
+                exp.span.clone(),
+            );
+            asm_buf.push(Op {
+                opcode: either::Either::Left(VirtualOp::RVRT(code_register)),
+                comment: "revert".into(),
+                owning_span: Some(exp.span.clone()),
+            });
+            ok(asm_buf, warnings, errors)
+        }
         a => {
             println!("unimplemented: {:?}", a);
             errors.push(CompileError::Unimplemented(
@@ -419,6 +467,27 @@ pub(crate) fn convert_code_block_to_asm(
     ok(asm_buf, warnings, errors)
 }
 
+/// Compiles the body of a function, whose return value is put into `return_register`. A return
+/// statement anywhere within the body jumps to the end of it.
+pub(crate) fn convert_fn_body_to_asm(
+    body: &TypedCodeBlock,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    return_register: &VirtualRegister,
+) -> CompileResult<Vec<Op>> {
+    let exit_label = register_sequencer.get_label();
+    let outer_function_exit = namespace.function_exit.replace(FunctionExit {
+        label: exit_label.clone(),
+        return_register: return_register.clone(),
+    });
+    let asm = convert_code_block_to_asm(body, namespace, register_sequencer, Some(return_register));
+    namespace.function_exit = outer_function_exit;
+    asm.map(|mut asm_buf| {
+        asm_buf.push(Op::unowned_jump_label(exit_label));
+        asm_buf
+    })
+}
+
 /// Initializes [Literal] `lit` into [VirtualRegister] `return_register`.
 fn convert_literal_to_asm(
     lit: &Literal,
@@ -437,17 +506,44 @@ fn convert_literal_to_asm(
     }]
 }
 
-/// For now, all functions are handled by inlining at the time of application.
+/// Functions are either called or inlined at the time of application, see the `functions` module
+/// for how that is decided.
 fn convert_fn_app_to_asm(
     name: &CallPath,
     arguments: &[(Ident, TypedExpression)],
     function_body: &TypedCodeBlock,
+    return_type: TypeId,
     parent_namespace: &mut AsmNamespace,
     return_register: &VirtualRegister,
     register_sequencer: &mut RegisterSequencer,
 ) -> CompileResult<Vec<Op>> {
     let mut warnings = vec![];
     let mut errors = vec![];
+    let definition = parent_namespace.functions.definition_of(function_body);
+    let function_body = definition.as_ref().unwrap_or(function_body);
+    let function = check!(
+        compile_function_out_of_line(
+            name.suffix.as_str(),
+            arguments,
+            function_body,
+            return_type,
+            parent_namespace,
+            register_sequencer,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    if let Some(function) = function {
+        return convert_fn_call_to_asm(
+            function,
+            arguments,
+            parent_namespace,
+            return_register,
+            register_sequencer,
+        );
+    }
+
     let mut asm_buf = vec![Op::new_comment(format!("{} fn call", name.suffix.as_str()))];
     // Make a local namespace so that the namespace of this function does not pollute the outer
     // scope
@@ -473,11 +569,11 @@ fn convert_fn_app_to_asm(
 
     // evaluate the function body
     let mut body = check!(
-        convert_code_block_to_asm(
+        convert_fn_body_to_asm(
             function_body,
             &mut namespace,
             register_sequencer,
-            Some(return_register),
+            return_register
         ),
         vec![],
         warnings,
@@ -485,6 +581,7 @@ fn convert_fn_app_to_asm(
     );
     asm_buf.append(&mut body);
     parent_namespace.data_section = namespace.data_section;
+    parent_namespace.functions = namespace.functions;
 
     // the return  value is already put in its proper register via the above statement, so the buf
     // is done
@@ -518,11 +615,11 @@ pub(crate) fn convert_abi_fn_to_asm(
     namespace.insert_variable(coin_color.0, coin_color.1);
     // evaluate the function body
     let mut body = check!(
-        convert_code_block_to_asm(
+        convert_fn_body_to_asm(
             &decl.body,
             &mut namespace,
             register_sequencer,
-            Some(&return_register),
+            &return_register
         ),
         vec![],
         warnings,
@@ -539,6 +636,7 @@ pub(crate) fn convert_abi_fn_to_asm(
     ));
 
     parent_namespace.data_section = namespace.data_section;
+    parent_namespace.functions = namespace.functions;
 
     // the return  value is already put in its proper register via the above statement, so the buf
     // is done
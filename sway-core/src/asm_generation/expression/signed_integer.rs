@@ -0,0 +1,223 @@
+//! Signed integers are held in two's complement, sign extended to a whole word. FuelVM only has
+//! instructions for unsigned integers, so comparison, division and right shifts need lowering of
+//! their own, while the results of wrapping arithmetic are sign extended again from their width.
+
+use super::*;
+use crate::semantic_analysis::ast_node::{SignedIntegerOp, SignedIntegerOpKind};
+use crate::type_engine::IntegerBits;
+
+pub(super) fn convert_signed_integer_op_to_asm(
+    op: &SignedIntegerOp,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut buf = vec![];
+    let mut arguments = vec![];
+    for argument in &op.arguments {
+        let register = register_sequencer.next();
+        buf.append(&mut check!(
+            convert_expression_to_asm(argument, namespace, &register, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+        arguments.push(register);
+    }
+    let mut lowering = Lowering {
+        buf,
+        register_sequencer,
+        span: span.clone(),
+    };
+    let result = lowering.lower(op.op, op.bits, &arguments);
+    let mut buf = lowering.buf;
+    buf.push(Op::register_move(
+        return_register.into(),
+        result,
+        span.clone(),
+    ));
+    ok(buf, warnings, errors)
+}
+
+struct Lowering<'a> {
+    buf: Vec<Op>,
+    register_sequencer: &'a mut RegisterSequencer,
+    span: Span,
+}
+
+impl Lowering<'_> {
+    fn lower(
+        &mut self,
+        op: SignedIntegerOpKind,
+        bits: IntegerBits,
+        arguments: &[VirtualRegister],
+    ) -> VirtualRegister {
+        use SignedIntegerOpKind::*;
+        let lhs = &arguments[0];
+        let rhs = || {
+            arguments
+                .get(1)
+                .expect("binary operators have two arguments")
+        };
+        match op {
+            Add => {
+                let sum = self.binary(VirtualOp::ADD, lhs, rhs());
+                self.sign_extend(&sum, bits)
+            }
+            Subtract => {
+                let difference = self.binary(VirtualOp::SUB, lhs, rhs());
+                self.sign_extend(&difference, bits)
+            }
+            Multiply => {
+                let product = self.binary(VirtualOp::MUL, lhs, rhs());
+                self.sign_extend(&product, bits)
+            }
+            Negate => {
+                let negated = self.binary(VirtualOp::SUB, &zero(), lhs);
+                self.sign_extend(&negated, bits)
+            }
+            // the quotient is negative if exactly one of the operands is
+            Divide => {
+                let lhs_mask = self.sign_mask(lhs);
+                let rhs_mask = self.sign_mask(rhs());
+                let lhs_abs = self.abs(lhs, &lhs_mask);
+                let rhs_abs = self.abs(rhs(), &rhs_mask);
+                let quotient = self.binary(VirtualOp::DIV, &lhs_abs, &rhs_abs);
+                let mask = self.binary(VirtualOp::XOR, &lhs_mask, &rhs_mask);
+                let quotient = self.apply_sign(&quotient, &mask);
+                self.sign_extend(&quotient, bits)
+            }
+            // the remainder has the sign of the dividend
+            Modulo => {
+                let lhs_mask = self.sign_mask(lhs);
+                let rhs_mask = self.sign_mask(rhs());
+                let lhs_abs = self.abs(lhs, &lhs_mask);
+                let rhs_abs = self.abs(rhs(), &rhs_mask);
+                let remainder = self.binary(VirtualOp::MOD, &lhs_abs, &rhs_abs);
+                self.apply_sign(&remainder, &lhs_mask)
+            }
+            // sign extension makes the representation of each value unique
+            Equals => self.binary(VirtualOp::EQ, lhs, rhs()),
+            NotEquals => {
+                let equal = self.binary(VirtualOp::EQ, lhs, rhs());
+                self.not(&equal)
+            }
+            LessThan => self.compare(VirtualOp::LT, lhs, rhs()),
+            GreaterThan => self.compare(VirtualOp::GT, lhs, rhs()),
+            LessThanOrEqualTo => {
+                let greater = self.compare(VirtualOp::GT, lhs, rhs());
+                self.not(&greater)
+            }
+            GreaterThanOrEqualTo => {
+                let less = self.compare(VirtualOp::LT, lhs, rhs());
+                self.not(&less)
+            }
+            // bitwise operations on sign extended values are sign extended themselves
+            BinaryAnd => self.binary(VirtualOp::AND, lhs, rhs()),
+            BinaryOr => self.binary(VirtualOp::OR, lhs, rhs()),
+            Xor => self.binary(VirtualOp::XOR, lhs, rhs()),
+            ShiftLeft => {
+                let shifted = self.binary(VirtualOp::SLL, lhs, rhs());
+                self.sign_extend(&shifted, bits)
+            }
+            // an arithmetic shift is a logical shift of the value with its sign bits flipped off
+            ShiftRight => {
+                let mask = self.sign_mask(lhs);
+                let flipped = self.binary(VirtualOp::XOR, lhs, &mask);
+                let shifted = self.binary(VirtualOp::SRL, &flipped, rhs());
+                self.binary(VirtualOp::XOR, &shifted, &mask)
+            }
+        }
+    }
+
+    fn push(&mut self, op: VirtualOp, comment: &str) {
+        self.buf
+            .push(Op::new_with_comment(op, self.span.clone(), comment));
+    }
+
+    fn binary(
+        &mut self,
+        op: fn(VirtualRegister, VirtualRegister, VirtualRegister) -> VirtualOp,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+    ) -> VirtualRegister {
+        let result = self.register_sequencer.next();
+        self.push(
+            op(result.clone(), lhs.clone(), rhs.clone()),
+            "signed integer op",
+        );
+        result
+    }
+
+    fn immediate(
+        &mut self,
+        op: fn(VirtualRegister, VirtualRegister, VirtualImmediate12) -> VirtualOp,
+        value: &VirtualRegister,
+        imm: u64,
+        comment: &str,
+    ) -> VirtualRegister {
+        let result = self.register_sequencer.next();
+        let imm = VirtualImmediate12::new_unchecked(imm, "shifts of a word fit in 12 bits");
+        self.push(op(result.clone(), value.clone(), imm), comment);
+        result
+    }
+
+    fn not(&mut self, boolean: &VirtualRegister) -> VirtualRegister {
+        self.binary(VirtualOp::EQ, boolean, &zero())
+    }
+
+    /// All ones if `value` is negative, or zero otherwise.
+    fn sign_mask(&mut self, value: &VirtualRegister) -> VirtualRegister {
+        let sign = self.immediate(VirtualOp::SRLI, value, 63, "sign bit");
+        self.binary(VirtualOp::SUB, &zero(), &sign)
+    }
+
+    /// Negates `value` if `mask` is all ones, or leaves it be if it is zero.
+    fn apply_sign(&mut self, value: &VirtualRegister, mask: &VirtualRegister) -> VirtualRegister {
+        let flipped = self.binary(VirtualOp::XOR, value, mask);
+        self.binary(VirtualOp::SUB, &flipped, mask)
+    }
+
+    fn abs(&mut self, value: &VirtualRegister, mask: &VirtualRegister) -> VirtualRegister {
+        self.apply_sign(value, mask)
+    }
+
+    /// Flipping the sign bit of both operands orders them as unsigned integers.
+    fn compare(
+        &mut self,
+        op: fn(VirtualRegister, VirtualRegister, VirtualRegister) -> VirtualOp,
+        lhs: &VirtualRegister,
+        rhs: &VirtualRegister,
+    ) -> VirtualRegister {
+        let sign_bit = self.immediate(VirtualOp::SLLI, &one(), 63, "sign bit");
+        let lhs = self.binary(VirtualOp::XOR, lhs, &sign_bit);
+        let rhs = self.binary(VirtualOp::XOR, rhs, &sign_bit);
+        self.binary(op, &lhs, &rhs)
+    }
+
+    /// Wraps `value` to a width of `bits` and sign extends it back to a whole word.
+    fn sign_extend(&mut self, value: &VirtualRegister, bits: IntegerBits) -> VirtualRegister {
+        let shift = match bits {
+            IntegerBits::Eight => 56,
+            IntegerBits::Sixteen => 48,
+            IntegerBits::ThirtyTwo => 32,
+            IntegerBits::SixtyFour => return value.clone(),
+        };
+        let shifted = self.immediate(VirtualOp::SLLI, value, shift, "wrap to width");
+        let mask = self.sign_mask(&shifted);
+        let flipped = self.binary(VirtualOp::XOR, &shifted, &mask);
+        let shifted = self.immediate(VirtualOp::SRLI, &flipped, shift, "sign extend");
+        self.binary(VirtualOp::XOR, &shifted, &mask)
+    }
+}
+
+fn zero() -> VirtualRegister {
+    VirtualRegister::Constant(ConstantRegister::Zero)
+}
+
+fn one() -> VirtualRegister {
+    VirtualRegister::Constant(ConstantRegister::One)
+}
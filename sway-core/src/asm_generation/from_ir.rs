@@ -0,0 +1,871 @@
+//! Lowers the [IR](crate::ir) to ASM.
+//!
+//! Every IR value gets a virtual register. Values of a type which fits in a word, i.e. units,
+//! bools, integers and short strings, are held in their register directly. Every other value,
+//! i.e. a `b256`, a long string or an aggregate, is held in memory, and its register holds the
+//! address of that memory.
+//!
+//! Values are immutable, so aggregates share memory freely: extracting an aggregate field
+//! yields a pointer into its parent, and copying a value copies only its address. The exception
+//! is `insert_value` and `insert_element`, which write into their aggregate in place when it is
+//! fresh, i.e. a constant or the result of another insert which nothing else reads. Otherwise
+//! the aggregate is copied into newly allocated stack memory first.
+//!
+//! Locals are registers as well, so `load` and `store` are moves. Phis are lowered to moves on
+//! every edge into their block.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{
+    build_contract_abi_switch, build_preamble, functions::expand_function_calls, load_bal,
+    load_cgas, load_coin_color, load_user_argument, AbstractInstructionSet, AsmNamespace,
+    HllAsmSet, RegisterSequencer,
+};
+use crate::{
+    asm_lang::{
+        virtual_register::*, FunctionRegisters, Label, Op, VirtualImmediate12, VirtualImmediate24,
+        VirtualOp,
+    },
+    error::*,
+    ir::*,
+    parse_tree::Literal,
+    span::Span,
+};
+
+pub(crate) fn compile_ir_to_asm(
+    context: &Context,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<(HllAsmSet, AsmNamespace)> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let module = match context.module_iter().next() {
+        Some(module) => module,
+        None => return ok((HllAsmSet::Library, Default::default()), warnings, errors),
+    };
+    let kind = module.get_kind(context);
+    if kind == Kind::Library {
+        return ok((HllAsmSet::Library, Default::default()), warnings, errors);
+    }
+
+    let mut builder = AsmBuilder::new(context, register_sequencer);
+    let mut asm_buf = build_preamble(builder.register_sequencer).to_vec();
+    let predicate_end_label = builder.register_sequencer.get_label();
+    match kind {
+        Kind::Contract => {
+            let abi_functions = module
+                .function_iter(context)
+                .into_iter()
+                .filter_map(|function| {
+                    function
+                        .get_selector(context)
+                        .map(|selector| (function, selector))
+                })
+                .map(|(function, selector)| {
+                    (function, selector, builder.register_sequencer.get_label())
+                })
+                .collect::<Vec<_>>();
+            asm_buf.append(&mut build_contract_abi_switch(
+                builder.register_sequencer,
+                &mut builder.namespace,
+                abi_functions
+                    .iter()
+                    .map(|(_, selector, label)| (*selector, label.clone()))
+                    .collect(),
+            ));
+            for (function, _, label) in abi_functions {
+                builder.ops.push(Op::unowned_jump_label_comment(
+                    label,
+                    format!("fn {}", function.get_name(context)),
+                ));
+                // ABI functions take the gas to forward, the coins to forward, the color of
+                // the coins and the user's argument, in that order
+                let loaders: [fn(VirtualRegister) -> Op; 4] =
+                    [load_cgas, load_bal, load_coin_color, load_user_argument];
+                let args = function
+                    .args_iter(context)
+                    .map(|(_, value)| *value)
+                    .collect::<Vec<_>>();
+                for (load, arg) in loaders.iter().zip(args) {
+                    let reg = builder.value_register(arg);
+                    builder.ops.push(load(reg));
+                }
+                check!(
+                    builder.compile_function(function, ReturnKind::Entry),
+                    (),
+                    warnings,
+                    errors
+                );
+            }
+        }
+        Kind::Predicate | Kind::Script => {
+            let main = module
+                .get_function(context, "main")
+                .expect("scripts and predicates have a main function");
+            let return_kind = if kind == Kind::Predicate {
+                // execution of a predicate ends at the end of the program, so any functions it
+                // calls must be jumped over
+                ReturnKind::JumpToEnd(predicate_end_label.clone())
+            } else {
+                ReturnKind::Entry
+            };
+            check!(
+                builder.compile_function(main, return_kind),
+                (),
+                warnings,
+                errors
+            );
+        }
+        Kind::Library => unreachable!(),
+    }
+
+    // compile every function which has been called, including those called by functions
+    // compiled in this loop
+    while let Some(function) = builder.pending.pop() {
+        let registers = builder.functions[&function].clone();
+        let exit_label = builder.register_sequencer.get_label();
+        builder.ops.push(Op::unowned_jump_label_comment(
+            registers.label.clone(),
+            format!("fn {}", registers.name),
+        ));
+        check!(
+            builder.compile_function(
+                function,
+                ReturnKind::ToCaller(registers.return_value.clone(), exit_label.clone())
+            ),
+            (),
+            warnings,
+            errors
+        );
+        builder.ops.push(Op::unowned_jump_label(exit_label));
+        builder.ops.push(Op::function_return(registers));
+    }
+    if kind == Kind::Predicate {
+        builder
+            .ops
+            .push(Op::unowned_jump_label(predicate_end_label));
+    }
+    if !errors.is_empty() {
+        return err(warnings, errors);
+    }
+
+    asm_buf.append(&mut builder.ops);
+    let asm_buf = expand_function_calls(
+        asm_buf,
+        &mut builder.namespace.data_section,
+        builder.register_sequencer,
+    );
+    let namespace = builder.namespace;
+    let program_section = AbstractInstructionSet { ops: asm_buf };
+    let data_section = namespace.data_section.clone();
+    let asm = match kind {
+        Kind::Contract => HllAsmSet::ContractAbi {
+            data_section,
+            program_section,
+        },
+        Kind::Predicate => HllAsmSet::PredicateMain {
+            data_section,
+            program_section,
+        },
+        Kind::Script => HllAsmSet::ScriptMain {
+            data_section,
+            program_section,
+        },
+        Kind::Library => unreachable!(),
+    };
+    ok((asm, namespace), warnings, errors)
+}
+
+/// What a `ret` in the function being compiled does.
+enum ReturnKind {
+    /// Ends the program with RET or RETD, for script mains and contract ABI functions.
+    Entry,
+    /// Jumps to the end of the program, for predicate mains.
+    JumpToEnd(Label),
+    /// Moves the value into the return value register and jumps to the label before the
+    /// function's return, for functions which are called.
+    ToCaller(VirtualRegister, Label),
+}
+
+struct AsmBuilder<'ir, 'rs> {
+    context: &'ir Context,
+    register_sequencer: &'rs mut RegisterSequencer,
+    namespace: AsmNamespace,
+    ops: Vec<Op>,
+    /// The register of every argument and instruction value. Constants are materialized anew
+    /// each time they are used.
+    values: HashMap<Value, VirtualRegister>,
+    locals: HashMap<Pointer, VirtualRegister>,
+    /// The registers of the functions which are called.
+    functions: HashMap<Function, FunctionRegisters>,
+    /// Functions which are called but haven't been compiled yet.
+    pending: Vec<Function>,
+    /// The labels of the blocks of the function being compiled.
+    block_labels: HashMap<Block, Label>,
+    /// How many times each value of the function being compiled is used as an operand.
+    use_counts: HashMap<Value, usize>,
+}
+
+impl<'ir, 'rs> AsmBuilder<'ir, 'rs> {
+    fn new(context: &'ir Context, register_sequencer: &'rs mut RegisterSequencer) -> Self {
+        AsmBuilder {
+            context,
+            register_sequencer,
+            namespace: Default::default(),
+            ops: vec![],
+            values: HashMap::new(),
+            locals: HashMap::new(),
+            functions: HashMap::new(),
+            pending: vec![],
+            block_labels: HashMap::new(),
+            use_counts: HashMap::new(),
+        }
+    }
+
+    fn compile_function(
+        &mut self,
+        function: Function,
+        return_kind: ReturnKind,
+    ) -> CompileResult<()> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let context = self.context;
+        let blocks = function.block_iter(context);
+        let register_sequencer = &mut self.register_sequencer;
+        self.block_labels = blocks
+            .iter()
+            .map(|block| (*block, register_sequencer.get_label()))
+            .collect();
+        self.use_counts.clear();
+        for block in &blocks {
+            for value in block.instruction_iter(context) {
+                for operand in value
+                    .get_instruction(context)
+                    .expect("blocks only contain instructions")
+                    .operands()
+                {
+                    *self.use_counts.entry(operand).or_default() += 1;
+                }
+            }
+        }
+
+        for (_, ptr) in function.locals_iter(context) {
+            let reg = self.register_sequencer.next();
+            if let Some(initializer) = ptr.get_initializer(context) {
+                let value = self.materialize_constant(initializer);
+                self.ops.push(Op::unowned_register_move_comment(
+                    reg.clone(),
+                    value,
+                    "init local",
+                ));
+            }
+            self.locals.insert(*ptr, reg);
+        }
+
+        for block in blocks {
+            self.ops
+                .push(Op::unowned_jump_label(self.block_labels[&block].clone()));
+            for value in block.instruction_iter(context) {
+                check!(
+                    self.compile_instruction(function, block, value, &return_kind),
+                    continue,
+                    warnings,
+                    errors
+                );
+            }
+        }
+        if errors.is_empty() {
+            ok((), warnings, errors)
+        } else {
+            err(warnings, errors)
+        }
+    }
+
+    fn compile_instruction(
+        &mut self,
+        function: Function,
+        block: Block,
+        value: Value,
+        return_kind: &ReturnKind,
+    ) -> CompileResult<()> {
+        let context = self.context;
+        let instruction = value
+            .get_instruction(context)
+            .expect("blocks only contain instructions");
+        match instruction {
+            Instruction::AsmBlock(asm_block) => return self.compile_asm_block(value, asm_block),
+            Instruction::Branch(to_block) => {
+                self.compile_phi_moves(block, *to_block);
+                self.ops
+                    .push(Op::jump_to_label(self.block_labels[to_block].clone()));
+            }
+            Instruction::Call(callee, args) => {
+                let args = args.iter().map(|arg| self.operand_register(*arg)).collect();
+                let registers = self.function_registers(*callee);
+                let return_register = self.value_register(value);
+                self.ops
+                    .push(Op::call_function(registers, args, return_register));
+            }
+            Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            } => self.compile_conditional_branch(block, *cond_value, *true_block, *false_block),
+            Instruction::ExtractElement {
+                array,
+                ty,
+                index_val,
+            } => {
+                let element_type = ty.get_element_type(context).expect("verified array type");
+                let base = self.operand_register(*array);
+                let address = self.element_address(base, element_type, *index_val);
+                let result = self.value_register(value);
+                self.read_at(result, address, 0, element_type);
+            }
+            Instruction::ExtractValue {
+                aggregate,
+                ty,
+                indices,
+            } => {
+                let (offset, field_type) = self.field_offset(*ty, indices);
+                let base = self.operand_register(*aggregate);
+                let result = self.value_register(value);
+                self.read_at(result, base, offset, field_type);
+            }
+            Instruction::InsertElement {
+                array,
+                ty,
+                value: element,
+                index_val,
+            } => {
+                let element_type = ty.get_element_type(context).expect("verified array type");
+                let base = self.fresh_aggregate(*array, *ty);
+                let element = self.operand_register(*element);
+                let address = self.element_address(base.clone(), element_type, *index_val);
+                self.write_at(address, 0, element, element_type);
+                let result = self.value_register(value);
+                self.ops.push(Op::unowned_register_move(result, base));
+            }
+            Instruction::InsertValue {
+                aggregate,
+                ty,
+                value: field,
+                indices,
+            } => {
+                let (offset, field_type) = self.field_offset(*ty, indices);
+                let base = self.fresh_aggregate(*aggregate, *ty);
+                let field = self.operand_register(*field);
+                self.write_at(base.clone(), offset, field, field_type);
+                let result = self.value_register(value);
+                self.ops.push(Op::unowned_register_move(result, base));
+            }
+            Instruction::Load(ptr) => {
+                let local = self.locals[ptr].clone();
+                let result = self.value_register(value);
+                self.ops.push(Op::unowned_register_move(result, local));
+            }
+            // phis are written to on the edges into their block
+            Instruction::Phi(_) => (),
+            Instruction::Ret(ret_value, ty) => {
+                let reg = self.operand_register(*ret_value);
+                self.compile_ret(function, reg, *ty, return_kind);
+            }
+            Instruction::Store { ptr, stored_val } => {
+                let reg = self.operand_register(*stored_val);
+                let local = self.locals[ptr].clone();
+                self.ops.push(Op::unowned_register_move(local, reg));
+            }
+        }
+        ok((), vec![], vec![])
+    }
+
+    fn compile_asm_block(&mut self, value: Value, asm_block: &AsmBlock) -> CompileResult<()> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let mut mapping: HashMap<&str, VirtualRegister> = HashMap::new();
+        for arg in &asm_block.args {
+            let reg = self.register_sequencer.next();
+            if let Some(initializer) = arg.initializer {
+                let initializer = self.operand_register(initializer);
+                self.ops.push(Op::unowned_register_move_comment(
+                    reg.clone(),
+                    initializer,
+                    format!("init asm register {}", arg.name.as_str()),
+                ));
+            }
+            mapping.insert(arg.name.as_str(), reg);
+        }
+        let realize = |name: &crate::Ident| -> Result<VirtualRegister, CompileError> {
+            match mapping.get(name.as_str()) {
+                Some(reg) => Ok(reg.clone()),
+                None => ConstantRegister::parse_register_name(name.as_str())
+                    .map(VirtualRegister::Constant)
+                    .ok_or_else(|| CompileError::UnknownRegister {
+                        span: name.span().clone(),
+                        initialized_registers: asm_block
+                            .args
+                            .iter()
+                            .map(|arg| arg.name.as_str().to_string())
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    }),
+            }
+        };
+        for op in &asm_block.body {
+            let args = op
+                .args
+                .iter()
+                .filter_map(|arg| match realize(arg) {
+                    Ok(reg) => Some(reg),
+                    Err(e) => {
+                        errors.push(e);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            let opcode = check!(
+                Op::parse_opcode(&op.name, &args, &op.immediate, op.name.span().clone()),
+                continue,
+                warnings,
+                errors
+            );
+            self.ops.push(Op::new(opcode, op.name.span().clone()));
+        }
+        let returned = match &asm_block.return_name {
+            Some(name) => match realize(name) {
+                Ok(reg) => reg,
+                Err(e) => {
+                    errors.push(e);
+                    return err(warnings, errors);
+                }
+            },
+            None => VirtualRegister::Constant(ConstantRegister::Zero),
+        };
+        let result = self.value_register(value);
+        self.ops.push(Op::unowned_register_move_comment(
+            result,
+            returned,
+            "return value from inline asm",
+        ));
+        if errors.is_empty() {
+            ok((), warnings, errors)
+        } else {
+            err(warnings, errors)
+        }
+    }
+
+    fn compile_conditional_branch(
+        &mut self,
+        block: Block,
+        cond_value: Value,
+        true_block: Block,
+        false_block: Block,
+    ) {
+        let cond = self.operand_register(cond_value);
+        let true_label = self.block_labels[&true_block].clone();
+        let false_label = self.block_labels[&false_block].clone();
+        if !self.has_phis(true_block) && !self.has_phis(false_block) {
+            self.ops.push(Op::jump_if_not_equal(
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                cond,
+                true_label,
+            ));
+            self.ops.push(Op::jump_to_label(false_label));
+            return;
+        }
+        // the phis of each successor are written to on their own edge
+        let true_edge_label = self.register_sequencer.get_label();
+        self.ops.push(Op::jump_if_not_equal(
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            cond,
+            true_edge_label.clone(),
+        ));
+        self.compile_phi_moves(block, false_block);
+        self.ops.push(Op::jump_to_label(false_label));
+        self.ops.push(Op::unowned_jump_label(true_edge_label));
+        self.compile_phi_moves(block, true_block);
+        self.ops.push(Op::jump_to_label(true_label));
+    }
+
+    fn phis(&self, block: Block) -> Vec<(Value, Vec<(Block, Value)>)> {
+        block
+            .instruction_iter(self.context)
+            .into_iter()
+            .map(|value| (value, value.get_instruction(self.context)))
+            .take_while(|(_, instruction)| matches!(instruction, Some(Instruction::Phi(_))))
+            .filter_map(|(value, instruction)| match instruction {
+                Some(Instruction::Phi(entries)) => Some((value, entries.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn has_phis(&self, block: Block) -> bool {
+        !self.phis(block).is_empty()
+    }
+
+    /// Moves the values flowing from `from_block` into the phis of `to_block`.
+    fn compile_phi_moves(&mut self, from_block: Block, to_block: Block) {
+        let moves = self
+            .phis(to_block)
+            .into_iter()
+            .filter_map(|(phi, entries)| {
+                entries
+                    .iter()
+                    .find(|(block, _)| *block == from_block)
+                    .map(|(_, value)| (phi, *value))
+            })
+            .collect::<Vec<_>>();
+        // a phi may be an incoming value of another phi of the same block, so when there are
+        // several, every incoming value is read before any phi is written
+        let sources = moves
+            .iter()
+            .map(|(_, value)| {
+                let reg = self.operand_register(*value);
+                if moves.len() == 1 {
+                    reg
+                } else {
+                    let temp = self.register_sequencer.next();
+                    self.ops.push(Op::unowned_register_move(temp.clone(), reg));
+                    temp
+                }
+            })
+            .collect::<Vec<_>>();
+        for ((phi, _), source) in moves.iter().zip(sources) {
+            let phi = self.value_register(*phi);
+            self.ops
+                .push(Op::unowned_register_move_comment(phi, source, "phi"));
+        }
+    }
+
+    fn compile_ret(
+        &mut self,
+        function: Function,
+        reg: VirtualRegister,
+        ty: Type,
+        return_kind: &ReturnKind,
+    ) {
+        let name = function.get_name(self.context);
+        match return_kind {
+            ReturnKind::Entry if ty == Type::Unit => {
+                self.ops.push(Op::unowned_new_with_comment(
+                    VirtualOp::RET(VirtualRegister::Constant(ConstantRegister::Zero)),
+                    format!("fn {} returns unit", name),
+                ));
+            }
+            ReturnKind::Entry if self.is_copy_type(ty) => {
+                self.ops.push(Op::unowned_new_with_comment(
+                    VirtualOp::RET(reg),
+                    format!("{} fn return value", name),
+                ));
+            }
+            ReturnKind::Entry => {
+                let size_bytes = self
+                    .namespace
+                    .insert_data_value(&Literal::U64(self.size_in_words(ty) * 8));
+                let rb_register = self.register_sequencer.next();
+                self.ops.push(Op::unowned_load_data_comment(
+                    rb_register.clone(),
+                    size_bytes,
+                    "loading rB for RETD",
+                ));
+                self.ops.push(Op::unowned_new_with_comment(
+                    VirtualOp::RETD(reg, rb_register),
+                    format!("{} fn return value", name),
+                ));
+            }
+            ReturnKind::JumpToEnd(end_label) => {
+                self.ops.push(Op::jump_to_label(end_label.clone()));
+            }
+            ReturnKind::ToCaller(return_value, exit_label) => {
+                self.ops
+                    .push(Op::unowned_register_move(return_value.clone(), reg));
+                self.ops.push(Op::jump_to_label(exit_label.clone()));
+            }
+        }
+    }
+
+    /// The registers of a function which is called, which is queued to be compiled the first
+    /// time this is called for it.
+    fn function_registers(&mut self, function: Function) -> FunctionRegisters {
+        if let Some(registers) = self.functions.get(&function) {
+            return registers.clone();
+        }
+        let parameters = function
+            .args_iter(self.context)
+            .map(|(_, value)| *value)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|value| self.value_register(value))
+            .collect();
+        let registers = FunctionRegisters {
+            label: self.register_sequencer.get_label(),
+            name: function.get_name(self.context).to_string(),
+            parameters,
+            return_value: self.register_sequencer.next(),
+            frame: self.register_sequencer.next(),
+        };
+        self.functions.insert(function, registers.clone());
+        self.pending.push(function);
+        registers
+    }
+
+    /// The register of an argument or instruction value.
+    fn value_register(&mut self, value: Value) -> VirtualRegister {
+        let register_sequencer = &mut self.register_sequencer;
+        self.values
+            .entry(value)
+            .or_insert_with(|| register_sequencer.next())
+            .clone()
+    }
+
+    /// The register of an operand, materializing it first if it is a constant.
+    fn operand_register(&mut self, value: Value) -> VirtualRegister {
+        match value.get_constant(self.context) {
+            Some(constant) => self.materialize_constant(constant),
+            None => self.value_register(value),
+        }
+    }
+
+    /// The register of an aggregate which may be written to in place: the aggregate itself if
+    /// it is fresh, otherwise a copy of it.
+    fn fresh_aggregate(&mut self, aggregate: Value, ty: Type) -> VirtualRegister {
+        let is_fresh = match aggregate.get_content(self.context) {
+            ValueContent::Constant(_) => true,
+            ValueContent::Instruction(
+                Instruction::InsertValue { .. } | Instruction::InsertElement { .. },
+            ) => self.use_counts.get(&aggregate) == Some(&1),
+            _ => false,
+        };
+        let reg = self.operand_register(aggregate);
+        if is_fresh {
+            return reg;
+        }
+        let copy = self.allocate(ty);
+        self.copy_memory(copy.clone(), reg, self.size_in_words(ty));
+        copy
+    }
+
+    fn materialize_constant(&mut self, constant: &Constant) -> VirtualRegister {
+        let ty = constant.ty;
+        let literal = match &constant.value {
+            ConstantValue::Unit => return VirtualRegister::Constant(ConstantRegister::Zero),
+            ConstantValue::Undef if self.is_copy_type(ty) => {
+                return VirtualRegister::Constant(ConstantRegister::Zero)
+            }
+            ConstantValue::Undef => return self.allocate(ty),
+            ConstantValue::Bool(b) => Literal::Boolean(*b),
+            ConstantValue::Uint(n) => Literal::U64(*n),
+            ConstantValue::B256(bytes) => Literal::B256(*bytes),
+            ConstantValue::String(bytes) => {
+                let string: Arc<str> = String::from_utf8_lossy(bytes).into();
+                let len = string.len();
+                Literal::String(Span {
+                    span: pest::Span::new(string, 0, len).unwrap(),
+                    path: None,
+                })
+            }
+            ConstantValue::Array(elements) => {
+                let base = self.allocate(ty);
+                let element_type = ty.get_element_type(self.context).expect("array type");
+                let element_size = self.size_in_words(element_type);
+                for (ix, element) in elements.iter().enumerate() {
+                    let reg = self.materialize_constant(element);
+                    self.write_at(base.clone(), ix as u64 * element_size, reg, element_type);
+                }
+                return base;
+            }
+            ConstantValue::Struct(fields) => {
+                let base = self.allocate(ty);
+                let mut offset = 0;
+                for field in fields {
+                    let reg = self.materialize_constant(field);
+                    self.write_at(base.clone(), offset, reg, field.ty);
+                    offset += self.size_in_words(field.ty);
+                }
+                return base;
+            }
+        };
+        let data_id = self.namespace.insert_data_value(&literal);
+        let reg = self.register_sequencer.next();
+        self.ops.push(Op::unowned_load_data_comment(
+            reg.clone(),
+            data_id,
+            format!("literal {}", constant.as_string(self.context)),
+        ));
+        reg
+    }
+
+    /// Allocates stack memory for a value of type `ty`, returning a register with its address.
+    fn allocate(&mut self, ty: Type) -> VirtualRegister {
+        let reg = self.register_sequencer.next();
+        self.ops.push(Op::unowned_register_move_comment(
+            reg.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+            format!("allocate {}", ty.as_string(self.context)),
+        ));
+        let size_in_bytes = self.size_in_words(ty) * 8;
+        if size_in_bytes > 0 {
+            self.ops.push(Op::unowned_stack_allocate_memory(
+                VirtualImmediate24::new_unchecked(size_in_bytes, "aggregate too large for stack"),
+            ));
+        }
+        reg
+    }
+
+    fn copy_memory(&mut self, dst: VirtualRegister, src: VirtualRegister, size_in_words: u64) {
+        if size_in_words > 0 {
+            self.ops.push(Op::unowned_new_with_comment(
+                VirtualOp::MCPI(
+                    dst,
+                    src,
+                    VirtualImmediate12::new_unchecked(
+                        size_in_words * 8,
+                        "aggregate too large to copy",
+                    ),
+                ),
+                "copy aggregate",
+            ));
+        }
+    }
+
+    /// Reads the value of type `ty` at `offset` words from `base` into `dst`.
+    fn read_at(&mut self, dst: VirtualRegister, base: VirtualRegister, offset: u64, ty: Type) {
+        let opcode = if self.size_in_words(ty) == 0 {
+            VirtualOp::MOVE(dst, VirtualRegister::Constant(ConstantRegister::Zero))
+        } else if self.is_copy_type(ty) {
+            VirtualOp::LW(
+                dst,
+                base,
+                VirtualImmediate12::new_unchecked(offset, "field offset too large"),
+            )
+        } else {
+            VirtualOp::ADDI(
+                dst,
+                base,
+                VirtualImmediate12::new_unchecked(offset * 8, "field offset too large"),
+            )
+        };
+        self.ops.push(Op::unowned_new_with_comment(opcode, ""));
+    }
+
+    /// Writes the value of type `ty` in `value` to `offset` words from `base`.
+    fn write_at(&mut self, base: VirtualRegister, offset: u64, value: VirtualRegister, ty: Type) {
+        let size_in_words = self.size_in_words(ty);
+        if size_in_words == 0 {
+            return;
+        }
+        if self.is_copy_type(ty) {
+            self.ops.push(Op::unowned_new_with_comment(
+                VirtualOp::SW(
+                    base,
+                    value,
+                    VirtualImmediate12::new_unchecked(offset, "field offset too large"),
+                ),
+                "",
+            ));
+        } else {
+            let address = self.register_sequencer.next();
+            self.ops.push(Op::unowned_new_with_comment(
+                VirtualOp::ADDI(
+                    address.clone(),
+                    base,
+                    VirtualImmediate12::new_unchecked(offset * 8, "field offset too large"),
+                ),
+                "",
+            ));
+            self.copy_memory(address, value, size_in_words);
+        }
+    }
+
+    /// Computes the address of the element of an array at a runtime index.
+    fn element_address(
+        &mut self,
+        base: VirtualRegister,
+        element_type: Type,
+        index_val: Value,
+    ) -> VirtualRegister {
+        let index = self.operand_register(index_val);
+        let offset = self.register_sequencer.next();
+        let address = self.register_sequencer.next();
+        self.ops.push(Op::unowned_new_with_comment(
+            VirtualOp::MULI(
+                offset.clone(),
+                index,
+                VirtualImmediate12::new_unchecked(
+                    self.size_in_words(element_type) * 8,
+                    "array element too large",
+                ),
+            ),
+            "offset of array element",
+        ));
+        self.ops.push(Op::unowned_new_with_comment(
+            VirtualOp::ADD(address.clone(), base, offset),
+            "address of array element",
+        ));
+        address
+    }
+
+    /// The offset in words of the field of `ty` at `indices`, and the type of the field.
+    fn field_offset(&self, ty: Type, indices: &[u64]) -> (u64, Type) {
+        indices.iter().fold((0, ty), |(offset, ty), index| {
+            let aggregate = match ty {
+                Type::Array(aggregate) | Type::Struct(aggregate) | Type::Union(aggregate) => {
+                    aggregate
+                }
+                _ => unreachable!("verified aggregate type"),
+            };
+            match (ty, aggregate.get_content(self.context)) {
+                (_, AggregateContent::ArrayType(element_type, _)) => (
+                    offset + index * self.size_in_words(*element_type),
+                    *element_type,
+                ),
+                // every variant of a union starts at its beginning
+                (Type::Union(_), AggregateContent::FieldTypes(field_types)) => {
+                    (offset, field_types[*index as usize])
+                }
+                (_, AggregateContent::FieldTypes(field_types)) => (
+                    offset
+                        + field_types[..*index as usize]
+                            .iter()
+                            .map(|field_type| self.size_in_words(*field_type))
+                            .sum::<u64>(),
+                    field_types[*index as usize],
+                ),
+            }
+        })
+    }
+
+    /// Whether values of type `ty` are held in a register rather than in memory.
+    fn is_copy_type(&self, ty: Type) -> bool {
+        match ty {
+            Type::Unit | Type::Bool | Type::Uint(_) => true,
+            Type::String(len) => len <= 8,
+            Type::B256 | Type::Array(_) | Type::Union(_) | Type::Struct(_) => false,
+        }
+    }
+
+    fn size_in_words(&self, ty: Type) -> u64 {
+        match ty {
+            Type::Unit => 0,
+            Type::Bool | Type::Uint(_) => 1,
+            Type::B256 => 4,
+            Type::String(len) => (len + 7) / 8,
+            Type::Array(aggregate) | Type::Struct(aggregate) | Type::Union(aggregate) => {
+                match aggregate.get_content(self.context) {
+                    AggregateContent::ArrayType(element_type, count) => {
+                        self.size_in_words(*element_type) * count
+                    }
+                    AggregateContent::FieldTypes(field_types) => {
+                        let sizes = field_types.iter().map(|ty| self.size_in_words(*ty));
+                        if matches!(ty, Type::Union(_)) {
+                            sizes.max().unwrap_or(0)
+                        } else {
+                            sizes.sum()
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
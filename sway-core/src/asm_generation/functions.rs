@@ -0,0 +1,574 @@
+//! Functions are either inlined at every call site, or compiled once, out of line, and called.
+//! Recursive functions and functions which compile to more than [MAX_INLINED_FUNCTION_SIZE] ops
+//! are called.
+//!
+//! The calling convention is as follows. The call site allocates a stack frame with CFEI, saves
+//! every register which is live across the call into it, and writes an id identifying the call
+//! site into the first word of the frame. It then moves the arguments into the function's
+//! parameter registers, puts the address of the frame into the function's frame register and
+//! jumps to the function's label. The function puts its return value into its return value
+//! register, reads the call site id back out of its frame and jumps to the matching call site.
+//! There, the saved registers are restored and, if the function leaves the stack as it found it,
+//! the frame is freed with CFSI.
+//!
+//! Code generation emits [OrganizationalOp::Call] and [OrganizationalOp::FunctionReturn] ops,
+//! which are expanded by [expand_function_calls] once the liveness of every register is known.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+use super::{
+    compiler_constants::{MAX_INLINED_FUNCTION_SIZE, TWELVE_BITS},
+    convert_expression_to_asm, convert_fn_body_to_asm, liveness, AsmNamespace, DataSection,
+    RegisterSequencer,
+};
+use crate::{
+    asm_lang::{
+        ConstantRegister, FunctionRegisters, Label, Op, OrganizationalOp, VirtualImmediate12,
+        VirtualImmediate24, VirtualOp, VirtualRegister,
+    },
+    error::*,
+    parse_tree::Literal,
+    semantic_analysis::{ast_node::TypedCodeBlock, Namespace, TypedDeclaration, TypedExpression},
+    span::Span,
+    type_engine::{look_up_type_id, TypeId},
+    Ident,
+};
+use either::Either;
+
+/// Identifies a function which has been compiled out of line, by the span of its body, its name
+/// and its argument and return types. Monomorphized copies of a generic function share the span
+/// of their body, as do the methods the compiler synthesizes for a type, like those of the signed
+/// integers.
+type FunctionKey = (Span, String, String);
+
+#[derive(Clone)]
+struct CompiledFunction {
+    registers: FunctionRegisters,
+    /// The ops of the function's body, not including its label or its return.
+    body: Vec<Op>,
+}
+
+/// Keeps track of the functions which have been compiled out of line.
+#[derive(Default, Clone)]
+pub(crate) struct FunctionTable {
+    compiled: HashMap<FunctionKey, CompiledFunction>,
+    /// The functions which are currently being compiled, innermost last.
+    in_progress: Vec<(FunctionKey, FunctionRegisters)>,
+    recursive: HashSet<FunctionKey>,
+    /// The variables which are visible from within every function, i.e. constants.
+    globals: HashMap<Ident, VirtualRegister>,
+    /// The bodies of the non-generic functions declared in the program, by their spans. These are
+    /// shared, rather than copied, by the namespaces of functions which are inlined.
+    definitions: Rc<HashMap<Span, TypedCodeBlock>>,
+}
+
+impl fmt::Debug for FunctionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.compiled
+                    .values()
+                    .map(|function| function.registers.name.as_str()),
+            )
+            .finish()
+    }
+}
+
+impl FunctionTable {
+    pub(crate) fn set_globals(&mut self, globals: HashMap<Ident, VirtualRegister>) {
+        self.globals = globals;
+    }
+
+    /// Adds the bodies of the non-generic functions declared in `namespace` and in every module
+    /// it imports, recursively.
+    pub(crate) fn add_definitions(&mut self, namespace: &Namespace) {
+        for decl in namespace.get_all_declared_symbols() {
+            if let TypedDeclaration::FunctionDeclaration(decl) = decl {
+                if decl.type_parameters.is_empty()
+                    && !self.definitions.contains_key(&decl.body.whole_block_span)
+                {
+                    Rc::make_mut(&mut self.definitions)
+                        .insert(decl.body.whole_block_span.clone(), decl.body.clone());
+                }
+            }
+        }
+        for module in namespace.get_all_imported_modules() {
+            self.add_definitions(module);
+        }
+    }
+
+    /// The body of the function being applied, if `function_body` is missing it. A function which
+    /// was type checked before another which it is mutually recursive with calls that function
+    /// without its body.
+    pub(crate) fn definition_of(&self, function_body: &TypedCodeBlock) -> Option<TypedCodeBlock> {
+        if function_body.contents.is_empty() {
+            self.definitions
+                .get(&function_body.whole_block_span)
+                .cloned()
+        } else {
+            None
+        }
+    }
+}
+
+fn function_key(
+    name: &str,
+    arguments: &[(Ident, TypedExpression)],
+    function_body: &TypedCodeBlock,
+    return_type: TypeId,
+) -> FunctionKey {
+    let signature = arguments
+        .iter()
+        .map(|(_, arg)| look_up_type_id(arg.return_type).friendly_type_str())
+        .chain(std::iter::once(
+            look_up_type_id(return_type).friendly_type_str(),
+        ))
+        .collect::<Vec<_>>()
+        .join(", ");
+    (
+        function_body.whole_block_span.clone(),
+        name.to_string(),
+        signature,
+    )
+}
+
+/// Compiles the function with body `function_body` out of line, if that has not already been
+/// done, and decides whether it should be called or inlined. Returns the function's registers if
+/// it should be called.
+pub(crate) fn compile_function_out_of_line(
+    name: &str,
+    arguments: &[(Ident, TypedExpression)],
+    function_body: &TypedCodeBlock,
+    return_type: TypeId,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Option<FunctionRegisters>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let key = function_key(name, arguments, function_body, return_type);
+
+    // a call to a function which is still being compiled is a recursive call
+    if let Some((_, registers)) = namespace
+        .functions
+        .in_progress
+        .iter()
+        .find(|(in_progress, _)| *in_progress == key)
+    {
+        let registers = registers.clone();
+        namespace.functions.recursive.insert(key);
+        return ok(Some(registers), warnings, errors);
+    }
+
+    if !namespace.functions.compiled.contains_key(&key) {
+        let registers = FunctionRegisters {
+            label: register_sequencer.get_label(),
+            name: name.to_string(),
+            parameters: arguments
+                .iter()
+                .map(|_| register_sequencer.next())
+                .collect(),
+            return_value: register_sequencer.next(),
+            frame: register_sequencer.next(),
+        };
+        // the body of the function can only see global variables and its own parameters
+        let mut function_namespace = AsmNamespace {
+            data_section: namespace.data_section.clone(),
+            variables: namespace.functions.globals.clone(),
+            functions: std::mem::take(&mut namespace.functions),
+            loops: vec![],
+            function_exit: None,
+        };
+        for ((param_name, _), reg) in arguments.iter().zip(registers.parameters.iter()) {
+            function_namespace.insert_variable(param_name.clone(), reg.clone());
+        }
+        function_namespace
+            .functions
+            .in_progress
+            .push((key.clone(), registers.clone()));
+        let body = convert_fn_body_to_asm(
+            function_body,
+            &mut function_namespace,
+            register_sequencer,
+            &registers.return_value,
+        );
+        function_namespace.functions.in_progress.pop();
+        namespace.data_section = function_namespace.data_section;
+        namespace.functions = function_namespace.functions;
+        let body = check!(body, return err(warnings, errors), warnings, errors);
+        namespace
+            .functions
+            .compiled
+            .insert(key.clone(), CompiledFunction { registers, body });
+    }
+
+    let function = &namespace.functions.compiled[&key];
+    let size = function
+        .body
+        .iter()
+        .filter(|op| op.opcode.is_left())
+        .count();
+    if namespace.functions.recursive.contains(&key) || size > MAX_INLINED_FUNCTION_SIZE {
+        ok(Some(function.registers.clone()), warnings, errors)
+    } else {
+        ok(None, warnings, errors)
+    }
+}
+
+/// Evaluates `arguments` and calls the function described by `function`, putting its return
+/// value into `return_register`.
+pub(crate) fn convert_fn_call_to_asm(
+    function: FunctionRegisters,
+    arguments: &[(Ident, TypedExpression)],
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_buf = vec![Op::new_comment(format!("{} fn call", function.name))];
+    let mut argument_registers = Vec::with_capacity(arguments.len());
+    for (_, arg) in arguments {
+        let arg_register = register_sequencer.next();
+        let mut ops = check!(
+            convert_expression_to_asm(arg, namespace, &arg_register, register_sequencer),
+            vec![],
+            warnings,
+            errors
+        );
+        asm_buf.append(&mut ops);
+        argument_registers.push(arg_register);
+    }
+    asm_buf.push(Op::call_function(
+        function,
+        argument_registers,
+        return_register.clone(),
+    ));
+    ok(asm_buf, warnings, errors)
+}
+
+/// Appends the body of every function which is called from `asm_buf`, directly or indirectly,
+/// to `asm_buf`.
+pub(crate) fn append_called_functions(asm_buf: &mut Vec<Op>, namespace: &AsmNamespace) {
+    let functions: HashMap<&Label, &CompiledFunction> = namespace
+        .functions
+        .compiled
+        .values()
+        .map(|function| (&function.registers.label, function))
+        .collect();
+    let mut appended = HashSet::new();
+    // the buffer grows as function bodies are appended, which in turn are scanned for calls
+    let mut ix = 0;
+    while ix < asm_buf.len() {
+        let callee = match asm_buf[ix].opcode {
+            Either::Right(OrganizationalOp::Call { ref function, .. }) => {
+                Some(function.label.clone())
+            }
+            _ => None,
+        };
+        if let Some(label) = callee {
+            if appended.insert(label.clone()) {
+                let function = functions[&label];
+                asm_buf.push(Op::unowned_jump_label_comment(
+                    label,
+                    format!("fn {}", function.registers.name),
+                ));
+                asm_buf.extend(function.body.iter().cloned());
+                asm_buf.push(Op::function_return(function.registers.clone()));
+            }
+        }
+        ix += 1;
+    }
+}
+
+/// Expands every [OrganizationalOp::Call] and [OrganizationalOp::FunctionReturn] in `ops` into
+/// real ops, following the calling convention described at the top of this module.
+pub(crate) fn expand_function_calls(
+    ops: Vec<Op>,
+    data_section: &mut DataSection,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    if !ops
+        .iter()
+        .any(|op| matches!(op.opcode, Either::Right(OrganizationalOp::Call { .. })))
+    {
+        return ops;
+    }
+    let live_out = liveness::live_out(&ops);
+    let stack_neutral = stack_neutral_functions(&ops);
+
+    // every call site gets an id, which its callee uses to find the label to return to
+    let mut return_labels: HashMap<Label, Vec<Label>> = HashMap::new();
+    let mut call_sites: HashMap<usize, (u64, Label)> = HashMap::new();
+    for (ix, op) in ops.iter().enumerate() {
+        if let Either::Right(OrganizationalOp::Call { ref function, .. }) = op.opcode {
+            let labels = return_labels.entry(function.label.clone()).or_default();
+            let return_label = register_sequencer.get_label();
+            call_sites.insert(ix, (labels.len() as u64, return_label.clone()));
+            labels.push(return_label);
+        }
+    }
+
+    let mut buf = Vec::with_capacity(ops.len());
+    for (ix, op) in ops.iter().enumerate() {
+        match op.opcode {
+            Either::Right(OrganizationalOp::Call {
+                ref function,
+                ref arguments,
+                ref return_register,
+            }) => {
+                let (call_site_id, return_label) = call_sites.remove(&ix).unwrap();
+                // sorted so that the generated ASM is deterministic
+                let mut saved_registers = live_out[ix]
+                    .iter()
+                    .filter(|reg| *reg != return_register)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                saved_registers.sort_by_key(|reg| reg.to_string());
+                buf.append(&mut expand_call(
+                    function,
+                    arguments,
+                    return_register,
+                    &saved_registers,
+                    call_site_id,
+                    return_label,
+                    stack_neutral.contains(&function.label),
+                    data_section,
+                    register_sequencer,
+                ));
+            }
+            Either::Right(OrganizationalOp::FunctionReturn(ref function)) => {
+                buf.append(&mut expand_return(
+                    function,
+                    &return_labels[&function.label],
+                    data_section,
+                    register_sequencer,
+                ));
+            }
+            _ => buf.push(op.clone()),
+        }
+    }
+    buf
+}
+
+#[allow(clippy::too_many_arguments)]
+fn expand_call(
+    function: &FunctionRegisters,
+    arguments: &[VirtualRegister],
+    return_register: &VirtualRegister,
+    saved_registers: &[VirtualRegister],
+    call_site_id: u64,
+    return_label: Label,
+    callee_is_stack_neutral: bool,
+    data_section: &mut DataSection,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    let frame_size = VirtualImmediate24::new_unchecked(
+        (saved_registers.len() as u64 + 1) * 8,
+        "Too many registers are live across this call",
+    );
+    let frame = register_sequencer.next();
+    let call_site_id_register = register_sequencer.next();
+    let mut buf = vec![
+        Op::unowned_register_move_comment(
+            frame.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+            format!("set up the stack frame for a call to {}", function.name),
+        ),
+        Op::unowned_new_with_comment(VirtualOp::CFEI(frame_size.clone()), ""),
+    ];
+    for (ix, reg) in saved_registers.iter().enumerate() {
+        buf.push(Op::unowned_new_with_comment(
+            VirtualOp::SW(
+                frame.clone(),
+                reg.clone(),
+                VirtualImmediate12::new_unchecked(ix as u64 + 1, "Too many saved registers"),
+            ),
+            "save register which is live across the call",
+        ));
+    }
+    buf.push(load_call_site_id(
+        call_site_id_register.clone(),
+        call_site_id,
+        "call site id",
+        data_section,
+    ));
+    buf.push(Op::unowned_new_with_comment(
+        VirtualOp::SW(
+            frame.clone(),
+            call_site_id_register,
+            VirtualImmediate12::new_unchecked(0, ""),
+        ),
+        "",
+    ));
+    for (param, arg) in function.parameters.iter().zip(arguments.iter()) {
+        buf.push(Op::unowned_register_move_comment(
+            param.clone(),
+            arg.clone(),
+            "pass argument",
+        ));
+    }
+    buf.push(Op::unowned_register_move(function.frame.clone(), frame));
+    buf.push(Op::jump_to_label_comment(
+        function.label.clone(),
+        format!("call {}", function.name),
+    ));
+
+    buf.push(Op::unowned_jump_label_comment(
+        return_label,
+        format!("return from {}", function.name),
+    ));
+    buf.push(Op::unowned_register_move(
+        return_register.clone(),
+        function.return_value.clone(),
+    ));
+    // the frame register still points to this call's frame
+    let restore_base = register_sequencer.next();
+    buf.push(Op::unowned_register_move(
+        restore_base.clone(),
+        function.frame.clone(),
+    ));
+    for (ix, reg) in saved_registers.iter().enumerate() {
+        buf.push(Op::unowned_new_with_comment(
+            VirtualOp::LW(
+                reg.clone(),
+                restore_base.clone(),
+                VirtualImmediate12::new_unchecked(ix as u64 + 1, "Too many saved registers"),
+            ),
+            "restore register which is live across the call",
+        ));
+    }
+    if callee_is_stack_neutral {
+        buf.push(Op::unowned_new_with_comment(
+            VirtualOp::CFSI(frame_size),
+            "free the stack frame",
+        ));
+    }
+    buf
+}
+
+fn expand_return(
+    function: &FunctionRegisters,
+    return_labels: &[Label],
+    data_section: &mut DataSection,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    let call_site_id = register_sequencer.next();
+    let mut buf = vec![Op::unowned_new_with_comment(
+        VirtualOp::LW(
+            call_site_id.clone(),
+            function.frame.clone(),
+            VirtualImmediate12::new_unchecked(0, ""),
+        ),
+        format!("return from {}", function.name),
+    )];
+    let (last, rest) = return_labels
+        .split_last()
+        .expect("functions are only generated when they are called");
+    for (id, return_label) in rest.iter().enumerate() {
+        let expected_id = register_sequencer.next();
+        let next_check = register_sequencer.get_label();
+        buf.push(load_call_site_id(
+            expected_id.clone(),
+            id as u64,
+            "",
+            data_section,
+        ));
+        buf.push(Op::jump_if_not_equal(
+            call_site_id.clone(),
+            expected_id,
+            next_check.clone(),
+        ));
+        buf.push(Op::jump_to_label(return_label.clone()));
+        buf.push(Op::unowned_jump_label(next_check));
+    }
+    buf.push(Op::jump_to_label(last.clone()));
+    buf
+}
+
+/// Loads the id of a call site into `register`. Ids which don't fit in an immediate are loaded
+/// from the data section.
+fn load_call_site_id(
+    register: VirtualRegister,
+    call_site_id: u64,
+    comment: &str,
+    data_section: &mut DataSection,
+) -> Op {
+    let op = if call_site_id <= TWELVE_BITS {
+        VirtualOp::ADDI(
+            register,
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            VirtualImmediate12::new_unchecked(call_site_id, "checked above"),
+        )
+    } else {
+        VirtualOp::LWDataId(
+            register,
+            data_section.insert_data_value(&Literal::U64(call_site_id)),
+        )
+    };
+    Op::unowned_new_with_comment(op, comment)
+}
+
+/// Finds the functions which leave the stack pointer as they found it, so their callers may
+/// free the frames they set up for them.
+fn stack_neutral_functions(ops: &[Op]) -> HashSet<Label> {
+    let function_labels: HashSet<&Label> = ops
+        .iter()
+        .filter_map(|op| match op.opcode {
+            Either::Right(OrganizationalOp::FunctionReturn(ref function)) => Some(&function.label),
+            _ => None,
+        })
+        .collect();
+
+    let mut grows_stack: HashMap<&Label, bool> = HashMap::new();
+    let mut callees: HashMap<&Label, Vec<&Label>> = HashMap::new();
+    let mut current = None;
+    for op in ops {
+        match op.opcode {
+            Either::Right(OrganizationalOp::Label(ref label))
+                if function_labels.contains(label) =>
+            {
+                grows_stack.insert(label, false);
+                current = Some(label);
+            }
+            Either::Right(OrganizationalOp::FunctionReturn(_)) => current = None,
+            Either::Right(OrganizationalOp::Call { ref function, .. }) => {
+                if let Some(current) = current {
+                    callees.entry(current).or_default().push(&function.label);
+                }
+            }
+            Either::Left(VirtualOp::CFEI(_)) => {
+                if let Some(current) = current {
+                    grows_stack.insert(current, true);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let mut neutral: HashSet<&Label> = grows_stack
+        .iter()
+        .filter(|(_, grows)| !**grows)
+        .map(|(label, _)| *label)
+        .collect();
+    loop {
+        let not_neutral = neutral
+            .iter()
+            .filter(|label| {
+                callees
+                    .get(*label)
+                    .map(|callees| callees.iter().any(|callee| !neutral.contains(callee)))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if not_neutral.is_empty() {
+            break;
+        }
+        for label in not_neutral {
+            neutral.remove(label);
+        }
+    }
+    neutral.into_iter().cloned().collect()
+}
@@ -0,0 +1,119 @@
+//! Liveness analysis over an [AbstractInstructionSet](super::AbstractInstructionSet), used to
+//! find out which registers must be preserved across function calls.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::asm_lang::{Label, Op, OrganizationalOp, VirtualOp, VirtualRegister};
+use either::Either;
+
+/// Computes, for every op in `ops`, the set of virtual registers which are live immediately after
+/// that op executes. Constant registers are never included.
+///
+/// A [OrganizationalOp::Call] is treated as an op which falls through to the next op, reads its
+/// arguments along with any registers the callee reads before writing, and writes only its return
+/// register.
+pub(crate) fn live_out(ops: &[Op]) -> Vec<HashSet<VirtualRegister>> {
+    let label_indices: HashMap<&Label, usize> = ops
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, op)| match op.opcode {
+            Either::Right(OrganizationalOp::Label(ref label)) => Some((label, ix)),
+            _ => None,
+        })
+        .collect();
+
+    let successors = ops
+        .iter()
+        .enumerate()
+        .map(|(ix, op)| successors(ix, op, ops.len(), &label_indices))
+        .collect::<Vec<_>>();
+
+    let mut live_in: Vec<HashSet<VirtualRegister>> = vec![HashSet::new(); ops.len()];
+    let mut live_out: Vec<HashSet<VirtualRegister>> = vec![HashSet::new(); ops.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (ix, op) in ops.iter().enumerate().rev() {
+            let mut out = HashSet::new();
+            for succ in &successors[ix] {
+                out.extend(live_in[*succ].iter().cloned());
+            }
+
+            let (uses, defs) = uses_and_defs(op, &live_in, &label_indices);
+            let mut inn: HashSet<VirtualRegister> = out
+                .iter()
+                .filter(|reg| !defs.contains(reg))
+                .cloned()
+                .collect();
+            inn.extend(uses);
+
+            if inn.len() != live_in[ix].len() || out.len() != live_out[ix].len() {
+                changed = true;
+            }
+            live_in[ix] = inn;
+            live_out[ix] = out;
+        }
+    }
+    live_out
+}
+
+fn successors(
+    ix: usize,
+    op: &Op,
+    num_ops: usize,
+    label_indices: &HashMap<&Label, usize>,
+) -> Vec<usize> {
+    let next = if ix + 1 < num_ops {
+        vec![ix + 1]
+    } else {
+        vec![]
+    };
+    match op.opcode {
+        Either::Right(OrganizationalOp::Jump(ref label)) => vec![label_indices[label]],
+        Either::Right(OrganizationalOp::JumpIfNotEq(_, _, ref label)) => {
+            let mut succs = next;
+            succs.push(label_indices[label]);
+            succs
+        }
+        Either::Right(OrganizationalOp::FunctionReturn(_))
+        | Either::Left(VirtualOp::RET(_))
+        | Either::Left(VirtualOp::RETD(..))
+        | Either::Left(VirtualOp::RVRT(_)) => vec![],
+        _ => next,
+    }
+}
+
+fn uses_and_defs(
+    op: &Op,
+    live_in: &[HashSet<VirtualRegister>],
+    label_indices: &HashMap<&Label, usize>,
+) -> (HashSet<VirtualRegister>, HashSet<VirtualRegister>) {
+    let (uses, defs): (Vec<VirtualRegister>, Vec<VirtualRegister>) = match op.opcode {
+        Either::Left(ref op) => (
+            op.use_registers().into_iter().cloned().collect(),
+            op.def_registers().into_iter().cloned().collect(),
+        ),
+        Either::Right(OrganizationalOp::Call {
+            ref function,
+            ref arguments,
+            ref return_register,
+        }) => {
+            // anything the callee reads, other than the registers set up by the call itself,
+            // must be live at the call site
+            let callee_reads = live_in[label_indices[&function.label]]
+                .iter()
+                .filter(|reg| !function.parameters.contains(reg) && **reg != function.frame)
+                .cloned();
+            (
+                arguments.iter().cloned().chain(callee_reads).collect(),
+                vec![return_register.clone()],
+            )
+        }
+        Either::Right(ref op) => (op.registers().into_iter().cloned().collect(), vec![]),
+    };
+    let is_virtual = |reg: &VirtualRegister| matches!(reg, VirtualRegister::Virtual(_));
+    (
+        uses.into_iter().filter(is_virtual).collect(),
+        defs.into_iter().filter(is_virtual).collect(),
+    )
+}
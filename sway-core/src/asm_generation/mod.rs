@@ -4,12 +4,12 @@ use crate::type_engine::resolve_type;
 use crate::{
     asm_generation::expression::convert_abi_fn_to_asm,
     asm_lang::{
-        allocated_ops::{AllocatedOp, AllocatedRegister},
-        virtual_register::*,
-        Label, Op, OrganizationalOp, RealizedOp, VirtualImmediate12, VirtualImmediate24, VirtualOp,
+        allocated_ops::AllocatedOp, virtual_register::*, Label, Op, OrganizationalOp, RealizedOp,
+        VirtualImmediate12, VirtualImmediate24, VirtualOp,
     },
     error::*,
-    parse_tree::Literal,
+    ir_generation,
+    parse_tree::{Literal, Purity},
     semantic_analysis::{
         Namespace, TypedAstNode, TypedAstNodeContent, TypedDeclaration, TypedFunctionDeclaration,
         TypedParseTree,
@@ -24,45 +24,38 @@ pub(crate) mod compiler_constants;
 mod declaration;
 mod expression;
 mod finalized_asm;
+mod from_ir;
+mod functions;
+mod liveness;
+mod optimizations;
+mod register_allocator;
 mod register_sequencer;
+mod storage;
 mod while_loop;
 
 pub(crate) use declaration::*;
 pub(crate) use expression::*;
 pub use finalized_asm::FinalizedAsm;
+pub use optimizations::OptimizationPass;
+pub(crate) use register_allocator::RegisterAllocation;
 pub(crate) use register_sequencer::*;
 
-use while_loop::convert_while_loop_to_asm;
+use functions::{append_called_functions, expand_function_calls, FunctionTable};
+use storage::convert_storage_initialization_to_asm;
+use while_loop::{convert_for_loop_to_asm, convert_while_loop_to_asm};
 
 // Initially, the bytecode will have a lot of individual registers being used. Each register will
 // have a new unique identifier. For example, two separate invocations of `+` will result in 4
 // registers being used for arguments and 2 for outputs.
 //
-// After that, the level 0 bytecode will go through a process where register use is minified,
-// producing level 1 bytecode. This process is as such:
+// After that, registers are allocated by the graph coloring allocator in `register_allocator`.
+// The live range of each virtual register is computed, and any two virtual registers which are
+// live at the same time are assigned different registers. Moves between two virtual registers do
+// not cause them to interfere, so they can share a register.
 //
-// 1. Detect the last time a register is read. After that, it can be reused and recycled to fit the
-//    needs of the next "level 0 bytecode" register
-//
-// 2. Detect needless assignments and movements, and substitute registers in.
-//    i.e.
-//    a = b
-//    c = a
-//
-//    would become
-//    c = b
-//
-//
-// After the level 1 bytecode is produced, level 2 bytecode is created by limiting the maximum
-// number of registers and inserting bytecode to read from/write to memory where needed. Ideally,
-// the algorithm for determining which registers will be written off to memory is based on how
-// frequently that register is accessed in a particular section of code. Using this strategy, we
-// hope to minimize memory writing.
-//
-// For each line, the number of times a virtual register is accessed between then and the end of the
-// program is its register precedence. A virtual register's precedence is 0 if it is currently in
-// "memory", and the above described number if it is not. This prevents over-prioritization of
-// registers that have already been written off to memory.
+// If there are not enough registers for every virtual register which is live at some point in the
+// program, some of them are spilled to stack slots, and are loaded into and stored from short
+// lived temporaries around every op which accesses them.
 //
 /// The [HllAsmSet] contains either a contract ABI and corresponding ASM, a script's main
 /// function's ASM, or a predicate's main function's ASM. ASM is never generated for libraries,
@@ -98,36 +91,17 @@ pub struct RealizedAbstractInstructionSet {
 }
 
 impl RealizedAbstractInstructionSet {
-    fn allocate_registers(self) -> InstructionSet {
-        // Eventually, we will use a cool graph-coloring algorithm.
-        // For now, just keep a pool of registers and return
-        // registers when they are not read anymore
-
-        // construct a mapping from every op to the registers it uses
-        let op_register_mapping = self
+    fn allocate_registers(self, allocation: &RegisterAllocation) -> InstructionSet {
+        let ops = self
             .ops
             .into_iter()
-            .map(|op| {
-                (
-                    op.clone(),
-                    op.opcode.registers().into_iter().cloned().collect(),
-                )
-            })
-            .collect::<Vec<_>>();
-
-        // get registers from the pool.
-        let mut pool = RegisterPool::init();
-        let mut buf = vec![];
-        for (ix, (op, _)) in op_register_mapping.iter().enumerate() {
-            buf.push(AllocatedOp {
-                opcode: op
-                    .opcode
-                    .allocate_registers(&mut pool, &op_register_mapping, ix),
-                comment: op.comment.clone(),
-                owning_span: op.owning_span.clone(),
+            .map(|op| AllocatedOp {
+                opcode: op.opcode.allocate_registers(allocation),
+                comment: op.comment,
+                owning_span: op.owning_span,
             })
-        }
-        InstructionSet { ops: buf }
+            .collect();
+        InstructionSet { ops }
     }
 }
 
@@ -186,6 +160,19 @@ impl AbstractInstructionSet {
         AbstractInstructionSet { ops: buf2 }
     }
 
+    /// Allocates registers by coloring the interference graph of the virtual registers, spilling
+    /// them to the stack where necessary, and then realizes the labels.
+    fn allocate_registers(
+        self,
+        data_section: &DataSection,
+        namespace: &AsmNamespace,
+    ) -> InstructionSet {
+        let (ops, allocation) = register_allocator::allocate_registers(self.ops);
+        AbstractInstructionSet { ops }
+            .realize_labels(data_section, namespace)
+            .allocate_registers(&allocation)
+    }
+
     /// Runs two passes -- one to get the instruction offsets of the labels
     /// and one to replace the labels in the organizational ops
     fn realize_labels(
@@ -223,6 +210,10 @@ impl AbstractInstructionSet {
                     // to load the data, which loads a whole word, so for now this is 2.
                     counter += 2
                 }
+                Either::Right(OrganizationalOp::Call { .. })
+                | Either::Right(OrganizationalOp::FunctionReturn(..)) => {
+                    unreachable!("function calls are expanded before labels are realized")
+                }
             }
         }
 
@@ -273,6 +264,9 @@ impl AbstractInstructionSet {
                     }
                     OrganizationalOp::Comment => continue,
                     OrganizationalOp::Label(..) => continue,
+                    OrganizationalOp::Call { .. } | OrganizationalOp::FunctionReturn(..) => {
+                        unreachable!("function calls are expanded before labels are realized")
+                    }
                 },
             };
         }
@@ -280,82 +274,6 @@ impl AbstractInstructionSet {
     }
 }
 
-#[derive(Debug)]
-struct RegisterAllocationStatus {
-    reg: AllocatedRegister,
-    in_use: Option<VirtualRegister>,
-}
-#[derive(Debug)]
-pub(crate) struct RegisterPool {
-    registers: Vec<RegisterAllocationStatus>,
-}
-
-impl RegisterPool {
-    fn init() -> Self {
-        let register_pool: Vec<RegisterAllocationStatus> = (0
-            // - 1 because we reserve the final register for the data_section begin
-            ..compiler_constants::NUM_ALLOCATABLE_REGISTERS)
-            .map(|x| RegisterAllocationStatus {
-                reg: AllocatedRegister::Allocated(x),
-                in_use: None,
-            })
-            .collect();
-        Self {
-            registers: register_pool,
-        }
-    }
-
-    /// Checks if any currently used registers are no longer in use, updates the pool,
-    /// and grabs an available register.
-    pub(crate) fn get_register(
-        &mut self,
-        virtual_register: &VirtualRegister,
-        op_register_mapping: &[(RealizedOp, std::collections::HashSet<VirtualRegister>)],
-    ) -> Option<AllocatedRegister> {
-        // check if this register has already been allocated for
-        if let a @ Some(_) = self.registers.iter().find_map(
-            |RegisterAllocationStatus { reg, in_use }| match in_use {
-                Some(x) if x == virtual_register => Some(reg),
-                _ => None,
-            },
-        ) {
-            return a.cloned();
-        }
-
-        // scan to see if any of the old ones are no longer in use
-        for RegisterAllocationStatus { in_use, .. } in
-            self.registers.iter_mut().filter(|r| r.in_use.is_some())
-        {
-            if virtual_register_is_never_accessed_again(
-                in_use.as_ref().unwrap(),
-                op_register_mapping,
-            ) {
-                *in_use = None;
-            }
-        }
-        // find the next unused register, return it, assign it
-        let next_available = self
-            .registers
-            .iter_mut()
-            .find(|RegisterAllocationStatus { in_use, .. }| in_use.is_none());
-
-        match next_available {
-            Some(RegisterAllocationStatus { in_use, reg }) => {
-                *in_use = Some(virtual_register.clone());
-                Some(reg.clone())
-            }
-            None => None,
-        }
-    }
-}
-
-fn virtual_register_is_never_accessed_again(
-    reg: &VirtualRegister,
-    ops: &[(RealizedOp, std::collections::HashSet<VirtualRegister>)],
-) -> bool {
-    !ops.iter().any(|(_, regs)| regs.contains(reg))
-}
-
 /// helper function to check if a label is used in a given buffer of ops
 fn label_is_used(buf: &[Op], label: &Label) -> bool {
     buf.iter().any(|Op { ref opcode, .. }| match opcode {
@@ -432,6 +350,10 @@ impl fmt::Display for DataSection {
                 Literal::U16(num) => format!(".u16 {:#04x}", num),
                 Literal::U32(num) => format!(".u32 {:#04x}", num),
                 Literal::U64(num) => format!(".u64 {:#04x}", num),
+                Literal::I8(num) => format!(".i8 {}", num),
+                Literal::I16(num) => format!(".i16 {}", num),
+                Literal::I32(num) => format!(".i32 {}", num),
+                Literal::I64(num) => format!(".i64 {}", num),
                 Literal::Boolean(b) => format!(".bool {}", if *b { "0x01" } else { "0x00" }),
                 Literal::String(st) => format!(".str \"{}\"", st.as_str()),
                 Literal::Byte(b) => format!(".byte {:#08b}", b),
@@ -573,6 +495,28 @@ impl fmt::Display for InstructionSet {
 pub(crate) struct AsmNamespace {
     data_section: DataSection,
     variables: HashMap<Ident, VirtualRegister>,
+    functions: FunctionTable,
+    /// The loops which are being compiled, innermost last.
+    loops: Vec<LoopLabels>,
+    /// Where a `return` jumps to in the function which is being compiled.
+    function_exit: Option<FunctionExit>,
+}
+
+/// The labels which `continue` and `break` jump to in a loop.
+#[derive(Clone, Debug)]
+pub(crate) struct LoopLabels {
+    /// The label before the loop condition.
+    pub(crate) condition: Label,
+    /// The label after the loop body.
+    pub(crate) exit: Label,
+}
+
+/// The label at the end of a function, which a `return` jumps to after it puts the returned value
+/// into the register which holds the return value of the function.
+#[derive(Clone, Debug)]
+pub(crate) struct FunctionExit {
+    pub(crate) label: Label,
+    pub(crate) return_register: VirtualRegister,
 }
 
 /// An address which refers to a value in the data section of the asm.
@@ -617,6 +561,24 @@ pub(crate) fn compile_ast_to_asm(
     let mut register_sequencer = RegisterSequencer::new();
     let mut warnings = vec![];
     let mut errors = vec![];
+    if build_config.use_ir {
+        let context = check!(
+            ir_generation::compile_ast(&ast),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        if build_config.print_ir {
+            println!("{}", context);
+        }
+        let (asm, asm_namespace) = check!(
+            from_ir::compile_ir_to_asm(&context, &mut register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        return finalize_asm(asm, &asm_namespace, build_config, warnings, errors);
+    }
     let (asm, asm_namespace) = match ast {
         TypedParseTree::Script {
             main_function,
@@ -641,12 +603,11 @@ pub(crate) fn compile_ast_to_asm(
             // start generating from the main function
             let return_register = register_sequencer.next();
             let mut body = check!(
-                convert_code_block_to_asm(
+                convert_fn_body_to_asm(
                     &main_function.body,
                     &mut namespace,
                     &mut register_sequencer,
-                    // TODO validate that this isn't just implicit returns?
-                    Some(&return_register),
+                    &return_register,
                 ),
                 vec![],
                 warnings,
@@ -664,6 +625,12 @@ pub(crate) fn compile_ast_to_asm(
                 warnings,
                 errors
             ));
+            append_called_functions(&mut asm_buf, &namespace);
+            let asm_buf = expand_function_calls(
+                asm_buf,
+                &mut namespace.data_section,
+                &mut register_sequencer,
+            );
 
             (
                 HllAsmSet::ScriptMain {
@@ -693,19 +660,32 @@ pub(crate) fn compile_ast_to_asm(
                 warnings,
                 errors
             );
-            // start generating from the main function
+            // start generating from the main function. The value it returns isn't read, so its
+            // register is thrown away later (in coalescing).
+            let return_register = register_sequencer.next();
             let mut body = check!(
-                convert_code_block_to_asm(
+                convert_fn_body_to_asm(
                     &main_function.body,
                     &mut namespace,
                     &mut register_sequencer,
-                    None,
+                    &return_register,
                 ),
                 vec![],
                 warnings,
                 errors
             );
             asm_buf.append(&mut body);
+            // execution of a predicate ends at the end of the program, so any functions it calls
+            // must be jumped over
+            let end_label = register_sequencer.get_label();
+            asm_buf.push(Op::jump_to_label(end_label.clone()));
+            append_called_functions(&mut asm_buf, &namespace);
+            asm_buf.push(Op::unowned_jump_label(end_label));
+            let asm_buf = expand_function_calls(
+                asm_buf,
+                &mut namespace.data_section,
+                &mut register_sequencer,
+            );
 
             (
                 HllAsmSet::PredicateMain {
@@ -735,6 +715,22 @@ pub(crate) fn compile_ast_to_asm(
                 warnings,
                 errors
             );
+            let storage_initialization = match declarations.iter().find_map(|decl| match decl {
+                TypedDeclaration::StorageDeclaration(decl) => Some(decl),
+                _ => None,
+            }) {
+                Some(storage) => check!(
+                    convert_storage_initialization_to_asm(
+                        storage,
+                        &mut namespace,
+                        &mut register_sequencer
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
+                None => vec![],
+            };
             let (selectors_and_labels, mut contract_asm) = check!(
                 compile_contract_to_selectors(abi_entries, &mut namespace, &mut register_sequencer),
                 return err(warnings, errors),
@@ -745,8 +741,15 @@ pub(crate) fn compile_ast_to_asm(
                 &mut register_sequencer,
                 &mut namespace,
                 selectors_and_labels,
+                storage_initialization,
             ));
             asm_buf.append(&mut contract_asm);
+            append_called_functions(&mut asm_buf, &namespace);
+            let asm_buf = expand_function_calls(
+                asm_buf,
+                &mut namespace.data_section,
+                &mut register_sequencer,
+            );
 
             (
                 HllAsmSet::ContractAbi {
@@ -758,14 +761,24 @@ pub(crate) fn compile_ast_to_asm(
         }
         TypedParseTree::Library { .. } => (HllAsmSet::Library, Default::default()),
     };
+    finalize_asm(asm, &asm_namespace, build_config, warnings, errors)
+}
 
+fn finalize_asm(
+    asm: HllAsmSet,
+    asm_namespace: &AsmNamespace,
+    build_config: &BuildConfig,
+    mut warnings: Vec<CompileWarning>,
+    mut errors: Vec<CompileError>,
+) -> CompileResult<FinalizedAsm> {
     if build_config.print_intermediate_asm {
         println!("{}", asm);
     }
 
     let finalized_asm = asm
         .remove_unnecessary_jumps()
-        .allocate_registers(&asm_namespace)
+        .optimize(&build_config.optimization_passes)
+        .allocate_registers(asm_namespace)
         .optimize();
 
     if build_config.print_finalized_asm {
@@ -812,6 +825,42 @@ impl HllAsmSet {
 }
 
 impl JumpOptimizedAsmSet {
+    /// Runs the optimization `passes` over the program, which may add constants to its data
+    /// section.
+    fn optimize(self, passes: &[OptimizationPass]) -> JumpOptimizedAsmSet {
+        let optimize_section = |program_section: AbstractInstructionSet,
+                                data_section: &mut DataSection| {
+            AbstractInstructionSet {
+                ops: optimizations::run_passes(program_section.ops, data_section, passes),
+            }
+            .remove_sequential_jumps()
+        };
+        match self {
+            JumpOptimizedAsmSet::Library => JumpOptimizedAsmSet::Library,
+            JumpOptimizedAsmSet::ScriptMain {
+                mut data_section,
+                program_section,
+            } => JumpOptimizedAsmSet::ScriptMain {
+                program_section: optimize_section(program_section, &mut data_section),
+                data_section,
+            },
+            JumpOptimizedAsmSet::PredicateMain {
+                mut data_section,
+                program_section,
+            } => JumpOptimizedAsmSet::PredicateMain {
+                program_section: optimize_section(program_section, &mut data_section),
+                data_section,
+            },
+            JumpOptimizedAsmSet::ContractAbi {
+                mut data_section,
+                program_section,
+            } => JumpOptimizedAsmSet::ContractAbi {
+                program_section: optimize_section(program_section, &mut data_section),
+                data_section,
+            },
+        }
+    }
+
     fn allocate_registers(self, namespace: &AsmNamespace) -> RegisterAllocatedAsmSet {
         match self {
             JumpOptimizedAsmSet::Library => RegisterAllocatedAsmSet::Library,
@@ -819,9 +868,7 @@ impl JumpOptimizedAsmSet {
                 data_section,
                 program_section,
             } => {
-                let program_section = program_section
-                    .realize_labels(&data_section, namespace)
-                    .allocate_registers();
+                let program_section = program_section.allocate_registers(&data_section, namespace);
                 RegisterAllocatedAsmSet::ScriptMain {
                     data_section,
                     program_section,
@@ -831,9 +878,7 @@ impl JumpOptimizedAsmSet {
                 data_section,
                 program_section,
             } => {
-                let program_section = program_section
-                    .realize_labels(&data_section, namespace)
-                    .allocate_registers();
+                let program_section = program_section.allocate_registers(&data_section, namespace);
                 RegisterAllocatedAsmSet::PredicateMain {
                     data_section,
                     program_section,
@@ -843,9 +888,7 @@ impl JumpOptimizedAsmSet {
                 program_section,
                 data_section,
             } => RegisterAllocatedAsmSet::ContractAbi {
-                program_section: program_section
-                    .realize_labels(&data_section, namespace)
-                    .allocate_registers(),
+                program_section: program_section.allocate_registers(&data_section, namespace),
                 data_section,
             },
         }
@@ -976,6 +1019,33 @@ fn convert_node_to_asm(
             );
             ok(NodeAsmResult::JustAsm(res), warnings, errors)
         }
+        TypedAstNodeContent::ForLoop(r#loop) => {
+            let res = check!(
+                convert_for_loop_to_asm(r#loop, namespace, register_sequencer),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            ok(NodeAsmResult::JustAsm(res), warnings, errors)
+        }
+        TypedAstNodeContent::Break | TypedAstNodeContent::Continue => {
+            let LoopLabels { condition, exit } = match namespace.loops.last() {
+                Some(labels) => labels.clone(),
+                None => {
+                    errors.push(CompileError::Internal(
+                        "Loop control flow outside of a loop in assembly generation. This should \
+                         have been an error during type checking.",
+                        node.span.clone(),
+                    ));
+                    return err(warnings, errors);
+                }
+            };
+            let op = match node.content {
+                TypedAstNodeContent::Break => Op::jump_to_label_comment(exit, "break"),
+                _ => Op::jump_to_label_comment(condition, "continue"),
+            };
+            ok(NodeAsmResult::JustAsm(vec![op]), warnings, errors)
+        }
         TypedAstNodeContent::Declaration(typed_decl) => {
             let res = check!(
                 convert_decl_to_asm(typed_decl, namespace, register_sequencer),
@@ -1006,14 +1076,21 @@ fn convert_node_to_asm(
             )
         }
         TypedAstNodeContent::ReturnStatement(exp) => {
-            // if a return register was specified, we use it. If not, we generate a register but
-            // it is going to get thrown away later (in coalescing) as it is never read
-            let return_register = if let Some(return_register) = return_register {
-                return_register.clone()
-            } else {
-                register_sequencer.next()
+            // a return may be nested in blocks within the function, which it returns from all of
+            let FunctionExit {
+                label,
+                return_register,
+            } = match namespace.function_exit.clone() {
+                Some(function_exit) => function_exit,
+                None => {
+                    errors.push(CompileError::Internal(
+                        "Return statement outside of a function in assembly generation.",
+                        node.span.clone(),
+                    ));
+                    return err(warnings, errors);
+                }
             };
-            let ops = check!(
+            let mut ops = check!(
                 convert_expression_to_asm(
                     &exp.expr,
                     namespace,
@@ -1024,11 +1101,8 @@ fn convert_node_to_asm(
                 warnings,
                 errors
             );
-            ok(
-                NodeAsmResult::ReturnStatement { asm: ops },
-                warnings,
-                errors,
-            )
+            ops.push(Op::jump_to_label_comment(label, "return"));
+            ok(NodeAsmResult::JustAsm(ops), warnings, errors)
         }
         TypedAstNodeContent::Expression(ref typed_expr) => {
             let return_register = if let Some(return_register) = return_register {
@@ -1110,11 +1184,13 @@ fn build_preamble(register_sequencer: &mut RegisterSequencer) -> [Op; 6] {
 
 /// Builds the contract switch statement, or function selector, which takes the selector
 /// stored in the call frame (see https://github.com/FuelLabs/sway/issues/97#issuecomment-870150684
-/// for an explanation of its location)
+/// for an explanation of its location). The selectors of functions which don't access storage are
+/// checked first, so that `storage_initialization` only runs when a function which does is called.
 fn build_contract_abi_switch(
     register_sequencer: &mut RegisterSequencer,
     namespace: &mut AsmNamespace,
-    selectors_and_labels: Vec<([u8; 4], Label)>,
+    selectors_and_labels: JumpDestination,
+    mut storage_initialization: Vec<Op>,
 ) -> Vec<Op> {
     let input_selector_register = register_sequencer.next();
     let mut asm_buf = vec![Op {
@@ -1136,7 +1212,18 @@ fn build_contract_abi_switch(
         owning_span: None,
     });
 
-    for (selector, label) in selectors_and_labels {
+    let (storage_selectors, pure_selectors): (Vec<_>, Vec<_>) = selectors_and_labels
+        .into_iter()
+        .partition(|(_, _, purity)| *purity != Purity::Pure);
+    let pure_selector_count = pure_selectors.len();
+    for (index, (selector, label, _)) in pure_selectors
+        .into_iter()
+        .chain(storage_selectors.into_iter())
+        .enumerate()
+    {
+        if index == pure_selector_count {
+            asm_buf.append(&mut storage_initialization);
+        }
         // put the selector in the data section
         let data_label = namespace.insert_data_value(&Literal::U32(u32::from_be_bytes(selector)));
         // load the data into a register for comparison
@@ -1208,6 +1295,10 @@ fn add_all_constant_decls(
         warnings,
         errors
     );
+    // constants are the only variables visible from within functions which are called
+    namespace.functions.set_globals(namespace.variables.clone());
+    // and mutually recursive functions may be called without their bodies, which are found here
+    namespace.functions.add_definitions(ast_namespace);
     ok((), warnings, errors)
 }
 
@@ -1269,8 +1360,8 @@ fn add_module_constant_decls(
     ok((), warnings, errors)
 }
 
-/// The function selector value and corresponding label.
-type JumpDestination = Vec<([u8; 4], Label)>;
+/// The function selector value, corresponding label and the storage access of the function.
+type JumpDestination = Vec<([u8; 4], Label, Purity)>;
 /// A vector of opcodes representing the body of a contract ABI function.
 type AbiFunctionOpcodeBuffer = Vec<Op>;
 /// The function selector information and compiled body of a contract ABI function.
@@ -1336,7 +1427,7 @@ fn compile_contract_to_selectors(
             warnings,
             errors
         ));
-        selectors_labels_buf.push((selector, fn_label));
+        selectors_labels_buf.push((selector, fn_label, decl.purity));
     }
 
     ok((selectors_labels_buf, asm_buf), warnings, errors)
@@ -0,0 +1,580 @@
+//! Optimization passes over an [AbstractInstructionSet](super::AbstractInstructionSet). They run
+//! once function calls have been expanded and before registers are allocated, so they see the
+//! whole program and can still create virtual registers freely.
+//!
+//! Registers are not in SSA form, so the values of registers are only tracked within a straight
+//! line of ops: everything known is forgotten at every label. Ops which are folded no longer
+//! update `$of` and `$err`, which only matters to inline assembly which reads them after an
+//! arithmetic op whose operands were all known at compile time.
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use super::{
+    compiler_constants::{MAX_OPTIMIZATION_ROUNDS, TWELVE_BITS},
+    liveness, DataSection,
+};
+use crate::{
+    asm_lang::{
+        ConstantRegister, Op, OrganizationalOp, VirtualImmediate12, VirtualOp, VirtualRegister,
+    },
+    parse_tree::Literal,
+};
+use either::Either;
+
+/// An optimization which can be enabled in a [crate::BuildConfig].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptimizationPass {
+    /// Evaluates arithmetic and comparisons whose operands are known at compile time.
+    ConstantFolding,
+    /// Resolves conditional jumps whose operands are known at compile time and removes the ops
+    /// which can never be reached.
+    BranchFolding,
+    /// Rewrites arithmetic with a small known operand into its immediate form, and collapses
+    /// chains of moves.
+    Peephole,
+    /// Removes ops whose results are never read.
+    DeadCodeElimination,
+}
+
+impl OptimizationPass {
+    /// Every pass, in the order they are run by default.
+    pub fn all() -> Vec<OptimizationPass> {
+        vec![
+            OptimizationPass::ConstantFolding,
+            OptimizationPass::BranchFolding,
+            OptimizationPass::Peephole,
+            OptimizationPass::DeadCodeElimination,
+        ]
+    }
+
+    /// Runs this pass, returning the new ops and whether anything changed.
+    fn run(&self, ops: Vec<Op>, data_section: &mut DataSection) -> (Vec<Op>, bool) {
+        match self {
+            OptimizationPass::ConstantFolding => fold_constants(ops, data_section),
+            OptimizationPass::BranchFolding => fold_branches(ops, data_section),
+            OptimizationPass::Peephole => peephole(ops, data_section),
+            OptimizationPass::DeadCodeElimination => eliminate_dead_code(ops),
+        }
+    }
+}
+
+/// Runs `passes` over `ops` in order, over and over until none of them changes anything, since
+/// each may create opportunities for the others.
+pub(crate) fn run_passes(
+    mut ops: Vec<Op>,
+    data_section: &mut DataSection,
+    passes: &[OptimizationPass],
+) -> Vec<Op> {
+    for _ in 0..MAX_OPTIMIZATION_ROUNDS {
+        let mut changed = false;
+        for pass in passes {
+            let (new_ops, pass_changed) = pass.run(ops, data_section);
+            ops = new_ops;
+            changed |= pass_changed;
+        }
+        if !changed {
+            break;
+        }
+    }
+    ops
+}
+
+/// The values of the registers which are known at the current op.
+#[derive(Default)]
+struct KnownValues {
+    values: HashMap<VirtualRegister, u64>,
+}
+
+impl KnownValues {
+    fn get(&self, reg: &VirtualRegister) -> Option<u64> {
+        match reg {
+            VirtualRegister::Constant(ConstantRegister::Zero) => Some(0),
+            VirtualRegister::Constant(ConstantRegister::One) => Some(1),
+            VirtualRegister::Constant(_) => None,
+            VirtualRegister::Virtual(_) => self.values.get(reg).copied(),
+        }
+    }
+
+    /// Records the effect of `op`, once it has been inspected.
+    fn update(&mut self, op: &Op, data_section: &DataSection) {
+        match op.opcode {
+            Either::Left(ref op) => {
+                let value = match op {
+                    VirtualOp::LWDataId(_, data_id) => data_section
+                        .value_pairs
+                        .get(data_id.0 as usize)
+                        .and_then(word_value),
+                    op => self.evaluate(op),
+                };
+                for reg in op.def_registers() {
+                    match value {
+                        Some(value) => self.values.insert(reg.clone(), value),
+                        None => self.values.remove(reg),
+                    };
+                }
+            }
+            Either::Right(OrganizationalOp::Label(_)) => self.values.clear(),
+            Either::Right(_) => (),
+        }
+    }
+
+    /// Computes the result of `op` if all of its operands are known. Ops which would overflow or
+    /// divide by zero aren't evaluated, so that they still panic or set `$of` and `$err` at
+    /// runtime.
+    fn evaluate(&self, op: &VirtualOp) -> Option<u64> {
+        use VirtualOp::*;
+        let binary =
+            |r2: &VirtualRegister, r3: &VirtualRegister, f: fn(u64, u64) -> Option<u64>| {
+                f(self.get(r2)?, self.get(r3)?)
+            };
+        let immediate =
+            |r2: &VirtualRegister, imm: &VirtualImmediate12, f: fn(u64, u64) -> Option<u64>| {
+                f(self.get(r2)?, imm.value as u64)
+            };
+        match op {
+            MOVE(_, r2) => self.get(r2),
+            NOT(_, r2) => self.get(r2).map(|a| !a),
+            ADD(_, r2, r3) => binary(r2, r3, u64::checked_add),
+            SUB(_, r2, r3) => binary(r2, r3, u64::checked_sub),
+            MUL(_, r2, r3) => binary(r2, r3, u64::checked_mul),
+            DIV(_, r2, r3) => binary(r2, r3, u64::checked_div),
+            MOD(_, r2, r3) => binary(r2, r3, u64::checked_rem),
+            EXP(_, r2, r3) => binary(r2, r3, checked_exp),
+            AND(_, r2, r3) => binary(r2, r3, |a, b| Some(a & b)),
+            OR(_, r2, r3) => binary(r2, r3, |a, b| Some(a | b)),
+            XOR(_, r2, r3) => binary(r2, r3, |a, b| Some(a ^ b)),
+            SLL(_, r2, r3) => binary(r2, r3, checked_shl),
+            SRL(_, r2, r3) => binary(r2, r3, checked_shr),
+            EQ(_, r2, r3) => binary(r2, r3, |a, b| Some((a == b) as u64)),
+            LT(_, r2, r3) => binary(r2, r3, |a, b| Some((a < b) as u64)),
+            GT(_, r2, r3) => binary(r2, r3, |a, b| Some((a > b) as u64)),
+            ADDI(_, r2, imm) => immediate(r2, imm, u64::checked_add),
+            SUBI(_, r2, imm) => immediate(r2, imm, u64::checked_sub),
+            MULI(_, r2, imm) => immediate(r2, imm, u64::checked_mul),
+            DIVI(_, r2, imm) => immediate(r2, imm, u64::checked_div),
+            MODI(_, r2, imm) => immediate(r2, imm, u64::checked_rem),
+            EXPI(_, r2, imm) => immediate(r2, imm, checked_exp),
+            ANDI(_, r2, imm) => immediate(r2, imm, |a, b| Some(a & b)),
+            ORI(_, r2, imm) => immediate(r2, imm, |a, b| Some(a | b)),
+            XORI(_, r2, imm) => immediate(r2, imm, |a, b| Some(a ^ b)),
+            SLLI(_, r2, imm) => immediate(r2, imm, checked_shl),
+            SRLI(_, r2, imm) => immediate(r2, imm, checked_shr),
+            _ => None,
+        }
+    }
+}
+
+fn checked_exp(a: u64, b: u64) -> Option<u64> {
+    a.checked_pow(u32::try_from(b).ok()?)
+}
+
+fn checked_shl(a: u64, b: u64) -> Option<u64> {
+    if b < 64 {
+        Some(a << b)
+    } else {
+        None
+    }
+}
+
+fn checked_shr(a: u64, b: u64) -> Option<u64> {
+    if b < 64 {
+        Some(a >> b)
+    } else {
+        None
+    }
+}
+
+/// The value of a piece of data which is loaded directly into a register, rather than by
+/// address.
+fn word_value(data: &Literal) -> Option<u64> {
+    match data {
+        Literal::U8(n) | Literal::Byte(n) => Some(*n as u64),
+        Literal::U16(n) => Some(*n as u64),
+        Literal::U32(n) => Some(*n as u64),
+        Literal::U64(n) => Some(*n),
+        // signed integers are sign extended to a whole word
+        Literal::I8(n) => Some(*n as u64),
+        Literal::I16(n) => Some(*n as u64),
+        Literal::I32(n) => Some(*n as u64),
+        Literal::I64(n) => Some(*n as u64),
+        Literal::Boolean(b) => Some(*b as u64),
+        Literal::String(_) | Literal::B256(_) => None,
+    }
+}
+
+fn is_virtual(reg: &VirtualRegister) -> bool {
+    matches!(reg, VirtualRegister::Virtual(_))
+}
+
+/// Whether `op` already sets its register to a constant in the cheapest way possible.
+fn is_materialized_constant(op: &VirtualOp) -> bool {
+    matches!(
+        op,
+        VirtualOp::LWDataId(..)
+            | VirtualOp::MOVE(_, VirtualRegister::Constant(ConstantRegister::Zero))
+            | VirtualOp::MOVE(_, VirtualRegister::Constant(ConstantRegister::One))
+            | VirtualOp::ADDI(_, VirtualRegister::Constant(ConstantRegister::Zero), _)
+    )
+}
+
+/// Sets `reg` to `value`, loading it from the data section if it doesn't fit in an immediate.
+fn materialize(reg: VirtualRegister, value: u64, data_section: &mut DataSection) -> VirtualOp {
+    match value {
+        0 => VirtualOp::MOVE(reg, VirtualRegister::Constant(ConstantRegister::Zero)),
+        1 => VirtualOp::MOVE(reg, VirtualRegister::Constant(ConstantRegister::One)),
+        value if value <= TWELVE_BITS => VirtualOp::ADDI(
+            reg,
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            VirtualImmediate12::new_unchecked(value, "value was checked to fit in 12 bits"),
+        ),
+        value => VirtualOp::LWDataId(reg, data_section.insert_data_value(&Literal::U64(value))),
+    }
+}
+
+/// Replaces ops whose result is known at compile time with ops which set the result directly.
+fn fold_constants(ops: Vec<Op>, data_section: &mut DataSection) -> (Vec<Op>, bool) {
+    let mut changed = false;
+    let mut known = KnownValues::default();
+    let mut buf = Vec::with_capacity(ops.len());
+    for op in ops {
+        let folded = match op.opcode {
+            Either::Left(ref virtual_op) if !is_materialized_constant(virtual_op) => {
+                let defs = virtual_op.def_registers();
+                match (known.evaluate(virtual_op), defs.into_iter().next()) {
+                    // loading a large constant from the data section is no cheaper than a move
+                    (Some(value), _)
+                        if value > TWELVE_BITS && matches!(virtual_op, VirtualOp::MOVE(..)) =>
+                    {
+                        None
+                    }
+                    (Some(value), Some(reg)) if is_virtual(reg) => {
+                        Some(materialize(reg.clone(), value, data_section))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        known.update(&op, data_section);
+        match folded {
+            Some(opcode) => {
+                changed = true;
+                buf.push(Op {
+                    opcode: Either::Left(opcode),
+                    ..op
+                });
+            }
+            None => buf.push(op),
+        }
+    }
+    (buf, changed)
+}
+
+/// Whether control never falls through from `op` to the op after it.
+fn is_unconditional_jump(op: &Op) -> bool {
+    matches!(
+        op.opcode,
+        Either::Right(OrganizationalOp::Jump(_))
+            | Either::Left(VirtualOp::RET(_))
+            | Either::Left(VirtualOp::RETD(..))
+            | Either::Left(VirtualOp::RVRT(_))
+    )
+}
+
+/// Whether `op` can be removed when it can't be reached. The placeholders and padding of the
+/// program's preamble are never reached but must stay where they are.
+fn is_removable_when_unreachable(op: &Op) -> bool {
+    match op.opcode {
+        Either::Left(VirtualOp::NOOP)
+        | Either::Left(VirtualOp::DataSectionOffsetPlaceholder)
+        | Either::Left(VirtualOp::DataSectionRegisterLoadPlaceholder) => false,
+        Either::Left(_) => true,
+        Either::Right(OrganizationalOp::Jump(_))
+        | Either::Right(OrganizationalOp::JumpIfNotEq(..))
+        | Either::Right(OrganizationalOp::Comment) => true,
+        Either::Right(_) => false,
+    }
+}
+
+/// Turns conditional jumps whose outcome is known into unconditional jumps, or removes them, and
+/// then removes the ops between an unconditional jump and the next label.
+fn fold_branches(ops: Vec<Op>, data_section: &DataSection) -> (Vec<Op>, bool) {
+    let mut changed = false;
+    let mut known = KnownValues::default();
+    let mut buf: Vec<Op> = Vec::with_capacity(ops.len());
+    let mut reachable = true;
+    for op in ops {
+        if let Either::Right(OrganizationalOp::Label(_)) = op.opcode {
+            reachable = true;
+        }
+        if !reachable && is_removable_when_unreachable(&op) {
+            changed = true;
+            continue;
+        }
+        known.update(&op, data_section);
+        let op = match op.opcode {
+            Either::Right(OrganizationalOp::JumpIfNotEq(ref r0, ref r1, ref label)) => {
+                let equal = if r0 == r1 {
+                    Some(true)
+                } else {
+                    known.get(r0).zip(known.get(r1)).map(|(a, b)| a == b)
+                };
+                match equal {
+                    Some(true) => {
+                        changed = true;
+                        continue;
+                    }
+                    Some(false) => {
+                        changed = true;
+                        Op {
+                            opcode: Either::Right(OrganizationalOp::Jump(label.clone())),
+                            ..op
+                        }
+                    }
+                    None => op,
+                }
+            }
+            _ => op,
+        };
+        if is_unconditional_jump(&op) {
+            reachable = false;
+        }
+        buf.push(op);
+    }
+    (buf, changed)
+}
+
+/// Rewrites arithmetic with a small known operand into its immediate form, and points moves at
+/// the original register when they copy a copy.
+fn peephole(ops: Vec<Op>, data_section: &DataSection) -> (Vec<Op>, bool) {
+    let mut changed = false;
+    let mut known = KnownValues::default();
+    // the register each register is a copy of, for registers which were set by a move
+    let mut copies: HashMap<VirtualRegister, VirtualRegister> = HashMap::new();
+    let mut buf = Vec::with_capacity(ops.len());
+    for op in ops {
+        let rewritten = match op.opcode {
+            Either::Left(ref virtual_op) => match to_immediate_form(virtual_op, &known) {
+                Some(rewritten) => Some(rewritten),
+                None => match virtual_op {
+                    VirtualOp::MOVE(r1, r2) => copies
+                        .get(r2)
+                        .map(|original| VirtualOp::MOVE(r1.clone(), original.clone())),
+                    _ => None,
+                },
+            },
+            Either::Right(_) => None,
+        };
+        let op = match rewritten {
+            Some(opcode) => {
+                changed = true;
+                Op {
+                    opcode: Either::Left(opcode),
+                    ..op
+                }
+            }
+            None => op,
+        };
+        known.update(&op, data_section);
+
+        match op.opcode {
+            Either::Left(VirtualOp::MOVE(ref r1, ref r2)) if r1 == r2 => {
+                changed = true;
+                continue;
+            }
+            Either::Left(ref virtual_op) => {
+                for reg in virtual_op.def_registers() {
+                    copies.retain(|copy, original| copy != reg && original != reg);
+                }
+                if let VirtualOp::MOVE(r1, r2) = virtual_op {
+                    let is_stable = |reg: &VirtualRegister| {
+                        matches!(
+                            reg,
+                            VirtualRegister::Virtual(_)
+                                | VirtualRegister::Constant(ConstantRegister::Zero)
+                                | VirtualRegister::Constant(ConstantRegister::One)
+                        )
+                    };
+                    if is_virtual(r1) && is_stable(r2) {
+                        copies.insert(r1.clone(), r2.clone());
+                    }
+                }
+            }
+            Either::Right(OrganizationalOp::Label(_)) => copies.clear(),
+            Either::Right(_) => (),
+        }
+        buf.push(op);
+    }
+    (buf, changed)
+}
+
+/// The constructor of an op which takes a register and an immediate.
+type ImmediateOp = fn(VirtualRegister, VirtualRegister, VirtualImmediate12) -> VirtualOp;
+
+/// The immediate form of `op`, if one of its operands is known and small enough. Only
+/// commutative ops may have their left hand side moved into the immediate.
+fn to_immediate_form(op: &VirtualOp, known: &KnownValues) -> Option<VirtualOp> {
+    use VirtualOp::*;
+    let small = |reg: &VirtualRegister| match known.get(reg) {
+        Some(value) if value <= TWELVE_BITS => Some(VirtualImmediate12::new_unchecked(
+            value,
+            "value was checked to fit in 12 bits",
+        )),
+        _ => None,
+    };
+    let commutative = |make: ImmediateOp,
+                       r1: &VirtualRegister,
+                       r2: &VirtualRegister,
+                       r3: &VirtualRegister| match (small(r3), small(r2)) {
+        (Some(imm), _) => Some(make(r1.clone(), r2.clone(), imm)),
+        (None, Some(imm)) => Some(make(r1.clone(), r3.clone(), imm)),
+        (None, None) => None,
+    };
+    let rhs =
+        |make: ImmediateOp, r1: &VirtualRegister, r2: &VirtualRegister, r3: &VirtualRegister| {
+            small(r3).map(|imm| make(r1.clone(), r2.clone(), imm))
+        };
+    match op {
+        ADD(r1, r2, r3) => commutative(ADDI, r1, r2, r3),
+        MUL(r1, r2, r3) => commutative(MULI, r1, r2, r3),
+        AND(r1, r2, r3) => commutative(ANDI, r1, r2, r3),
+        OR(r1, r2, r3) => commutative(ORI, r1, r2, r3),
+        XOR(r1, r2, r3) => commutative(XORI, r1, r2, r3),
+        SUB(r1, r2, r3) => rhs(SUBI, r1, r2, r3),
+        DIV(r1, r2, r3) => rhs(DIVI, r1, r2, r3),
+        MOD(r1, r2, r3) => rhs(MODI, r1, r2, r3),
+        EXP(r1, r2, r3) => rhs(EXPI, r1, r2, r3),
+        SLL(r1, r2, r3) => rhs(SLLI, r1, r2, r3),
+        SRL(r1, r2, r3) => rhs(SRLI, r1, r2, r3),
+        _ => None,
+    }
+}
+
+/// Whether `op` does nothing but set its registers, so that it can be removed if they are never
+/// read. Division and exponentiation are not, as they may panic.
+fn is_pure(op: &VirtualOp) -> bool {
+    use VirtualOp::*;
+    matches!(
+        op,
+        ADD(..)
+            | ADDI(..)
+            | SUB(..)
+            | SUBI(..)
+            | MUL(..)
+            | MULI(..)
+            | AND(..)
+            | ANDI(..)
+            | OR(..)
+            | ORI(..)
+            | XOR(..)
+            | XORI(..)
+            | SLL(..)
+            | SLLI(..)
+            | SRL(..)
+            | SRLI(..)
+            | EQ(..)
+            | LT(..)
+            | GT(..)
+            | NOT(..)
+            | MOVE(..)
+            | LW(..)
+            | LWDataId(..)
+    )
+}
+
+/// Removes pure ops which only set virtual registers that are never read afterwards.
+fn eliminate_dead_code(ops: Vec<Op>) -> (Vec<Op>, bool) {
+    let live_out = liveness::live_out(&ops);
+    let mut changed = false;
+    let mut buf = Vec::with_capacity(ops.len());
+    for (op, live) in ops.into_iter().zip(live_out) {
+        if let Either::Left(ref virtual_op) = op.opcode {
+            let defs = virtual_op.def_registers();
+            if is_pure(virtual_op)
+                && !defs.is_empty()
+                && defs
+                    .into_iter()
+                    .all(|reg| is_virtual(reg) && !live.contains(reg))
+            {
+                changed = true;
+                continue;
+            }
+        }
+        buf.push(op);
+    }
+    (buf, changed)
+}
+
+#[cfg(test)]
+fn unowned_ops(opcodes: Vec<Either<VirtualOp, OrganizationalOp>>) -> Vec<Op> {
+    opcodes
+        .into_iter()
+        .map(|opcode| Op {
+            opcode,
+            comment: String::new(),
+            owning_span: None,
+        })
+        .collect()
+}
+
+#[test]
+fn folds_known_arithmetic_and_removes_what_it_made_dead() {
+    let reg = |name: &str| VirtualRegister::Virtual(name.into());
+    let mut data_section = DataSection::default();
+    let five = data_section.insert_data_value(&Literal::U64(5));
+    let seven = data_section.insert_data_value(&Literal::U64(7));
+    let ops = unowned_ops(vec![
+        Either::Left(VirtualOp::LWDataId(reg("a"), five)),
+        Either::Left(VirtualOp::LWDataId(reg("b"), seven)),
+        Either::Left(VirtualOp::ADD(reg("c"), reg("a"), reg("b"))),
+        Either::Left(VirtualOp::RET(reg("c"))),
+    ]);
+    let ops = run_passes(ops, &mut data_section, &OptimizationPass::all());
+    assert_eq!(ops.len(), 2);
+    assert!(matches!(
+        ops[0].opcode,
+        Either::Left(VirtualOp::ADDI(ref r1, VirtualRegister::Constant(ConstantRegister::Zero), ref imm))
+            if *r1 == reg("c") && imm.value == 12
+    ));
+}
+
+#[test]
+fn folds_branches_on_known_conditions() {
+    let reg = |name: &str| VirtualRegister::Virtual(name.into());
+    let zero = VirtualRegister::Constant(ConstantRegister::Zero);
+    let one = VirtualRegister::Constant(ConstantRegister::One);
+    let mut sequencer = super::RegisterSequencer::new();
+    let (taken, end) = (sequencer.get_label(), sequencer.get_label());
+    let ops = unowned_ops(vec![
+        Either::Right(OrganizationalOp::JumpIfNotEq(
+            zero.clone(),
+            one.clone(),
+            taken.clone(),
+        )),
+        Either::Left(VirtualOp::RET(zero.clone())),
+        Either::Right(OrganizationalOp::Label(taken)),
+        Either::Left(VirtualOp::EQ(reg("a"), one.clone(), one.clone())),
+        Either::Right(OrganizationalOp::JumpIfNotEq(
+            reg("a"),
+            one.clone(),
+            end.clone(),
+        )),
+        Either::Left(VirtualOp::RET(one)),
+        Either::Right(OrganizationalOp::Label(end)),
+        Either::Left(VirtualOp::RET(zero)),
+    ]);
+    let (ops, changed) = fold_branches(ops, &DataSection::default());
+    assert!(changed);
+    // the first branch is always taken, so the return after it can never be reached, and the
+    // second never is
+    assert!(matches!(
+        ops[0].opcode,
+        Either::Right(OrganizationalOp::Jump(_))
+    ));
+    assert!(matches!(
+        ops[1].opcode,
+        Either::Right(OrganizationalOp::Label(_))
+    ));
+    assert!(matches!(ops[2].opcode, Either::Left(VirtualOp::EQ(..))));
+    assert!(matches!(ops[3].opcode, Either::Left(VirtualOp::RET(_))));
+}
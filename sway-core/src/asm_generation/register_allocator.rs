@@ -0,0 +1,319 @@
+//! A graph coloring register allocator.
+//!
+//! Two virtual registers interfere if one of them is written to while the other is live. Every
+//! virtual register is a node in the interference graph, and registers which interfere are
+//! connected by an edge. Coloring the graph with one color per allocatable register gives an
+//! allocation. If the graph cannot be colored, some virtual registers are spilled: they are kept in
+//! stack slots instead, and are loaded into short lived temporaries around each op which accesses
+//! them. Allocation is then attempted again.
+//!
+//! The stack slots are reserved with CFEI at the start of the program, before anything else is
+//! put on the stack, so they are addressed relative to `$ssp`. Registers which are live across a
+//! function call are saved into the caller's frame (see the `functions` module), so spilled
+//! registers in recursive functions are preserved the same way that allocated ones are.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::{compiler_constants, liveness};
+use crate::asm_lang::{
+    allocated_ops::AllocatedRegister, ConstantRegister, Op, OrganizationalOp, VirtualImmediate12,
+    VirtualImmediate24, VirtualOp, VirtualRegister,
+};
+use either::Either;
+
+type InterferenceGraph = HashMap<VirtualRegister, HashSet<VirtualRegister>>;
+
+/// The register which has been allocated for every virtual register in a program.
+pub(crate) struct RegisterAllocation {
+    registers: HashMap<VirtualRegister, AllocatedRegister>,
+}
+
+impl RegisterAllocation {
+    pub(crate) fn get_register(&self, reg: &VirtualRegister) -> AllocatedRegister {
+        self.registers
+            .get(reg)
+            .cloned()
+            .expect("Every virtual register in the program has been allocated a register")
+    }
+}
+
+/// Allocates a register for every virtual register in `ops`, spilling virtual registers to the
+/// stack where needed. Returns `ops` with any code needed for spilling inserted, and the
+/// allocation.
+pub(crate) fn allocate_registers(mut ops: Vec<Op>) -> (Vec<Op>, RegisterAllocation) {
+    let available = allocatable_registers();
+    let mut spill_slots: HashMap<VirtualRegister, u64> = HashMap::new();
+    let mut spill_temporaries: HashSet<VirtualRegister> = HashSet::new();
+    loop {
+        let live_out = liveness::live_out(&ops);
+        let graph = build_interference_graph(&ops, &live_out);
+        match color_graph(&graph, available.len(), &spill_temporaries) {
+            Ok(colors) => {
+                if !spill_slots.is_empty() {
+                    reserve_spill_slots(&mut ops, spill_slots.len() as u64);
+                }
+                let registers = colors
+                    .into_iter()
+                    .map(|(reg, color)| (reg, available[color].clone()))
+                    .collect();
+                return (ops, RegisterAllocation { registers });
+            }
+            Err(spilled) => {
+                let mut newly_spilled = HashMap::new();
+                for reg in spilled {
+                    let slot = spill_slots.len() as u64;
+                    spill_slots.insert(reg.clone(), slot);
+                    newly_spilled.insert(reg, slot);
+                }
+                ops = insert_spill_code(ops, &newly_spilled, &mut spill_temporaries);
+            }
+        }
+    }
+}
+
+/// Every general purpose register other than those reserved by the compiler.
+fn allocatable_registers() -> Vec<AllocatedRegister> {
+    (0..compiler_constants::NUM_ALLOCATABLE_REGISTERS)
+        .map(AllocatedRegister::Allocated)
+        .filter(|reg| reg.to_register_id() != ConstantRegister::DataSectionStart.to_register_id())
+        .collect()
+}
+
+fn virtual_registers<'a>(
+    regs: impl IntoIterator<Item = &'a VirtualRegister>,
+) -> impl Iterator<Item = &'a VirtualRegister> {
+    regs.into_iter()
+        .filter(|reg| matches!(reg, VirtualRegister::Virtual(_)))
+}
+
+fn build_interference_graph(
+    ops: &[Op],
+    live_out: &[HashSet<VirtualRegister>],
+) -> InterferenceGraph {
+    let mut graph: InterferenceGraph = HashMap::new();
+    for (op, live) in ops.iter().zip(live_out.iter()) {
+        let registers = match op.opcode {
+            Either::Left(ref op) => op.registers(),
+            Either::Right(ref op) => op.registers(),
+        };
+        for reg in virtual_registers(registers) {
+            graph.entry(reg.clone()).or_default();
+        }
+
+        let (defs, move_source) = match op.opcode {
+            Either::Left(VirtualOp::MOVE(ref dest, ref source)) => (vec![dest], Some(source)),
+            Either::Left(ref op) => (op.def_registers().into_iter().collect(), None),
+            Either::Right(_) => (vec![], None),
+        };
+        for def in virtual_registers(defs) {
+            // the destination of a move does not interfere with its source, as they hold the same
+            // value
+            for other in live
+                .iter()
+                .filter(|other| *other != def && Some(*other) != move_source)
+            {
+                graph.entry(def.clone()).or_default().insert(other.clone());
+                graph.entry(other.clone()).or_default().insert(def.clone());
+            }
+        }
+    }
+    graph
+}
+
+/// Colors `graph` with `num_colors` colors. If that is not possible, returns the registers which
+/// need to be spilled. Registers in `unspillable` are only chosen for spilling as a last resort.
+fn color_graph(
+    graph: &InterferenceGraph,
+    num_colors: usize,
+    unspillable: &HashSet<VirtualRegister>,
+) -> Result<HashMap<VirtualRegister, usize>, Vec<VirtualRegister>> {
+    // sorted so that allocation is deterministic, and numbered in that order
+    let mut nodes = graph.keys().collect::<Vec<_>>();
+    nodes.sort_by_key(|reg| reg.to_string());
+    let index: HashMap<&VirtualRegister, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(ix, reg)| (*reg, ix))
+        .collect();
+
+    // Repeatedly remove a node with fewer neighbors than there are colors, as it can always be
+    // colored once its neighbors have been. If there is no such node, optimistically remove the
+    // one most likely to need spilling instead. The nodes are kept in two worklists, which are
+    // updated as the degrees of the neighbors of each removed node drop: those which can always be
+    // colored, by number, and the rest, by how likely they are to need spilling.
+    let mut degrees = nodes
+        .iter()
+        .map(|reg| graph[*reg].len())
+        .collect::<Vec<_>>();
+    let spill_priority = |ix: usize, degree: usize| (!unspillable.contains(nodes[ix]), degree, ix);
+    let mut low_degree = (0..nodes.len())
+        .filter(|ix| degrees[*ix] < num_colors)
+        .collect::<BTreeSet<_>>();
+    let mut high_degree = (0..nodes.len())
+        .filter(|ix| degrees[*ix] >= num_colors)
+        .map(|ix| spill_priority(ix, degrees[ix]))
+        .collect::<BTreeSet<_>>();
+    let mut removed = vec![false; nodes.len()];
+    let mut stack = Vec::with_capacity(nodes.len());
+    while stack.len() < nodes.len() {
+        let next = match low_degree.iter().next().cloned() {
+            Some(ix) => {
+                low_degree.remove(&ix);
+                ix
+            }
+            None => {
+                let priority = high_degree
+                    .iter()
+                    .next_back()
+                    .cloned()
+                    .expect("there are nodes remaining");
+                high_degree.remove(&priority);
+                priority.2
+            }
+        };
+        removed[next] = true;
+        stack.push(nodes[next]);
+        for neighbor in &graph[nodes[next]] {
+            let ix = index[neighbor];
+            if removed[ix] {
+                continue;
+            }
+            if degrees[ix] >= num_colors {
+                high_degree.remove(&spill_priority(ix, degrees[ix]));
+                degrees[ix] -= 1;
+                if degrees[ix] < num_colors {
+                    low_degree.insert(ix);
+                } else {
+                    high_degree.insert(spill_priority(ix, degrees[ix]));
+                }
+            } else {
+                degrees[ix] -= 1;
+            }
+        }
+    }
+
+    let mut colors: HashMap<VirtualRegister, usize> = HashMap::new();
+    let mut spilled = vec![];
+    while let Some(reg) = stack.pop() {
+        let neighbor_colors = graph[reg]
+            .iter()
+            .filter_map(|neighbor| colors.get(neighbor))
+            .collect::<HashSet<_>>();
+        match (0..num_colors).find(|color| !neighbor_colors.contains(color)) {
+            Some(color) => {
+                colors.insert(reg.clone(), color);
+            }
+            None => spilled.push(reg.clone()),
+        }
+    }
+
+    if spilled.is_empty() {
+        Ok(colors)
+    } else {
+        Err(spilled)
+    }
+}
+
+/// Rewrites every op which accesses a register in `spilled` to load it from, or store it to, its
+/// stack slot via a new temporary register.
+fn insert_spill_code(
+    ops: Vec<Op>,
+    spilled: &HashMap<VirtualRegister, u64>,
+    temporaries: &mut HashSet<VirtualRegister>,
+) -> Vec<Op> {
+    let mut buf = Vec::with_capacity(ops.len());
+    for op in ops {
+        let (mut uses, mut defs): (Vec<VirtualRegister>, Vec<VirtualRegister>) = match op.opcode {
+            Either::Left(ref op) => (
+                op.use_registers().into_iter().cloned().collect(),
+                op.def_registers().into_iter().cloned().collect(),
+            ),
+            Either::Right(ref op) => (op.registers().into_iter().cloned().collect(), vec![]),
+        };
+        // sorted so that temporaries are named deterministically
+        uses.sort_by_key(|reg| reg.to_string());
+        defs.sort_by_key(|reg| reg.to_string());
+        let mut mapping = HashMap::new();
+        for reg in uses.iter().chain(defs.iter()) {
+            if spilled.contains_key(reg) && !mapping.contains_key(reg) {
+                // the name of a temporary cannot clash with any other register, as it is not
+                // a valid identifier
+                let temporary = VirtualRegister::Virtual(format!("spill.{}", temporaries.len()));
+                temporaries.insert(temporary.clone());
+                mapping.insert(reg.clone(), temporary);
+            }
+        }
+        if mapping.is_empty() {
+            buf.push(op);
+            continue;
+        }
+
+        for reg in uses.iter().filter(|reg| spilled.contains_key(reg)) {
+            buf.push(Op::unowned_new_with_comment(
+                VirtualOp::LW(
+                    mapping[reg].clone(),
+                    VirtualRegister::Constant(ConstantRegister::StackStartPointer),
+                    spill_slot_offset(spilled[reg]),
+                ),
+                format!("reload spilled register {}", reg),
+            ));
+        }
+        let opcode = match op.opcode {
+            Either::Left(ref virtual_op) => Either::Left(virtual_op.update_register(&mapping)),
+            Either::Right(OrganizationalOp::JumpIfNotEq(ref r1, ref r2, ref label)) => {
+                Either::Right(OrganizationalOp::JumpIfNotEq(
+                    mapping.get(r1).unwrap_or(r1).clone(),
+                    mapping.get(r2).unwrap_or(r2).clone(),
+                    label.clone(),
+                ))
+            }
+            Either::Right(_) => unreachable!(
+                "function calls are expanded before registers are allocated, so no other \
+                 organizational op accesses registers"
+            ),
+        };
+        buf.push(Op { opcode, ..op });
+        for reg in defs.iter().filter(|reg| spilled.contains_key(reg)) {
+            buf.push(Op::unowned_new_with_comment(
+                VirtualOp::SW(
+                    VirtualRegister::Constant(ConstantRegister::StackStartPointer),
+                    mapping[reg].clone(),
+                    spill_slot_offset(spilled[reg]),
+                ),
+                format!("spill register {}", reg),
+            ));
+        }
+    }
+    buf
+}
+
+fn spill_slot_offset(slot: u64) -> VirtualImmediate12 {
+    VirtualImmediate12::new_unchecked(
+        slot,
+        "Programs which spill more than 2^12 registers are unsupported right now",
+    )
+}
+
+/// Reserves `num_slots` words at the bottom of the stack for spilled registers. This is done
+/// as part of the preamble, before anything else is put on the stack.
+fn reserve_spill_slots(ops: &mut Vec<Op>, num_slots: u64) {
+    let position = ops
+        .iter()
+        .position(|op| {
+            matches!(
+                op.opcode,
+                Either::Left(VirtualOp::DataSectionRegisterLoadPlaceholder)
+            )
+        })
+        .unwrap_or(0);
+    ops.insert(
+        position,
+        Op::unowned_new_with_comment(
+            VirtualOp::CFEI(VirtualImmediate24::new_unchecked(
+                num_slots * 8,
+                "Programs which spill more than 2^21 registers are unsupported right now",
+            )),
+            "reserve stack slots for spilled registers",
+        ),
+    );
+}
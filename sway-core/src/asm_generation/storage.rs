@@ -0,0 +1,601 @@
+//! Contract storage. Each field of a contract's `storage` declaration is kept in as many 32 byte
+//! slots as its type needs. The key of each slot is a hash of the field's name and the slot's
+//! number, so the keys are deterministic and don't depend on the order of the fields.
+//!
+//! The entries of a `StorageMap` are kept in slots of their own. The key of each of those is a
+//! hash of the key of the map's slot, the key of the entry and the slot's number.
+
+use super::{
+    compiler_constants::TWENTY_FOUR_BITS, convert_expression_to_asm, AsmNamespace,
+    RegisterSequencer,
+};
+use crate::{
+    asm_lang::{
+        ConstantRegister, Op, VirtualImmediate12, VirtualImmediate18, VirtualImmediate24,
+        VirtualOp, VirtualRegister,
+    },
+    error::*,
+    parse_tree::Literal,
+    semantic_analysis::{
+        ast_node::{
+            StorageMapMethod, TypedStorageDeclaration, TypedStorageField, TypedStorageReassignment,
+        },
+        TypedExpression,
+    },
+    span::Span,
+    type_engine::{resolve_type, TypeId, TypeInfo},
+    Ident,
+};
+use sha2::{Digest, Sha256};
+
+/// The slot which records whether the storage of a contract has been initialized. It can't clash
+/// with the slot of a field, as those always include the field's name and a slot number.
+const INITIALIZED_KEY: &str = "storage";
+
+/// How a value of some type is moved in and out of storage.
+enum StorageLayout {
+    /// The type holds no data, so it needs no slots at all.
+    Empty,
+    /// The type fits in a register, so it is held in the first word of a single slot.
+    Word,
+    /// The type is held in memory, so it is read and written in this many whole slots.
+    Slots(u64),
+    /// The type is a `StorageMap`, whose entries are all in slots of their own. The field itself
+    /// is never read or written, only the key of its slot is used.
+    Map,
+}
+
+fn storage_layout(r#type: TypeId, span: &Span) -> Result<StorageLayout, CompileError> {
+    let r#type = resolve_type(r#type, span)?;
+    Ok(match r#type {
+        _ if fits_in_register(&r#type) => StorageLayout::Word,
+        TypeInfo::StorageMap { .. } => StorageLayout::Map,
+        _ => match r#type.size_in_words(span)? {
+            0 => StorageLayout::Empty,
+            words => StorageLayout::Slots((words + 3) / 4),
+        },
+    })
+}
+
+/// The number of bytes of a value of a type with [StorageLayout::Slots] which are in its last
+/// slot, as the size of the value needn't be a multiple of the size of a slot.
+fn size_in_last_slot(r#type: TypeId, span: &Span) -> Result<u64, CompileError> {
+    let words_in_last_slot = (resolve_type(r#type, span)?.size_in_words(span)? + 3) % 4 + 1;
+    Ok(words_in_last_slot * 8)
+}
+
+fn fits_in_register(r#type: &TypeInfo) -> bool {
+    matches!(
+        r#type,
+        TypeInfo::UnsignedInteger(_)
+            | TypeInfo::SignedInteger(_)
+            | TypeInfo::Numeric
+            | TypeInfo::Boolean
+            | TypeInfo::Byte
+    )
+}
+
+/// Loads the key of a storage slot, which is a pointer to the hash of `name` in the data section,
+/// into a new register.
+fn load_key(
+    name: &str,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> (VirtualRegister, Op) {
+    let mut hasher = Sha256::new();
+    hasher.update(name);
+    let mut key = [0; 32];
+    key.copy_from_slice(&hasher.finalize());
+    let data_id = namespace.insert_data_value(&Literal::B256(key));
+    let key_register = register_sequencer.next();
+    let op =
+        Op::unowned_load_data_comment(key_register.clone(), data_id, format!("key of {}", name));
+    (key_register, op)
+}
+
+fn slot_name(field_name: &Ident, slot: u64) -> String {
+    format!("storage.{}.{}", field_name.as_str(), slot)
+}
+
+/// Reads the whole of `storage.field_name` into the `return_register`. Values which don't fit in
+/// a register are copied onto the stack, and a pointer to them is returned.
+pub(super) fn convert_storage_access_to_asm(
+    field_name: &Ident,
+    r#type: TypeId,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let warnings = vec![];
+    let mut errors = vec![];
+    let mut buf = vec![];
+    match check_std_result!(storage_layout(r#type, span), warnings, errors) {
+        StorageLayout::Empty => (),
+        StorageLayout::Word => {
+            let (key, load) = load_key(&slot_name(field_name, 0), namespace, register_sequencer);
+            buf.push(load);
+            buf.push(Op::new_with_comment(
+                VirtualOp::SRW(return_register.clone(), key),
+                span.clone(),
+                format!("read storage.{}", field_name.as_str()),
+            ));
+        }
+        StorageLayout::Slots(slots) => {
+            buf.append(&mut allocate_on_stack(return_register, slots * 32));
+            let pointer = register_sequencer.next();
+            buf.push(Op::unowned_register_move(
+                pointer.clone(),
+                return_register.clone(),
+            ));
+            for slot in 0..slots {
+                let (key, load) =
+                    load_key(&slot_name(field_name, slot), namespace, register_sequencer);
+                buf.push(load);
+                buf.push(Op::new_with_comment(
+                    VirtualOp::SRWQ(pointer.clone(), key),
+                    span.clone(),
+                    format!("read slot {} of storage.{}", slot, field_name.as_str()),
+                ));
+                buf.push(step_to_next_slot(&pointer));
+            }
+        }
+        StorageLayout::Map => {
+            let (key, load) = load_key(&slot_name(field_name, 0), namespace, register_sequencer);
+            buf.push(load);
+            buf.push(Op::register_move_comment(
+                return_register.clone(),
+                key,
+                span.clone(),
+                format!(
+                    "storage.{} is a map, so use the key of its slot",
+                    field_name.as_str()
+                ),
+            ));
+        }
+    }
+    ok(buf, warnings, errors)
+}
+
+pub(super) fn convert_storage_reassignment_to_asm(
+    reassignment: &TypedStorageReassignment,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let value = register_sequencer.next();
+    let mut buf = check!(
+        convert_expression_to_asm(&reassignment.rhs, namespace, &value, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    buf.append(&mut check!(
+        write_to_storage(
+            &reassignment.field_name,
+            reassignment.r#type,
+            &value,
+            &reassignment.rhs.span,
+            namespace,
+            register_sequencer
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    ));
+    ok(buf, warnings, errors)
+}
+
+/// Initializes each field of a contract's storage with its initial value the first time a
+/// function of the contract which accesses storage is called. A slot of its own records that this
+/// has been done, so functions which don't access storage skip the check altogether.
+pub(super) fn convert_storage_initialization_to_asm(
+    decl: &TypedStorageDeclaration,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let (flag_key, load_flag_key) = load_key(INITIALIZED_KEY, namespace, register_sequencer);
+    let flag = register_sequencer.next();
+    let end_label = register_sequencer.get_label();
+    let mut buf = vec![
+        load_flag_key,
+        Op::unowned_new_with_comment(
+            VirtualOp::SRW(flag.clone(), flag_key.clone()),
+            "read whether storage is initialized",
+        ),
+        Op::jump_if_not_equal(
+            flag,
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            end_label.clone(),
+        ),
+    ];
+    for TypedStorageField {
+        name,
+        r#type,
+        initializer,
+        span,
+        ..
+    } in &decl.fields
+    {
+        let value = register_sequencer.next();
+        buf.append(&mut check!(
+            convert_expression_to_asm(initializer, namespace, &value, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+        buf.append(&mut check!(
+            write_to_storage(name, *r#type, &value, span, namespace, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+    }
+    buf.push(Op::unowned_new_with_comment(
+        VirtualOp::SWW(flag_key, VirtualRegister::Constant(ConstantRegister::One)),
+        "mark storage as initialized",
+    ));
+    buf.push(Op::unowned_jump_label(end_label));
+    ok(buf, warnings, errors)
+}
+
+/// Writes the whole of `storage.field_name` from the `value` register, which holds either the
+/// value itself or a pointer to it, depending on its type.
+fn write_to_storage(
+    field_name: &Ident,
+    r#type: TypeId,
+    value: &VirtualRegister,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let warnings = vec![];
+    let mut errors = vec![];
+    let mut buf = vec![];
+    match check_std_result!(storage_layout(r#type, span), warnings, errors) {
+        // a map only has entries once they are inserted
+        StorageLayout::Empty | StorageLayout::Map => (),
+        StorageLayout::Word => {
+            let (key, load) = load_key(&slot_name(field_name, 0), namespace, register_sequencer);
+            buf.push(load);
+            buf.push(Op::new_with_comment(
+                VirtualOp::SWW(key, value.clone()),
+                span.clone(),
+                format!("write storage.{}", field_name.as_str()),
+            ));
+        }
+        StorageLayout::Slots(slots) => {
+            let size_in_last_slot =
+                check_std_result!(size_in_last_slot(r#type, span), warnings, errors);
+            let last_slot = LastSlot::allocate(size_in_last_slot, register_sequencer);
+            buf.append(&mut last_slot.allocation());
+            let pointer = register_sequencer.next();
+            buf.push(Op::unowned_register_move(pointer.clone(), value.clone()));
+            for slot in 0..slots {
+                let (key, load) =
+                    load_key(&slot_name(field_name, slot), namespace, register_sequencer);
+                buf.push(load);
+                let source = if slot + 1 == slots {
+                    buf.append(&mut last_slot.pad(&pointer, span));
+                    last_slot.source(&pointer)
+                } else {
+                    pointer.clone()
+                };
+                buf.push(Op::new_with_comment(
+                    VirtualOp::SWWQ(key, source),
+                    span.clone(),
+                    format!("write slot {} of storage.{}", slot, field_name.as_str()),
+                ));
+                buf.push(step_to_next_slot(&pointer));
+            }
+            buf.append(&mut last_slot.free());
+        }
+    }
+    ok(buf, warnings, errors)
+}
+
+/// A value whose size isn't a multiple of the size of a slot only partly fills its last slot, so
+/// writing that slot straight from the value would read past the end of it. Instead, the rest of
+/// the value is copied into a slot sized buffer on the stack, padded with zeroes, and the slot is
+/// written from there.
+struct LastSlot {
+    size: u64,
+    buffer: Option<VirtualRegister>,
+}
+
+impl LastSlot {
+    fn allocate(size: u64, register_sequencer: &mut RegisterSequencer) -> Self {
+        LastSlot {
+            size,
+            buffer: if size < 32 {
+                Some(register_sequencer.next())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Reserves the buffer on the stack, if one is needed.
+    fn allocation(&self) -> Vec<Op> {
+        match &self.buffer {
+            Some(buffer) => allocate_on_stack(buffer, 32),
+            None => vec![],
+        }
+    }
+
+    /// Copies the rest of the value, which `pointer` points at, into the buffer.
+    fn pad(&self, pointer: &VirtualRegister, span: &Span) -> Vec<Op> {
+        match &self.buffer {
+            Some(buffer) => vec![
+                Op::unowned_new_with_comment(
+                    VirtualOp::MCLI(
+                        buffer.clone(),
+                        VirtualImmediate18::new_unchecked(32, "32 fits in 18 bits"),
+                    ),
+                    "clear the padding of the last slot",
+                ),
+                Op::new_with_comment(
+                    VirtualOp::MCPI(
+                        buffer.clone(),
+                        pointer.clone(),
+                        VirtualImmediate12::new_unchecked(
+                            self.size,
+                            "less than 32 fits in 12 bits",
+                        ),
+                    ),
+                    span.clone(),
+                    "copy the rest of the value into the last slot",
+                ),
+            ],
+            None => vec![],
+        }
+    }
+
+    /// Where the last slot is written from.
+    fn source(&self, pointer: &VirtualRegister) -> VirtualRegister {
+        self.buffer.clone().unwrap_or_else(|| pointer.clone())
+    }
+
+    /// Frees the buffer again, which has to be on top of the stack by then.
+    fn free(&self) -> Vec<Op> {
+        match &self.buffer {
+            Some(_) => vec![Op::unowned_new_with_comment(
+                VirtualOp::CFSI(VirtualImmediate24::new_unchecked(32, "32 fits in 24 bits")),
+                "free the last slot",
+            )],
+            None => vec![],
+        }
+    }
+}
+
+fn step_to_next_slot(pointer: &VirtualRegister) -> Op {
+    Op::unowned_new_with_comment(
+        VirtualOp::ADDI(
+            pointer.clone(),
+            pointer.clone(),
+            VirtualImmediate12::new_unchecked(32, "32 fits in 12 bits"),
+        ),
+        "step to the next slot",
+    )
+}
+
+/// Points `register` at `size` bytes of newly reserved memory on the stack.
+fn allocate_on_stack(register: &VirtualRegister, mut size: u64) -> Vec<Op> {
+    let mut buf = vec![Op::unowned_register_move(
+        register.clone(),
+        VirtualRegister::Constant(ConstantRegister::StackPointer),
+    )];
+    while size != 0 {
+        let expansion_size = std::cmp::min(TWENTY_FOUR_BITS, size);
+        buf.push(Op::unowned_stack_allocate_memory(
+            VirtualImmediate24::new_unchecked(expansion_size, "guaranteed to be < than 2^24"),
+        ));
+        size -= expansion_size;
+    }
+    buf
+}
+
+/// Reads or writes the entry of a key in a `StorageMap`. The keys of the entry's slots are
+/// computed in scratch memory on the stack, which is freed again once the entry has been accessed.
+/// It holds the hash followed by its preimage:
+///
+/// ```ignore
+/// | hash (32 bytes) | key of the map's slot (32 bytes) | key of the entry | slot number (8 bytes) |
+/// ```
+pub(super) fn convert_storage_map_method_to_asm(
+    method: &StorageMapMethod,
+    return_type: TypeId,
+    span: &Span,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let (map, key, value) = match method {
+        StorageMapMethod::Get { map, key } => (map, key, None),
+        StorageMapMethod::Insert { map, key, value } => (map, key, Some(value)),
+    };
+    let mut buf = vec![];
+    let mut evaluate = |expr: &TypedExpression, buf: &mut Vec<Op>| {
+        let register = register_sequencer.next();
+        buf.append(&mut check!(
+            convert_expression_to_asm(expr, namespace, &register, register_sequencer),
+            vec![],
+            warnings,
+            errors
+        ));
+        register
+    };
+    let map_register = evaluate(map, &mut buf);
+    let key_register = evaluate(key, &mut buf);
+    let value_register = value.map(|value| evaluate(value, &mut buf));
+    let value_type = value.map(|value| value.return_type).unwrap_or(return_type);
+
+    let key_type = check_std_result!(resolve_type(key.return_type, span), warnings, errors);
+    let key_size = if fits_in_register(&key_type) {
+        8
+    } else {
+        check_std_result!(key_type.size_in_words(span), warnings, errors) * 8
+    };
+    let layout = check_std_result!(storage_layout(value_type, span), warnings, errors);
+    let slots = match layout {
+        StorageLayout::Empty => 0,
+        StorageLayout::Word => 1,
+        StorageLayout::Slots(slots) => slots,
+        StorageLayout::Map => {
+            errors.push(CompileError::Unimplemented(
+                "The values of a StorageMap can't be StorageMaps themselves yet.",
+                span.clone(),
+            ));
+            return err(warnings, errors);
+        }
+    };
+    let preimage_size = 32 + key_size + 8;
+    let imm12 = |value: u64| VirtualImmediate12::new(value, span.clone());
+    let key_size_imm = check_std_result!(imm12(key_size), warnings, errors);
+    let preimage_size_imm = check_std_result!(imm12(preimage_size), warnings, errors);
+    let slot_number_offset = check_std_result!(imm12(4 + key_size / 8), warnings, errors);
+
+    // values in memory are read into, or written from, consecutive slots. The value which is
+    // read, or the last slot of the value which is written, is reserved before the preimage, so
+    // that the preimage can be freed again afterwards.
+    let last_slot = match (&layout, &value_register) {
+        (StorageLayout::Slots(_), Some(_)) => LastSlot::allocate(
+            check_std_result!(size_in_last_slot(value_type, span), warnings, errors),
+            register_sequencer,
+        ),
+        _ => LastSlot::allocate(32, register_sequencer),
+    };
+    buf.append(&mut last_slot.allocation());
+    let pointer = register_sequencer.next();
+    if let StorageLayout::Slots(_) = layout {
+        match value_register {
+            Some(ref value_register) => buf.push(Op::unowned_register_move(
+                pointer.clone(),
+                value_register.clone(),
+            )),
+            None => {
+                buf.append(&mut allocate_on_stack(return_register, slots * 32));
+                buf.push(Op::unowned_register_move(
+                    pointer.clone(),
+                    return_register.clone(),
+                ));
+            }
+        }
+    }
+
+    let scratch_size = 32 + preimage_size;
+    let hash = register_sequencer.next();
+    buf.append(&mut allocate_on_stack(&hash, scratch_size));
+    let preimage = register_sequencer.next();
+    buf.push(Op::unowned_new_with_comment(
+        VirtualOp::ADDI(
+            preimage.clone(),
+            hash.clone(),
+            VirtualImmediate12::new_unchecked(32, "32 fits in 12 bits"),
+        ),
+        "the preimage follows the hash",
+    ));
+    buf.push(Op::new_with_comment(
+        VirtualOp::MCPI(
+            preimage.clone(),
+            map_register,
+            VirtualImmediate12::new_unchecked(32, "32 fits in 12 bits"),
+        ),
+        span.clone(),
+        "copy the key of the map's slot",
+    ));
+    if fits_in_register(&key_type) {
+        buf.push(Op::write_register_to_memory_comment(
+            preimage.clone(),
+            key_register,
+            VirtualImmediate12::new_unchecked(4, "4 fits in 12 bits"),
+            span.clone(),
+            "write the key of the entry",
+        ));
+    } else if key_size != 0 {
+        let entry_key = register_sequencer.next();
+        buf.push(Op::unowned_new_with_comment(
+            VirtualOp::ADDI(
+                entry_key.clone(),
+                preimage.clone(),
+                VirtualImmediate12::new_unchecked(32, "32 fits in 12 bits"),
+            ),
+            "the key of the entry follows the key of the map's slot",
+        ));
+        buf.push(Op::new_with_comment(
+            VirtualOp::MCPI(entry_key, key_register, key_size_imm),
+            span.clone(),
+            "copy the key of the entry",
+        ));
+    }
+    let preimage_size_register = register_sequencer.next();
+    buf.push(Op::unowned_new_with_comment(
+        VirtualOp::ADDI(
+            preimage_size_register.clone(),
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            preimage_size_imm,
+        ),
+        "size of the preimage",
+    ));
+    for slot in 0..slots {
+        let slot_number = register_sequencer.next();
+        buf.push(Op::unowned_new_with_comment(
+            VirtualOp::ADDI(
+                slot_number.clone(),
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                check_std_result!(imm12(slot), warnings, errors),
+            ),
+            format!("slot {} of the entry", slot),
+        ));
+        buf.push(Op::write_register_to_memory_comment(
+            preimage.clone(),
+            slot_number,
+            slot_number_offset.clone(),
+            span.clone(),
+            "write the number of the slot",
+        ));
+        buf.push(Op::new_with_comment(
+            VirtualOp::S256(
+                hash.clone(),
+                preimage.clone(),
+                preimage_size_register.clone(),
+            ),
+            span.clone(),
+            format!("hash the key of slot {} of the entry", slot),
+        ));
+        let op = match (&layout, &value_register) {
+            (StorageLayout::Word, None) => VirtualOp::SRW(return_register.clone(), hash.clone()),
+            (StorageLayout::Word, Some(value)) => VirtualOp::SWW(hash.clone(), value.clone()),
+            (_, None) => VirtualOp::SRWQ(pointer.clone(), hash.clone()),
+            (_, Some(_)) if slot + 1 == slots => {
+                buf.append(&mut last_slot.pad(&pointer, span));
+                VirtualOp::SWWQ(hash.clone(), last_slot.source(&pointer))
+            }
+            (_, Some(_)) => VirtualOp::SWWQ(hash.clone(), pointer.clone()),
+        };
+        buf.push(Op::new_with_comment(
+            op,
+            span.clone(),
+            format!("access slot {} of the entry", slot),
+        ));
+        if let StorageLayout::Slots(_) = layout {
+            buf.push(step_to_next_slot(&pointer));
+        }
+    }
+    // the hash and preimage are on top of the stack, so they can be freed straight away and
+    // accessing a map in a loop doesn't grow the stack
+    buf.push(Op::unowned_new_with_comment(
+        VirtualOp::CFSI(VirtualImmediate24::new_unchecked(
+            scratch_size,
+            "the preimage size fits in 12 bits, so this fits in 24",
+        )),
+        "free the hash and preimage",
+    ));
+    buf.append(&mut last_slot.free());
+    ok(buf, warnings, errors)
+}
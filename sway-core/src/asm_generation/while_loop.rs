@@ -1,6 +1,6 @@
 use super::*;
 use crate::asm_lang::{ConstantRegister, VirtualRegister};
-use crate::semantic_analysis::ast_node::TypedWhileLoop;
+use crate::semantic_analysis::ast_node::{TypedForLoop, TypedWhileLoop};
 pub(super) fn convert_while_loop_to_asm(
     r#loop: &TypedWhileLoop,
     namespace: &mut AsmNamespace,
@@ -53,13 +53,14 @@ pub(super) fn convert_while_loop_to_asm(
 
     // the implicit return value of a while loop block, if any, should be ignored,
     // so we pass None into the final argument of code block conversion
-    // step 3: run the loop body
-    let mut body = check!(
-        convert_code_block_to_asm(&r#loop.body, namespace, register_sequencer, None),
-        vec![],
-        warnings,
-        errors
-    );
+    // step 3: run the loop body, in which `continue` jumps to step 0 and `break` to step 5
+    namespace.loops.push(LoopLabels {
+        condition: label.clone(),
+        exit: exit_label.clone(),
+    });
+    let body = convert_code_block_to_asm(&r#loop.body, namespace, register_sequencer, None);
+    namespace.loops.pop();
+    let mut body = check!(body, vec![], warnings, errors);
     buf.append(&mut body);
 
     // step 4: jump back to beginning to re-evaluate the condition
@@ -74,3 +75,34 @@ pub(super) fn convert_while_loop_to_asm(
 
     ok(buf, warnings, errors)
 }
+
+/// A for loop is its setup, which only declares variables, followed by a while loop.
+pub(super) fn convert_for_loop_to_asm(
+    r#loop: &TypedForLoop,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut buf: Vec<Op> = vec![];
+    for node in &r#loop.setup {
+        match check!(
+            convert_node_to_asm(node, namespace, register_sequencer, None),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ) {
+            NodeAsmResult::JustAsm(mut asm) | NodeAsmResult::ReturnStatement { mut asm } => {
+                buf.append(&mut asm)
+            }
+        }
+    }
+    let mut asm = check!(
+        convert_while_loop_to_asm(&r#loop.r#loop, namespace, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    buf.append(&mut asm);
+    ok(buf, warnings, errors)
+}
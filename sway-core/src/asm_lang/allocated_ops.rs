@@ -39,7 +39,7 @@ impl fmt::Display for AllocatedRegister {
 }
 
 impl AllocatedRegister {
-    fn to_register_id(&self) -> fuel_asm::RegisterId {
+    pub(crate) fn to_register_id(&self) -> fuel_asm::RegisterId {
         match self {
             AllocatedRegister::Allocated(a) => (a + 16) as fuel_asm::RegisterId,
             AllocatedRegister::Constant(constant) => constant.to_register_id(),
@@ -249,6 +249,33 @@ impl Op {
         }
     }
 
+    /// Calls the function described by `function` with the values in `arguments`, putting its
+    /// return value into `return_register`.
+    pub(crate) fn call_function(
+        function: FunctionRegisters,
+        arguments: Vec<VirtualRegister>,
+        return_register: VirtualRegister,
+    ) -> Self {
+        Op {
+            opcode: Either::Right(OrganizationalOp::Call {
+                function: Box::new(function),
+                arguments,
+                return_register,
+            }),
+            comment: String::new(),
+            owning_span: None,
+        }
+    }
+
+    /// Returns from the function described by `function` to its caller.
+    pub(crate) fn function_return(function: FunctionRegisters) -> Self {
+        Op {
+            opcode: Either::Right(OrganizationalOp::FunctionReturn(Box::new(function))),
+            comment: String::new(),
+            owning_span: None,
+        }
+    }
+
     pub(crate) fn parse_opcode(
         name: &Ident,
         args: &[VirtualRegister],
@@ -1362,6 +1389,12 @@ impl fmt::Display for Op {
                 OrganizationalOp::DataSectionOffsetPlaceholder => {
                     "data section offset placeholder".into()
                 }
+                Call {
+                    function,
+                    arguments,
+                    return_register,
+                } => format_call(function, arguments, return_register),
+                FunctionReturn(function) => format!("return from {}", function.label),
             },
         };
         // we want the comment to always be 40 characters offset to the right
@@ -1392,6 +1425,31 @@ pub(crate) enum OrganizationalOp {
     JumpIfNotEq(VirtualRegister, VirtualRegister, Label),
     // placeholder for the DataSection offset
     DataSectionOffsetPlaceholder,
+    // Calls a function which was compiled out of line, putting its return value in
+    // `return_register`. This is expanded into real ops once it is known which registers are live
+    // across the call.
+    Call {
+        function: Box<FunctionRegisters>,
+        arguments: Vec<VirtualRegister>,
+        return_register: VirtualRegister,
+    },
+    // Returns from a function which was compiled out of line to the site it was called from
+    FunctionReturn(Box<FunctionRegisters>),
+}
+
+/// The registers a function which is compiled out of line uses to communicate with its callers.
+#[derive(Clone)]
+pub(crate) struct FunctionRegisters {
+    /// The label at the start of the function's body.
+    pub(crate) label: Label,
+    /// The function's name, for readability of the ASM.
+    pub(crate) name: String,
+    pub(crate) parameters: Vec<VirtualRegister>,
+    pub(crate) return_value: VirtualRegister,
+    /// Holds the address of the stack frame the caller set up for this call. The first word of
+    /// the frame identifies the call site to return to, which makes this the return address
+    /// register.
+    pub(crate) frame: VirtualRegister,
 }
 impl fmt::Display for OrganizationalOp {
     fn fmt(&self, fmtr: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -1406,6 +1464,12 @@ impl fmt::Display for OrganizationalOp {
                 JumpIfNotEq(r1, r2, lab) => format!("jnei {} {} {}", r1, r2, lab),
                 DataSectionOffsetPlaceholder =>
                     "DATA SECTION OFFSET[0..32]\nDATA SECTION OFFSET[32..64]".into(),
+                Call {
+                    function,
+                    arguments,
+                    return_register,
+                } => format_call(function, arguments, return_register),
+                FunctionReturn(function) => format!("return from {}", function.label),
             }
         )
     }
@@ -1417,8 +1481,31 @@ impl OrganizationalOp {
         (match self {
             Label(_) | Comment | Jump(_) | DataSectionOffsetPlaceholder => vec![],
             JumpIfNotEq(r1, r2, _) => vec![r1, r2],
+            Call {
+                arguments,
+                return_register,
+                ..
+            } => arguments.iter().chain(Some(return_register)).collect(),
+            FunctionReturn(function) => vec![&function.return_value, &function.frame],
         })
         .into_iter()
         .collect()
     }
 }
+
+fn format_call(
+    function: &FunctionRegisters,
+    arguments: &[VirtualRegister],
+    return_register: &VirtualRegister,
+) -> String {
+    format!(
+        "call {} ({}) -> {}",
+        function.label,
+        arguments
+            .iter()
+            .map(|x| format!("{}", x))
+            .collect::<Vec<_>>()
+            .join(", "),
+        return_register
+    )
+}
@@ -8,9 +8,9 @@ use super::{
     allocated_ops::{AllocatedOpcode, AllocatedRegister},
     virtual_immediate::*,
     virtual_register::*,
-    DataId, RealizedOp,
+    DataId,
 };
-use crate::asm_generation::RegisterPool;
+use crate::asm_generation::RegisterAllocation;
 
 use std::collections::{HashMap, HashSet};
 
@@ -223,37 +223,370 @@ impl VirtualOp {
         .collect()
     }
 
-    pub(crate) fn allocate_registers(
+    /// Returns the registers which this op writes to. Registers which only hold an address
+    /// that is written through are not included here; they are read by the op.
+    pub(crate) fn def_registers(&self) -> HashSet<&VirtualRegister> {
+        use VirtualOp::*;
+        (match self {
+            ADD(r1, _r2, _r3) => vec![r1],
+            ADDI(r1, _r2, _i) => vec![r1],
+            AND(r1, _r2, _r3) => vec![r1],
+            ANDI(r1, _r2, _i) => vec![r1],
+            DIV(r1, _r2, _r3) => vec![r1],
+            DIVI(r1, _r2, _i) => vec![r1],
+            EQ(r1, _r2, _r3) => vec![r1],
+            EXP(r1, _r2, _r3) => vec![r1],
+            EXPI(r1, _r2, _i) => vec![r1],
+            GT(r1, _r2, _r3) => vec![r1],
+            LT(r1, _r2, _r3) => vec![r1],
+            MLOG(r1, _r2, _r3) => vec![r1],
+            MROO(r1, _r2, _r3) => vec![r1],
+            MOD(r1, _r2, _r3) => vec![r1],
+            MODI(r1, _r2, _i) => vec![r1],
+            MOVE(r1, _r2) => vec![r1],
+            MUL(r1, _r2, _r3) => vec![r1],
+            MULI(r1, _r2, _i) => vec![r1],
+            NOT(r1, _r2) => vec![r1],
+            OR(r1, _r2, _r3) => vec![r1],
+            ORI(r1, _r2, _i) => vec![r1],
+            SLL(r1, _r2, _r3) => vec![r1],
+            SLLI(r1, _r2, _i) => vec![r1],
+            SRL(r1, _r2, _r3) => vec![r1],
+            SRLI(r1, _r2, _i) => vec![r1],
+            SUB(r1, _r2, _r3) => vec![r1],
+            SUBI(r1, _r2, _i) => vec![r1],
+            XOR(r1, _r2, _r3) => vec![r1],
+            XORI(r1, _r2, _i) => vec![r1],
+            CIMV(r1, _r2, _r3) => vec![r1],
+            CTMV(r1, _r2) => vec![r1],
+            LB(r1, _r2, _i) => vec![r1],
+            LWDataId(r1, _i) => vec![r1],
+            LW(r1, _r2, _i) => vec![r1],
+            MEQ(r1, _r2, _r3, _r4) => vec![r1],
+            BAL(r1, _r2, _r3) => vec![r1],
+            BHEI(r1) => vec![r1],
+            CSIZ(r1, _r2) => vec![r1],
+            SRW(r1, _r2) => vec![r1],
+            XOS(r1, _r2) => vec![r1],
+            GM(r1, _imm) => vec![r1],
+            JI(_)
+            | JNEI(..)
+            | RET(_)
+            | RETD(..)
+            | CFEI(_)
+            | CFSI(_)
+            | ALOC(_)
+            | MCL(..)
+            | MCLI(..)
+            | MCP(..)
+            | MCPI(..)
+            | SB(..)
+            | SW(..)
+            | BHSH(..)
+            | BURN(_)
+            | CALL(..)
+            | CCP(..)
+            | CROO(..)
+            | CB(_)
+            | LDC(..)
+            | LOG(..)
+            | MINT(_)
+            | RVRT(_)
+            | SLDC(..)
+            | SRWQ(..)
+            | SWW(..)
+            | SWWQ(..)
+            | TR(..)
+            | TRO(..)
+            | ECR(..)
+            | K256(..)
+            | S256(..)
+            | NOOP
+            | FLAG(_)
+            | Undefined
+            | DataSectionOffsetPlaceholder
+            | DataSectionRegisterLoadPlaceholder => vec![],
+        })
+        .into_iter()
+        .collect()
+    }
+
+    /// Returns the registers which this op reads from.
+    pub(crate) fn use_registers(&self) -> HashSet<&VirtualRegister> {
+        use VirtualOp::*;
+        (match self {
+            ADD(_r1, r2, r3) => vec![r2, r3],
+            ADDI(_r1, r2, _i) => vec![r2],
+            AND(_r1, r2, r3) => vec![r2, r3],
+            ANDI(_r1, r2, _i) => vec![r2],
+            DIV(_r1, r2, r3) => vec![r2, r3],
+            DIVI(_r1, r2, _i) => vec![r2],
+            EQ(_r1, r2, r3) => vec![r2, r3],
+            EXP(_r1, r2, r3) => vec![r2, r3],
+            EXPI(_r1, r2, _i) => vec![r2],
+            GT(_r1, r2, r3) => vec![r2, r3],
+            LT(_r1, r2, r3) => vec![r2, r3],
+            MLOG(_r1, r2, r3) => vec![r2, r3],
+            MROO(_r1, r2, r3) => vec![r2, r3],
+            MOD(_r1, r2, r3) => vec![r2, r3],
+            MODI(_r1, r2, _i) => vec![r2],
+            MOVE(_r1, r2) => vec![r2],
+            MUL(_r1, r2, r3) => vec![r2, r3],
+            MULI(_r1, r2, _i) => vec![r2],
+            NOT(_r1, r2) => vec![r2],
+            OR(_r1, r2, r3) => vec![r2, r3],
+            ORI(_r1, r2, _i) => vec![r2],
+            SLL(_r1, r2, r3) => vec![r2, r3],
+            SLLI(_r1, r2, _i) => vec![r2],
+            SRL(_r1, r2, r3) => vec![r2, r3],
+            SRLI(_r1, r2, _i) => vec![r2],
+            SUB(_r1, r2, r3) => vec![r2, r3],
+            SUBI(_r1, r2, _i) => vec![r2],
+            XOR(_r1, r2, r3) => vec![r2, r3],
+            XORI(_r1, r2, _i) => vec![r2],
+            CIMV(_r1, r2, r3) => vec![r2, r3],
+            CTMV(_r1, r2) => vec![r2],
+            LB(_r1, r2, _i) => vec![r2],
+            LWDataId(_r1, _i) => vec![],
+            LW(_r1, r2, _i) => vec![r2],
+            MEQ(_r1, r2, r3, r4) => vec![r2, r3, r4],
+            BAL(_r1, r2, r3) => vec![r2, r3],
+            BHEI(_r1) => vec![],
+            CSIZ(_r1, r2) => vec![r2],
+            SRW(_r1, r2) => vec![r2],
+            XOS(_r1, r2) => vec![r2],
+            GM(_r1, _imm) => vec![],
+            // every other op only reads its registers
+            _ => return self.registers(),
+        })
+        .into_iter()
+        .collect()
+    }
+
+    /// Returns a copy of this op in which every register that is a key of `mapping` is replaced
+    /// by its value.
+    pub(crate) fn update_register(
         &self,
-        pool: &mut RegisterPool,
-        op_register_mapping: &[(RealizedOp, HashSet<VirtualRegister>)],
-        ix: usize,
-    ) -> AllocatedOpcode {
-        let virtual_registers = self.registers();
-        let register_allocation_result = virtual_registers
-            .clone()
+        mapping: &HashMap<VirtualRegister, VirtualRegister>,
+    ) -> VirtualOp {
+        use VirtualOp::*;
+        match self {
+            ADD(r1, r2, r3) => ADD(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            ADDI(r1, r2, i) => ADDI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            AND(r1, r2, r3) => AND(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            ANDI(r1, r2, i) => ANDI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            DIV(r1, r2, r3) => DIV(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            DIVI(r1, r2, i) => DIVI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            EQ(r1, r2, r3) => EQ(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            EXP(r1, r2, r3) => EXP(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            EXPI(r1, r2, i) => EXPI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            GT(r1, r2, r3) => GT(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            LT(r1, r2, r3) => LT(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            MLOG(r1, r2, r3) => MLOG(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            MROO(r1, r2, r3) => MROO(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            MOD(r1, r2, r3) => MOD(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            MODI(r1, r2, i) => MODI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            MOVE(r1, r2) => MOVE(update_reg(mapping, r1), update_reg(mapping, r2)),
+            MUL(r1, r2, r3) => MUL(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            MULI(r1, r2, i) => MULI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            NOT(r1, r2) => NOT(update_reg(mapping, r1), update_reg(mapping, r2)),
+            OR(r1, r2, r3) => OR(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            ORI(r1, r2, i) => ORI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            SLL(r1, r2, r3) => SLL(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            SLLI(r1, r2, i) => SLLI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            SRL(r1, r2, r3) => SRL(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            SRLI(r1, r2, i) => SRLI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            SUB(r1, r2, r3) => SUB(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            SUBI(r1, r2, i) => SUBI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            XOR(r1, r2, r3) => XOR(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            XORI(r1, r2, i) => XORI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            CIMV(r1, r2, r3) => CIMV(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            CTMV(r1, r2) => CTMV(update_reg(mapping, r1), update_reg(mapping, r2)),
+            JI(i) => JI(i.clone()),
+            JNEI(r1, r2, i) => JNEI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            RET(r1) => RET(update_reg(mapping, r1)),
+            RETD(r1, r2) => RETD(update_reg(mapping, r1), update_reg(mapping, r2)),
+            CFEI(i) => CFEI(i.clone()),
+            CFSI(i) => CFSI(i.clone()),
+            LB(r1, r2, i) => LB(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            LWDataId(r1, i) => LWDataId(update_reg(mapping, r1), i.clone()),
+            LW(r1, r2, i) => LW(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            ALOC(r1) => ALOC(update_reg(mapping, r1)),
+            MCL(r1, r2) => MCL(update_reg(mapping, r1), update_reg(mapping, r2)),
+            MCLI(r1, i) => MCLI(update_reg(mapping, r1), i.clone()),
+            MCP(r1, r2, r3) => MCP(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            MEQ(r1, r2, r3, r4) => MEQ(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+                update_reg(mapping, r4),
+            ),
+            MCPI(r1, r2, i) => MCPI(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            SB(r1, r2, i) => SB(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            SW(r1, r2, i) => SW(update_reg(mapping, r1), update_reg(mapping, r2), i.clone()),
+            BAL(r1, r2, r3) => BAL(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            BHSH(r1, r2) => BHSH(update_reg(mapping, r1), update_reg(mapping, r2)),
+            BHEI(r1) => BHEI(update_reg(mapping, r1)),
+            BURN(r1) => BURN(update_reg(mapping, r1)),
+            CALL(r1, r2, r3, r4) => CALL(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+                update_reg(mapping, r4),
+            ),
+            CCP(r1, r2, r3, r4) => CCP(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+                update_reg(mapping, r4),
+            ),
+            CROO(r1, r2) => CROO(update_reg(mapping, r1), update_reg(mapping, r2)),
+            CSIZ(r1, r2) => CSIZ(update_reg(mapping, r1), update_reg(mapping, r2)),
+            CB(r1) => CB(update_reg(mapping, r1)),
+            LDC(r1, r2, r3) => LDC(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            LOG(r1, r2, r3, r4) => LOG(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+                update_reg(mapping, r4),
+            ),
+            MINT(r1) => MINT(update_reg(mapping, r1)),
+            RVRT(r1) => RVRT(update_reg(mapping, r1)),
+            SLDC(r1, r2, r3) => SLDC(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            SRW(r1, r2) => SRW(update_reg(mapping, r1), update_reg(mapping, r2)),
+            SRWQ(r1, r2) => SRWQ(update_reg(mapping, r1), update_reg(mapping, r2)),
+            SWW(r1, r2) => SWW(update_reg(mapping, r1), update_reg(mapping, r2)),
+            SWWQ(r1, r2) => SWWQ(update_reg(mapping, r1), update_reg(mapping, r2)),
+            TR(r1, r2, r3) => TR(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            TRO(r1, r2, r3, r4) => TRO(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+                update_reg(mapping, r4),
+            ),
+            ECR(r1, r2, r3) => ECR(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            K256(r1, r2, r3) => K256(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            S256(r1, r2, r3) => S256(
+                update_reg(mapping, r1),
+                update_reg(mapping, r2),
+                update_reg(mapping, r3),
+            ),
+            XOS(r1, r2) => XOS(update_reg(mapping, r1), update_reg(mapping, r2)),
+            NOOP => NOOP,
+            FLAG(r1) => FLAG(update_reg(mapping, r1)),
+            GM(r1, i) => GM(update_reg(mapping, r1), i.clone()),
+            Undefined => Undefined,
+            DataSectionOffsetPlaceholder => DataSectionOffsetPlaceholder,
+            DataSectionRegisterLoadPlaceholder => DataSectionRegisterLoadPlaceholder,
+        }
+    }
+
+    pub(crate) fn allocate_registers(&self, allocation: &RegisterAllocation) -> AllocatedOpcode {
+        // Maps virtual registers to their allocated equivalent
+        let mapping = self
+            .registers()
             .into_iter()
             .map(|x| match x {
-                VirtualRegister::Constant(c) => (x, Some(AllocatedRegister::Constant(c.clone()))),
-                VirtualRegister::Virtual(_) => {
-                    (x, pool.get_register(x, &op_register_mapping[ix..]))
-                }
+                VirtualRegister::Constant(c) => (x, AllocatedRegister::Constant(c.clone())),
+                VirtualRegister::Virtual(_) => (x, allocation.get_register(x)),
             })
-            .map(|(x, register_opt)| register_opt.map(|register| (x, register)))
-            .collect::<Option<Vec<_>>>();
-
-        // Maps virtual registers to their allocated equivalent
-        let mut mapping = HashMap::default();
-        match register_allocation_result {
-            Some(o) => {
-                for (key, val) in o {
-                    mapping.insert(key, val);
-                }
-            }
-            None => {
-                unimplemented!("The allocator cannot resolve a register mapping for this program. This is a temporary artifact of the extremely early stage version of this language. Try to lower the number of variables you use.")
-            }
-        };
+            .collect::<HashMap<_, _>>();
 
         use VirtualOp::*;
         match self {
@@ -562,6 +895,14 @@ impl VirtualOp {
     }
 }
 
+/// Looks up the replacement for `reg` in `mapping`, keeping `reg` if it has none.
+fn update_reg(
+    mapping: &HashMap<VirtualRegister, VirtualRegister>,
+    reg: &VirtualRegister,
+) -> VirtualRegister {
+    mapping.get(reg).unwrap_or(reg).clone()
+}
+
 /// An unchecked function which serves as a convenience for looking up register mappings
 fn map_reg(
     mapping: &HashMap<&VirtualRegister, AllocatedRegister>,
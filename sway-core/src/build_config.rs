@@ -1,3 +1,4 @@
+use crate::OptimizationPass;
 use std::{path::PathBuf, sync::Arc};
 
 /// Configuration for the overall build and compilation process.
@@ -8,6 +9,9 @@ pub struct BuildConfig {
     pub(crate) manifest_path: Arc<PathBuf>,
     pub(crate) print_intermediate_asm: bool,
     pub(crate) print_finalized_asm: bool,
+    pub(crate) use_ir: bool,
+    pub(crate) print_ir: bool,
+    pub(crate) optimization_passes: Vec<OptimizationPass>,
 }
 
 impl BuildConfig {
@@ -25,6 +29,9 @@ impl BuildConfig {
             manifest_path: Arc::new(canonicalized_manifest_path),
             print_intermediate_asm: false,
             print_finalized_asm: false,
+            use_ir: false,
+            print_ir: false,
+            optimization_passes: OptimizationPass::all(),
         }
     }
 
@@ -42,6 +49,26 @@ impl BuildConfig {
         }
     }
 
+    /// Generate the ASM via the IR rather than directly from the typed AST.
+    pub fn use_ir(self, a: bool) -> Self {
+        Self { use_ir: a, ..self }
+    }
+
+    pub fn print_ir(self, a: bool) -> Self {
+        Self {
+            print_ir: a,
+            ..self
+        }
+    }
+
+    /// The passes to optimize the ASM with. All of them are run by default.
+    pub fn optimization_passes(self, passes: Vec<OptimizationPass>) -> Self {
+        Self {
+            optimization_passes: passes,
+            ..self
+        }
+    }
+
     pub fn path(&self) -> Arc<PathBuf> {
         self.file_name.clone()
     }
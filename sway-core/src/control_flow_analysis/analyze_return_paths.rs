@@ -7,7 +7,7 @@ use crate::ident::Ident;
 use crate::parse_tree::CallPath;
 use crate::semantic_analysis::{
     ast_node::{
-        TypedCodeBlock, TypedDeclaration, TypedExpression, TypedFunctionDeclaration,
+        TypedCodeBlock, TypedDeclaration, TypedExpression, TypedForLoop, TypedFunctionDeclaration,
         TypedReassignment, TypedWhileLoop,
     },
     TypedAstNode, TypedAstNodeContent,
@@ -141,16 +141,25 @@ fn connect_node(
             }
             NodeConnection::Return(this_index)
         }
-        TypedAstNodeContent::WhileLoop(TypedWhileLoop { .. }) => {
+        TypedAstNodeContent::WhileLoop(TypedWhileLoop { .. })
+        | TypedAstNodeContent::ForLoop(TypedForLoop { .. }) => {
             // An abridged version of the dead code analysis for a while loop
             // since we don't really care about what the loop body contains when detecting
-            // divergent paths
+            // divergent paths. A for loop's body may never run either, so it is the same.
             let node = graph.add_node(node.into());
             for leaf in leaves {
                 graph.add_edge(*leaf, node, "while loop entry".into());
             }
             NodeConnection::NextStep(vec![node])
         }
+        // an expression which reverts ends the path, just as a return does
+        TypedAstNodeContent::Expression(exp) if exp.diverges() => {
+            let this_index = graph.add_node(node.into());
+            for leaf_ix in leaves {
+                graph.add_edge(*leaf_ix, this_index, "".into());
+            }
+            NodeConnection::Return(this_index)
+        }
         TypedAstNodeContent::Expression(TypedExpression { .. }) => {
             let entry = graph.add_node(node.into());
             // insert organizational dominator node
@@ -160,6 +169,14 @@ fn connect_node(
             }
             NodeConnection::NextStep(vec![entry])
         }
+        TypedAstNodeContent::Break | TypedAstNodeContent::Continue => {
+            let this_index = graph.add_node(node.into());
+            for leaf in leaves {
+                graph.add_edge(*leaf, this_index, "".into());
+            }
+            // nothing after a break or continue can be reached, so there is nothing to step to
+            NodeConnection::NextStep(vec![])
+        }
         TypedAstNodeContent::SideEffect => NodeConnection::NextStep(leaves.to_vec()),
         TypedAstNodeContent::Declaration(decl) => {
             NodeConnection::NextStep(connect_declaration(node, decl, graph, span, leaves))
@@ -178,8 +195,10 @@ fn connect_declaration(
     match decl {
         TraitDeclaration(_)
         | AbiDeclaration(_)
+        | StorageDeclaration(_)
         | StructDeclaration(_)
         | EnumDeclaration(_)
+        | TypeAliasDeclaration(_)
         | GenericTypeForFunctionScope { .. } => leaves.to_vec(),
         VariableDeclaration(_) | ConstantDeclaration(_) => {
             let entry_node = graph.add_node(node.into());
@@ -196,7 +215,7 @@ fn connect_declaration(
             connect_typed_fn_decl(fn_decl, graph, entry_node, span);
             leaves.to_vec()
         }
-        Reassignment(TypedReassignment { .. }) => {
+        Reassignment(TypedReassignment { .. }) | StorageReassignment(_) => {
             let entry_node = graph.add_node(node.into());
             for leaf in leaves {
                 graph.add_edge(*leaf, entry_node, "".into());
@@ -5,8 +5,9 @@ use crate::{
     semantic_analysis::{
         ast_node::{
             TypedAbiDeclaration, TypedCodeBlock, TypedConstantDeclaration, TypedDeclaration,
-            TypedEnumDeclaration, TypedExpression, TypedExpressionVariant,
+            TypedEnumDeclaration, TypedExpression, TypedExpressionVariant, TypedForLoop,
             TypedFunctionDeclaration, TypedReassignment, TypedReturnStatement,
+            TypedStorageDeclaration, TypedStorageField, TypedStorageReassignment,
             TypedStructDeclaration, TypedStructExpressionField, TypedTraitDeclaration,
             TypedVariableDeclaration, TypedWhileLoop,
         },
@@ -109,11 +110,11 @@ impl ControlFlowGraph {
         }
 
         // calculate the entry points based on the tree type
-        graph.entry_points = match tree_type {
-            TreeType::Predicate | TreeType::Script => {
-                // a predicate or script have a main function as the only entry point
-                vec![
-                    graph
+        graph.entry_points =
+            match tree_type {
+                TreeType::Predicate | TreeType::Script => {
+                    // a predicate or script have a main function as the only entry point
+                    vec![graph
                         .graph
                         .node_indices()
                         .find(|i| match graph.graph[*i] {
@@ -129,53 +130,68 @@ impl ControlFlowGraph {
                             }) => name.as_str() == "main",
                             _ => false,
                         })
-                        .unwrap(),
-                ]
-            }
-            TreeType::Contract | TreeType::Library { .. } => graph
-                .graph
-                .node_indices()
-                .filter(|i| match graph.graph[*i] {
-                    ControlFlowGraphNode::OrganizationalDominator(_) => false,
-                    ControlFlowGraphNode::ProgramNode(TypedAstNode {
-                        content:
-                            TypedAstNodeContent::Declaration(TypedDeclaration::FunctionDeclaration(
-                                TypedFunctionDeclaration {
-                                    visibility: Visibility::Public,
-                                    ..
-                                },
-                            )),
-                        ..
-                    }) => true,
-                    ControlFlowGraphNode::ProgramNode(TypedAstNode {
-                        content:
-                            TypedAstNodeContent::Declaration(TypedDeclaration::TraitDeclaration(
-                                TypedTraitDeclaration {
-                                    visibility: Visibility::Public,
-                                    ..
-                                },
-                            )),
-                        ..
-                    }) => true,
-                    ControlFlowGraphNode::ProgramNode(TypedAstNode {
-                        content:
-                            TypedAstNodeContent::Declaration(TypedDeclaration::StructDeclaration(
-                                TypedStructDeclaration {
-                                    visibility: Visibility::Public,
-                                    ..
-                                },
-                            )),
-                        ..
-                    }) => true,
-                    ControlFlowGraphNode::ProgramNode(TypedAstNode {
-                        content:
-                            TypedAstNodeContent::Declaration(TypedDeclaration::ImplTrait { .. }),
-                        ..
-                    }) => true,
-                    _ => false,
-                })
-                .collect(),
-        };
+                        .unwrap()]
+                }
+                TreeType::Contract | TreeType::Library { .. } => {
+                    graph
+                        .graph
+                        .node_indices()
+                        .filter(|i| match graph.graph[*i] {
+                            ControlFlowGraphNode::OrganizationalDominator(_) => false,
+                            ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                                content:
+                                    TypedAstNodeContent::Declaration(
+                                        TypedDeclaration::FunctionDeclaration(
+                                            TypedFunctionDeclaration {
+                                                visibility: Visibility::Public,
+                                                ..
+                                            },
+                                        ),
+                                    ),
+                                ..
+                            }) => true,
+                            ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                                content:
+                                    TypedAstNodeContent::Declaration(
+                                        TypedDeclaration::TraitDeclaration(TypedTraitDeclaration {
+                                            visibility: Visibility::Public,
+                                            ..
+                                        }),
+                                    ),
+                                ..
+                            }) => true,
+                            ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                                content:
+                                    TypedAstNodeContent::Declaration(
+                                        TypedDeclaration::StructDeclaration(
+                                            TypedStructDeclaration {
+                                                visibility: Visibility::Public,
+                                                ..
+                                            },
+                                        ),
+                                    ),
+                                ..
+                            }) => true,
+                            ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                                content:
+                                    TypedAstNodeContent::Declaration(TypedDeclaration::ImplTrait {
+                                        ..
+                                    }),
+                                ..
+                            }) => true,
+                            // storage is initialized whenever a contract is first called
+                            ControlFlowGraphNode::ProgramNode(TypedAstNode {
+                                content:
+                                    TypedAstNodeContent::Declaration(
+                                        TypedDeclaration::StorageDeclaration(_),
+                                    ),
+                                ..
+                            }) => true,
+                            _ => false,
+                        })
+                        .collect()
+                }
+            };
         Ok(())
     }
 }
@@ -242,36 +258,19 @@ fn connect_node(
             (return_contents, None)
         }
         TypedAstNodeContent::WhileLoop(TypedWhileLoop { body, .. }) => {
-            // a while loop can loop back to the beginning,
-            // or it can terminate.
-            // so we connect the _end_ of the while loop _both_ to its beginning and the next node.
-            // the loop could also be entirely skipped
-
-            let entry = graph.add_node(node.into());
-            let while_loop_exit = graph.add_node("while loop exit".to_string().into());
-            for leaf in leaves {
-                graph.add_edge(*leaf, entry, "".into());
-            }
-            // it is possible for a whole while loop to be skipped so add edge from
-            // beginning of while loop straight to exit
-            graph.add_edge(
-                entry,
-                while_loop_exit,
-                "condition is initially false".into(),
-            );
-            let mut leaves = vec![entry];
-            let (l_leaves, _l_exit_node) =
-                depth_first_insertion_code_block(body, graph, &leaves, exit_node, tree_type)?;
-            // insert edges from end of block back to beginning of it
-            for leaf in &l_leaves {
-                graph.add_edge(*leaf, entry, "loop repeats".into());
-            }
-
-            leaves = l_leaves;
-            for leaf in leaves {
-                graph.add_edge(leaf, while_loop_exit, "".into());
+            connect_while_loop(node, body, graph, leaves, exit_node, tree_type)?
+        }
+        TypedAstNodeContent::ForLoop(TypedForLoop { setup, r#loop }) => {
+            // the setup runs once, before the loop
+            let mut leaves = leaves.to_vec();
+            let mut exit_node = exit_node;
+            for setup_node in setup {
+                let (l_leaves, l_exit_node) =
+                    connect_node(setup_node, graph, &leaves, exit_node, tree_type)?;
+                leaves = l_leaves;
+                exit_node = l_exit_node;
             }
-            (vec![while_loop_exit], exit_node)
+            connect_while_loop(node, &r#loop.body, graph, &leaves, exit_node, tree_type)?
         }
         TypedAstNodeContent::Expression(TypedExpression {
             expression: expr_variant,
@@ -298,6 +297,14 @@ fn connect_node(
                 exit_node,
             )
         }
+        TypedAstNodeContent::Break | TypedAstNodeContent::Continue => {
+            let this_index = graph.add_node(node.into());
+            for leaf in leaves {
+                graph.add_edge(*leaf, this_index, "".into());
+            }
+            // the loop carries on from its condition or exit, so nothing after this is reached
+            (vec![], exit_node)
+        }
         TypedAstNodeContent::SideEffect => (leaves.to_vec(), exit_node),
         TypedAstNodeContent::Declaration(decl) => {
             // all leaves connect to this node, then this node is the singular leaf
@@ -366,6 +373,29 @@ fn connect_declaration(
             tree_type,
             rhs.clone().span,
         ),
+        StorageDeclaration(TypedStorageDeclaration { fields, .. }) => {
+            for TypedStorageField { initializer, .. } in fields {
+                connect_expression(
+                    &initializer.expression,
+                    graph,
+                    &[entry_node],
+                    exit_node,
+                    "storage field initializer",
+                    tree_type,
+                    initializer.span.clone(),
+                )?;
+            }
+            Ok(leaves.to_vec())
+        }
+        StorageReassignment(TypedStorageReassignment { rhs, .. }) => connect_expression(
+            &rhs.expression,
+            graph,
+            &[entry_node],
+            exit_node,
+            "storage reassignment",
+            tree_type,
+            rhs.clone().span,
+        ),
         ImplTrait {
             trait_name,
             methods,
@@ -374,7 +404,9 @@ fn connect_declaration(
             connect_impl_trait(trait_name, graph, methods, entry_node, tree_type)?;
             Ok(leaves.to_vec())
         }
-        ErrorRecovery | GenericTypeForFunctionScope { .. } => Ok(leaves.to_vec()),
+        TypeAliasDeclaration(_) | ErrorRecovery | GenericTypeForFunctionScope { .. } => {
+            Ok(leaves.to_vec())
+        }
     }
 }
 
@@ -573,6 +605,47 @@ fn connect_typed_fn_decl(
     Ok(())
 }
 
+/// Connects a loop, whose end leads both back to its beginning and on to whatever follows it.
+fn connect_while_loop(
+    node: &TypedAstNode,
+    body: &TypedCodeBlock,
+    graph: &mut ControlFlowGraph,
+    leaves: &[NodeIndex],
+    exit_node: Option<NodeIndex>,
+    tree_type: &TreeType,
+) -> Result<(Vec<NodeIndex>, Option<NodeIndex>), CompileError> {
+    // a while loop can loop back to the beginning,
+    // or it can terminate.
+    // so we connect the _end_ of the while loop _both_ to its beginning and the next node.
+    // the loop could also be entirely skipped
+
+    let entry = graph.add_node(node.into());
+    let while_loop_exit = graph.add_node("while loop exit".to_string().into());
+    for leaf in leaves {
+        graph.add_edge(*leaf, entry, "".into());
+    }
+    // it is possible for a whole while loop to be skipped so add edge from
+    // beginning of while loop straight to exit
+    graph.add_edge(
+        entry,
+        while_loop_exit,
+        "condition is initially false".into(),
+    );
+    let mut leaves = vec![entry];
+    let (l_leaves, _l_exit_node) =
+        depth_first_insertion_code_block(body, graph, &leaves, exit_node, tree_type)?;
+    // insert edges from end of block back to beginning of it
+    for leaf in &l_leaves {
+        graph.add_edge(*leaf, entry, "loop repeats".into());
+    }
+
+    leaves = l_leaves;
+    for leaf in leaves {
+        graph.add_edge(leaf, while_loop_exit, "".into());
+    }
+    Ok((vec![while_loop_exit], exit_node))
+}
+
 fn depth_first_insertion_code_block(
     node_content: &TypedCodeBlock,
     graph: &mut ControlFlowGraph,
@@ -881,6 +954,14 @@ fn connect_expression(
             }
             Ok(vec![exit])
         }
+        // storage fields aren't declarations which can be dead, as they outlive the contract call
+        StorageAccess { .. } => Ok(leaves.to_vec()),
+        // these are only ever the bodies of the methods of `StorageMap`, which aren't analyzed
+        StorageMapMethod(_) => Ok(leaves.to_vec()),
+        // and these are only ever the bodies of the operator methods of signed integers
+        SignedIntegerOp(_) => Ok(leaves.to_vec()),
+        // the code of a revert is a constant which has already been evaluated
+        Revert { .. } => Ok(leaves.to_vec()),
         AbiCast { address, .. } => connect_expression(
             &address.expression,
             graph,
@@ -928,7 +1009,7 @@ fn connect_expression(
             )?;
             Ok([prefix_idx, index_idx].concat())
         }
-        EnumArgAccess { prefix, .. } => {
+        EnumArgAccess { prefix, .. } | EnumTag { exp: prefix } => {
             let prefix_idx = connect_expression(
                 &prefix.expression,
                 graph,
@@ -1044,6 +1125,12 @@ fn construct_dead_code_warning_from_node(node: &TypedAstNode) -> Option<CompileW
             content: TypedAstNodeContent::Declaration(TypedDeclaration::AbiDeclaration { .. }),
             ..
         } => return None,
+        // the uses of a type alias are replaced by the type it is an alias for, so they aren't
+        // connected to the alias
+        TypedAstNode {
+            content: TypedAstNodeContent::Declaration(TypedDeclaration::TypeAliasDeclaration(..)),
+            ..
+        } => return None,
         TypedAstNode {
             content: TypedAstNodeContent::Declaration(..),
             span,
@@ -1,6 +1,7 @@
 //! Tools related to handling/recovering from Sway compile errors and reporting them to the user.
 
 use crate::ident::Ident;
+use crate::parse_tree::Purity;
 use crate::parser::Rule;
 use crate::span::Span;
 use crate::style::{to_screaming_snake_case, to_snake_case, to_upper_camel_case};
@@ -230,6 +231,9 @@ pub enum Warning {
     NonClassCaseEnumVariantName {
         variant_name: Ident,
     },
+    NonClassCaseTypeAliasName {
+        name: Ident,
+    },
     NonSnakeCaseStructFieldName {
         field_name: Ident,
     },
@@ -260,6 +264,7 @@ pub enum Warning {
     DeadStructDeclaration,
     DeadTrait,
     UnreachableCode,
+    UnreachableMatchArm,
     DeadEnumVariant {
         variant_name: String,
     },
@@ -268,6 +273,26 @@ pub enum Warning {
     ShadowingReservedRegister {
         reg_name: Ident,
     },
+    UnknownAttribute {
+        attribute: Ident,
+    },
+    MisplacedAttribute {
+        attribute: Ident,
+        target: &'static str,
+    },
+    UnneededStorageAccess {
+        fn_name: Ident,
+        declared: Purity,
+        inferred: Purity,
+    },
+    DeprecatedImpurityKeyword {
+        attribute: String,
+    },
+    IgnoredSignatureImpurity,
+    UndeclaredAsmStorageAccess {
+        access: &'static str,
+        attribute: String,
+    },
 }
 
 impl fmt::Display for Warning {
@@ -298,6 +323,13 @@ impl fmt::Display for Warning {
                 enum_name,
                 to_upper_camel_case(enum_name.as_str())
             ),
+            NonClassCaseTypeAliasName { name } => write!(
+                f,
+                "Type alias name \"{}\" is not idiomatic. Type aliases should have a ClassCase \
+                 name, like \"{}\".",
+                name,
+                to_upper_camel_case(name.as_str())
+            ),
             NonSnakeCaseStructFieldName { field_name } => write!(
                 f,
                 "Struct field name \"{}\" is not idiomatic. Struct field names should have a \
@@ -362,6 +394,10 @@ impl fmt::Display for Warning {
             DeadStructDeclaration => write!(f, "This struct is never instantiated."),
             DeadFunctionDeclaration => write!(f, "This function is never called."),
             UnreachableCode => write!(f, "This code is unreachable."),
+            UnreachableMatchArm => write!(
+                f,
+                "This match arm is unreachable, as the arms before it match everything it does."
+            ),
             DeadEnumVariant { variant_name } => {
                 write!(f, "Enum variant {} is never constructed.", variant_name)
             }
@@ -373,6 +409,57 @@ impl fmt::Display for Warning {
                 "This register declaration shadows the reserved register, \"{}\".",
                 reg_name
             ),
+            UnknownAttribute { attribute } => {
+                write!(f, "Unknown attribute \"{}\". It will be ignored.", attribute)
+            }
+            MisplacedAttribute { attribute, target } => write!(
+                f,
+                "The attribute \"{}\" does not apply to {}. It will be ignored.",
+                attribute, target
+            ),
+            UnneededStorageAccess {
+                fn_name,
+                declared,
+                inferred: Purity::Pure,
+            } => write!(
+                f,
+                "Function \"{}\" is declared with \"{}\", but never accesses storage. Consider \
+                 removing the attribute.",
+                fn_name,
+                declared.to_attribute_syntax()
+            ),
+            UnneededStorageAccess {
+                fn_name,
+                declared,
+                inferred,
+            } => write!(
+                f,
+                "Function \"{}\" is declared with \"{}\", but storage is only {} by it. \
+                 Consider declaring it with \"{}\" instead.",
+                fn_name,
+                declared.to_attribute_syntax(),
+                inferred.access_description(),
+                inferred.to_attribute_syntax()
+            ),
+            DeprecatedImpurityKeyword { attribute } => write!(
+                f,
+                "The \"impure\" keyword is deprecated. Declare the function with \"{}\", or with \
+                 only the storage access it needs, instead.",
+                attribute
+            ),
+            IgnoredSignatureImpurity => write!(
+                f,
+                "The \"impure\" keyword has no effect on a trait or abi method signature, as the \
+                 storage access of the method is declared by its implementation. It will be \
+                 ignored."
+            ),
+            UndeclaredAsmStorageAccess { access, attribute } => write!(
+                f,
+                "Storage is {} by this asm instruction, but the surrounding function isn't \
+                 declared to access storage. This will become an error in a future release. Try \
+                 declaring the function with \"{}\".",
+                access, attribute
+            ),
         }
     }
 }
@@ -423,6 +510,11 @@ pub enum CompileError {
          code that triggered this error."
     )]
     Internal(&'static str, Span),
+    #[error(
+        "Internal compiler error: {0}\nPlease file an issue on the repository and include the \
+         code that triggered this error."
+    )]
+    InternalOwned(String, Span),
     #[error("Unimplemented feature: {0:?}")]
     UnimplementedRule(Rule, Span),
     #[error(
@@ -469,6 +561,26 @@ pub enum CompileError {
         type_name: Ident,
         span: Span,
     },
+    #[error(
+        "Type \"{ty}\" does not implement trait \"{trait_name}\", which is required by the bound \
+         on type parameter \"{type_parameter}\". Consider adding `impl {trait_name} for {ty}`."
+    )]
+    TraitConstraintNotSatisfied {
+        ty: String,
+        trait_name: Ident,
+        type_parameter: Ident,
+        span: Span,
+    },
+    #[error(
+        "Operator \"{operator}\" cannot be applied to type \"{ty}\", which does not implement \
+         trait \"{trait_name}\". Consider adding `impl {trait_name} for {ty}`."
+    )]
+    OperatorNotImplemented {
+        operator: &'static str,
+        trait_name: &'static str,
+        ty: String,
+        span: Span,
+    },
     #[error(
         "Predicate definition contains multiple main functions. Multiple functions in the same \
          scope cannot have the same name."
@@ -539,6 +651,53 @@ pub enum CompileError {
         missing_functions: String,
         span: Span,
     },
+    #[error("\"{supertrait}\" is not {expected}, so \"{name}\" cannot extend it.")]
+    InvalidSupertrait {
+        supertrait: Ident,
+        name: Ident,
+        expected: &'static str,
+        span: Span,
+    },
+    #[error(
+        "\"{trait_name}\" extends \"{supertrait}\", which is not implemented for type \"{ty}\". \
+         Consider adding `impl {supertrait} for {ty}`."
+    )]
+    SupertraitNotImplemented {
+        supertrait: Ident,
+        trait_name: Ident,
+        ty: String,
+        span: Span,
+    },
+    #[error(
+        "\"{name}\" is not an associated constant or type of trait \"{trait_name}\", so it \
+         cannot be defined in its implementation."
+    )]
+    NotAnAssociatedItemOfTrait {
+        name: Ident,
+        trait_name: Ident,
+        span: Span,
+    },
+    #[error(
+        "These associated constants and types of trait \"{trait_name}\" are missing from this \
+         implementation: {missing_items}"
+    )]
+    MissingAssociatedItems {
+        trait_name: Ident,
+        missing_items: String,
+        span: Span,
+    },
+    #[error(
+        "\"{name}\" is ambiguous, as the implementations of more than one trait for type \"{ty}\" \
+         define it: {traits}"
+    )]
+    AmbiguousAssociatedItem {
+        name: String,
+        ty: String,
+        traits: String,
+        span: Span,
+    },
+    #[error("\"{trait_name}\" cannot be derived. Only \"Eq\" and \"Ord\" can be derived.")]
+    UnknownDerive { trait_name: Ident, span: Span },
     #[error("Expected {expected} type arguments, but instead found {given}.")]
     IncorrectNumberOfTypeArguments {
         given: usize,
@@ -791,10 +950,9 @@ pub enum CompileError {
         should_be: String,
         provided: String,
     },
-    #[error("Function {fn_name} is recursive, which is unsupported at this time.")]
-    RecursiveCall { fn_name: Ident, span: Span },
     #[error(
-        "Function {fn_name} is recursive via {call_chain}, which is unsupported at this time."
+        "Function {fn_name} is recursive via {call_chain}, which is unsupported for generic \
+         functions at this time."
     )]
     RecursiveCallChain {
         fn_name: Ident,
@@ -825,9 +983,21 @@ pub enum CompileError {
          "
     )]
     MatchWrongType { expected: TypeId, span: Span },
-    #[error("Impure function called inside of pure function. Pure functions can only call other pure functions. Try making the surrounding function impure by prepending \"impure\" to the function declaration.")]
-    PureCalledImpure { span: Span },
-    #[error("Impure function inside of non-contract. Contract storage is only accessible from contracts.")]
+    #[error("This pattern destructures a \"{struct_name}\", but the value being destructured is of type \"{actually}\".")]
+    DestructuredWrongType {
+        struct_name: String,
+        actually: String,
+        span: Span,
+    },
+    #[error("Storage is {access} here, but the surrounding function isn't declared to access it this way. Try declaring the function with \"{attribute}\".")]
+    StorageAccessNotDeclared {
+        access: &'static str,
+        attribute: String,
+        span: Span,
+    },
+    #[error("Unknown storage access \"{access}\". A function can be declared to \"read\" storage, \"write\" storage, or both.")]
+    UnknownStorageAccess { access: String, span: Span },
+    #[error("Function which accesses storage inside of a script or predicate. Contract storage is only accessible from contracts and the libraries they use.")]
     ImpureInNonContract { span: Span },
     #[error("Literal value is too large for type {ty}.")]
     IntegerTooLarge { span: Span, ty: String },
@@ -835,6 +1005,50 @@ pub enum CompileError {
     IntegerTooSmall { span: Span, ty: String },
     #[error("Literal value contains digits which are not valid for type {ty}.")]
     IntegerContainsInvalidDigit { span: Span, ty: String },
+    #[error(
+        "{r#type} cannot be iterated over. A for loop can only iterate over a static array or a \
+         range of integers."
+    )]
+    NotIterable { r#type: String, span: Span },
+    #[error("\"break\" can only be used inside of a loop.")]
+    BreakOutsideLoop { span: Span },
+    #[error("\"continue\" can only be used inside of a loop.")]
+    ContinueOutsideLoop { span: Span },
+    #[error("Contract storage can only be declared once.")]
+    MultipleStorageDeclarations { span: Span },
+    #[error("Storage declaration inside of non-contract. Only contracts have storage.")]
+    StorageDeclarationInNonContract { span: Span },
+    #[error("Storage is accessed here, but this contract does not declare any storage.")]
+    NoDeclaredStorage { span: Span },
+    #[error("Storage field \"{field_name}\" does not exist.")]
+    StorageFieldDoesNotExist { field_name: String, span: Span },
+    #[error("\"StorageMap\" takes two type arguments, the types of its keys and values, but {received} were provided.")]
+    StorageMapTypeArguments { received: usize, span: Span },
+    #[error("Storage field \"{field_name}\" is a \"StorageMap\", which can't be reassigned. Try using its \"insert\" method instead.")]
+    StorageMapReassignment { field_name: String, span: Span },
+    #[error("A \"StorageMap\" can only be used through the field of storage which holds it.")]
+    StorageMapOutsideOfStorage { span: Span },
+    #[error("Non-exhaustive match expression. Patterns not covered: {missing_patterns}.")]
+    MatchNonExhaustive {
+        missing_patterns: String,
+        span: Span,
+    },
+    #[error("Variable \"{var_name}\" is not bound in every alternative of this pattern.")]
+    MatchVariableNotBoundInAllPatterns { var_name: String, span: Span },
+    #[error("This expression must be a constant, but it can't be evaluated at compile time.")]
+    NotAConstant { span: Span },
+    #[error("The length of an array must be a constant \"u64\", but \"{name}\" isn't one.")]
+    ArrayLengthNotConstant { name: String, span: Span },
+    #[error("This constant overflows type {ty}.")]
+    ConstantOverflow { ty: String, span: Span },
+    #[error("This constant divides by zero.")]
+    ConstantDivisionByZero { span: Span },
+    #[error(
+        "The \"?\" operator can only be applied to an enum like an \"Option\" or a \"Result\", \
+         whose variants are \"Some\" and \"None\" or \"Ok\" and \"Err\", but this is of type \
+         {r#type}."
+    )]
+    TryOnNonResultLikeType { r#type: String, span: Span },
 }
 
 impl std::convert::From<TypeError> for CompileError {
@@ -849,7 +1063,7 @@ pub enum TypeError {
         "Mismatched types.\n\
          expected: {expected}\n\
          found:    {received}.\n\
-         {help}", expected=look_up_type_id(*expected).friendly_type_str(), received=look_up_type_id(*received).friendly_type_str(), help=if !help_text.is_empty() { format!("help: {}", help_text) } else { String::new() }
+         {help}", expected=expected.friendly_type_str(), received=received.friendly_type_str(), help=if !help_text.is_empty() { format!("help: {}", help_text) } else { String::new() }
     )]
     MismatchedType {
         expected: TypeId,
@@ -933,6 +1147,7 @@ impl CompileError {
             ParseFailure { span, .. } => span,
             InvalidTopLevelItem(_, span) => span,
             Internal(_, span) => span,
+            InternalOwned(_, span) => span,
             UnimplementedRule(_, span) => span,
             InvalidByteLiteralLength { span, .. } => span,
             ExpectedExprAfterOp { span, .. } => span,
@@ -943,6 +1158,8 @@ impl CompileError {
             MultipleScripts(span) => span,
             MultipleContracts(span) => span,
             ConstrainedNonExistentType { span, .. } => span,
+            TraitConstraintNotSatisfied { span, .. } => span,
+            OperatorNotImplemented { span, .. } => span,
             MultiplePredicateMainFunctions(span) => span,
             NoPredicateMainFunction(span) => span,
             PredicateMainDoesNotReturnBool(span) => span,
@@ -957,6 +1174,12 @@ impl CompileError {
             UnknownTrait { span, .. } => span,
             FunctionNotAPartOfInterfaceSurface { span, .. } => span,
             MissingInterfaceSurfaceMethods { span, .. } => span,
+            InvalidSupertrait { span, .. } => span,
+            SupertraitNotImplemented { span, .. } => span,
+            NotAnAssociatedItemOfTrait { span, .. } => span,
+            MissingAssociatedItems { span, .. } => span,
+            AmbiguousAssociatedItem { span, .. } => span,
+            UnknownDerive { span, .. } => span,
             IncorrectNumberOfTypeArguments { span, .. } => span,
             StructNotFound { span, .. } => span,
             DeclaredNonStructAsStruct { span, .. } => span,
@@ -1015,7 +1238,6 @@ impl CompileError {
             IncorrectNumberOfInterfaceSurfaceFunctionParameters { span, .. } => span,
             AbiFunctionRequiresSpecificSignature { span, .. } => span,
             ArgumentParameterTypeMismatch { span, .. } => span,
-            RecursiveCall { span, .. } => span,
             RecursiveCallChain { span, .. } => span,
             TypeWithUnknownSize { span, .. } => span,
             InfiniteDependencies { span, .. } => span,
@@ -1026,13 +1248,32 @@ impl CompileError {
             ArrayOutOfBounds { span, .. } => span,
             ShadowsOtherSymbol { span, .. } => span,
             MatchWrongType { span, .. } => span,
+            DestructuredWrongType { span, .. } => span,
             NotAnEnum { span, .. } => span,
             PatternMatchingAlgorithmFailure(_, span) => span,
-            PureCalledImpure { span, .. } => span,
+            StorageAccessNotDeclared { span, .. } => span,
+            UnknownStorageAccess { span, .. } => span,
             ImpureInNonContract { span, .. } => span,
             IntegerTooLarge { span, .. } => span,
             IntegerTooSmall { span, .. } => span,
             IntegerContainsInvalidDigit { span, .. } => span,
+            NotIterable { span, .. } => span,
+            BreakOutsideLoop { span } => span,
+            ContinueOutsideLoop { span } => span,
+            MultipleStorageDeclarations { span } => span,
+            StorageDeclarationInNonContract { span } => span,
+            NoDeclaredStorage { span } => span,
+            StorageFieldDoesNotExist { span, .. } => span,
+            StorageMapTypeArguments { span, .. } => span,
+            StorageMapReassignment { span, .. } => span,
+            StorageMapOutsideOfStorage { span } => span,
+            MatchNonExhaustive { span, .. } => span,
+            MatchVariableNotBoundInAllPatterns { span, .. } => span,
+            NotAConstant { span } => span,
+            ArrayLengthNotConstant { span, .. } => span,
+            ConstantOverflow { span, .. } => span,
+            ConstantDivisionByZero { span } => span,
+            TryOnNonResultLikeType { span, .. } => span,
         }
     }
 
@@ -11,6 +11,8 @@ use std::hash::{Hash, Hasher};
 #[derive(Debug, Clone)]
 pub struct Ident {
     name_override_opt: Option<&'static str>,
+    /// Whether this names a variable the compiler declared itself, see [hidden_ident].
+    is_hidden: bool,
     // sub-names are the stuff after periods
     // like x.test.thing.method()
     // `test`, `thing`, and `method` are sub-names
@@ -60,6 +62,7 @@ impl Ident {
         let span = span.trim();
         Ident {
             name_override_opt: None,
+            is_hidden: false,
             span,
         }
     }
@@ -67,10 +70,16 @@ impl Ident {
     pub fn new_with_override(name_override: &'static str, span: Span) -> Ident {
         Ident {
             name_override_opt: Some(name_override),
+            is_hidden: false,
             span,
         }
     }
 
+    /// Whether this names a variable the compiler declared itself, rather than the user.
+    pub(crate) fn is_hidden(&self) -> bool {
+        self.is_hidden
+    }
+
     pub(crate) fn parse_from_pair(
         pair: Pair<Rule>,
         config: Option<&BuildConfig>,
@@ -98,3 +107,51 @@ impl fmt::Display for Ident {
         write!(formatter, "{}", self.as_str())
     }
 }
+
+/// The variables the compiler declares itself, when it desugars a construct into simpler ones,
+/// to hold a value which must only be evaluated once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HiddenVariable {
+    /// The counter of a `for` loop.
+    ForCounter,
+    /// The end of the range a `for` loop iterates over.
+    ForEnd,
+    /// The array a `for` loop iterates over.
+    ForArray,
+    /// The value a `match` matches on.
+    MatchValue,
+    /// The value tested by an `if let` or a `while let`.
+    LetValue,
+    /// The value a destructuring `let` destructures.
+    Destructured,
+    /// The operand of a `?`.
+    TryValue,
+}
+
+impl HiddenVariable {
+    /// The names of hidden variables aren't valid identifiers, so they can't clash with those of
+    /// the user's variables.
+    fn name(self) -> &'static str {
+        use HiddenVariable::*;
+        match self {
+            ForCounter => "__for_counter",
+            ForEnd => "__for_end",
+            ForArray => "__for_array",
+            MatchValue => "__match_value",
+            LetValue => "__let_value",
+            Destructured => "__destructured",
+            TryValue => "__try_value",
+        }
+    }
+}
+
+/// The name of the hidden variable of `kind` declared for the construct at `span`. Hidden
+/// variables of nested constructs shadow each other, which isn't worth warning about, so they are
+/// marked to tell them apart from the user's variables.
+pub(crate) fn hidden_ident(kind: HiddenVariable, span: &Span) -> Ident {
+    Ident {
+        name_override_opt: Some(kind.name()),
+        is_hidden: true,
+        span: span.clone(),
+    }
+}
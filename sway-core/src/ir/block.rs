@@ -0,0 +1,61 @@
+//! A [Block] is a labelled list of instructions, ending with a terminator.
+
+use super::{Context, Function, Instruction, InstructionInserter, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Block(pub(super) usize);
+
+pub(crate) struct BlockContent {
+    pub(crate) label: String,
+    pub(crate) function: Function,
+    pub(crate) instructions: Vec<Value>,
+}
+
+impl Block {
+    /// Creates a new, empty block. It is not part of `function` until it is added to it, which
+    /// is usually done with [Function::create_block] instead.
+    pub(crate) fn new(context: &mut Context, function: Function, label: String) -> Block {
+        context.blocks.push(BlockContent {
+            label,
+            function,
+            instructions: Vec::new(),
+        });
+        Block(context.blocks.len() - 1)
+    }
+
+    /// Returns an inserter which appends instructions to the end of this block.
+    pub(crate) fn ins<'a>(&self, context: &'a mut Context) -> InstructionInserter<'a> {
+        InstructionInserter::new(context, *self)
+    }
+
+    pub(crate) fn get_label(&self, context: &Context) -> String {
+        context.blocks[self.0].label.clone()
+    }
+
+    pub(crate) fn get_function(&self, context: &Context) -> Function {
+        context.blocks[self.0].function
+    }
+
+    pub(crate) fn instruction_iter(&self, context: &Context) -> Vec<Value> {
+        context.blocks[self.0].instructions.clone()
+    }
+
+    pub(crate) fn get_terminator<'a>(&self, context: &'a Context) -> Option<&'a Instruction> {
+        context.blocks[self.0]
+            .instructions
+            .last()
+            .and_then(|value| value.get_instruction(context))
+            .filter(|instruction| instruction.is_terminator())
+    }
+
+    pub(crate) fn is_terminated(&self, context: &Context) -> bool {
+        self.get_terminator(context).is_some()
+    }
+
+    /// The blocks this block's terminator may jump to.
+    pub(crate) fn successors(&self, context: &Context) -> Vec<Block> {
+        self.get_terminator(context)
+            .map(|terminator| terminator.successors())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,118 @@
+//! Constant values, which are known at compile time.
+
+use super::{Context, Type, Value};
+
+#[derive(Clone, Debug)]
+pub(crate) struct Constant {
+    pub(crate) ty: Type,
+    pub(crate) value: ConstantValue,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum ConstantValue {
+    /// A value of any type whose contents are unspecified, usually an aggregate which is about
+    /// to have its fields inserted.
+    Undef,
+    Unit,
+    Bool(bool),
+    Uint(u64),
+    B256([u8; 32]),
+    String(Vec<u8>),
+    Array(Vec<Constant>),
+    Struct(Vec<Constant>),
+}
+
+impl Constant {
+    pub(crate) fn new_undef(ty: Type) -> Self {
+        Constant {
+            ty,
+            value: ConstantValue::Undef,
+        }
+    }
+
+    pub(crate) fn new_unit() -> Self {
+        Constant {
+            ty: Type::Unit,
+            value: ConstantValue::Unit,
+        }
+    }
+
+    pub(crate) fn new_bool(b: bool) -> Self {
+        Constant {
+            ty: Type::Bool,
+            value: ConstantValue::Bool(b),
+        }
+    }
+
+    pub(crate) fn new_uint(bits: u8, n: u64) -> Self {
+        Constant {
+            ty: Type::Uint(bits),
+            value: ConstantValue::Uint(n),
+        }
+    }
+
+    pub(crate) fn new_b256(bytes: [u8; 32]) -> Self {
+        Constant {
+            ty: Type::B256,
+            value: ConstantValue::B256(bytes),
+        }
+    }
+
+    pub(crate) fn new_string(bytes: Vec<u8>) -> Self {
+        Constant {
+            ty: Type::String(bytes.len() as u64),
+            value: ConstantValue::String(bytes),
+        }
+    }
+
+    pub(crate) fn get_undef(context: &mut Context, ty: Type) -> Value {
+        Value::new_constant(context, Constant::new_undef(ty))
+    }
+
+    pub(crate) fn get_unit(context: &mut Context) -> Value {
+        Value::new_constant(context, Constant::new_unit())
+    }
+
+    pub(crate) fn get_uint(context: &mut Context, bits: u8, n: u64) -> Value {
+        Value::new_constant(context, Constant::new_uint(bits, n))
+    }
+
+    pub(crate) fn as_string(&self, context: &Context) -> String {
+        format!(
+            "{} {}",
+            self.ty.as_string(context),
+            self.value_as_string(context)
+        )
+    }
+
+    fn value_as_string(&self, context: &Context) -> String {
+        let constants_as_string = |constants: &[Constant]| {
+            constants
+                .iter()
+                .map(|constant| constant.as_string(context))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match &self.value {
+            ConstantValue::Undef => "undef".into(),
+            ConstantValue::Unit => "()".into(),
+            ConstantValue::Bool(b) => b.to_string(),
+            ConstantValue::Uint(n) => n.to_string(),
+            ConstantValue::B256(bytes) => format!(
+                "0x{}",
+                bytes
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>()
+            ),
+            ConstantValue::String(bytes) => format!(
+                "\"{}\"",
+                String::from_utf8_lossy(bytes)
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+            ),
+            ConstantValue::Array(elements) => format!("[{}]", constants_as_string(elements)),
+            ConstantValue::Struct(fields) => format!("{{ {} }}", constants_as_string(fields)),
+        }
+    }
+}
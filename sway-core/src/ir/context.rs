@@ -0,0 +1,28 @@
+//! The [Context] owns all of the IR.
+
+use std::collections::HashMap;
+
+use super::{
+    AggregateContent, BlockContent, FunctionContent, ModuleContent, PointerContent, ValueContent,
+};
+
+/// Owns every module, function, block, value, pointer and aggregate type of a program. The
+/// handles to them, e.g. [super::Function], are indices into the vectors here.
+#[derive(Default)]
+pub(crate) struct Context {
+    pub(crate) modules: Vec<ModuleContent>,
+    pub(crate) functions: Vec<FunctionContent>,
+    pub(crate) blocks: Vec<BlockContent>,
+    pub(crate) values: Vec<ValueContent>,
+    pub(crate) pointers: Vec<PointerContent>,
+    pub(crate) aggregates: Vec<AggregateContent>,
+    /// Aggregate types are interned, so that two aggregate types are the same if and only if
+    /// their handles are equal.
+    pub(super) aggregate_ids: HashMap<AggregateContent, super::Aggregate>,
+}
+
+impl Context {
+    pub(crate) fn module_iter(&self) -> impl Iterator<Item = super::Module> {
+        (0..self.modules.len()).map(super::Module)
+    }
+}
@@ -0,0 +1,135 @@
+//! Errors from parsing and verifying IR.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IrError {
+    ParseFailure {
+        expected: String,
+        found: String,
+    },
+    UndefinedBlock(String),
+    UndefinedFunction(String),
+    UndefinedLocal(String),
+    UndefinedValue(String),
+
+    VerifyBlockMissingTerminator {
+        function: String,
+        block: String,
+    },
+    VerifyBranchToForeignBlock {
+        function: String,
+        block: String,
+    },
+    VerifyCallArgumentCount {
+        function: String,
+        callee: String,
+    },
+    VerifyForeignPointer {
+        function: String,
+    },
+    VerifyForeignValue {
+        function: String,
+    },
+    VerifyInvalidIndices {
+        function: String,
+        ty: String,
+    },
+    VerifyMisplacedPhi {
+        function: String,
+        block: String,
+    },
+    VerifyMisplacedTerminator {
+        function: String,
+        block: String,
+    },
+    VerifyMismatchedTypes {
+        function: String,
+        instruction: &'static str,
+        expected: String,
+        found: String,
+    },
+    VerifyPhiFromNonPredecessor {
+        function: String,
+        block: String,
+        from: String,
+    },
+}
+
+impl fmt::Display for IrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IrError::ParseFailure { expected, found } => {
+                write!(f, "Parse failure: expected {}, found {}", expected, found)
+            }
+            IrError::UndefinedBlock(name) => write!(f, "Undefined block \"{}\"", name),
+            IrError::UndefinedFunction(name) => write!(f, "Undefined function \"{}\"", name),
+            IrError::UndefinedLocal(name) => write!(f, "Undefined local \"{}\"", name),
+            IrError::UndefinedValue(name) => write!(f, "Undefined value \"{}\"", name),
+            IrError::VerifyBlockMissingTerminator { function, block } => write!(
+                f,
+                "Verification failed: block \"{}\" in function \"{}\" has no terminator",
+                block, function
+            ),
+            IrError::VerifyBranchToForeignBlock { function, block } => write!(
+                f,
+                "Verification failed: block \"{}\" in function \"{}\" branches to a block in \
+                 another function",
+                block, function
+            ),
+            IrError::VerifyCallArgumentCount { function, callee } => write!(
+                f,
+                "Verification failed: function \"{}\" calls \"{}\" with the wrong number of \
+                 arguments",
+                function, callee
+            ),
+            IrError::VerifyForeignPointer { function } => write!(
+                f,
+                "Verification failed: function \"{}\" accesses a local of another function",
+                function
+            ),
+            IrError::VerifyForeignValue { function } => write!(
+                f,
+                "Verification failed: function \"{}\" uses a value from another function",
+                function
+            ),
+            IrError::VerifyInvalidIndices { function, ty } => write!(
+                f,
+                "Verification failed: function \"{}\" indexes into type {} with invalid indices",
+                function, ty
+            ),
+            IrError::VerifyMisplacedPhi { function, block } => write!(
+                f,
+                "Verification failed: block \"{}\" in function \"{}\" has a phi after a \
+                 non-phi instruction",
+                block, function
+            ),
+            IrError::VerifyMisplacedTerminator { function, block } => write!(
+                f,
+                "Verification failed: block \"{}\" in function \"{}\" has a terminator before \
+                 its end",
+                block, function
+            ),
+            IrError::VerifyMismatchedTypes {
+                function,
+                instruction,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Verification failed: {} in function \"{}\" expected type {}, found {}",
+                instruction, function, expected, found
+            ),
+            IrError::VerifyPhiFromNonPredecessor {
+                function,
+                block,
+                from,
+            } => write!(
+                f,
+                "Verification failed: phi in block \"{}\" of function \"{}\" has an entry for \
+                 \"{}\", which is not a predecessor",
+                block, function, from
+            ),
+        }
+    }
+}
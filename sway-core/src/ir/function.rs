@@ -0,0 +1,140 @@
+//! A [Function] has arguments, a return type, local variables and a list of blocks.
+
+use std::collections::BTreeMap;
+
+use super::{Block, Constant, Context, Module, Pointer, Type, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Function(pub(super) usize);
+
+pub(crate) struct FunctionContent {
+    pub(crate) name: String,
+    pub(crate) arguments: Vec<(String, Value)>,
+    pub(crate) return_type: Type,
+    /// The blocks of the function, the first of which is its entry block.
+    pub(crate) blocks: Vec<Block>,
+    /// The function selector of a contract ABI function.
+    pub(crate) selector: Option<[u8; 4]>,
+    pub(crate) local_storage: BTreeMap<String, Pointer>,
+}
+
+impl Function {
+    /// Creates a new function in `module`, with an empty entry block.
+    pub(crate) fn new(
+        context: &mut Context,
+        module: Module,
+        name: String,
+        args: Vec<(String, Type)>,
+        return_type: Type,
+        selector: Option<[u8; 4]>,
+    ) -> Function {
+        let arguments = args
+            .into_iter()
+            .map(|(name, ty)| (name, Value::new_argument(context, ty)))
+            .collect();
+        context.functions.push(FunctionContent {
+            name,
+            arguments,
+            return_type,
+            blocks: Vec::new(),
+            selector,
+            local_storage: BTreeMap::new(),
+        });
+        let function = Function(context.functions.len() - 1);
+        context.modules[module.0].functions.push(function);
+        function.create_block(context, Some("entry".into()));
+        function
+    }
+
+    /// Creates a new empty block at the end of the function. The label is made unique within
+    /// the function if necessary.
+    pub(crate) fn create_block(&self, context: &mut Context, label: Option<String>) -> Block {
+        let label = self.get_unique_label(context, label);
+        let block = Block::new(context, *self, label);
+        context.functions[self.0].blocks.push(block);
+        block
+    }
+
+    fn get_unique_label(&self, context: &Context, label: Option<String>) -> String {
+        let is_taken = |label: &str| {
+            context.functions[self.0]
+                .blocks
+                .iter()
+                .any(|block| context.blocks[block.0].label == label)
+        };
+        match label {
+            Some(label) if !is_taken(&label) => label,
+            label => {
+                let base = label.unwrap_or_else(|| "block".into());
+                (0..)
+                    .map(|n| format!("{}{}", base, n))
+                    .find(|label| !is_taken(label))
+                    .unwrap()
+            }
+        }
+    }
+
+    pub(crate) fn get_name<'a>(&self, context: &'a Context) -> &'a str {
+        &context.functions[self.0].name
+    }
+
+    pub(crate) fn get_entry_block(&self, context: &Context) -> Block {
+        context.functions[self.0].blocks[0]
+    }
+
+    pub(crate) fn get_return_type(&self, context: &Context) -> Type {
+        context.functions[self.0].return_type
+    }
+
+    pub(crate) fn get_selector(&self, context: &Context) -> Option<[u8; 4]> {
+        context.functions[self.0].selector
+    }
+
+    pub(crate) fn args_iter<'a>(
+        &self,
+        context: &'a Context,
+    ) -> impl Iterator<Item = &'a (String, Value)> {
+        context.functions[self.0].arguments.iter()
+    }
+
+    pub(crate) fn block_iter(&self, context: &Context) -> Vec<Block> {
+        context.functions[self.0].blocks.clone()
+    }
+
+    /// Creates a new local variable called `name`. If the name is already taken, it is made
+    /// unique by appending a number to it, so the final name is returned with the pointer.
+    pub(crate) fn new_local_ptr(
+        &self,
+        context: &mut Context,
+        name: &str,
+        ty: Type,
+        is_mutable: bool,
+        initializer: Option<Constant>,
+    ) -> (String, Pointer) {
+        let local_storage = &context.functions[self.0].local_storage;
+        let name = if local_storage.contains_key(name) {
+            (0..)
+                .map(|n| format!("{}_{}", name, n))
+                .find(|name| !local_storage.contains_key(name))
+                .unwrap()
+        } else {
+            name.to_string()
+        };
+        let ptr = Pointer::new(context, ty, is_mutable, initializer);
+        context.functions[self.0]
+            .local_storage
+            .insert(name.clone(), ptr);
+        (name, ptr)
+    }
+
+    pub(crate) fn get_local_ptr(&self, context: &Context, name: &str) -> Option<Pointer> {
+        context.functions[self.0].local_storage.get(name).copied()
+    }
+
+    pub(crate) fn locals_iter<'a>(
+        &self,
+        context: &'a Context,
+    ) -> impl Iterator<Item = (&'a String, &'a Pointer)> {
+        context.functions[self.0].local_storage.iter()
+    }
+}
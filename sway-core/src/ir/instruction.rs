@@ -0,0 +1,255 @@
+//! The instructions of the IR, and [InstructionInserter] which appends them to blocks.
+
+use super::{Block, Context, Function, Pointer, Type, Value};
+use crate::Ident;
+
+#[derive(Clone, Debug)]
+pub(crate) enum Instruction {
+    /// An inline assembly block.
+    AsmBlock(AsmBlock),
+    /// An unconditional jump.
+    Branch(Block),
+    /// A call to a function in the same module.
+    Call(Function, Vec<Value>),
+    /// A jump to `true_block` if `cond_value` is true and to `false_block` otherwise.
+    ConditionalBranch {
+        cond_value: Value,
+        true_block: Block,
+        false_block: Block,
+    },
+    /// Reads an element of an array, at an index which is only known at runtime.
+    ExtractElement {
+        array: Value,
+        ty: Type,
+        index_val: Value,
+    },
+    /// Reads a (possibly nested) field or element of an aggregate.
+    ExtractValue {
+        aggregate: Value,
+        ty: Type,
+        indices: Vec<u64>,
+    },
+    /// Returns `array` with the element at `index_val` replaced with `value`.
+    InsertElement {
+        array: Value,
+        ty: Type,
+        value: Value,
+        index_val: Value,
+    },
+    /// Returns `aggregate` with the (possibly nested) field at `indices` replaced with `value`.
+    InsertValue {
+        aggregate: Value,
+        ty: Type,
+        value: Value,
+        indices: Vec<u64>,
+    },
+    /// Reads a local variable.
+    Load(Pointer),
+    /// Selects a value depending on which block control came from.
+    Phi(Vec<(Block, Value)>),
+    /// Returns from the function.
+    Ret(Value, Type),
+    /// Writes to a local variable.
+    Store { ptr: Pointer, stored_val: Value },
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AsmBlock {
+    pub(crate) args: Vec<AsmArg>,
+    pub(crate) body: Vec<AsmInstruction>,
+    /// The register whose value is the result of the block, if the block has a result.
+    pub(crate) return_name: Option<Ident>,
+    pub(crate) return_type: Type,
+}
+
+/// A register declared by an inline assembly block, optionally initialized with a value.
+#[derive(Clone, Debug)]
+pub(crate) struct AsmArg {
+    pub(crate) name: Ident,
+    pub(crate) initializer: Option<Value>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AsmInstruction {
+    pub(crate) name: Ident,
+    pub(crate) args: Vec<Ident>,
+    pub(crate) immediate: Option<Ident>,
+}
+
+impl Instruction {
+    /// The type of the value this instruction produces, or `None` if it doesn't produce one.
+    pub(crate) fn get_type(&self, context: &Context) -> Option<Type> {
+        match self {
+            Instruction::AsmBlock(asm_block) => Some(asm_block.return_type),
+            Instruction::Call(function, _) => Some(function.get_return_type(context)),
+            Instruction::ExtractElement { ty, .. } => ty.get_element_type(context),
+            Instruction::ExtractValue { ty, indices, .. } => ty.get_indexed_type(context, indices),
+            Instruction::InsertElement { ty, .. } | Instruction::InsertValue { ty, .. } => {
+                Some(*ty)
+            }
+            Instruction::Load(ptr) => Some(ptr.get_type(context)),
+            Instruction::Phi(entries) => entries
+                .first()
+                .and_then(|(_, value)| value.get_type(context)),
+            Instruction::Branch(_)
+            | Instruction::ConditionalBranch { .. }
+            | Instruction::Ret(..)
+            | Instruction::Store { .. } => None,
+        }
+    }
+
+    pub(crate) fn is_terminator(&self) -> bool {
+        matches!(
+            self,
+            Instruction::Branch(_) | Instruction::ConditionalBranch { .. } | Instruction::Ret(..)
+        )
+    }
+
+    /// The blocks this instruction may jump to.
+    pub(crate) fn successors(&self) -> Vec<Block> {
+        match self {
+            Instruction::Branch(block) => vec![*block],
+            Instruction::ConditionalBranch {
+                true_block,
+                false_block,
+                ..
+            } => vec![*true_block, *false_block],
+            _ => vec![],
+        }
+    }
+
+    /// Every value this instruction reads.
+    pub(crate) fn operands(&self) -> Vec<Value> {
+        match self {
+            Instruction::AsmBlock(asm_block) => asm_block
+                .args
+                .iter()
+                .filter_map(|arg| arg.initializer)
+                .collect(),
+            Instruction::Call(_, args) => args.clone(),
+            Instruction::ConditionalBranch { cond_value, .. } => vec![*cond_value],
+            Instruction::ExtractElement {
+                array, index_val, ..
+            } => vec![*array, *index_val],
+            Instruction::ExtractValue { aggregate, .. } => vec![*aggregate],
+            Instruction::InsertElement {
+                array,
+                value,
+                index_val,
+                ..
+            } => vec![*array, *value, *index_val],
+            Instruction::InsertValue {
+                aggregate, value, ..
+            } => vec![*aggregate, *value],
+            Instruction::Phi(entries) => entries.iter().map(|(_, value)| *value).collect(),
+            Instruction::Ret(value, _) => vec![*value],
+            Instruction::Store { stored_val, .. } => vec![*stored_val],
+            Instruction::Branch(_) | Instruction::Load(_) => vec![],
+        }
+    }
+}
+
+/// Appends instructions to the end of a block. Created with [Block::ins].
+pub(crate) struct InstructionInserter<'a> {
+    context: &'a mut Context,
+    block: Block,
+}
+
+impl<'a> InstructionInserter<'a> {
+    pub(crate) fn new(context: &'a mut Context, block: Block) -> InstructionInserter<'a> {
+        InstructionInserter { context, block }
+    }
+
+    fn append(self, instruction: Instruction) -> Value {
+        let value = Value::new_instruction(self.context, instruction);
+        self.context.blocks[self.block.0].instructions.push(value);
+        value
+    }
+
+    pub(crate) fn asm_block(self, asm_block: AsmBlock) -> Value {
+        self.append(Instruction::AsmBlock(asm_block))
+    }
+
+    pub(crate) fn branch(self, to_block: Block) -> Value {
+        self.append(Instruction::Branch(to_block))
+    }
+
+    pub(crate) fn call(self, function: Function, args: &[Value]) -> Value {
+        self.append(Instruction::Call(function, args.to_vec()))
+    }
+
+    pub(crate) fn conditional_branch(
+        self,
+        cond_value: Value,
+        true_block: Block,
+        false_block: Block,
+    ) -> Value {
+        self.append(Instruction::ConditionalBranch {
+            cond_value,
+            true_block,
+            false_block,
+        })
+    }
+
+    pub(crate) fn extract_element(self, array: Value, ty: Type, index_val: Value) -> Value {
+        self.append(Instruction::ExtractElement {
+            array,
+            ty,
+            index_val,
+        })
+    }
+
+    pub(crate) fn extract_value(self, aggregate: Value, ty: Type, indices: Vec<u64>) -> Value {
+        self.append(Instruction::ExtractValue {
+            aggregate,
+            ty,
+            indices,
+        })
+    }
+
+    pub(crate) fn insert_element(
+        self,
+        array: Value,
+        ty: Type,
+        value: Value,
+        index_val: Value,
+    ) -> Value {
+        self.append(Instruction::InsertElement {
+            array,
+            ty,
+            value,
+            index_val,
+        })
+    }
+
+    pub(crate) fn insert_value(
+        self,
+        aggregate: Value,
+        ty: Type,
+        value: Value,
+        indices: Vec<u64>,
+    ) -> Value {
+        self.append(Instruction::InsertValue {
+            aggregate,
+            ty,
+            value,
+            indices,
+        })
+    }
+
+    pub(crate) fn load(self, ptr: Pointer) -> Value {
+        self.append(Instruction::Load(ptr))
+    }
+
+    pub(crate) fn phi(self, entries: Vec<(Block, Value)>) -> Value {
+        self.append(Instruction::Phi(entries))
+    }
+
+    pub(crate) fn ret(self, value: Value, ty: Type) -> Value {
+        self.append(Instruction::Ret(value, ty))
+    }
+
+    pub(crate) fn store(self, ptr: Pointer, stored_val: Value) -> Value {
+        self.append(Instruction::Store { ptr, stored_val })
+    }
+}
@@ -0,0 +1,123 @@
+//! The types of IR values.
+
+use super::Context;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Type {
+    Unit,
+    Bool,
+    /// An unsigned integer with the given number of bits.
+    Uint(u8),
+    B256,
+    /// A string with the given number of bytes.
+    String(u64),
+    Array(Aggregate),
+    Union(Aggregate),
+    Struct(Aggregate),
+}
+
+/// An aggregate type, i.e. the fields of a struct or union, or the element type and length of
+/// an array.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Aggregate(pub(super) usize);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum AggregateContent {
+    ArrayType(Type, u64),
+    FieldTypes(Vec<Type>),
+}
+
+impl Aggregate {
+    /// The fields of a struct or union.
+    pub(crate) fn new_struct(context: &mut Context, field_types: Vec<Type>) -> Aggregate {
+        Aggregate::intern(context, AggregateContent::FieldTypes(field_types))
+    }
+
+    pub(crate) fn new_array(context: &mut Context, element_type: Type, count: u64) -> Aggregate {
+        Aggregate::intern(context, AggregateContent::ArrayType(element_type, count))
+    }
+
+    fn intern(context: &mut Context, content: AggregateContent) -> Aggregate {
+        if let Some(aggregate) = context.aggregate_ids.get(&content) {
+            return *aggregate;
+        }
+        context.aggregates.push(content.clone());
+        let aggregate = Aggregate(context.aggregates.len() - 1);
+        context.aggregate_ids.insert(content, aggregate);
+        aggregate
+    }
+
+    pub(crate) fn get_content<'a>(&self, context: &'a Context) -> &'a AggregateContent {
+        &context.aggregates[self.0]
+    }
+}
+
+impl Type {
+    /// The type found by indexing into this type with `indices`, where each index selects a
+    /// field of a struct or union, or an element of an array. Returns `None` if any index is out
+    /// of bounds or this isn't an aggregate.
+    pub(crate) fn get_indexed_type(&self, context: &Context, indices: &[u64]) -> Option<Type> {
+        indices.iter().try_fold(*self, |ty, index| {
+            let aggregate = match ty {
+                Type::Array(aggregate) | Type::Union(aggregate) | Type::Struct(aggregate) => {
+                    aggregate
+                }
+                _ => return None,
+            };
+            match aggregate.get_content(context) {
+                AggregateContent::FieldTypes(field_types) => {
+                    field_types.get(*index as usize).copied()
+                }
+                AggregateContent::ArrayType(element_type, count) => {
+                    if index < count {
+                        Some(*element_type)
+                    } else {
+                        None
+                    }
+                }
+            }
+        })
+    }
+
+    /// The type of the elements of an array, or `None` if this isn't an array.
+    pub(crate) fn get_element_type(&self, context: &Context) -> Option<Type> {
+        match self {
+            Type::Array(aggregate) => match aggregate.get_content(context) {
+                AggregateContent::ArrayType(element_type, _) => Some(*element_type),
+                AggregateContent::FieldTypes(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_string(&self, context: &Context) -> String {
+        let fields_as_string = |aggregate: &Aggregate, separator: &str| -> String {
+            match aggregate.get_content(context) {
+                AggregateContent::FieldTypes(field_types) => field_types
+                    .iter()
+                    .map(|ty| ty.as_string(context))
+                    .collect::<Vec<_>>()
+                    .join(separator),
+                AggregateContent::ArrayType(..) => unreachable!("struct or union is not an array"),
+            }
+        };
+        match self {
+            Type::Unit => "()".into(),
+            Type::Bool => "bool".into(),
+            Type::Uint(bits) => format!("u{}", bits),
+            Type::B256 => "b256".into(),
+            Type::String(len) => format!("string<{}>", len),
+            Type::Array(aggregate) => match aggregate.get_content(context) {
+                AggregateContent::ArrayType(element_type, count) => {
+                    format!("[{}; {}]", element_type.as_string(context), count)
+                }
+                AggregateContent::FieldTypes(_) => unreachable!("array is not a struct"),
+            },
+            Type::Union(aggregate) => format!("( {} )", fields_as_string(aggregate, " | ")),
+            Type::Struct(aggregate) => match aggregate.get_content(context) {
+                AggregateContent::FieldTypes(field_types) if field_types.is_empty() => "{}".into(),
+                _ => format!("{{ {} }}", fields_as_string(aggregate, ", ")),
+            },
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! A typed, block structured SSA intermediate representation of Sway programs, which sits between
+//! the typed AST and the ASM.
+//!
+//! A [Context] owns every part of the IR. The parts themselves, e.g. [Function], [Block] and
+//! [Value], are lightweight handles which index into the context.
+//!
+//! - A [Module] is a script, predicate, contract or library and contains [Function]s.
+//! - A [Function] has typed arguments, a return type, local variables, which are accessed via
+//!   [Pointer]s, and a list of [Block]s, the first of which is its entry block.
+//! - A [Block] is a labelled list of instructions, the last of which is its only terminator: a
+//!   branch, conditional branch or return.
+//! - A [Value] is either a function argument, a [Constant] or the result of an [Instruction].
+//!   Values are immutable; mutable state lives in locals and is read and written with `load`
+//!   and `store`.
+//! - Every value has a [Type]. Structs, unions and arrays are [Aggregate] types, and are built
+//!   and taken apart with the `insert_value`/`extract_value` and `insert_element`/
+//!   `extract_element` instructions.
+//!
+//! The IR can be printed to text with `to_string()` and parsed back with [parser::parse], and
+//! should be checked with [Context::verify] after it has been built or parsed.
+
+pub(crate) mod block;
+pub(crate) mod constant;
+pub(crate) mod context;
+pub(crate) mod error;
+pub(crate) mod function;
+pub(crate) mod instruction;
+pub(crate) mod irtype;
+pub(crate) mod module;
+pub(crate) mod parser;
+pub(crate) mod pointer;
+pub(crate) mod printer;
+pub(crate) mod value;
+pub(crate) mod verify;
+
+pub(crate) use block::*;
+pub(crate) use constant::*;
+pub(crate) use context::*;
+pub(crate) use error::*;
+pub(crate) use function::*;
+pub(crate) use instruction::*;
+pub(crate) use irtype::*;
+pub(crate) use module::*;
+pub(crate) use pointer::*;
+pub(crate) use value::*;
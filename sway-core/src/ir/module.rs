@@ -0,0 +1,56 @@
+//! A [Module] is a script, predicate, contract or library, and contains functions.
+
+use super::{Context, Function};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Module(pub(super) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Contract,
+    Library,
+    Predicate,
+    Script,
+}
+
+impl Kind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Contract => "contract",
+            Kind::Library => "library",
+            Kind::Predicate => "predicate",
+            Kind::Script => "script",
+        }
+    }
+}
+
+pub(crate) struct ModuleContent {
+    pub(crate) kind: Kind,
+    pub(crate) functions: Vec<Function>,
+}
+
+impl Module {
+    pub(crate) fn new(context: &mut Context, kind: Kind) -> Module {
+        context.modules.push(ModuleContent {
+            kind,
+            functions: Vec::new(),
+        });
+        Module(context.modules.len() - 1)
+    }
+
+    pub(crate) fn get_kind(&self, context: &Context) -> Kind {
+        context.modules[self.0].kind
+    }
+
+    pub(crate) fn function_iter(&self, context: &Context) -> Vec<Function> {
+        context.modules[self.0].functions.clone()
+    }
+
+    pub(crate) fn get_function(&self, context: &Context, name: &str) -> Option<Function> {
+        context.modules[self.0]
+            .functions
+            .iter()
+            .find(|function| function.get_name(context) == name)
+            .copied()
+    }
+}
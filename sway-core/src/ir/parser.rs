@@ -0,0 +1,900 @@
+//! Parses IR from the text format written by the [super::printer].
+//!
+//! The text is tokenized and then parsed with recursive descent. Types and constants are built
+//! directly into the [Context] while parsing, but instructions refer to values, blocks, locals and
+//! functions by name, so each module is parsed in full before its functions are built.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::*;
+use crate::{Ident, Span};
+
+/// Parses every module in `input` into a new [Context].
+// only the tests read IR back in, for now
+#[allow(dead_code)]
+pub(crate) fn parse(input: &str) -> Result<Context, IrError> {
+    let src: Arc<str> = input.into();
+    let tokens = tokenize(&src)?;
+    let mut parser = Parser {
+        src,
+        tokens,
+        pos: 0,
+        context: Context::default(),
+    };
+    while parser.peek().is_some() {
+        parser.parse_module()?;
+    }
+    Ok(parser.context)
+}
+
+// -------------------------------------------------------------------------------------------------
+// Tokens.
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Hex(Vec<u8>),
+    Str(Vec<u8>),
+    Punct(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => format!("\"{}\"", ident),
+            Token::Number(n) => n.to_string(),
+            Token::Hex(_) => "a hex literal".into(),
+            Token::Str(_) => "a string literal".into(),
+            Token::Punct(punct) => format!("'{}'", punct),
+        }
+    }
+}
+
+/// A token with the start and end byte offsets of its text.
+struct SpannedToken {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+const PUNCTUATION: &[&str] = &[
+    "->", "{", "}", "(", ")", "[", "]", "<", ">", ",", ":", ";", "=", "|",
+];
+
+fn tokenize(src: &str) -> Result<Vec<SpannedToken>, IrError> {
+    let bytes = src.as_bytes();
+    let failure = |expected: &str, pos: usize| IrError::ParseFailure {
+        expected: expected.into(),
+        found: format!("'{}'", src[pos..].chars().next().unwrap_or(' ')),
+    };
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let c = bytes[pos];
+        let token = if c.is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        } else if c == b'0' && bytes.get(pos + 1) == Some(&b'x') {
+            pos += 2;
+            while pos < bytes.len() && bytes[pos].is_ascii_hexdigit() {
+                pos += 1;
+            }
+            let digits = &src[start + 2..pos];
+            if digits.is_empty() || digits.len() % 2 != 0 {
+                return Err(failure("an even number of hex digits", start));
+            }
+            Token::Hex(
+                (0..digits.len())
+                    .step_by(2)
+                    .map(|ix| u8::from_str_radix(&digits[ix..ix + 2], 16).unwrap())
+                    .collect(),
+            )
+        } else if c.is_ascii_digit() {
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            Token::Number(
+                src[start..pos]
+                    .parse()
+                    .map_err(|_| failure("a number which fits in 64 bits", start))?,
+            )
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            Token::Ident(src[start..pos].to_string())
+        } else if c == b'"' {
+            pos += 1;
+            let mut string = Vec::new();
+            loop {
+                match bytes.get(pos) {
+                    None => return Err(failure("the end of a string literal", start)),
+                    Some(b'"') => break,
+                    Some(b'\\') => {
+                        match bytes.get(pos + 1) {
+                            Some(escaped @ (b'"' | b'\\')) => string.push(*escaped),
+                            _ => return Err(failure("a valid escape sequence", pos)),
+                        }
+                        pos += 2;
+                    }
+                    Some(byte) => {
+                        string.push(*byte);
+                        pos += 1;
+                    }
+                }
+            }
+            pos += 1;
+            Token::Str(string)
+        } else {
+            match PUNCTUATION
+                .iter()
+                .find(|punct| src[pos..].starts_with(**punct))
+            {
+                Some(punct) => {
+                    pos += punct.len();
+                    Token::Punct(*punct)
+                }
+                None => return Err(failure("a token", pos)),
+            }
+        };
+        tokens.push(SpannedToken {
+            token,
+            start,
+            end: pos,
+        });
+    }
+    Ok(tokens)
+}
+
+// -------------------------------------------------------------------------------------------------
+// Declarations, which are parsed before they're built into the context.
+
+struct FunctionDecl {
+    name: String,
+    selector: Option<[u8; 4]>,
+    args: Vec<(String, Type)>,
+    return_type: Type,
+    locals: Vec<LocalDecl>,
+    blocks: Vec<BlockDecl>,
+}
+
+struct LocalDecl {
+    name: String,
+    ty: Type,
+    is_mutable: bool,
+    initializer: Option<Constant>,
+}
+
+struct BlockDecl {
+    label: String,
+    instructions: Vec<InstructionDecl>,
+}
+
+struct InstructionDecl {
+    /// The name of the value the instruction produces, if any.
+    name: Option<String>,
+    op: OpDecl,
+}
+
+enum OpDecl {
+    Asm {
+        args: Vec<(Ident, Option<String>)>,
+        body: Vec<AsmInstruction>,
+        return_name: Option<Ident>,
+        return_type: Type,
+    },
+    Branch(String),
+    Call(String, Vec<String>),
+    ConditionalBranch(String, String, String),
+    Const(Constant),
+    ExtractElement(String, Type, String),
+    ExtractValue(String, Type, Vec<u64>),
+    InsertElement(String, Type, String, String),
+    InsertValue(String, Type, String, Vec<u64>),
+    Load(String),
+    Phi(Vec<(String, String)>),
+    Ret(Type, String),
+    Store(String, String),
+}
+
+// -------------------------------------------------------------------------------------------------
+// The parser.
+
+struct Parser {
+    src: Arc<str>,
+    tokens: Vec<SpannedToken>,
+    pos: usize,
+    context: Context,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n).map(|spanned| &spanned.token)
+    }
+
+    fn failure(&self, expected: &str) -> IrError {
+        IrError::ParseFailure {
+            expected: expected.into(),
+            found: self
+                .peek()
+                .map(Token::describe)
+                .unwrap_or_else(|| "the end of the input".into()),
+        }
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let is_punct = self.is_punct(punct);
+        if is_punct {
+            self.pos += 1;
+        }
+        is_punct
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = self.is_keyword(keyword);
+        if is_keyword {
+            self.pos += 1;
+        }
+        is_keyword
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), IrError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.failure(&format!("'{}'", punct)))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), IrError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.failure(&format!("\"{}\"", keyword)))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, IrError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.failure("an identifier")),
+        }
+    }
+
+    /// An identifier whose span points into the source text, for use in asm blocks.
+    fn expect_spanned_ident(&mut self) -> Result<Ident, IrError> {
+        match self.tokens.get(self.pos) {
+            Some(SpannedToken {
+                token: Token::Ident(_),
+                start,
+                end,
+            }) => {
+                let span = Span {
+                    span: pest::Span::new(self.src.clone(), *start, *end).unwrap(),
+                    path: None,
+                };
+                self.pos += 1;
+                Ok(Ident::new(span))
+            }
+            _ => Err(self.failure("an identifier")),
+        }
+    }
+
+    fn expect_number(&mut self) -> Result<u64, IrError> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => Err(self.failure("a number")),
+        }
+    }
+
+    /// Parses `item`s separated by commas, up to and including the `close` punctuation.
+    fn parse_list<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, IrError>,
+    ) -> Result<Vec<T>, IrError> {
+        let mut items = Vec::new();
+        if self.eat_punct(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat_punct(close) {
+                return Ok(items);
+            }
+            self.expect_punct(",")?;
+        }
+    }
+
+    fn parse_module(&mut self) -> Result<(), IrError> {
+        let kind = match self.expect_ident()?.as_str() {
+            "contract" => module::Kind::Contract,
+            "library" => module::Kind::Library,
+            "predicate" => module::Kind::Predicate,
+            "script" => module::Kind::Script,
+            _ => {
+                self.pos -= 1;
+                return Err(self.failure("a module kind"));
+            }
+        };
+        self.expect_punct("{")?;
+        let mut function_decls = Vec::new();
+        while !self.eat_punct("}") {
+            function_decls.push(self.parse_function()?);
+        }
+        self.build_module(kind, function_decls)
+    }
+
+    fn parse_function(&mut self) -> Result<FunctionDecl, IrError> {
+        self.expect_keyword("fn")?;
+        let name = self.expect_ident()?;
+        let selector = if self.eat_punct("<") {
+            let selector = match self.peek() {
+                Some(Token::Hex(bytes)) if bytes.len() == 4 => {
+                    [bytes[0], bytes[1], bytes[2], bytes[3]]
+                }
+                _ => return Err(self.failure("a four byte selector")),
+            };
+            self.pos += 1;
+            self.expect_punct(">")?;
+            Some(selector)
+        } else {
+            None
+        };
+        self.expect_punct("(")?;
+        let args = self.parse_list(")", |parser| {
+            let name = parser.expect_ident()?;
+            parser.expect_punct(":")?;
+            Ok((name, parser.parse_type()?))
+        })?;
+        self.expect_punct("->")?;
+        let return_type = self.parse_type()?;
+        self.expect_punct("{")?;
+
+        let mut locals = Vec::new();
+        while self.eat_keyword("local") {
+            let is_mutable = self.eat_keyword("mut");
+            self.expect_keyword("ptr")?;
+            let ty = self.parse_type()?;
+            let name = self.expect_ident()?;
+            let initializer = if self.eat_punct("=") {
+                self.expect_keyword("const")?;
+                Some(self.parse_constant()?)
+            } else {
+                None
+            };
+            locals.push(LocalDecl {
+                name,
+                ty,
+                is_mutable,
+                initializer,
+            });
+        }
+
+        let mut blocks = Vec::new();
+        while !self.eat_punct("}") {
+            let label = self.expect_ident()?;
+            self.expect_punct(":")?;
+            let mut instructions = Vec::new();
+            // a block continues until the next label or the end of the function
+            while !self.is_punct("}") && self.peek_nth(1) != Some(&Token::Punct(":")) {
+                instructions.push(self.parse_instruction()?);
+            }
+            blocks.push(BlockDecl {
+                label,
+                instructions,
+            });
+        }
+
+        Ok(FunctionDecl {
+            name,
+            selector,
+            args,
+            return_type,
+            locals,
+            blocks,
+        })
+    }
+
+    fn parse_instruction(&mut self) -> Result<InstructionDecl, IrError> {
+        let name = if self.peek_nth(1) == Some(&Token::Punct("=")) {
+            let name = self.expect_ident()?;
+            self.pos += 1;
+            Some(name)
+        } else {
+            None
+        };
+        let op = match self.expect_ident()?.as_str() {
+            "asm" => self.parse_asm()?,
+            "br" => OpDecl::Branch(self.expect_ident()?),
+            "call" => {
+                let callee = self.expect_ident()?;
+                self.expect_punct("(")?;
+                OpDecl::Call(callee, self.parse_list(")", Self::expect_ident)?)
+            }
+            "cbr" => {
+                let cond_value = self.expect_ident()?;
+                self.expect_punct(",")?;
+                let true_block = self.expect_ident()?;
+                self.expect_punct(",")?;
+                OpDecl::ConditionalBranch(cond_value, true_block, self.expect_ident()?)
+            }
+            "const" => OpDecl::Const(self.parse_constant()?),
+            "extract_element" => {
+                let array = self.expect_ident()?;
+                self.expect_punct(",")?;
+                let ty = self.parse_type()?;
+                self.expect_punct(",")?;
+                OpDecl::ExtractElement(array, ty, self.expect_ident()?)
+            }
+            "extract_value" => {
+                let aggregate = self.expect_ident()?;
+                self.expect_punct(",")?;
+                let ty = self.parse_type()?;
+                OpDecl::ExtractValue(aggregate, ty, self.parse_indices()?)
+            }
+            "insert_element" => {
+                let array = self.expect_ident()?;
+                self.expect_punct(",")?;
+                let ty = self.parse_type()?;
+                self.expect_punct(",")?;
+                let value = self.expect_ident()?;
+                self.expect_punct(",")?;
+                OpDecl::InsertElement(array, ty, value, self.expect_ident()?)
+            }
+            "insert_value" => {
+                let aggregate = self.expect_ident()?;
+                self.expect_punct(",")?;
+                let ty = self.parse_type()?;
+                self.expect_punct(",")?;
+                let value = self.expect_ident()?;
+                OpDecl::InsertValue(aggregate, ty, value, self.parse_indices()?)
+            }
+            "load" => {
+                self.expect_keyword("ptr")?;
+                OpDecl::Load(self.expect_ident()?)
+            }
+            "phi" => {
+                self.expect_punct("(")?;
+                OpDecl::Phi(self.parse_list(")", |parser| {
+                    let label = parser.expect_ident()?;
+                    parser.expect_punct(":")?;
+                    Ok((label, parser.expect_ident()?))
+                })?)
+            }
+            "ret" => {
+                let ty = self.parse_type()?;
+                OpDecl::Ret(ty, self.expect_ident()?)
+            }
+            "store" => {
+                let value = self.expect_ident()?;
+                self.expect_punct(",")?;
+                self.expect_keyword("ptr")?;
+                OpDecl::Store(value, self.expect_ident()?)
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.failure("an instruction"));
+            }
+        };
+        Ok(InstructionDecl { name, op })
+    }
+
+    /// Parses `, 0, 1, 2` after an `extract_value` or `insert_value`.
+    fn parse_indices(&mut self) -> Result<Vec<u64>, IrError> {
+        let mut indices = Vec::new();
+        while self.eat_punct(",") {
+            indices.push(self.expect_number()?);
+        }
+        Ok(indices)
+    }
+
+    fn parse_asm(&mut self) -> Result<OpDecl, IrError> {
+        self.expect_punct("(")?;
+        let args = self.parse_list(")", |parser| {
+            let name = parser.expect_spanned_ident()?;
+            let initializer = if parser.eat_punct(":") {
+                Some(parser.expect_ident()?)
+            } else {
+                None
+            };
+            Ok((name, initializer))
+        })?;
+        self.expect_punct("->")?;
+        let return_type = self.parse_type()?;
+        let return_name = match self.peek() {
+            Some(Token::Ident(_)) => Some(self.expect_spanned_ident()?),
+            _ => None,
+        };
+        self.expect_punct("{")?;
+        let mut body = Vec::new();
+        while !self.eat_punct("}") {
+            let name = self.expect_spanned_ident()?;
+            let mut args = Vec::new();
+            while !self.eat_punct(";") {
+                args.push(self.expect_spanned_ident()?);
+            }
+            let is_immediate = |arg: &Ident| {
+                let arg = arg.as_str();
+                arg.len() > 1
+                    && arg.starts_with('i')
+                    && arg[1..].bytes().all(|byte| byte.is_ascii_digit())
+            };
+            let immediate = match args.last() {
+                Some(arg) if is_immediate(arg) => args.pop(),
+                _ => None,
+            };
+            body.push(AsmInstruction {
+                name,
+                args,
+                immediate,
+            });
+        }
+        Ok(OpDecl::Asm {
+            args,
+            body,
+            return_name,
+            return_type,
+        })
+    }
+
+    fn parse_type(&mut self) -> Result<Type, IrError> {
+        if self.eat_punct("(") {
+            if self.eat_punct(")") {
+                return Ok(Type::Unit);
+            }
+            let mut variants = vec![self.parse_type()?];
+            while self.eat_punct("|") {
+                variants.push(self.parse_type()?);
+            }
+            self.expect_punct(")")?;
+            return Ok(Type::Union(Aggregate::new_struct(
+                &mut self.context,
+                variants,
+            )));
+        }
+        if self.eat_punct("{") {
+            let fields = self.parse_list("}", Self::parse_type)?;
+            return Ok(Type::Struct(Aggregate::new_struct(
+                &mut self.context,
+                fields,
+            )));
+        }
+        if self.eat_punct("[") {
+            let element_type = self.parse_type()?;
+            self.expect_punct(";")?;
+            let count = self.expect_number()?;
+            self.expect_punct("]")?;
+            return Ok(Type::Array(Aggregate::new_array(
+                &mut self.context,
+                element_type,
+                count,
+            )));
+        }
+        let ty = match self.peek() {
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "bool" => Type::Bool,
+                "b256" => Type::B256,
+                "string" => {
+                    self.pos += 1;
+                    self.expect_punct("<")?;
+                    let len = self.expect_number()?;
+                    self.expect_punct(">")?;
+                    return Ok(Type::String(len));
+                }
+                ident => match ident.strip_prefix('u').map(str::parse::<u8>) {
+                    Some(Ok(bits)) => Type::Uint(bits),
+                    _ => return Err(self.failure("a type")),
+                },
+            },
+            _ => return Err(self.failure("a type")),
+        };
+        self.pos += 1;
+        Ok(ty)
+    }
+
+    fn parse_constant(&mut self) -> Result<Constant, IrError> {
+        let ty = self.parse_type()?;
+        let value = if self.eat_keyword("undef") {
+            ConstantValue::Undef
+        } else if self.eat_keyword("true") {
+            ConstantValue::Bool(true)
+        } else if self.eat_keyword("false") {
+            ConstantValue::Bool(false)
+        } else if self.eat_punct("(") {
+            self.expect_punct(")")?;
+            ConstantValue::Unit
+        } else if self.eat_punct("[") {
+            ConstantValue::Array(self.parse_list("]", Self::parse_constant)?)
+        } else if self.eat_punct("{") {
+            ConstantValue::Struct(self.parse_list("}", Self::parse_constant)?)
+        } else {
+            let value = match self.peek() {
+                Some(Token::Number(n)) => ConstantValue::Uint(*n),
+                Some(Token::Hex(bytes)) if bytes.len() == 32 => {
+                    let mut b256 = [0; 32];
+                    b256.copy_from_slice(bytes);
+                    ConstantValue::B256(b256)
+                }
+                Some(Token::Str(bytes)) => ConstantValue::String(bytes.clone()),
+                _ => return Err(self.failure("a constant value")),
+            };
+            self.pos += 1;
+            value
+        };
+        Ok(Constant { ty, value })
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Building the parsed declarations into the context.
+
+    fn build_module(
+        &mut self,
+        kind: module::Kind,
+        function_decls: Vec<FunctionDecl>,
+    ) -> Result<(), IrError> {
+        let context = &mut self.context;
+        let module = Module::new(context, kind);
+        // every function is created first, so that calls can refer to functions defined later
+        let functions = function_decls
+            .iter()
+            .map(|decl| {
+                Function::new(
+                    context,
+                    module,
+                    decl.name.clone(),
+                    decl.args.clone(),
+                    decl.return_type,
+                    decl.selector,
+                )
+            })
+            .collect::<Vec<_>>();
+        for (function, decl) in functions.into_iter().zip(function_decls) {
+            build_function(context, module, function, decl)?;
+        }
+        Ok(())
+    }
+}
+
+fn build_function(
+    context: &mut Context,
+    module: Module,
+    function: Function,
+    decl: FunctionDecl,
+) -> Result<(), IrError> {
+    for local in decl.locals {
+        function.new_local_ptr(
+            context,
+            &local.name,
+            local.ty,
+            local.is_mutable,
+            local.initializer,
+        );
+    }
+
+    let mut blocks = HashMap::new();
+    for (ix, block_decl) in decl.blocks.iter().enumerate() {
+        let block = if ix == 0 {
+            if block_decl.label != "entry" {
+                return Err(IrError::ParseFailure {
+                    expected: "\"entry\"".into(),
+                    found: format!("\"{}\"", block_decl.label),
+                });
+            }
+            function.get_entry_block(context)
+        } else {
+            function.create_block(context, Some(block_decl.label.clone()))
+        };
+        blocks.insert(block_decl.label.clone(), block);
+    }
+    let get_block = |label: &String| {
+        blocks
+            .get(label)
+            .copied()
+            .ok_or_else(|| IrError::UndefinedBlock(label.clone()))
+    };
+
+    let mut values: HashMap<String, Value> = function
+        .args_iter(context)
+        .map(|(name, value)| (name.clone(), *value))
+        .collect();
+    let get_value = |values: &HashMap<String, Value>, name: &String| {
+        values
+            .get(name)
+            .copied()
+            .ok_or_else(|| IrError::UndefinedValue(name.clone()))
+    };
+    let get_local = |context: &Context, name: &String| {
+        function
+            .get_local_ptr(context, name)
+            .ok_or_else(|| IrError::UndefinedLocal(name.clone()))
+    };
+    // phis may refer to values defined further down, so their entries are filled in last
+    let mut phis = Vec::new();
+
+    for block_decl in decl.blocks {
+        let block = get_block(&block_decl.label)?;
+        for InstructionDecl { name, op } in block_decl.instructions {
+            let value = match op {
+                OpDecl::Asm {
+                    args,
+                    body,
+                    return_name,
+                    return_type,
+                } => {
+                    let args = args
+                        .into_iter()
+                        .map(|(name, initializer)| {
+                            Ok(AsmArg {
+                                name,
+                                initializer: initializer
+                                    .map(|initializer| get_value(&values, &initializer))
+                                    .transpose()?,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, IrError>>()?;
+                    block.ins(context).asm_block(AsmBlock {
+                        args,
+                        body,
+                        return_name,
+                        return_type,
+                    })
+                }
+                OpDecl::Branch(label) => block.ins(context).branch(get_block(&label)?),
+                OpDecl::Call(callee, args) => {
+                    let callee = module
+                        .get_function(context, &callee)
+                        .ok_or(IrError::UndefinedFunction(callee))?;
+                    let args = args
+                        .iter()
+                        .map(|arg| get_value(&values, arg))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    block.ins(context).call(callee, &args)
+                }
+                OpDecl::ConditionalBranch(cond_value, true_block, false_block) => {
+                    let cond_value = get_value(&values, &cond_value)?;
+                    block.ins(context).conditional_branch(
+                        cond_value,
+                        get_block(&true_block)?,
+                        get_block(&false_block)?,
+                    )
+                }
+                OpDecl::Const(constant) => Value::new_constant(context, constant),
+                OpDecl::ExtractElement(array, ty, index_val) => {
+                    let array = get_value(&values, &array)?;
+                    let index_val = get_value(&values, &index_val)?;
+                    block.ins(context).extract_element(array, ty, index_val)
+                }
+                OpDecl::ExtractValue(aggregate, ty, indices) => {
+                    let aggregate = get_value(&values, &aggregate)?;
+                    block.ins(context).extract_value(aggregate, ty, indices)
+                }
+                OpDecl::InsertElement(array, ty, value, index_val) => {
+                    let array = get_value(&values, &array)?;
+                    let value = get_value(&values, &value)?;
+                    let index_val = get_value(&values, &index_val)?;
+                    block
+                        .ins(context)
+                        .insert_element(array, ty, value, index_val)
+                }
+                OpDecl::InsertValue(aggregate, ty, value, indices) => {
+                    let aggregate = get_value(&values, &aggregate)?;
+                    let value = get_value(&values, &value)?;
+                    block
+                        .ins(context)
+                        .insert_value(aggregate, ty, value, indices)
+                }
+                OpDecl::Load(local) => {
+                    let ptr = get_local(context, &local)?;
+                    block.ins(context).load(ptr)
+                }
+                OpDecl::Phi(entries) => {
+                    let phi = block.ins(context).phi(Vec::new());
+                    phis.push((phi, entries));
+                    phi
+                }
+                OpDecl::Ret(ty, value) => {
+                    let value = get_value(&values, &value)?;
+                    block.ins(context).ret(value, ty)
+                }
+                OpDecl::Store(value, local) => {
+                    let stored_val = get_value(&values, &value)?;
+                    let ptr = get_local(context, &local)?;
+                    block.ins(context).store(ptr, stored_val)
+                }
+            };
+            if let Some(name) = name {
+                values.insert(name, value);
+            }
+        }
+    }
+
+    for (phi, entries) in phis {
+        let entries = entries
+            .iter()
+            .map(|(label, value)| Ok((get_block(label)?, get_value(&values, value)?)))
+            .collect::<Result<Vec<_>, IrError>>()?;
+        context.values[phi.0] = ValueContent::Instruction(Instruction::Phi(entries));
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_and_print_round_trip() {
+    let text = r#"script {
+    fn main() -> u64 {
+        local mut ptr u64 x = const u64 0
+
+        entry:
+        v0 = const u64 5
+        store v0, ptr x
+        v1 = load ptr x
+        v2 = const bool true
+        cbr v2, double, pair
+        double:
+        v3 = call add(v1, v1)
+        br end
+        pair:
+        v4 = call make_pair(v1)
+        v5 = extract_value v4, { u64, bool }, 0
+        br end
+        end:
+        v6 = phi(double: v3, pair: v5)
+        ret u64 v6
+    }
+
+    fn add(a: u64, b: u64) -> u64 {
+        entry:
+        v0 = asm(r1: a, r2: b, r3) -> u64 r3 {
+            add r3 r1 r2;
+            addi r3 r3 i1;
+        }
+        ret u64 v0
+    }
+
+    fn make_pair(a: u64) -> { u64, bool } {
+        entry:
+        v0 = const { u64, bool } undef
+        v1 = insert_value v0, { u64, bool }, a, 0
+        v2 = const bool false
+        v3 = insert_value v1, { u64, bool }, v2, 1
+        ret { u64, bool } v3
+    }
+}
+contract {
+    fn get_name<0x2a3b4c5d>(i: u64) -> string<5> {
+        entry:
+        v0 = const [u64; 2] [u64 1, u64 2]
+        v1 = extract_element v0, [u64; 2], i
+        v2 = const string<5> "\"sw\\y"
+        ret string<5> v2
+    }
+}
+"#;
+    let context = parse(text).expect("failed to parse IR");
+    context.verify().expect("failed to verify IR");
+    assert_eq!(context.to_string(), text);
+}
@@ -0,0 +1,41 @@
+//! A [Pointer] refers to a local variable of a function.
+
+use super::{Constant, Context, Type};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Pointer(pub(super) usize);
+
+pub(crate) struct PointerContent {
+    pub(crate) ty: Type,
+    pub(crate) is_mutable: bool,
+    /// The value the local is set to when the function is entered, if any.
+    pub(crate) initializer: Option<Constant>,
+}
+
+impl Pointer {
+    pub(crate) fn new(
+        context: &mut Context,
+        ty: Type,
+        is_mutable: bool,
+        initializer: Option<Constant>,
+    ) -> Pointer {
+        context.pointers.push(PointerContent {
+            ty,
+            is_mutable,
+            initializer,
+        });
+        Pointer(context.pointers.len() - 1)
+    }
+
+    pub(crate) fn get_type(&self, context: &Context) -> Type {
+        context.pointers[self.0].ty
+    }
+
+    pub(crate) fn is_mutable(&self, context: &Context) -> bool {
+        context.pointers[self.0].is_mutable
+    }
+
+    pub(crate) fn get_initializer<'a>(&self, context: &'a Context) -> Option<&'a Constant> {
+        context.pointers[self.0].initializer.as_ref()
+    }
+}
@@ -0,0 +1,323 @@
+//! Prints IR as text, in the format read by [super::parser::parse].
+//!
+//! ```text
+//! script {
+//!     fn main() -> u64 {
+//!         local mut ptr u64 x = const u64 0
+//!
+//!         entry:
+//!         v0 = const u64 5
+//!         store v0, ptr x
+//!         v1 = load ptr x
+//!         ret u64 v1
+//!     }
+//! }
+//! ```
+//!
+//! Instructions which produce a value are named `v0`, `v1`, etc., and function arguments are
+//! named after the arguments themselves. Constants are printed as if they were instructions, just
+//! before the first instruction which uses them.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{Block, Context, Function, Instruction, Module, Pointer, Value, ValueContent};
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for module in self.module_iter() {
+            write!(f, "{}", module_to_string(self, module))?;
+        }
+        Ok(())
+    }
+}
+
+fn module_to_string(context: &Context, module: Module) -> String {
+    let functions = module
+        .function_iter(context)
+        .into_iter()
+        .map(|function| function_to_string(context, function))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{} {{\n{}}}\n",
+        module.get_kind(context).as_str(),
+        functions
+    )
+}
+
+/// Names the values of a single function.
+#[derive(Default)]
+struct Namer {
+    names: HashMap<Value, String>,
+    next_id: usize,
+    printed_constants: HashSet<Value>,
+}
+
+impl Namer {
+    fn name(&mut self, value: Value) -> String {
+        if let Some(name) = self.names.get(&value) {
+            return name.clone();
+        }
+        let name = format!("v{}", self.next_id);
+        self.next_id += 1;
+        self.names.insert(value, name.clone());
+        name
+    }
+
+    /// Names an operand. If it is a constant which hasn't been printed yet, its definition is
+    /// appended to `buf`.
+    fn operand(&mut self, context: &Context, value: Value, buf: &mut String) -> String {
+        let name = self.name(value);
+        if let ValueContent::Constant(constant) = value.get_content(context) {
+            if self.printed_constants.insert(value) {
+                buf.push_str(&format!(
+                    "        {} = const {}\n",
+                    name,
+                    constant.as_string(context)
+                ));
+            }
+        }
+        name
+    }
+}
+
+fn function_to_string(context: &Context, function: Function) -> String {
+    let mut namer = Namer::default();
+    for (name, value) in function.args_iter(context) {
+        namer.names.insert(*value, name.clone());
+    }
+    // values are named up front, in the order they're printed, so that phis can refer to values
+    // defined further down
+    for block in function.block_iter(context) {
+        for value in block.instruction_iter(context) {
+            let instruction = value
+                .get_instruction(context)
+                .expect("blocks only contain instructions");
+            for operand in instruction.operands() {
+                if operand.get_constant(context).is_some() {
+                    namer.name(operand);
+                }
+            }
+            if value.get_type(context).is_some() {
+                namer.name(value);
+            }
+        }
+    }
+    let pointer_names: HashMap<Pointer, String> = function
+        .locals_iter(context)
+        .map(|(name, ptr)| (*ptr, name.clone()))
+        .collect();
+
+    let selector = function
+        .get_selector(context)
+        .map(|selector| {
+            format!(
+                "<0x{}>",
+                selector
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>()
+            )
+        })
+        .unwrap_or_default();
+    let args = function
+        .args_iter(context)
+        .map(|(name, value)| {
+            format!(
+                "{}: {}",
+                name,
+                value
+                    .get_type(context)
+                    .expect("arguments are typed")
+                    .as_string(context)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut buf = format!(
+        "    fn {}{}({}) -> {} {{\n",
+        function.get_name(context),
+        selector,
+        args,
+        function.get_return_type(context).as_string(context)
+    );
+
+    let mut has_locals = false;
+    for (name, ptr) in function.locals_iter(context) {
+        has_locals = true;
+        buf.push_str(&format!(
+            "        local {}ptr {} {}{}\n",
+            if ptr.is_mutable(context) { "mut " } else { "" },
+            ptr.get_type(context).as_string(context),
+            name,
+            ptr.get_initializer(context)
+                .map(|constant| format!(" = const {}", constant.as_string(context)))
+                .unwrap_or_default()
+        ));
+    }
+    if has_locals {
+        buf.push('\n');
+    }
+
+    for block in function.block_iter(context) {
+        buf.push_str(&format!("        {}:\n", block.get_label(context)));
+        for value in block.instruction_iter(context) {
+            instruction_to_string(context, value, &mut namer, &pointer_names, &mut buf);
+        }
+    }
+    buf.push_str("    }\n");
+    buf
+}
+
+fn instruction_to_string(
+    context: &Context,
+    value: Value,
+    namer: &mut Namer,
+    pointer_names: &HashMap<Pointer, String>,
+    buf: &mut String,
+) {
+    let instruction = value
+        .get_instruction(context)
+        .expect("blocks only contain instructions");
+    let label = |block: &Block| block.get_label(context);
+    let text = match instruction {
+        Instruction::AsmBlock(asm_block) => {
+            let args = asm_block
+                .args
+                .iter()
+                .map(|arg| match arg.initializer {
+                    Some(initializer) => format!(
+                        "{}: {}",
+                        arg.name.as_str(),
+                        namer.operand(context, initializer, buf)
+                    ),
+                    None => arg.name.as_str().to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let body = asm_block
+                .body
+                .iter()
+                .map(|op| {
+                    let operands = op
+                        .args
+                        .iter()
+                        .chain(op.immediate.iter())
+                        .map(|arg| format!(" {}", arg.as_str()))
+                        .collect::<String>();
+                    format!("            {}{};\n", op.name.as_str(), operands)
+                })
+                .collect::<String>();
+            format!(
+                "asm({}) -> {}{} {{\n{}        }}",
+                args,
+                asm_block.return_type.as_string(context),
+                asm_block
+                    .return_name
+                    .as_ref()
+                    .map(|name| format!(" {}", name.as_str()))
+                    .unwrap_or_default(),
+                body
+            )
+        }
+        Instruction::Branch(block) => format!("br {}", label(block)),
+        Instruction::Call(function, args) => format!(
+            "call {}({})",
+            function.get_name(context),
+            args.iter()
+                .map(|arg| namer.operand(context, *arg, buf))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Instruction::ConditionalBranch {
+            cond_value,
+            true_block,
+            false_block,
+        } => format!(
+            "cbr {}, {}, {}",
+            namer.operand(context, *cond_value, buf),
+            label(true_block),
+            label(false_block)
+        ),
+        Instruction::ExtractElement {
+            array,
+            ty,
+            index_val,
+        } => format!(
+            "extract_element {}, {}, {}",
+            namer.operand(context, *array, buf),
+            ty.as_string(context),
+            namer.operand(context, *index_val, buf)
+        ),
+        Instruction::ExtractValue {
+            aggregate,
+            ty,
+            indices,
+        } => format!(
+            "extract_value {}, {}, {}",
+            namer.operand(context, *aggregate, buf),
+            ty.as_string(context),
+            indices_to_string(indices)
+        ),
+        Instruction::InsertElement {
+            array,
+            ty,
+            value,
+            index_val,
+        } => format!(
+            "insert_element {}, {}, {}, {}",
+            namer.operand(context, *array, buf),
+            ty.as_string(context),
+            namer.operand(context, *value, buf),
+            namer.operand(context, *index_val, buf)
+        ),
+        Instruction::InsertValue {
+            aggregate,
+            ty,
+            value,
+            indices,
+        } => format!(
+            "insert_value {}, {}, {}, {}",
+            namer.operand(context, *aggregate, buf),
+            ty.as_string(context),
+            namer.operand(context, *value, buf),
+            indices_to_string(indices)
+        ),
+        Instruction::Load(ptr) => format!("load ptr {}", pointer_names[ptr]),
+        Instruction::Phi(entries) => format!(
+            "phi({})",
+            entries
+                .iter()
+                .map(|(block, value)| format!(
+                    "{}: {}",
+                    label(block),
+                    namer.operand(context, *value, buf)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Instruction::Ret(value, ty) => format!(
+            "ret {} {}",
+            ty.as_string(context),
+            namer.operand(context, *value, buf)
+        ),
+        Instruction::Store { ptr, stored_val } => format!(
+            "store {}, ptr {}",
+            namer.operand(context, *stored_val, buf),
+            pointer_names[ptr]
+        ),
+    };
+    match namer.names.get(&value) {
+        Some(name) => buf.push_str(&format!("        {} = {}\n", name, text)),
+        None => buf.push_str(&format!("        {}\n", text)),
+    }
+}
+
+fn indices_to_string(indices: &[u64]) -> String {
+    indices
+        .iter()
+        .map(|index| index.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
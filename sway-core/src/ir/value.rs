@@ -0,0 +1,67 @@
+//! A [Value] is a function argument, a constant or the result of an instruction.
+
+use super::{Constant, Context, Instruction, Type};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Value(pub(super) usize);
+
+#[derive(Clone, Debug)]
+pub(crate) enum ValueContent {
+    Argument(Type),
+    Constant(Constant),
+    Instruction(Instruction),
+}
+
+impl Value {
+    pub(crate) fn new_argument(context: &mut Context, ty: Type) -> Value {
+        Value::new(context, ValueContent::Argument(ty))
+    }
+
+    pub(crate) fn new_constant(context: &mut Context, constant: Constant) -> Value {
+        Value::new(context, ValueContent::Constant(constant))
+    }
+
+    /// Creates a new instruction value. It is not part of any block until it is added to one,
+    /// which is usually done with [super::Block::ins] instead.
+    pub(crate) fn new_instruction(context: &mut Context, instruction: Instruction) -> Value {
+        Value::new(context, ValueContent::Instruction(instruction))
+    }
+
+    fn new(context: &mut Context, content: ValueContent) -> Value {
+        context.values.push(content);
+        Value(context.values.len() - 1)
+    }
+
+    pub(crate) fn get_content<'a>(&self, context: &'a Context) -> &'a ValueContent {
+        &context.values[self.0]
+    }
+
+    pub(crate) fn get_instruction<'a>(&self, context: &'a Context) -> Option<&'a Instruction> {
+        match &context.values[self.0] {
+            ValueContent::Instruction(instruction) => Some(instruction),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_constant<'a>(&self, context: &'a Context) -> Option<&'a Constant> {
+        match &context.values[self.0] {
+            ValueContent::Constant(constant) => Some(constant),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_terminator(&self, context: &Context) -> bool {
+        self.get_instruction(context)
+            .map(|instruction| instruction.is_terminator())
+            .unwrap_or(false)
+    }
+
+    /// The type of this value, or `None` if it is an instruction which doesn't produce a value.
+    pub(crate) fn get_type(&self, context: &Context) -> Option<Type> {
+        match &context.values[self.0] {
+            ValueContent::Argument(ty) => Some(*ty),
+            ValueContent::Constant(constant) => Some(constant.ty),
+            ValueContent::Instruction(instruction) => instruction.get_type(context),
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Checks that IR is well formed: every block ends with its only terminator, phis come first and
+//! refer to predecessors, values and locals aren't shared between functions, and the operands of
+//! every instruction have the types it expects.
+
+use std::collections::{HashMap, HashSet};
+
+use super::*;
+
+impl Context {
+    pub(crate) fn verify(&self) -> Result<(), IrError> {
+        for module in self.module_iter() {
+            for function in module.function_iter(self) {
+                FunctionVerifier::new(self, function).verify()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct FunctionVerifier<'a> {
+    context: &'a Context,
+    function: Function,
+    /// The arguments of the function and the instructions of its blocks.
+    own_values: HashSet<Value>,
+    own_locals: HashSet<Pointer>,
+    predecessors: HashMap<Block, Vec<Block>>,
+}
+
+impl<'a> FunctionVerifier<'a> {
+    fn new(context: &'a Context, function: Function) -> Self {
+        let mut own_values: HashSet<Value> = function
+            .args_iter(context)
+            .map(|(_, value)| *value)
+            .collect();
+        let mut predecessors: HashMap<Block, Vec<Block>> = HashMap::new();
+        for block in function.block_iter(context) {
+            own_values.extend(block.instruction_iter(context));
+            for successor in block.successors(context) {
+                predecessors.entry(successor).or_default().push(block);
+            }
+        }
+        FunctionVerifier {
+            context,
+            function,
+            own_values,
+            own_locals: function.locals_iter(context).map(|(_, ptr)| *ptr).collect(),
+            predecessors,
+        }
+    }
+
+    fn function_name(&self) -> String {
+        self.function.get_name(self.context).to_string()
+    }
+
+    fn type_name(&self, ty: Option<Type>) -> String {
+        ty.map(|ty| ty.as_string(self.context))
+            .unwrap_or_else(|| "no value".into())
+    }
+
+    fn verify(&self) -> Result<(), IrError> {
+        for block in self.function.block_iter(self.context) {
+            self.verify_block(block)?;
+        }
+        Ok(())
+    }
+
+    fn verify_block(&self, block: Block) -> Result<(), IrError> {
+        let block_error = |make_error: fn(String, String) -> IrError| {
+            make_error(self.function_name(), block.get_label(self.context))
+        };
+        let instructions = block.instruction_iter(self.context);
+        if !block.is_terminated(self.context) {
+            return Err(block_error(|function, block| {
+                IrError::VerifyBlockMissingTerminator { function, block }
+            }));
+        }
+        let mut seen_non_phi = false;
+        for (ix, value) in instructions.iter().enumerate() {
+            let instruction = value
+                .get_instruction(self.context)
+                .expect("blocks only contain instructions");
+            if instruction.is_terminator() && ix != instructions.len() - 1 {
+                return Err(block_error(|function, block| {
+                    IrError::VerifyMisplacedTerminator { function, block }
+                }));
+            }
+            match instruction {
+                Instruction::Phi(_) if seen_non_phi => {
+                    return Err(block_error(|function, block| IrError::VerifyMisplacedPhi {
+                        function,
+                        block,
+                    }))
+                }
+                Instruction::Phi(_) => (),
+                _ => seen_non_phi = true,
+            }
+            for operand in instruction.operands() {
+                if operand.get_constant(self.context).is_none()
+                    && !self.own_values.contains(&operand)
+                {
+                    return Err(IrError::VerifyForeignValue {
+                        function: self.function_name(),
+                    });
+                }
+            }
+            self.verify_instruction(block, instruction)?;
+        }
+        for successor in block.successors(self.context) {
+            if successor.get_function(self.context) != self.function {
+                return Err(block_error(|function, block| {
+                    IrError::VerifyBranchToForeignBlock { function, block }
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `found` is `expected`.
+    fn expect_type(
+        &self,
+        instruction: &'static str,
+        expected: Type,
+        found: Option<Type>,
+    ) -> Result<(), IrError> {
+        if found == Some(expected) {
+            Ok(())
+        } else {
+            Err(IrError::VerifyMismatchedTypes {
+                function: self.function_name(),
+                instruction,
+                expected: expected.as_string(self.context),
+                found: self.type_name(found),
+            })
+        }
+    }
+
+    fn expect_uint(&self, instruction: &'static str, found: Option<Type>) -> Result<(), IrError> {
+        match found {
+            Some(Type::Uint(_)) => Ok(()),
+            found => Err(IrError::VerifyMismatchedTypes {
+                function: self.function_name(),
+                instruction,
+                expected: "an unsigned integer".into(),
+                found: self.type_name(found),
+            }),
+        }
+    }
+
+    fn expect_local(&self, ptr: &Pointer) -> Result<(), IrError> {
+        if self.own_locals.contains(ptr) {
+            Ok(())
+        } else {
+            Err(IrError::VerifyForeignPointer {
+                function: self.function_name(),
+            })
+        }
+    }
+
+    fn invalid_indices(&self, ty: &Type) -> IrError {
+        IrError::VerifyInvalidIndices {
+            function: self.function_name(),
+            ty: ty.as_string(self.context),
+        }
+    }
+
+    fn verify_instruction(&self, block: Block, instruction: &Instruction) -> Result<(), IrError> {
+        let context = self.context;
+        let type_of = |value: &Value| value.get_type(context);
+        match instruction {
+            Instruction::AsmBlock(_) | Instruction::Branch(_) => Ok(()),
+            Instruction::Call(callee, args) => {
+                let params = callee.args_iter(context).collect::<Vec<_>>();
+                if params.len() != args.len() {
+                    return Err(IrError::VerifyCallArgumentCount {
+                        function: self.function_name(),
+                        callee: callee.get_name(context).to_string(),
+                    });
+                }
+                for ((_, param), arg) in params.into_iter().zip(args) {
+                    self.expect_type(
+                        "call",
+                        type_of(param).expect("arguments are typed"),
+                        type_of(arg),
+                    )?;
+                }
+                Ok(())
+            }
+            Instruction::ConditionalBranch { cond_value, .. } => {
+                self.expect_type("cbr", Type::Bool, type_of(cond_value))
+            }
+            Instruction::ExtractElement {
+                array,
+                ty,
+                index_val,
+            } => {
+                ty.get_element_type(context)
+                    .ok_or_else(|| self.invalid_indices(ty))?;
+                self.expect_type("extract_element", *ty, type_of(array))?;
+                self.expect_uint("extract_element", type_of(index_val))
+            }
+            Instruction::ExtractValue {
+                aggregate,
+                ty,
+                indices,
+            } => {
+                if indices.is_empty() || ty.get_indexed_type(context, indices).is_none() {
+                    return Err(self.invalid_indices(ty));
+                }
+                self.expect_type("extract_value", *ty, type_of(aggregate))
+            }
+            Instruction::InsertElement {
+                array,
+                ty,
+                value,
+                index_val,
+            } => {
+                let element_type = ty
+                    .get_element_type(context)
+                    .ok_or_else(|| self.invalid_indices(ty))?;
+                self.expect_type("insert_element", *ty, type_of(array))?;
+                self.expect_type("insert_element", element_type, type_of(value))?;
+                self.expect_uint("insert_element", type_of(index_val))
+            }
+            Instruction::InsertValue {
+                aggregate,
+                ty,
+                value,
+                indices,
+            } => {
+                let field_type = match ty.get_indexed_type(context, indices) {
+                    Some(field_type) if !indices.is_empty() => field_type,
+                    _ => return Err(self.invalid_indices(ty)),
+                };
+                self.expect_type("insert_value", *ty, type_of(aggregate))?;
+                self.expect_type("insert_value", field_type, type_of(value))
+            }
+            Instruction::Load(ptr) => self.expect_local(ptr),
+            Instruction::Phi(entries) => {
+                let predecessors = self.predecessors.get(&block).cloned().unwrap_or_default();
+                for (from, _) in entries {
+                    if !predecessors.contains(from) {
+                        return Err(IrError::VerifyPhiFromNonPredecessor {
+                            function: self.function_name(),
+                            block: block.get_label(context),
+                            from: from.get_label(context),
+                        });
+                    }
+                }
+                match entries.first() {
+                    Some((_, first)) => {
+                        let ty = type_of(first).expect("phi entries are typed");
+                        entries
+                            .iter()
+                            .try_for_each(|(_, value)| self.expect_type("phi", ty, type_of(value)))
+                    }
+                    None => Ok(()),
+                }
+            }
+            Instruction::Ret(value, ty) => {
+                self.expect_type("ret", self.function.get_return_type(context), Some(*ty))?;
+                self.expect_type("ret", *ty, type_of(value))
+            }
+            Instruction::Store { ptr, stored_val } => {
+                self.expect_local(ptr)?;
+                self.expect_type("store", ptr.get_type(context), type_of(stored_val))
+            }
+        }
+    }
+}
+
+#[test]
+fn verify_rejects_ill_formed_ir() {
+    let verify = |text: &str| {
+        super::parser::parse(text)
+            .expect("failed to parse IR")
+            .verify()
+    };
+
+    let missing_terminator = "script {
+    fn main() -> u64 {
+        entry:
+        v0 = const u64 0
+    }
+}";
+    assert!(matches!(
+        verify(missing_terminator),
+        Err(IrError::VerifyBlockMissingTerminator { .. })
+    ));
+
+    let mismatched_return = "script {
+    fn main() -> u64 {
+        entry:
+        v0 = const bool true
+        ret u64 v0
+    }
+}";
+    assert!(matches!(
+        verify(mismatched_return),
+        Err(IrError::VerifyMismatchedTypes {
+            instruction: "ret",
+            ..
+        })
+    ));
+
+    let phi_from_non_predecessor = "script {
+    fn main() -> u64 {
+        entry:
+        v0 = const u64 0
+        br end
+        unreachable:
+        br end
+        other:
+        ret u64 v0
+        end:
+        v1 = phi(other: v0)
+        ret u64 v1
+    }
+}";
+    assert!(matches!(
+        verify(phi_from_non_predecessor),
+        Err(IrError::VerifyPhiFromNonPredecessor { .. })
+    ));
+}
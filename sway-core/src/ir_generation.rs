@@ -0,0 +1,977 @@
+//! Lowers the typed AST to the IR in [crate::ir].
+//!
+//! The entry functions of the program, i.e. `main` or the ABI functions of a contract, are
+//! lowered first. Every function they call is given its own IR function, which is created the
+//! first time it is called and lowered once the function which called it is done. Monomorphized
+//! copies of a generic function share the span of their body, as do the methods the compiler
+//! synthesizes for a type, so, like in the ASM generator, the name and the argument and return
+//! types are part of the key which identifies a function. A function which was type checked
+//! before another which it is mutually recursive with calls that function without its body, so
+//! the bodies of the functions in the program are also looked up by span.
+//!
+//! Immutable variables are bound directly to the value they are initialized with, while mutable
+//! variables become locals which are accessed with `load` and `store`. Global constants are
+//! lowered at every use.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::*,
+    ir::*,
+    parse_tree::{LazyOp, Literal},
+    semantic_analysis::{
+        ast_node::{
+            TypedAsmRegisterDeclaration, TypedCodeBlock, TypedExpressionVariant, TypedForLoop,
+            TypedReassignment, TypedReturnStatement, TypedStructExpressionField,
+            TypedVariableDeclaration, TypedWhileLoop,
+        },
+        Namespace, TypedAstNode, TypedAstNodeContent, TypedDeclaration, TypedExpression,
+        TypedFunctionDeclaration, TypedParseTree,
+    },
+    type_engine::{resolve_type, IntegerBits, TypeId, TypeInfo},
+    Ident, Span,
+};
+
+pub(crate) fn compile_ast(ast: &TypedParseTree) -> CompileResult<Context> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let (kind, namespace, declarations, entry_functions) = match ast {
+        TypedParseTree::Script {
+            main_function,
+            namespace,
+            declarations,
+            ..
+        } => (
+            module::Kind::Script,
+            namespace,
+            &declarations[..],
+            std::slice::from_ref(main_function),
+        ),
+        TypedParseTree::Predicate {
+            main_function,
+            namespace,
+            declarations,
+            ..
+        } => (
+            module::Kind::Predicate,
+            namespace,
+            &declarations[..],
+            std::slice::from_ref(main_function),
+        ),
+        TypedParseTree::Contract {
+            abi_entries,
+            namespace,
+            declarations,
+            ..
+        } => (
+            module::Kind::Contract,
+            namespace,
+            &declarations[..],
+            &abi_entries[..],
+        ),
+        TypedParseTree::Library { namespace, .. } => {
+            (module::Kind::Library, namespace, &[][..], &[][..])
+        }
+    };
+
+    let mut compiler = ModuleCompiler::new(kind);
+    compiler.add_constants(declarations, namespace);
+    compiler.add_definitions(namespace);
+    for decl in entry_functions {
+        let selector = if kind == module::Kind::Contract {
+            // there are currently four parameters to every ABI function: the gas, the coin
+            // balance and the coin color which were forwarded by the caller, and the user
+            // argument
+            if decl.parameters.len() != 4 {
+                errors.push(CompileError::InvalidNumberOfAbiParams {
+                    span: decl.parameters_span(),
+                });
+                continue;
+            }
+            Some(check!(
+                decl.to_fn_selector_value(),
+                [0; 4],
+                warnings,
+                errors
+            ))
+        } else {
+            None
+        };
+        if let Err(e) = compiler.compile_entry_function(decl, selector) {
+            errors.push(e);
+        }
+    }
+    if let Err(e) = compiler.compile_called_functions() {
+        errors.push(e);
+    }
+    warnings.append(&mut compiler.warnings);
+    if errors.is_empty() {
+        // ill formed IR is a bug in the lowering above rather than in the program
+        if let (Err(e), Some(decl)) = (compiler.context.verify(), entry_functions.first()) {
+            errors.push(CompileError::InternalOwned(
+                e.to_string(),
+                decl.span.clone(),
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        ok(compiler.context, warnings, errors)
+    } else {
+        err(warnings, errors)
+    }
+}
+
+/// Identifies a function which has been called. See the module documentation.
+type FunctionKey = (Span, String, String);
+
+struct ModuleCompiler<'a> {
+    context: Context,
+    module: Module,
+    warnings: Vec<CompileWarning>,
+    /// The global constants, which are lowered wherever they are used.
+    constants: HashMap<Ident, &'a TypedExpression>,
+    functions: HashMap<FunctionKey, Function>,
+    /// The bodies of the non-generic functions declared in the program, by their spans.
+    definitions: HashMap<Span, &'a TypedCodeBlock>,
+    /// Functions which have been called but not yet lowered, along with the names of their
+    /// parameters and their bodies.
+    pending: Vec<(Function, Vec<Ident>, &'a TypedCodeBlock)>,
+}
+
+impl<'a> ModuleCompiler<'a> {
+    fn new(kind: module::Kind) -> Self {
+        let mut context = Context::default();
+        let module = Module::new(&mut context, kind);
+        ModuleCompiler {
+            context,
+            module,
+            warnings: vec![],
+            constants: HashMap::new(),
+            functions: HashMap::new(),
+            definitions: HashMap::new(),
+            pending: vec![],
+        }
+    }
+
+    /// Adds the constants declared in `declarations` and in every module imported by
+    /// `namespace`, recursively.
+    fn add_constants(&mut self, declarations: &'a [TypedDeclaration], namespace: &'a Namespace) {
+        for declaration in declarations {
+            if let TypedDeclaration::ConstantDeclaration(decl) = declaration {
+                self.constants.insert(decl.name.clone(), &decl.value);
+            }
+        }
+        for module in namespace.get_all_imported_modules() {
+            for declaration in module.get_all_declared_symbols() {
+                if let TypedDeclaration::ConstantDeclaration(decl) = declaration {
+                    self.constants.insert(decl.name.clone(), &decl.value);
+                }
+            }
+            self.add_constants(&[], module);
+        }
+    }
+
+    /// Adds the bodies of the non-generic functions declared in `namespace` and in every module
+    /// it imports, recursively.
+    fn add_definitions(&mut self, namespace: &'a Namespace) {
+        for declaration in namespace.get_all_declared_symbols() {
+            if let TypedDeclaration::FunctionDeclaration(decl) = declaration {
+                if decl.type_parameters.is_empty() {
+                    self.definitions
+                        .insert(decl.body.whole_block_span.clone(), &decl.body);
+                }
+            }
+        }
+        for module in namespace.get_all_imported_modules() {
+            self.add_definitions(module);
+        }
+    }
+
+    fn compile_entry_function(
+        &mut self,
+        decl: &'a TypedFunctionDeclaration,
+        selector: Option<[u8; 4]>,
+    ) -> Result<(), CompileError> {
+        let args = decl
+            .parameters
+            .iter()
+            .map(|param| {
+                Ok((
+                    param.name.as_str().to_string(),
+                    convert_type(&mut self.context, param.r#type, &param.type_span)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, CompileError>>()?;
+        let return_type =
+            convert_type(&mut self.context, decl.return_type, &decl.return_type_span)?;
+        let function = Function::new(
+            &mut self.context,
+            self.module,
+            decl.name.as_str().to_string(),
+            args,
+            return_type,
+            selector,
+        );
+        let param_names = decl
+            .parameters
+            .iter()
+            .map(|param| param.name.clone())
+            .collect();
+        self.compile_function_body(function, param_names, &decl.body)
+    }
+
+    fn compile_called_functions(&mut self) -> Result<(), CompileError> {
+        while let Some((function, param_names, body)) = self.pending.pop() {
+            self.compile_function_body(function, param_names, body)?;
+        }
+        Ok(())
+    }
+
+    fn compile_function_body(
+        &mut self,
+        function: Function,
+        param_names: Vec<Ident>,
+        body: &'a TypedCodeBlock,
+    ) -> Result<(), CompileError> {
+        let params = param_names
+            .into_iter()
+            .zip(function.args_iter(&self.context).map(|(_, value)| *value))
+            .map(|(name, value)| (name, Binding::Value(value)))
+            .collect();
+        let mut compiler = FnCompiler {
+            current_block: function.get_entry_block(&self.context),
+            module: self,
+            function,
+            dead_blocks: HashSet::new(),
+            loops: vec![],
+            scopes: vec![params],
+        };
+        let value = compiler.compile_code_block(body)?;
+        compiler.finish(value);
+        Ok(())
+    }
+
+    /// Returns the function with body `function_body` which is being applied to `arguments`,
+    /// creating it if it hasn't been called before.
+    fn get_or_create_function(
+        &mut self,
+        name: &str,
+        arguments: &'a [(Ident, TypedExpression)],
+        function_body: &'a TypedCodeBlock,
+        return_type: TypeId,
+        span: &Span,
+    ) -> Result<Function, CompileError> {
+        let signature = arguments
+            .iter()
+            .map(|(_, arg)| resolve_type(arg.return_type, &arg.span))
+            .chain(std::iter::once(resolve_type(return_type, span)))
+            .map(|ty| ty.map(|ty| ty.friendly_type_str()))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", ");
+        let key = (
+            function_body.whole_block_span.clone(),
+            name.to_string(),
+            signature,
+        );
+        if let Some(function) = self.functions.get(&key) {
+            return Ok(*function);
+        }
+        let function_body = match self.definitions.get(&function_body.whole_block_span) {
+            Some(definition) if function_body.contents.is_empty() => *definition,
+            _ => function_body,
+        };
+
+        let args = arguments
+            .iter()
+            .map(|(name, arg)| {
+                Ok((
+                    name.as_str().to_string(),
+                    convert_type(&mut self.context, arg.return_type, &arg.span)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, CompileError>>()?;
+        let return_type = convert_type(&mut self.context, return_type, span)?;
+        let name = self.unique_function_name(name);
+        let function = Function::new(
+            &mut self.context,
+            self.module,
+            name,
+            args,
+            return_type,
+            None,
+        );
+        self.functions.insert(key, function);
+        self.pending.push((
+            function,
+            arguments.iter().map(|(name, _)| name.clone()).collect(),
+            function_body,
+        ));
+        Ok(function)
+    }
+
+    fn unique_function_name(&self, name: &str) -> String {
+        let is_taken = |name: &str| self.module.get_function(&self.context, name).is_some();
+        if !is_taken(name) {
+            return name.to_string();
+        }
+        (0..)
+            .map(|n| format!("{}_{}", name, n))
+            .find(|name| !is_taken(name))
+            .unwrap()
+    }
+}
+
+/// What a name in scope refers to.
+#[derive(Clone, Copy)]
+enum Binding {
+    Value(Value),
+    Local(Pointer),
+}
+
+struct FnCompiler<'m, 'a> {
+    module: &'m mut ModuleCompiler<'a>,
+    function: Function,
+    /// The block which instructions are currently appended to.
+    current_block: Block,
+    /// Blocks which can't be reached, because they follow a return. Values which flow out of
+    /// them are ignored.
+    dead_blocks: HashSet<Block>,
+    /// The condition and exit blocks of the loops being compiled, innermost last, which
+    /// `continue` and `break` branch to.
+    loops: Vec<(Block, Block)>,
+    scopes: Vec<HashMap<Ident, Binding>>,
+}
+
+impl<'m, 'a> FnCompiler<'m, 'a> {
+    fn context(&mut self) -> &mut Context {
+        &mut self.module.context
+    }
+
+    fn ins(&mut self) -> InstructionInserter<'_> {
+        self.current_block.ins(&mut self.module.context)
+    }
+
+    fn create_block(&mut self, label: &str) -> Block {
+        self.function
+            .create_block(&mut self.module.context, Some(label.to_string()))
+    }
+
+    fn convert_type(&mut self, ty: TypeId, span: &Span) -> Result<Type, CompileError> {
+        convert_type(&mut self.module.context, ty, span)
+    }
+
+    /// Returns from the function with the value of its body, unless it has already returned.
+    fn finish(mut self, value: Value) {
+        if self.current_block.is_terminated(&self.module.context) {
+            return;
+        }
+        let return_type = self.function.get_return_type(&self.module.context);
+        let value = if self.dead_blocks.contains(&self.current_block) {
+            Constant::get_undef(self.context(), return_type)
+        } else if return_type == Type::Unit {
+            Constant::get_unit(self.context())
+        } else {
+            value
+        };
+        self.ins().ret(value, return_type);
+    }
+
+    fn compile_code_block(&mut self, block: &'a TypedCodeBlock) -> Result<Value, CompileError> {
+        self.scopes.push(HashMap::new());
+        let mut value = None;
+        for node in &block.contents {
+            if let Some(node_value) = self.compile_ast_node(node)? {
+                value = Some(node_value);
+            }
+        }
+        self.scopes.pop();
+        Ok(value.unwrap_or_else(|| Constant::get_unit(self.context())))
+    }
+
+    /// Lowers `node`, returning its value if it is an implicit return.
+    fn compile_ast_node(&mut self, node: &'a TypedAstNode) -> Result<Option<Value>, CompileError> {
+        match &node.content {
+            TypedAstNodeContent::ReturnStatement(TypedReturnStatement { expr }) => {
+                let value = self.compile_expression(expr)?;
+                let return_type = self.function.get_return_type(&self.module.context);
+                self.ins().ret(value, return_type);
+                // anything which follows the return is unreachable
+                self.current_block = self.create_block("after_return");
+                self.dead_blocks.insert(self.current_block);
+                Ok(None)
+            }
+            TypedAstNodeContent::Declaration(decl) => {
+                self.compile_declaration(decl)?;
+                Ok(None)
+            }
+            TypedAstNodeContent::Expression(exp) => {
+                self.compile_expression(exp)?;
+                Ok(None)
+            }
+            TypedAstNodeContent::ImplicitReturnExpression(exp) => {
+                self.compile_expression(exp).map(Some)
+            }
+            TypedAstNodeContent::WhileLoop(r#loop) => {
+                self.compile_while_loop(r#loop)?;
+                Ok(None)
+            }
+            TypedAstNodeContent::ForLoop(TypedForLoop { setup, r#loop }) => {
+                // the setup only declares variables, which are scoped to the loop
+                self.scopes.push(HashMap::new());
+                for node in setup {
+                    self.compile_ast_node(node)?;
+                }
+                self.compile_while_loop(r#loop)?;
+                self.scopes.pop();
+                Ok(None)
+            }
+            TypedAstNodeContent::Break | TypedAstNodeContent::Continue => {
+                let (cond_block, end_block) =
+                    match self.loops.last() {
+                        Some(blocks) => *blocks,
+                        None => return Err(CompileError::Internal(
+                            "Loop control flow outside of a loop in IR generation. This should \
+                             have been an error during type checking.",
+                            node.span.clone(),
+                        )),
+                    };
+                if let TypedAstNodeContent::Break = node.content {
+                    self.ins().branch(end_block);
+                } else {
+                    self.ins().branch(cond_block);
+                }
+                // anything which follows the jump is unreachable
+                self.current_block = self.create_block("after_loop_control_flow");
+                self.dead_blocks.insert(self.current_block);
+                Ok(None)
+            }
+            TypedAstNodeContent::SideEffect => Ok(None),
+        }
+    }
+
+    fn compile_while_loop(&mut self, r#loop: &'a TypedWhileLoop) -> Result<(), CompileError> {
+        let TypedWhileLoop { condition, body } = r#loop;
+        let cond_block = self.create_block("while");
+        self.ins().branch(cond_block);
+        self.current_block = cond_block;
+        let cond_value = self.compile_expression(condition)?;
+        let body_block = self.create_block("while_body");
+        let end_block = self.create_block("end_while");
+        self.ins()
+            .conditional_branch(cond_value, body_block, end_block);
+
+        self.current_block = body_block;
+        self.loops.push((cond_block, end_block));
+        let body = self.compile_code_block(body);
+        self.loops.pop();
+        body?;
+        self.ins().branch(cond_block);
+
+        self.current_block = end_block;
+        Ok(())
+    }
+
+    fn compile_declaration(&mut self, decl: &'a TypedDeclaration) -> Result<(), CompileError> {
+        match decl {
+            TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                name,
+                body,
+                is_mutable,
+                ..
+            }) => {
+                let value = self.compile_expression(body)?;
+                let binding = if *is_mutable {
+                    let ty = self.convert_type(body.return_type, &body.span)?;
+                    let function = self.function;
+                    let (_, ptr) =
+                        function.new_local_ptr(self.context(), name.as_str(), ty, true, None);
+                    self.ins().store(ptr, value);
+                    Binding::Local(ptr)
+                } else {
+                    Binding::Value(value)
+                };
+                self.bind(name.clone(), binding);
+            }
+            TypedDeclaration::ConstantDeclaration(decl) => {
+                let value = self.compile_expression(&decl.value)?;
+                self.bind(decl.name.clone(), Binding::Value(value));
+            }
+            TypedDeclaration::Reassignment(TypedReassignment { lhs, rhs }) => {
+                let value = self.compile_expression(rhs)?;
+                let ptr =
+                    match self.look_up(&lhs[0].name) {
+                        Some(Binding::Local(ptr)) => ptr,
+                        _ => return Err(CompileError::Internal(
+                            "Reassignment of a variable which is not mutable in IR generation. \
+                             This should have been an error during type checking.",
+                            lhs[0].span(),
+                        )),
+                    };
+                if lhs.len() == 1 {
+                    self.ins().store(ptr, value);
+                } else {
+                    // a field is being reassigned, so the whole variable is rebuilt with the
+                    // new value in place of the field
+                    let indices = lhs
+                        .windows(2)
+                        .map(|pair| {
+                            field_index(pair[0].r#type, pair[1].name.as_str(), &pair[1].span())
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let ty = ptr.get_type(&self.module.context);
+                    let aggregate = self.ins().load(ptr);
+                    let aggregate = self.ins().insert_value(aggregate, ty, value, indices);
+                    self.ins().store(ptr, aggregate);
+                }
+            }
+            TypedDeclaration::StorageReassignment(reassignment) => {
+                return Err(CompileError::Unimplemented(
+                    "Storage is not yet supported by the IR.",
+                    reassignment.rhs.span.clone(),
+                ))
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn bind(&mut self, name: Ident, binding: Binding) {
+        self.scopes
+            .last_mut()
+            .expect("there is always a scope")
+            .insert(name, binding);
+    }
+
+    fn look_up(&self, name: &Ident) -> Option<Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+    }
+
+    fn compile_expression(&mut self, exp: &'a TypedExpression) -> Result<Value, CompileError> {
+        match &exp.expression {
+            TypedExpressionVariant::Literal(literal) => {
+                let ty = self.convert_type(exp.return_type, &exp.span)?;
+                Ok(self.compile_literal(literal, ty))
+            }
+            TypedExpressionVariant::FunctionApplication {
+                name,
+                arguments,
+                function_body,
+                selector,
+                ..
+            } => {
+                if selector.is_some() {
+                    return Err(CompileError::Unimplemented(
+                        "Contract calls are not yet supported by the IR.",
+                        exp.span.clone(),
+                    ));
+                }
+                let args = arguments
+                    .iter()
+                    .map(|(_, arg)| self.compile_expression(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let function = self.module.get_or_create_function(
+                    name.suffix.as_str(),
+                    arguments,
+                    function_body,
+                    exp.return_type,
+                    &exp.span,
+                )?;
+                Ok(self.ins().call(function, &args))
+            }
+            TypedExpressionVariant::LazyOperator { op, lhs, rhs } => {
+                let lhs_value = self.compile_expression(lhs)?;
+                let lhs_block = self.current_block;
+                let rhs_block = self.create_block("lazy_rhs");
+                let end_block = self.create_block("lazy_end");
+                match op {
+                    LazyOp::And => self
+                        .ins()
+                        .conditional_branch(lhs_value, rhs_block, end_block),
+                    LazyOp::Or => self
+                        .ins()
+                        .conditional_branch(lhs_value, end_block, rhs_block),
+                };
+                self.current_block = rhs_block;
+                let rhs_value = self.compile_expression(rhs)?;
+                let rhs_end_block = self.current_block;
+                self.ins().branch(end_block);
+
+                self.current_block = end_block;
+                Ok(self.merge(
+                    vec![(lhs_block, lhs_value), (rhs_end_block, rhs_value)],
+                    Type::Bool,
+                ))
+            }
+            TypedExpressionVariant::VariableExpression { name } => match self.look_up(name) {
+                Some(Binding::Value(value)) => Ok(value),
+                Some(Binding::Local(ptr)) => Ok(self.ins().load(ptr)),
+                None => match self.module.constants.get(name).copied() {
+                    Some(constant) => self.compile_expression(constant),
+                    None => Err(CompileError::Internal(
+                        "Unknown variable in IR generation. This should have been an error \
+                         during type checking.",
+                        name.span().clone(),
+                    )),
+                },
+            },
+            TypedExpressionVariant::Tuple { fields } => {
+                let ty = self.convert_type(exp.return_type, &exp.span)?;
+                if fields.is_empty() {
+                    return Ok(Constant::get_unit(self.context()));
+                }
+                let mut tuple = Constant::get_undef(self.context(), ty);
+                for (ix, field) in fields.iter().enumerate() {
+                    let value = self.compile_expression(field)?;
+                    tuple = self.ins().insert_value(tuple, ty, value, vec![ix as u64]);
+                }
+                Ok(tuple)
+            }
+            TypedExpressionVariant::Array { contents } => {
+                let ty = self.convert_type(exp.return_type, &exp.span)?;
+                let mut array = Constant::get_undef(self.context(), ty);
+                for (ix, element) in contents.iter().enumerate() {
+                    let value = self.compile_expression(element)?;
+                    let index = Constant::get_uint(self.context(), 64, ix as u64);
+                    array = self.ins().insert_element(array, ty, value, index);
+                }
+                Ok(array)
+            }
+            TypedExpressionVariant::ArrayIndex { prefix, index } => {
+                let ty = self.convert_type(prefix.return_type, &prefix.span)?;
+                let array = self.compile_expression(prefix)?;
+                let index = self.compile_expression(index)?;
+                Ok(self.ins().extract_element(array, ty, index))
+            }
+            TypedExpressionVariant::StructExpression { fields, .. } => {
+                let ty = self.convert_type(exp.return_type, &exp.span)?;
+                let mut aggregate = Constant::get_undef(self.context(), ty);
+                for TypedStructExpressionField { name, value } in fields {
+                    let index = field_index(exp.return_type, name.as_str(), name.span())?;
+                    let value = self.compile_expression(value)?;
+                    aggregate = self.ins().insert_value(aggregate, ty, value, vec![index]);
+                }
+                Ok(aggregate)
+            }
+            TypedExpressionVariant::CodeBlock(block) => self.compile_code_block(block),
+            TypedExpressionVariant::IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                let cond_value = self.compile_expression(condition)?;
+                let then_block = self.create_block("then");
+                let else_block = self.create_block("else");
+                self.ins()
+                    .conditional_branch(cond_value, then_block, else_block);
+
+                self.current_block = then_block;
+                let then_value = self.compile_expression(then)?;
+                let then_end_block = self.current_block;
+
+                self.current_block = else_block;
+                let else_value = match r#else {
+                    Some(r#else) => self.compile_expression(r#else)?,
+                    None => Constant::get_unit(self.context()),
+                };
+                let else_end_block = self.current_block;
+
+                let end_block = self.create_block("end_if");
+                then_end_block.ins(self.context()).branch(end_block);
+                else_end_block.ins(self.context()).branch(end_block);
+                self.current_block = end_block;
+                let ty = self.convert_type(exp.return_type, &exp.span)?;
+                Ok(self.merge(
+                    vec![(then_end_block, then_value), (else_end_block, else_value)],
+                    ty,
+                ))
+            }
+            TypedExpressionVariant::AsmExpression {
+                registers,
+                body,
+                returns,
+                whole_block_span,
+            } => {
+                let mut args = Vec::with_capacity(registers.len());
+                for TypedAsmRegisterDeclaration { name, initializer } in registers {
+                    let warnings = &mut self.module.warnings;
+                    assert_or_warn!(
+                        crate::asm_lang::ConstantRegister::parse_register_name(name.as_str())
+                            .is_none(),
+                        warnings,
+                        name.span().clone(),
+                        Warning::ShadowingReservedRegister {
+                            reg_name: name.clone()
+                        }
+                    );
+                    let initializer = match initializer {
+                        Some(initializer) => Some(self.compile_expression(initializer)?),
+                        None => None,
+                    };
+                    args.push(AsmArg {
+                        name: name.clone(),
+                        initializer,
+                    });
+                }
+                let return_type = self.convert_type(exp.return_type, &exp.span)?;
+                let return_name = returns.as_ref().map(|(_, span)| Ident::new(span.clone()));
+                if return_name.is_none() && return_type != Type::Unit {
+                    return Err(CompileError::InvalidAssemblyMismatchedReturn {
+                        span: whole_block_span.clone(),
+                    });
+                }
+                let body = body
+                    .iter()
+                    .map(|op| AsmInstruction {
+                        name: op.op_name.clone(),
+                        args: op.op_args.clone(),
+                        immediate: op.immediate.clone(),
+                    })
+                    .collect();
+                Ok(self.ins().asm_block(AsmBlock {
+                    args,
+                    body,
+                    return_name,
+                    return_type,
+                }))
+            }
+            TypedExpressionVariant::StructFieldAccess {
+                prefix,
+                field_to_access,
+                field_to_access_span,
+                resolved_type_of_parent,
+            } => {
+                let ty = self.convert_type(*resolved_type_of_parent, &prefix.span)?;
+                let index = field_index(
+                    *resolved_type_of_parent,
+                    field_to_access.name.as_str(),
+                    field_to_access_span,
+                )?;
+                let aggregate = self.compile_expression(prefix)?;
+                Ok(self.ins().extract_value(aggregate, ty, vec![index]))
+            }
+            TypedExpressionVariant::TupleElemAccess {
+                prefix,
+                elem_to_access_num,
+                resolved_type_of_parent,
+                ..
+            } => {
+                let ty = self.convert_type(*resolved_type_of_parent, &prefix.span)?;
+                let aggregate = self.compile_expression(prefix)?;
+                Ok(self
+                    .ins()
+                    .extract_value(aggregate, ty, vec![*elem_to_access_num as u64]))
+            }
+            // an enum is its tag followed by the union of its variants
+            TypedExpressionVariant::EnumArgAccess {
+                prefix,
+                tag,
+                resolved_type_of_parent,
+                ..
+            } => {
+                let ty = self.convert_type(*resolved_type_of_parent, &prefix.span)?;
+                let aggregate = self.compile_expression(prefix)?;
+                Ok(self
+                    .ins()
+                    .extract_value(aggregate, ty, vec![1, *tag as u64]))
+            }
+            TypedExpressionVariant::EnumTag { exp: prefix } => {
+                let ty = self.convert_type(prefix.return_type, &prefix.span)?;
+                let aggregate = self.compile_expression(prefix)?;
+                Ok(self.ins().extract_value(aggregate, ty, vec![0]))
+            }
+            TypedExpressionVariant::EnumInstantiation { tag, contents, .. } => {
+                let ty = self.convert_type(exp.return_type, &exp.span)?;
+                let tag_value = Constant::get_uint(self.context(), 64, *tag as u64);
+                let undef = Constant::get_undef(self.context(), ty);
+                let mut aggregate = self.ins().insert_value(undef, ty, tag_value, vec![0]);
+                if let Some(contents) = contents {
+                    let value = self.compile_expression(contents)?;
+                    aggregate = self
+                        .ins()
+                        .insert_value(aggregate, ty, value, vec![1, *tag as u64]);
+                }
+                Ok(aggregate)
+            }
+            // ABI casts are purely compile-time constructs
+            TypedExpressionVariant::AbiCast { .. } => Ok(Constant::get_unit(self.context())),
+            TypedExpressionVariant::StorageAccess { .. }
+            | TypedExpressionVariant::StorageMapMethod(_) => Err(CompileError::Unimplemented(
+                "Storage is not yet supported by the IR.",
+                exp.span.clone(),
+            )),
+            TypedExpressionVariant::Revert { code } => {
+                let span = exp.span.clone();
+                let code = Constant::get_uint(self.context(), 64, *code);
+                Ok(self.ins().asm_block(AsmBlock {
+                    args: vec![AsmArg {
+                        name: Ident::new_with_override("code", span.clone()),
+                        initializer: Some(code),
+                    }],
+                    body: vec![AsmInstruction {
+                        name: Ident::new_with_override("rvrt", span.clone()),
+                        args: vec![Ident::new_with_override("code", span)],
+                        immediate: None,
+                    }],
+                    return_name: None,
+                    return_type: Type::Unit,
+                }))
+            }
+            TypedExpressionVariant::SignedIntegerOp(_) => Err(CompileError::Unimplemented(
+                "Signed integers are not yet supported by the IR.",
+                exp.span.clone(),
+            )),
+            TypedExpressionVariant::FunctionParameter => Err(CompileError::Unimplemented(
+                "IR generation has not yet been implemented for this.",
+                exp.span.clone(),
+            )),
+        }
+    }
+
+    fn compile_literal(&mut self, literal: &Literal, ty: Type) -> Value {
+        let constant = match (literal, ty) {
+            // the type of an integer literal is inferred, so it may differ from the literal's own
+            (Literal::U8(n), Type::Uint(bits)) => Constant::new_uint(bits, *n as u64),
+            (Literal::U16(n), Type::Uint(bits)) => Constant::new_uint(bits, *n as u64),
+            (Literal::U32(n), Type::Uint(bits)) => Constant::new_uint(bits, *n as u64),
+            (Literal::U64(n), Type::Uint(bits)) => Constant::new_uint(bits, *n),
+            (Literal::Byte(n), _) => Constant::new_uint(8, *n as u64),
+            (Literal::U8(n), _) => Constant::new_uint(8, *n as u64),
+            (Literal::U16(n), _) => Constant::new_uint(16, *n as u64),
+            (Literal::U32(n), _) => Constant::new_uint(32, *n as u64),
+            (Literal::U64(n), _) => Constant::new_uint(64, *n),
+            // signed integers are sign extended to a whole word
+            (Literal::I8(n), _) => Constant::new_uint(64, *n as u64),
+            (Literal::I16(n), _) => Constant::new_uint(64, *n as u64),
+            (Literal::I32(n), _) => Constant::new_uint(64, *n as u64),
+            (Literal::I64(n), _) => Constant::new_uint(64, *n as u64),
+            (Literal::String(s), _) => Constant::new_string(s.as_str().as_bytes().to_vec()),
+            (Literal::Boolean(b), _) => Constant::new_bool(*b),
+            (Literal::B256(bytes), _) => Constant::new_b256(*bytes),
+        };
+        Value::new_constant(self.context(), constant)
+    }
+
+    /// Merges the values which flow into the current block from `incoming`, ignoring those
+    /// which come from dead blocks. If every incoming block is dead, so is the current one.
+    fn merge(&mut self, incoming: Vec<(Block, Value)>, ty: Type) -> Value {
+        let live = incoming
+            .into_iter()
+            .filter(|(block, _)| !self.dead_blocks.contains(block))
+            .collect::<Vec<_>>();
+        if live.is_empty() {
+            self.dead_blocks.insert(self.current_block);
+            return Constant::get_undef(self.context(), ty);
+        }
+        if ty == Type::Unit {
+            return Constant::get_unit(self.context());
+        }
+        self.ins().phi(live)
+    }
+}
+
+/// The index of the field `name` in the struct or tuple type `ty`.
+fn field_index(ty: TypeId, name: &str, span: &Span) -> Result<u64, CompileError> {
+    let position = match resolve_type(ty, span)? {
+        TypeInfo::Struct { fields, .. } => {
+            fields.iter().position(|field| field.name.as_str() == name)
+        }
+        TypeInfo::Tuple(fields) => name.parse::<usize>().ok().filter(|ix| *ix < fields.len()),
+        _ => None,
+    };
+    position.map(|ix| ix as u64).ok_or_else(|| {
+        CompileError::Internal(
+            "Unknown field in IR generation. This should have been an error during type \
+             checking.",
+            span.clone(),
+        )
+    })
+}
+
+fn convert_type(context: &mut Context, ty: TypeId, span: &Span) -> Result<Type, CompileError> {
+    let ty = resolve_type(ty, span)?;
+    convert_resolved_type(context, &ty, span)
+}
+
+fn convert_resolved_type(
+    context: &mut Context,
+    ty: &TypeInfo,
+    span: &Span,
+) -> Result<Type, CompileError> {
+    Ok(match ty {
+        TypeInfo::UnsignedInteger(bits) => Type::Uint(match bits {
+            IntegerBits::Eight => 8,
+            IntegerBits::Sixteen => 16,
+            IntegerBits::ThirtyTwo => 32,
+            IntegerBits::SixtyFour => 64,
+        }),
+        TypeInfo::Numeric => Type::Uint(64),
+        TypeInfo::Byte => Type::Uint(8),
+        TypeInfo::Boolean => Type::Bool,
+        TypeInfo::B256 => Type::B256,
+        TypeInfo::Str(len) => Type::String(*len),
+        TypeInfo::Tuple(fields) if fields.is_empty() => Type::Unit,
+        TypeInfo::Tuple(fields) => {
+            let fields = convert_types(context, fields.iter().copied(), span)?;
+            Type::Struct(Aggregate::new_struct(context, fields))
+        }
+        TypeInfo::Struct { fields, .. } => {
+            let fields = convert_types(context, fields.iter().map(|field| field.r#type), span)?;
+            Type::Struct(Aggregate::new_struct(context, fields))
+        }
+        // an enum is its tag followed by the union of its variants
+        TypeInfo::Enum { variant_types, .. } => {
+            let variants = convert_types(
+                context,
+                variant_types.iter().map(|variant| variant.r#type),
+                span,
+            )?;
+            let union = Type::Union(Aggregate::new_struct(context, variants));
+            Type::Struct(Aggregate::new_struct(context, vec![Type::Uint(64), union]))
+        }
+        TypeInfo::Array(element_type, count) => {
+            let element_type = convert_type(context, *element_type, span)?;
+            Type::Array(Aggregate::new_array(context, element_type, *count as u64))
+        }
+        TypeInfo::Ref(ty) | TypeInfo::Alias { ty, .. } => convert_type(context, *ty, span)?,
+        // contract callers only exist in the type system
+        TypeInfo::ContractCaller { .. } => Type::Unit,
+        TypeInfo::StorageMap { .. } => {
+            return Err(CompileError::Unimplemented(
+                "Storage is not yet supported by the IR.",
+                span.clone(),
+            ))
+        }
+        TypeInfo::SignedInteger(_) => {
+            return Err(CompileError::Unimplemented(
+                "Signed integers are not yet supported by the IR.",
+                span.clone(),
+            ))
+        }
+        TypeInfo::Unknown
+        | TypeInfo::UnknownGeneric { .. }
+        | TypeInfo::Custom { .. }
+        | TypeInfo::UnresolvedArray { .. }
+        | TypeInfo::AssociatedType { .. }
+        | TypeInfo::SelfType
+        | TypeInfo::Contract
+        | TypeInfo::ErrorRecovery => {
+            return Err(CompileError::TypeMustBeKnown {
+                ty: ty.friendly_type_str(),
+                span: span.clone(),
+            })
+        }
+    })
+}
+
+fn convert_types(
+    context: &mut Context,
+    types: impl Iterator<Item = TypeId>,
+    span: &Span,
+) -> Result<Vec<Type>, CompileError> {
+    types.map(|ty| convert_type(context, ty, span)).collect()
+}
@@ -10,6 +10,8 @@ mod concurrent_slab;
 pub mod constants;
 mod control_flow_analysis;
 mod ident;
+mod ir;
+mod ir_generation;
 pub mod parse_tree;
 mod parser;
 pub mod semantic_analysis;
@@ -21,13 +23,14 @@ use crate::asm_generation::checks::check_invalid_opcodes;
 pub use crate::parse_tree::*;
 pub use crate::parser::{HllParser, Rule};
 use crate::{asm_generation::compile_ast_to_asm, error::*};
-pub use asm_generation::{AbstractInstructionSet, FinalizedAsm, HllAsmSet};
+pub use asm_generation::{AbstractInstructionSet, FinalizedAsm, HllAsmSet, OptimizationPass};
 pub use build_config::BuildConfig;
 use control_flow_analysis::{ControlFlowGraph, Graph};
 use pest::iterators::Pair;
 use pest::Parser;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use sway_types::RevertCode;
 
 pub use semantic_analysis::TreeType;
 pub use semantic_analysis::TypedParseTree;
@@ -38,6 +41,7 @@ pub use crate::parse_tree::{Declaration, Expression, UseStatement, WhileLoop};
 pub use crate::span::Span;
 pub use error::{CompileError, CompileResult, CompileWarning};
 pub use ident::Ident;
+pub(crate) use ident::{hidden_ident, HiddenVariable};
 pub use semantic_analysis::{Namespace, TypedDeclaration, TypedFunctionDeclaration};
 pub use type_engine::TypeInfo;
 
@@ -93,6 +97,14 @@ pub enum AstNodeContent {
     /// A control flow element which loops continually until some boolean expression evaluates as
     /// `false`.
     WhileLoop(WhileLoop),
+    /// A control flow element which runs its body once for each element of an array or each
+    /// integer of a range.
+    ForLoop(ForLoop),
+    /// A statement of the form `break;`, which exits the innermost loop.
+    Break,
+    /// A statement of the form `continue;`, which skips to the next iteration of the innermost
+    /// loop.
+    Continue,
     /// A statement of the form `dep foo::bar;` which imports/includes another source file.
     IncludeStatement(IncludeStatement),
 }
@@ -159,6 +171,8 @@ pub enum CompilationResult {
     Success {
         asm: FinalizedAsm,
         warnings: Vec<CompileWarning>,
+        /// Where in the source the program reverts with each of the codes it can revert with.
+        revert_codes: Vec<RevertCode>,
     },
     Library {
         name: Ident,
@@ -189,6 +203,8 @@ pub enum BytecodeCompilationResult {
     Success {
         bytes: Vec<u8>,
         warnings: Vec<CompileWarning>,
+        /// Where in the source the program reverts with each of the codes it can revert with.
+        revert_codes: Vec<RevertCode>,
     },
     Library {
         warnings: Vec<CompileWarning>,
@@ -379,6 +395,7 @@ pub fn compile_to_asm(
             let mut errors = vec![];
             match tree_type {
                 TreeType::Contract | TreeType::Script | TreeType::Predicate => {
+                    let revert_codes = parse_tree.revert_codes();
                     let asm = check!(
                         compile_ast_to_asm(*parse_tree, &build_config),
                         return CompilationResult::Failure { errors, warnings },
@@ -388,7 +405,11 @@ pub fn compile_to_asm(
                     if !errors.is_empty() {
                         return CompilationResult::Failure { errors, warnings };
                     }
-                    CompilationResult::Success { asm, warnings }
+                    CompilationResult::Success {
+                        asm,
+                        warnings,
+                        revert_codes,
+                    }
                 }
                 TreeType::Library { name } => CompilationResult::Library {
                     warnings,
@@ -412,6 +433,7 @@ pub fn compile_to_bytecode(
         CompilationResult::Success {
             mut asm,
             mut warnings,
+            revert_codes,
         } => {
             let mut asm_res = asm.to_bytecode_mut();
             warnings.append(&mut asm_res.warnings);
@@ -425,6 +447,7 @@ pub fn compile_to_bytecode(
                 BytecodeCompilationResult::Success {
                     bytes: asm_res.value.unwrap(),
                     warnings,
+                    revert_codes,
                 }
             }
         }
@@ -483,13 +506,24 @@ fn parse_root_from_pairs(
                         warnings,
                         errors
                     );
+                    let derived_impls = check!(decl.derived_impls(), Vec::new(), warnings, errors);
+                    let span = span::Span {
+                        span: pair.as_span(),
+                        path: path.clone(),
+                    };
                     parse_tree.push(AstNode {
                         content: AstNodeContent::Declaration(decl),
-                        span: span::Span {
-                            span: pair.as_span(),
-                            path: path.clone(),
-                        },
+                        span: span.clone(),
                     });
+                    // the traits derived for a struct or an enum are implemented right after it
+                    for impl_trait in derived_impls {
+                        parse_tree.push(AstNode {
+                            content: AstNodeContent::Declaration(Declaration::ImplTrait(
+                                impl_trait,
+                            )),
+                            span: span.clone(),
+                        });
+                    }
                 }
                 Rule::use_statement => {
                     let stmt = check!(
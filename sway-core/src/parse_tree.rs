@@ -1,8 +1,10 @@
 //! Contains all the code related to parsing Sway source code.
+mod attribute;
 mod call_path;
 mod code_block;
 pub mod declaration;
 mod expression;
+mod for_loop;
 mod include_statement;
 mod literal;
 mod return_statement;
@@ -10,10 +12,12 @@ mod use_statement;
 mod visibility;
 mod while_loop;
 
+pub use attribute::*;
 pub use call_path::*;
 pub use code_block::*;
 pub use declaration::*;
 pub use expression::*;
+pub use for_loop::{ForLoop, ForLoopIterable};
 pub(crate) use include_statement::IncludeStatement;
 pub use literal::Literal;
 pub use return_statement::*;
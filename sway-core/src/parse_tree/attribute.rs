@@ -0,0 +1,154 @@
+use crate::build_config::BuildConfig;
+use crate::error::*;
+use crate::parser::Rule;
+use crate::span::Span;
+use crate::Ident;
+
+use pest::iterators::{Pair, Pairs};
+use std::iter::Peekable;
+
+/// The attribute which derives implementations of traits, like `#[derive(Eq, Ord)]`.
+pub(crate) const DERIVE_ATTRIBUTE_NAME: &str = "derive";
+
+/// The attribute which declares the access of a function to contract storage, like
+/// `#[storage(read, write)]`.
+pub(crate) const STORAGE_ATTRIBUTE_NAME: &str = "storage";
+pub(crate) const STORAGE_READ_ARG_NAME: &str = "read";
+pub(crate) const STORAGE_WRITE_ARG_NAME: &str = "write";
+
+/// The attributes the compiler knows. Any other attribute is ignored with a warning.
+const KNOWN_ATTRIBUTE_NAMES: &[&str] = &[DERIVE_ATTRIBUTE_NAME, STORAGE_ATTRIBUTE_NAME];
+
+/// The kind of declaration or field an attribute is attached to. A known attribute attached to
+/// something it doesn't apply to is ignored with a warning, like an unknown one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AttributeTarget {
+    Function,
+    Struct,
+    StructField,
+    Enum,
+    EnumVariant,
+    Abi,
+    Storage,
+    StorageField,
+}
+
+impl AttributeTarget {
+    /// The known attributes which apply to this kind of declaration or field.
+    fn applicable_attribute_names(self) -> &'static [&'static str] {
+        match self {
+            AttributeTarget::Function => &[STORAGE_ATTRIBUTE_NAME],
+            AttributeTarget::Struct | AttributeTarget::Enum => &[DERIVE_ATTRIBUTE_NAME],
+            AttributeTarget::StructField
+            | AttributeTarget::EnumVariant
+            | AttributeTarget::Abi
+            | AttributeTarget::Storage
+            | AttributeTarget::StorageField => &[],
+        }
+    }
+
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            AttributeTarget::Function => "a function",
+            AttributeTarget::Struct => "a struct",
+            AttributeTarget::StructField => "a struct field",
+            AttributeTarget::Enum => "an enum",
+            AttributeTarget::EnumVariant => "an enum variant",
+            AttributeTarget::Abi => "an abi",
+            AttributeTarget::Storage => "a storage declaration",
+            AttributeTarget::StorageField => "a storage field",
+        }
+    }
+}
+
+/// An attribute, like `#[inline(never)]`, which attaches metadata to the declaration or field
+/// after it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attribute {
+    pub name: Ident,
+    pub args: Vec<Ident>,
+    pub span: Span,
+}
+
+impl Attribute {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        target: AttributeTarget,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let span = Span {
+            span: pair.as_span(),
+            path: config.map(|c| c.path()),
+        };
+        let mut iter = pair.into_inner();
+        let name = check!(
+            Ident::parse_from_pair(iter.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let mut args = vec![];
+        if let Some(args_pair) = iter.next() {
+            for arg in args_pair.into_inner() {
+                args.push(check!(
+                    Ident::parse_from_pair(arg, config),
+                    continue,
+                    warnings,
+                    errors
+                ));
+            }
+        }
+        if !KNOWN_ATTRIBUTE_NAMES.contains(&name.as_str()) {
+            warnings.push(CompileWarning {
+                warning_content: Warning::UnknownAttribute {
+                    attribute: name.clone(),
+                },
+                span: span.clone(),
+            });
+        } else if !target.applicable_attribute_names().contains(&name.as_str()) {
+            warnings.push(CompileWarning {
+                warning_content: Warning::MisplacedAttribute {
+                    attribute: name.clone(),
+                    target: target.description(),
+                },
+                span: span.clone(),
+            });
+        }
+        ok(Attribute { name, args, span }, warnings, errors)
+    }
+
+    /// Parses the attributes at the front of `pairs`, which precede the rest of the `target`
+    /// declaration or field they are attached to.
+    pub(crate) fn parse_leading(
+        pairs: &mut Peekable<Pairs<Rule>>,
+        target: AttributeTarget,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Vec<Self>> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let mut attributes = vec![];
+        while let Some(pair) = pairs.next_if(|pair| pair.as_rule() == Rule::attribute) {
+            attributes.push(check!(
+                Attribute::parse_from_pair(pair, target, config),
+                continue,
+                warnings,
+                errors
+            ));
+        }
+        ok(attributes, warnings, errors)
+    }
+
+    /// The arguments of all the attributes named `name` among `attributes`, like the traits of
+    /// each `#[derive(..)]`.
+    pub(crate) fn args_of<'a>(
+        attributes: &'a [Attribute],
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Ident> {
+        attributes
+            .iter()
+            .filter(move |attribute| attribute.name.as_str() == name)
+            .flat_map(|attribute| attribute.args.iter())
+    }
+}
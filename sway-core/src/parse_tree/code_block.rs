@@ -1,11 +1,11 @@
-use super::WhileLoop;
+use super::{ForLoop, WhileLoop};
 use crate::build_config::BuildConfig;
 use crate::parser::Rule;
 use crate::span::Span;
 use crate::{
     error::*,
     parse_tree::{Expression, ReturnStatement},
-    span, AstNode, AstNodeContent, Declaration,
+    span, AstNode, AstNodeContent, Declaration, VariablePattern,
 };
 use pest::iterators::Pair;
 
@@ -30,19 +30,48 @@ impl CodeBlock {
         let block_inner = block.into_inner();
         let mut contents = Vec::new();
         for pair in block_inner {
-            contents.push(match pair.as_rule() {
-                Rule::declaration => AstNode {
-                    content: AstNodeContent::Declaration(check!(
+            let node = match pair.as_rule() {
+                Rule::declaration => {
+                    let declaration = check!(
                         Declaration::parse_from_pair(pair.clone(), config),
                         continue,
                         warnings,
                         errors
-                    )),
-                    span: span::Span {
+                    );
+                    let derived_impls =
+                        check!(declaration.derived_impls(), Vec::new(), warnings, errors);
+                    let span = span::Span {
                         span: pair.as_span(),
                         path: path.clone(),
-                    },
-                },
+                    };
+                    contents.push(AstNode {
+                        content: AstNodeContent::Declaration(declaration),
+                        span: span.clone(),
+                    });
+                    // the traits derived for a struct or an enum are implemented right after it
+                    contents.extend(derived_impls.into_iter().map(|impl_trait| AstNode {
+                        content: AstNodeContent::Declaration(Declaration::ImplTrait(impl_trait)),
+                        span: span.clone(),
+                    }));
+                    continue;
+                }
+                // destructuring declares a variable for each part, rather than a single node
+                Rule::destructuring_decl => {
+                    let declarations = check!(
+                        VariablePattern::parse_declarations_from_pair(pair.clone(), config),
+                        continue,
+                        warnings,
+                        errors
+                    );
+                    contents.extend(declarations.into_iter().map(|content| AstNode {
+                        content,
+                        span: span::Span {
+                            span: pair.as_span(),
+                            path: path.clone(),
+                        },
+                    }));
+                    continue;
+                }
                 Rule::expr_statement => {
                     let evaluated_node = check!(
                         Expression::parse_from_pair(
@@ -76,6 +105,20 @@ impl CodeBlock {
                         },
                     }
                 }
+                Rule::break_statement => AstNode {
+                    content: AstNodeContent::Break,
+                    span: span::Span {
+                        span: pair.as_span(),
+                        path: path.clone(),
+                    },
+                },
+                Rule::continue_statement => AstNode {
+                    content: AstNodeContent::Continue,
+                    span: span::Span {
+                        span: pair.as_span(),
+                        path: path.clone(),
+                    },
+                },
                 Rule::expr => {
                     let res = check!(
                         Expression::parse_from_pair(pair.clone(), config),
@@ -103,6 +146,21 @@ impl CodeBlock {
                         },
                     }
                 }
+                Rule::for_loop => {
+                    let res = check!(
+                        ForLoop::parse_from_pair(pair.clone(), config),
+                        continue,
+                        warnings,
+                        errors
+                    );
+                    AstNode {
+                        content: AstNodeContent::ForLoop(res),
+                        span: span::Span {
+                            span: pair.as_span(),
+                            path: path.clone(),
+                        },
+                    }
+                }
                 a => {
                     println!("In code block parsing: {:?} {:?}", a, pair.as_str());
                     errors.push(CompileError::UnimplementedRule(
@@ -114,7 +172,8 @@ impl CodeBlock {
                     ));
                     continue;
                 }
-            })
+            };
+            contents.push(node);
         }
 
         ok(
@@ -1,5 +1,6 @@
 mod abi;
 mod constant;
+mod derive;
 mod r#enum;
 pub mod function;
 mod impl_trait;
@@ -7,11 +8,13 @@ mod reassignment;
 mod storage;
 mod r#struct;
 mod r#trait;
+mod type_alias;
 mod type_parameter;
 mod variable;
 
 pub(crate) use abi::*;
 pub(crate) use constant::*;
+pub(crate) use derive::*;
 pub use function::*;
 pub(crate) use impl_trait::*;
 pub(crate) use r#enum::*;
@@ -19,6 +22,7 @@ pub use r#struct::*;
 pub use r#trait::*;
 pub(crate) use reassignment::*;
 pub use storage::*;
+pub use type_alias::*;
 pub(crate) use type_parameter::*;
 pub use variable::*;
 
@@ -41,6 +45,7 @@ pub enum Declaration {
     AbiDeclaration(AbiDeclaration),
     ConstantDeclaration(ConstantDeclaration),
     StorageDeclaration(StorageDeclaration),
+    TypeAliasDeclaration(TypeAliasDeclaration),
 }
 impl Declaration {
     pub(crate) fn parse_non_var_from_pair(
@@ -124,6 +129,12 @@ impl Declaration {
                 warnings,
                 errors
             )),
+            Rule::type_alias_decl => Declaration::TypeAliasDeclaration(check!(
+                TypeAliasDeclaration::parse_from_pair(decl_inner, config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            )),
             a => unreachable!("declarations don't have any other sub-types: {:?}", a),
         };
         ok(parsed_declaration, warnings, errors)
@@ -1,9 +1,9 @@
 use super::FunctionDeclaration;
-use super::TraitFn;
+use super::{parse_associated_type_name, parse_supertraits, TraitConst, TraitFn};
 use crate::build_config::BuildConfig;
 use crate::parser::Rule;
 use crate::span::Span;
-use crate::{error::*, Ident};
+use crate::{error::*, Attribute, AttributeTarget, CallPath, Ident};
 use pest::iterators::Pair;
 
 /// An `abi` declaration, which declares an interface for a contract
@@ -16,7 +16,14 @@ pub struct AbiDeclaration {
     pub(crate) interface_surface: Vec<TraitFn>,
     /// The methods provided to a contract "for free" upon opting in to this interface
     pub(crate) methods: Vec<FunctionDeclaration>,
+    /// The abis a contract must also implement in order to opt in to this interface
+    pub(crate) supertraits: Vec<CallPath>,
+    /// The constants a contract must give a value in order to opt in to this interface
+    pub(crate) associated_consts: Vec<TraitConst>,
+    /// The types a contract must define in order to opt in to this interface
+    pub(crate) associated_types: Vec<Ident>,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl AbiDeclaration {
@@ -28,9 +35,15 @@ impl AbiDeclaration {
             span: pair.as_span(),
             path: config.map(|c| c.path()),
         };
-        let mut iter = pair.into_inner();
+        let mut iter = pair.into_inner().peekable();
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
+        let attributes = check!(
+            Attribute::parse_leading(&mut iter, AttributeTarget::Abi, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let _abi_keyword = iter.next().expect("guaranteed by grammar");
         let name = check!(
             Ident::parse_from_pair(iter.next().expect("guaranteed by grammar"), config),
@@ -40,7 +53,21 @@ impl AbiDeclaration {
         );
         let mut interface_surface = vec![];
         let mut methods = vec![];
-        let trait_methods = iter.next().expect("guaranteed by grammar");
+        let mut associated_consts = vec![];
+        let mut associated_types = vec![];
+        let mut trait_methods = iter.next().expect("guaranteed by grammar");
+        let supertraits = if trait_methods.as_rule() == Rule::supertraits {
+            let supertraits = check!(
+                parse_supertraits(trait_methods, config),
+                Vec::new(),
+                warnings,
+                errors
+            );
+            trait_methods = iter.next().expect("guaranteed by grammar");
+            supertraits
+        } else {
+            vec![]
+        };
         for func in trait_methods.into_inner() {
             match func.as_rule() {
                 Rule::fn_signature => {
@@ -58,6 +85,18 @@ impl AbiDeclaration {
                     warnings,
                     errors
                 )),
+                Rule::trait_const => associated_consts.push(check!(
+                    TraitConst::parse_from_pair(func, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
+                Rule::trait_type => associated_types.push(check!(
+                    parse_associated_type_name(func, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
                 x => unreachable!("guaranteed to not be here: {:?}", x),
             }
         }
@@ -65,8 +104,12 @@ impl AbiDeclaration {
             AbiDeclaration {
                 methods,
                 interface_surface,
+                supertraits,
+                associated_consts,
+                associated_types,
                 name,
                 span,
+                attributes,
             },
             warnings,
             errors,
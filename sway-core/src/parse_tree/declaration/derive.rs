@@ -0,0 +1,287 @@
+//! Implementations of traits which are derived with `#[derive(..)]` on a struct or an enum. A
+//! derived implementation is desugared into an ordinary impl block, so it is type checked like
+//! one written by hand, and a field whose type doesn't implement the trait is reported as such.
+
+use super::{
+    Declaration, EnumDeclaration, FunctionDeclaration, FunctionParameter, ImplTrait, Purity,
+    StructDeclaration, TraitConstraint, TypeParameter,
+};
+use crate::error::*;
+use crate::parse_tree::{
+    Attribute, CallPath, CodeBlock, DelayedEnumTagResolution, DelayedEnumVariantResolution,
+    DelayedResolutionVariant, Expression, LazyOp, Literal, Op, OpVariant, Visibility,
+    DERIVE_ATTRIBUTE_NAME,
+};
+use crate::span::Span;
+use crate::type_engine::TypeInfo;
+use crate::utils::join_spans;
+use crate::{AstNode, AstNodeContent, Ident};
+
+impl Declaration {
+    /// The impl blocks of the traits derived for this declaration, if it is a struct or an enum.
+    pub(crate) fn derived_impls(&self) -> CompileResult<Vec<ImplTrait>> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let (name, type_parameters, attributes) = match self {
+            Declaration::StructDeclaration(StructDeclaration {
+                name,
+                type_parameters,
+                attributes,
+                ..
+            })
+            | Declaration::EnumDeclaration(EnumDeclaration {
+                name,
+                type_parameters,
+                attributes,
+                ..
+            }) => (name, type_parameters, attributes),
+            _ => return ok(vec![], warnings, errors),
+        };
+        let mut impls = vec![];
+        for trait_name in Attribute::args_of(attributes, DERIVE_ATTRIBUTE_NAME) {
+            let trait_name = CallPath::from(trait_name.clone());
+            let span = trait_name.span();
+            let method_names: &[_] = match trait_name.suffix.as_str() {
+                "Eq" => &["eq"],
+                "Ord" => &["eq", "lt", "gt"],
+                _ => {
+                    errors.push(CompileError::UnknownDerive {
+                        trait_name: trait_name.suffix.clone(),
+                        span,
+                    });
+                    continue;
+                }
+            };
+            let functions = method_names
+                .iter()
+                .map(|&method_name| {
+                    let body = match self {
+                        Declaration::StructDeclaration(decl) => {
+                            struct_comparison(decl, method_name)
+                        }
+                        Declaration::EnumDeclaration(decl) => {
+                            enum_comparison(decl, method_name, &span)
+                        }
+                        _ => unreachable!("only structs and enums derive traits"),
+                    };
+                    comparison_method(method_name, body, &span)
+                })
+                .collect();
+            // the type parameters of a generic type must implement the trait as well
+            let type_parameters = type_parameters
+                .iter()
+                .cloned()
+                .map(|mut type_parameter| {
+                    type_parameter.trait_constraints.push(TraitConstraint {
+                        name: trait_name.suffix.clone(),
+                    });
+                    type_parameter
+                })
+                .collect::<Vec<TypeParameter>>();
+            impls.push(ImplTrait {
+                trait_name,
+                type_implementing_for: TypeInfo::Custom {
+                    name: name.as_str().to_string(),
+                    type_arguments: vec![],
+                },
+                type_implementing_for_span: span.clone(),
+                type_implementing_for_arguments: type_parameters
+                    .iter()
+                    .map(|type_parameter| type_parameter.name.clone())
+                    .collect(),
+                type_parameters,
+                type_arguments: vec![],
+                functions,
+                consts: vec![],
+                associated_types: vec![],
+                block_span: span.clone(),
+                type_arguments_span: span,
+            });
+        }
+        if errors.is_empty() {
+            ok(impls, warnings, errors)
+        } else {
+            err(warnings, errors)
+        }
+    }
+}
+
+/// `fn <method_name>(self, other: Self) -> bool { <body> }`
+fn comparison_method(
+    method_name: &'static str,
+    body: Expression,
+    span: &Span,
+) -> FunctionDeclaration {
+    let parameter = |name| FunctionParameter {
+        name: Ident::new_with_override(name, span.clone()),
+        r#type: TypeInfo::SelfType,
+        type_span: span.clone(),
+    };
+    FunctionDeclaration {
+        purity: Purity::Pure,
+        name: Ident::new_with_override(method_name, span.clone()),
+        visibility: Visibility::Private,
+        body: CodeBlock {
+            contents: vec![AstNode {
+                content: AstNodeContent::ImplicitReturnExpression(body),
+                span: span.clone(),
+            }],
+            whole_block_span: span.clone(),
+        },
+        parameters: vec![parameter("self"), parameter("other")],
+        span: span.clone(),
+        return_type: TypeInfo::Boolean,
+        type_parameters: vec![],
+        return_type_span: span.clone(),
+        attributes: vec![],
+    }
+}
+
+/// Compares the fields of `self` and `other` in order of declaration. They are equal if all of
+/// their fields are, and otherwise ordered by the first field which differs.
+fn struct_comparison(decl: &StructDeclaration, method_name: &str) -> Expression {
+    let comparison = |op_variant, field: &Ident| {
+        let span = field.span().clone();
+        let access = |side| Expression::SubfieldExpression {
+            prefix: Box::new(Expression::VariableExpression {
+                name: Ident::new_with_override(side, span.clone()),
+                span: span.clone(),
+            }),
+            field_to_access: field.clone(),
+            span: span.clone(),
+        };
+        let op = Op {
+            op_variant,
+            span: span.clone(),
+        };
+        Expression::core_ops(op, vec![access("self"), access("other")], span)
+    };
+    let ordering = comparison_op(method_name);
+    if let OpVariant::Equals = ordering {
+        return decl
+            .fields
+            .iter()
+            .map(|field| comparison(OpVariant::Equals, &field.name))
+            .reduce(|lhs, rhs| lazy_operator(LazyOp::And, lhs, rhs))
+            .unwrap_or_else(|| boolean(true, decl.name.span()));
+    }
+    // `a.x < b.x || (a.x == b.x && (a.y < b.y || ..))`
+    decl.fields
+        .iter()
+        .rev()
+        .fold(None, |rest, field| {
+            let ordered = comparison(ordering.clone(), &field.name);
+            Some(match rest {
+                None => ordered,
+                Some(rest) => {
+                    let tied = comparison(OpVariant::Equals, &field.name);
+                    lazy_operator(LazyOp::Or, ordered, lazy_operator(LazyOp::And, tied, rest))
+                }
+            })
+        })
+        .unwrap_or_else(|| boolean(false, decl.name.span()))
+}
+
+/// Compares the tags of `self` and `other`, so that variants are ordered as they are declared.
+/// Values of the same variant are then ordered by the data it holds, if any.
+///
+/// `a < b` is `tag(a) < tag(b) || (tag(a) == tag(b) && if tag(a) == 0 { data(a) < data(b) } ..)`.
+fn enum_comparison(decl: &EnumDeclaration, method_name: &str, span: &Span) -> Expression {
+    let side = |name| {
+        Box::new(Expression::VariableExpression {
+            name: Ident::new_with_override(name, span.clone()),
+            span: span.clone(),
+        })
+    };
+    let tag = |name| Expression::DelayedMatchTypeResolution {
+        variant: DelayedResolutionVariant::EnumTag(DelayedEnumTagResolution { exp: side(name) }),
+        span: span.clone(),
+    };
+    let compare = |op_variant, lhs, rhs| {
+        let op = Op {
+            op_variant,
+            span: span.clone(),
+        };
+        Expression::core_ops(op, vec![lhs, rhs], span.clone())
+    };
+    let ordering = comparison_op(method_name);
+    let is_equals = matches!(ordering, OpVariant::Equals);
+
+    let tags = compare(ordering.clone(), tag("self"), tag("other"));
+    // the variants which hold no data are tied once their tags are
+    let data_variants = decl
+        .variants
+        .iter()
+        .filter(|variant| !matches!(&variant.r#type, TypeInfo::Tuple(fields) if fields.is_empty()))
+        .collect::<Vec<_>>();
+    if data_variants.is_empty() {
+        return tags;
+    }
+    let data_comparison =
+        data_variants
+            .into_iter()
+            .rev()
+            .fold(boolean(is_equals, span), |rest, variant| {
+                let data = |name| Expression::DelayedMatchTypeResolution {
+                    variant: DelayedResolutionVariant::EnumVariant(DelayedEnumVariantResolution {
+                        exp: side(name),
+                        call_path: CallPath {
+                            prefixes: vec![decl.name.clone()],
+                            suffix: variant.name.clone(),
+                        },
+                        arg_num: 0,
+                    }),
+                    span: span.clone(),
+                };
+                let is_variant = compare(
+                    OpVariant::Equals,
+                    tag("self"),
+                    Expression::Literal {
+                        value: Literal::U64(variant.tag as u64),
+                        span: span.clone(),
+                    },
+                );
+                Expression::IfExp {
+                    condition: Box::new(is_variant),
+                    then: Box::new(compare(ordering.clone(), data("self"), data("other"))),
+                    r#else: Some(Box::new(rest)),
+                    span: span.clone(),
+                }
+            });
+    if is_equals {
+        lazy_operator(LazyOp::And, tags, data_comparison)
+    } else {
+        let tied = compare(OpVariant::Equals, tag("self"), tag("other"));
+        lazy_operator(
+            LazyOp::Or,
+            tags,
+            lazy_operator(LazyOp::And, tied, data_comparison),
+        )
+    }
+}
+
+/// The operator of the comparison method `method_name`.
+fn comparison_op(method_name: &str) -> OpVariant {
+    match method_name {
+        "lt" => OpVariant::LessThan,
+        "gt" => OpVariant::GreaterThan,
+        _ => OpVariant::Equals,
+    }
+}
+
+fn lazy_operator(op: LazyOp, lhs: Expression, rhs: Expression) -> Expression {
+    let span = join_spans(lhs.span(), rhs.span());
+    Expression::LazyOperator {
+        op,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+        span,
+    }
+}
+
+fn boolean(value: bool, span: &Span) -> Expression {
+    Expression::Literal {
+        value: Literal::Boolean(value),
+        span: span.clone(),
+    }
+}
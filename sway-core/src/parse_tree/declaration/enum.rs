@@ -10,7 +10,7 @@ use crate::{
     semantic_analysis::ast_node::{declaration::insert_type_parameters, TypedEnumDeclaration},
 };
 use crate::{
-    parse_tree::{declaration::TypeParameter, Visibility},
+    parse_tree::{declaration::TypeParameter, Attribute, AttributeTarget, Visibility},
     semantic_analysis::ast_node::TypedEnumVariant,
     style::is_upper_camel_case,
 };
@@ -23,6 +23,7 @@ pub struct EnumDeclaration {
     pub(crate) variants: Vec<EnumVariant>,
     pub(crate) span: Span,
     pub visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +32,7 @@ pub(crate) struct EnumVariant {
     pub(crate) r#type: TypeInfo,
     pub(crate) tag: usize,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl EnumDeclaration {
@@ -60,6 +62,7 @@ impl EnumDeclaration {
             variants: variants_buf,
             span: self.span.clone(),
             visibility: self.visibility,
+            attributes: self.attributes.clone(),
         }
     }
 
@@ -80,8 +83,15 @@ impl EnumDeclaration {
         let mut type_params = None;
         let mut where_clause = None;
         let mut variants = None;
+        let mut attributes = Vec::new();
         for pair in inner {
             match pair.as_rule() {
+                Rule::attribute => attributes.push(check!(
+                    Attribute::parse_from_pair(pair, AttributeTarget::Enum, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
                 Rule::enum_name => {
                     enum_name = Some(pair);
                 }
@@ -143,6 +153,7 @@ impl EnumDeclaration {
                 variants,
                 span: whole_enum_span,
                 visibility,
+                attributes,
             },
             warnings,
             errors,
@@ -165,8 +176,8 @@ impl EnumVariant {
             } else {
                 namespace
                     .resolve_type_with_self(self.r#type.clone(), self_type)
-                    .unwrap_or_else(|_| {
-                        errors.push(CompileError::UnknownType { span });
+                    .unwrap_or_else(|error| {
+                        errors.push(error.into_error(span));
                         insert_type(TypeInfo::ErrorRecovery)
                     })
             };
@@ -176,6 +187,7 @@ impl EnumVariant {
                 r#type: enum_variant_type,
                 tag: self.tag,
                 span: self.span.clone(),
+                attributes: self.attributes.clone(),
             },
             vec![],
             errors,
@@ -190,14 +202,21 @@ impl EnumVariant {
         let mut fields_buf = Vec::new();
         let mut tag = 0;
         if let Some(decl_inner) = decl_inner {
-            let fields = decl_inner.into_inner().collect::<Vec<_>>();
-            for i in (0..fields.len()).step_by(2) {
+            for field in decl_inner.into_inner() {
+                let mut iter = field.into_inner().peekable();
+                let attributes = check!(
+                    Attribute::parse_leading(&mut iter, AttributeTarget::EnumVariant, config),
+                    Vec::new(),
+                    warnings,
+                    errors
+                );
+                let name_pair = iter.next().unwrap();
                 let variant_span = Span {
-                    span: fields[i].as_span(),
+                    span: name_pair.as_span(),
                     path: config.map(|c| c.path()),
                 };
                 let name = check!(
-                    Ident::parse_from_pair(fields[i].clone(), config),
+                    Ident::parse_from_pair(name_pair, config),
                     return err(warnings, errors),
                     warnings,
                     errors
@@ -211,7 +230,7 @@ impl EnumVariant {
                     }
                 );
                 let r#type = check!(
-                    TypeInfo::parse_from_pair(fields[i + 1].clone(), config),
+                    TypeInfo::parse_from_pair(iter.next().unwrap(), config),
                     TypeInfo::Tuple(Vec::new()),
                     warnings,
                     errors
@@ -221,6 +240,7 @@ impl EnumVariant {
                     r#type,
                     tag,
                     span: variant_span,
+                    attributes,
                 });
                 tag += 1;
             }
@@ -1,6 +1,6 @@
 use crate::build_config::BuildConfig;
 use crate::error::*;
-use crate::parse_tree::{declaration::TypeParameter, Visibility};
+use crate::parse_tree::{declaration::TypeParameter, Attribute, AttributeTarget, Visibility};
 use crate::span::Span;
 use crate::style::is_snake_case;
 use crate::type_engine::TypeInfo;
@@ -22,14 +22,21 @@ pub struct FunctionDeclaration {
     pub(crate) return_type: TypeInfo,
     pub(crate) type_parameters: Vec<TypeParameter>,
     pub(crate) return_type_span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl FunctionDeclaration {
     pub fn parse_from_pair(pair: Pair<Rule>, config: Option<&BuildConfig>) -> CompileResult<Self> {
         let path = config.map(|c| c.path());
-        let mut parts = pair.clone().into_inner();
+        let mut parts = pair.clone().into_inner().peekable();
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
+        let attributes = check!(
+            Attribute::parse_leading(&mut parts, AttributeTarget::Function, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let signature_or_visibility = parts.next().unwrap();
         let (visibility, signature) = if signature_or_visibility.as_rule() == Rule::visibility {
             (
@@ -40,16 +47,19 @@ impl FunctionDeclaration {
             (Visibility::Private, signature_or_visibility.into_inner())
         };
         let mut signature = signature.peekable();
-        let purity = if signature
-            .peek()
-            .map(|x| x.as_rule() == Rule::impurity_keyword)
-            .unwrap_or(false)
-        {
-            let _ = signature.next();
-            Purity::Impure
-        } else {
-            Purity::Pure
-        };
+        let deprecated_purity = check!(
+            Purity::parse_impurity_keyword(&mut signature, config),
+            Purity::Pure,
+            warnings,
+            errors
+        );
+        let purity = check!(
+            Purity::parse_from_attributes(&attributes),
+            Purity::Pure,
+            warnings,
+            errors
+        )
+        .union(deprecated_purity);
         let _fn_keyword = signature.next().unwrap();
         let name = signature.next().unwrap();
         let name_span = Span {
@@ -194,6 +204,7 @@ impl FunctionDeclaration {
                 },
                 return_type,
                 type_parameters,
+                attributes,
             },
             warnings,
             errors,
@@ -218,6 +229,12 @@ impl FunctionDeclaration {
                 type_field: self.return_type.friendly_type_str(),
                 components: None,
             }],
+            storage: self
+                .purity
+                .storage_access()
+                .into_iter()
+                .map(String::from)
+                .collect(),
         }
     }
 }
@@ -1,10 +1,23 @@
-/// The purity of a function is related to its access of contract storage. If a function accesses
-/// or could potentially access contract storage, it is [Purity::Impure]. If a function does not utilize any
-/// any accesses (reads _or_ writes) of storage, then it is [Purity::Pure].
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+use crate::build_config::BuildConfig;
+use crate::error::*;
+use crate::parse_tree::{
+    Attribute, STORAGE_ATTRIBUTE_NAME, STORAGE_READ_ARG_NAME, STORAGE_WRITE_ARG_NAME,
+};
+use crate::span::Span;
+use crate::Rule;
+use pest::iterators::Pairs;
+use std::iter::Peekable;
+
+/// The purity of a function is related to its access of contract storage. A function declares
+/// which access it needs with the `#[storage(..)]` attribute, e.g. `#[storage(read)]` lets it read
+/// storage and `#[storage(read, write)]` lets it both read and write storage. A function without
+/// the attribute is [Purity::Pure], and may not access storage at all.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
 pub enum Purity {
     Pure,
-    Impure,
+    Reads,
+    Writes,
+    ReadsWrites,
 }
 
 impl Default for Purity {
@@ -12,3 +25,126 @@ impl Default for Purity {
         Purity::Pure
     }
 }
+
+impl Purity {
+    pub(crate) fn from_access(reads: bool, writes: bool) -> Self {
+        match (reads, writes) {
+            (false, false) => Purity::Pure,
+            (true, false) => Purity::Reads,
+            (false, true) => Purity::Writes,
+            (true, true) => Purity::ReadsWrites,
+        }
+    }
+
+    /// Parses the storage access declared by the `#[storage(..)]` attributes among `attributes`.
+    pub(crate) fn parse_from_attributes(attributes: &[Attribute]) -> CompileResult<Self> {
+        let warnings = vec![];
+        let mut errors = vec![];
+        let (mut reads, mut writes) = (false, false);
+        for access in Attribute::args_of(attributes, STORAGE_ATTRIBUTE_NAME) {
+            match access.as_str() {
+                STORAGE_READ_ARG_NAME => reads = true,
+                STORAGE_WRITE_ARG_NAME => writes = true,
+                _ => errors.push(CompileError::UnknownStorageAccess {
+                    access: access.as_str().to_string(),
+                    span: access.span().clone(),
+                }),
+            }
+        }
+        ok(Purity::from_access(reads, writes), warnings, errors)
+    }
+
+    /// Consumes the deprecated `impure` keyword if it begins the function `signature`, with a
+    /// warning. An `impure` function is [Purity::ReadsWrites], as if it were declared with
+    /// `#[storage(read, write)]`.
+    pub(crate) fn parse_impurity_keyword(
+        signature: &mut Peekable<Pairs<Rule>>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let mut warnings = vec![];
+        let errors = vec![];
+        match signature.next_if(|pair| pair.as_rule() == Rule::impurity_keyword) {
+            Some(keyword) => {
+                warnings.push(CompileWarning {
+                    warning_content: Warning::DeprecatedImpurityKeyword {
+                        attribute: Purity::ReadsWrites.to_attribute_syntax(),
+                    },
+                    span: Span {
+                        span: keyword.as_span(),
+                        path: config.map(|c| c.path()),
+                    },
+                });
+                ok(Purity::ReadsWrites, warnings, errors)
+            }
+            None => ok(Purity::Pure, warnings, errors),
+        }
+    }
+
+    pub(crate) fn reads(self) -> bool {
+        matches!(self, Purity::Reads | Purity::ReadsWrites)
+    }
+
+    pub(crate) fn writes(self) -> bool {
+        matches!(self, Purity::Writes | Purity::ReadsWrites)
+    }
+
+    /// The access of both `self` and `other`.
+    pub(crate) fn union(self, other: Purity) -> Self {
+        Purity::from_access(
+            self.reads() || other.reads(),
+            self.writes() || other.writes(),
+        )
+    }
+
+    /// Whether a function declared with this purity may do all of the access of `other`, e.g.
+    /// call a function declared with `other`.
+    pub(crate) fn allows(self, other: Purity) -> bool {
+        self.union(other) == self
+    }
+
+    /// Checks that a function declared with this purity may access storage as `access` does at
+    /// `span`.
+    pub(crate) fn check_access(self, access: Purity, span: &Span) -> Result<(), CompileError> {
+        if self.allows(access) {
+            Ok(())
+        } else {
+            Err(CompileError::StorageAccessNotDeclared {
+                access: access.access_description(),
+                attribute: self.union(access).to_attribute_syntax(),
+                span: span.clone(),
+            })
+        }
+    }
+
+    /// The kinds of storage access, `"read"` and `"write"`, which this purity allows.
+    pub(crate) fn storage_access(self) -> Vec<&'static str> {
+        let mut access = vec![];
+        if self.reads() {
+            access.push(STORAGE_READ_ARG_NAME);
+        }
+        if self.writes() {
+            access.push(STORAGE_WRITE_ARG_NAME);
+        }
+        access
+    }
+
+    /// The attribute which declares this purity, like `#[storage(read, write)]`, for messages.
+    pub(crate) fn to_attribute_syntax(self) -> String {
+        format!(
+            "#[{}({})]",
+            STORAGE_ATTRIBUTE_NAME,
+            self.storage_access().join(", ")
+        )
+    }
+
+    /// How storage is accessed by a function of this purity, like "read and written", for
+    /// messages.
+    pub(crate) fn access_description(self) -> &'static str {
+        match self {
+            Purity::Pure => "not accessed",
+            Purity::Reads => "read",
+            Purity::Writes => "written",
+            Purity::ReadsWrites => "read and written",
+        }
+    }
+}
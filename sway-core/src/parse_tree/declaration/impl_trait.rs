@@ -1,8 +1,8 @@
-use super::{FunctionDeclaration, TypeParameter};
+use super::{ConstantDeclaration, FunctionDeclaration, TypeParameter};
 use crate::build_config::BuildConfig;
 use crate::parse_tree::CallPath;
 use crate::span::Span;
-use crate::{error::*, parser::Rule, type_engine::TypeInfo};
+use crate::{error::*, parser::Rule, type_engine::TypeInfo, Ident};
 use pest::iterators::Pair;
 
 #[derive(Debug, Clone)]
@@ -10,18 +10,38 @@ pub struct ImplTrait {
     pub(crate) trait_name: CallPath,
     pub(crate) type_implementing_for: TypeInfo,
     pub(crate) type_implementing_for_span: Span,
+    /// The type arguments of the type implementing the trait, like the `T` in `MyType<T>`.
+    pub(crate) type_implementing_for_arguments: Vec<TypeInfo>,
+    /// The generic parameters of the impl block itself, like the `T` in `impl<T> MyTrait for
+    /// MyType<T>`.
+    pub(crate) type_parameters: Vec<TypeParameter>,
     pub(crate) type_arguments: Vec<TypeParameter>,
     pub functions: Vec<FunctionDeclaration>,
+    /// The values of the trait's associated constants, like `const DECIMALS: u8 = 9;`.
+    pub(crate) consts: Vec<ConstantDeclaration>,
+    /// The definitions of the trait's associated types, like `type Item = u64;`.
+    pub(crate) associated_types: Vec<AssociatedTypeDefinition>,
     // the span of the whole impl trait and block
     pub(crate) block_span: Span,
     pub(crate) type_arguments_span: Span,
 }
 
+/// The definition of an associated type of a trait in an implementation of the trait, like
+/// `type Item = u64;`.
+#[derive(Debug, Clone)]
+pub(crate) struct AssociatedTypeDefinition {
+    pub(crate) name: Ident,
+    pub(crate) r#type: TypeInfo,
+    pub(crate) type_span: Span,
+}
+
 /// An impl of methods without a trait
 /// like `impl MyType { fn foo { .. } }`
 #[derive(Debug, Clone)]
 pub struct ImplSelf {
     pub(crate) type_implementing_for: TypeInfo,
+    /// The type arguments of the type being implemented, like the `T` in `impl<T> MyType<T>`.
+    pub(crate) type_implementing_for_arguments: Vec<TypeInfo>,
     pub(crate) type_arguments: Vec<TypeParameter>,
     pub functions: Vec<FunctionDeclaration>,
     // the span of the whole impl trait and block
@@ -48,6 +68,12 @@ impl ImplTrait {
         let mut iter = pair.into_inner();
         let impl_keyword = iter.next().unwrap();
         assert_eq!(impl_keyword.as_str(), "impl");
+        let mut iter = iter.peekable();
+        let impl_type_params_pair = if iter.peek().unwrap().as_rule() == Rule::type_params {
+            iter.next()
+        } else {
+            None
+        };
         let trait_name = iter.next().unwrap();
         assert_eq!(trait_name.as_rule(), Rule::trait_name);
         let trait_name = check!(
@@ -56,7 +82,6 @@ impl ImplTrait {
             warnings,
             errors
         );
-        let mut iter = iter.peekable();
         let type_params_pair = if iter.peek().unwrap().as_rule() == Rule::type_params {
             iter.next()
         } else {
@@ -68,6 +93,12 @@ impl ImplTrait {
             span: type_implementing_for_pair.as_span(),
             path: path.clone(),
         };
+        let type_implementing_for_arguments = check!(
+            parse_type_arguments(&type_implementing_for_pair, config),
+            vec![],
+            warnings,
+            errors
+        );
         let type_implementing_for = check!(
             TypeInfo::parse_from_pair(type_implementing_for_pair, config),
             return err(warnings, errors),
@@ -87,6 +118,18 @@ impl ImplTrait {
             },
             None => trait_name.span(),
         };
+        // the where clause constrains the generic parameters of the impl block, if it has any
+        let (impl_where_clause_pair, where_clause_pair) = if impl_type_params_pair.is_some() {
+            (where_clause_pair, None)
+        } else {
+            (None, where_clause_pair)
+        };
+        let type_parameters = TypeParameter::parse_from_type_params_and_where_clause(
+            impl_type_params_pair,
+            impl_where_clause_pair,
+            config,
+        )
+        .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
         let type_arguments = TypeParameter::parse_from_type_params_and_where_clause(
             type_params_pair,
             where_clause_pair,
@@ -95,24 +138,44 @@ impl ImplTrait {
         .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
 
         let mut fn_decls_buf = vec![];
+        let mut consts = vec![];
+        let mut associated_types = vec![];
 
         for pair in iter {
-            fn_decls_buf.push(check!(
-                FunctionDeclaration::parse_from_pair(pair, config),
-                continue,
-                warnings,
-                errors
-            ));
+            match pair.as_rule() {
+                Rule::const_decl => consts.push(check!(
+                    ConstantDeclaration::parse_from_pair(pair, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
+                Rule::impl_type => associated_types.push(check!(
+                    AssociatedTypeDefinition::parse_from_pair(pair, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
+                _ => fn_decls_buf.push(check!(
+                    FunctionDeclaration::parse_from_pair(pair, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
+            }
         }
 
         ok(
             ImplTrait {
                 trait_name,
+                type_parameters,
                 type_arguments,
                 type_arguments_span,
                 type_implementing_for,
                 type_implementing_for_span,
+                type_implementing_for_arguments,
                 functions: fn_decls_buf,
+                consts,
+                associated_types,
                 block_span,
             },
             warnings,
@@ -121,6 +184,44 @@ impl ImplTrait {
     }
 }
 
+impl AssociatedTypeDefinition {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut parts = pair.into_inner();
+        let _type_keyword = parts.next();
+        let name = check!(
+            Ident::parse_from_pair(parts.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let type_pair = parts.next().unwrap();
+        let type_span = Span {
+            span: type_pair.as_span(),
+            path: config.map(|c| c.path()),
+        };
+        let r#type = check!(
+            TypeInfo::parse_from_pair(type_pair, config),
+            TypeInfo::ErrorRecovery,
+            warnings,
+            errors
+        );
+        ok(
+            AssociatedTypeDefinition {
+                name,
+                r#type,
+                type_span,
+            },
+            warnings,
+            errors,
+        )
+    }
+}
+
 impl ImplSelf {
     pub(crate) fn parse_from_pair(
         pair: Pair<Rule>,
@@ -147,6 +248,12 @@ impl ImplSelf {
             span: type_pair.as_span(),
             path: path.clone(),
         };
+        let type_implementing_for_arguments = check!(
+            parse_type_arguments(&type_pair, config),
+            vec![],
+            warnings,
+            errors
+        );
 
         let type_implementing_for = check!(
             TypeInfo::parse_from_pair(type_pair, config),
@@ -189,6 +296,7 @@ impl ImplSelf {
                 type_arguments,
                 type_arguments_span,
                 type_implementing_for,
+                type_implementing_for_arguments,
                 functions: fn_decls_buf,
                 type_name_span,
                 block_span,
@@ -198,3 +306,28 @@ impl ImplSelf {
         )
     }
 }
+
+/// Parses the type arguments of the type an impl block is for, e.g. the `T` in `impl<T> MyType<T>`.
+fn parse_type_arguments(
+    type_pair: &Pair<Rule>,
+    config: Option<&BuildConfig>,
+) -> CompileResult<Vec<TypeInfo>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut type_arguments = vec![];
+    if let Some(type_params) = type_pair
+        .clone()
+        .into_inner()
+        .find(|pair| pair.as_rule() == Rule::type_params)
+    {
+        for pair in type_params.into_inner() {
+            type_arguments.push(check!(
+                TypeInfo::parse_from_pair(pair, config),
+                TypeInfo::ErrorRecovery,
+                warnings,
+                errors
+            ));
+        }
+    }
+    ok(type_arguments, warnings, errors)
+}
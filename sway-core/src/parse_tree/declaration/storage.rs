@@ -1,5 +1,10 @@
 use crate::{
-    error::*, ident::Ident, parse_tree::Expression, parser::Rule, type_engine::*, BuildConfig, Span,
+    error::*,
+    ident::Ident,
+    parse_tree::{Attribute, AttributeTarget, Expression},
+    parser::Rule,
+    type_engine::*,
+    BuildConfig, Span,
 };
 use pest::iterators::Pair;
 
@@ -9,6 +14,7 @@ use pest::iterators::Pair;
 pub struct StorageDeclaration {
     pub fields: Vec<StorageField>,
     pub span: Span,
+    pub attributes: Vec<Attribute>,
 }
 
 /// An individual field in a storage declaration.
@@ -20,6 +26,7 @@ pub struct StorageField {
     pub name: Ident,
     pub r#type: TypeInfo,
     pub initializer: Expression,
+    pub attributes: Vec<Attribute>,
 }
 
 impl StorageField {
@@ -29,7 +36,13 @@ impl StorageField {
     ) -> CompileResult<Self> {
         let mut errors = vec![];
         let mut warnings = vec![];
-        let mut iter = pair.into_inner();
+        let mut iter = pair.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut iter, AttributeTarget::StorageField, conf),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let name = iter.next().expect("guaranteed by grammar");
         let r#type = iter.next().expect("guaranteed by grammar");
         let initializer = iter.next().expect("guaranteed by grammar");
@@ -57,6 +70,7 @@ impl StorageField {
                 name,
                 r#type,
                 initializer,
+                attributes,
             },
             warnings,
             errors,
@@ -77,7 +91,13 @@ impl StorageDeclaration {
             span: pair.as_span(),
             path,
         };
-        let mut iter = pair.into_inner();
+        let mut iter = pair.into_inner().peekable();
+        let attributes = check!(
+            Attribute::parse_leading(&mut iter, AttributeTarget::Storage, config),
+            Vec::new(),
+            warnings,
+            errors
+        );
         let storage_keyword = iter.next();
         debug_assert_eq!(
             storage_keyword.map(|x| x.as_rule()),
@@ -94,6 +114,14 @@ impl StorageDeclaration {
             let ok = check!(res, continue, warnings, errors);
             fields.push(ok);
         }
-        ok(StorageDeclaration { fields, span }, warnings, errors)
+        ok(
+            StorageDeclaration {
+                fields,
+                span,
+                attributes,
+            },
+            warnings,
+            errors,
+        )
     }
 }
@@ -1,5 +1,5 @@
 use crate::build_config::BuildConfig;
-use crate::parse_tree::{declaration::TypeParameter, Visibility};
+use crate::parse_tree::{declaration::TypeParameter, Attribute, AttributeTarget, Visibility};
 use crate::parser::Rule;
 use crate::span::Span;
 use crate::style::{is_snake_case, is_upper_camel_case};
@@ -13,6 +13,7 @@ pub struct StructDeclaration {
     pub(crate) fields: Vec<StructField>,
     pub(crate) type_parameters: Vec<TypeParameter>,
     pub visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +22,7 @@ pub(crate) struct StructField {
     pub(crate) r#type: TypeInfo,
     pub(crate) span: Span,
     pub(crate) type_span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl StructDeclaration {
@@ -37,8 +39,15 @@ impl StructDeclaration {
         let mut type_params_pair = None;
         let mut where_clause_pair = None;
         let mut fields_pair = None;
+        let mut attributes = Vec::new();
         for pair in decl {
             match pair.as_rule() {
+                Rule::attribute => attributes.push(check!(
+                    Attribute::parse_from_pair(pair, AttributeTarget::Struct, config),
+                    continue,
+                    warnings,
+                    errors
+                )),
                 Rule::type_params => {
                     type_params_pair = Some(pair);
                 }
@@ -102,6 +111,7 @@ impl StructDeclaration {
                 fields,
                 type_parameters,
                 visibility,
+                attributes,
             },
             warnings,
             errors,
@@ -117,15 +127,22 @@ impl StructField {
         let path = config.map(|c| c.path());
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
-        let fields = pair.into_inner().collect::<Vec<_>>();
         let mut fields_buf = Vec::new();
-        for i in (0..fields.len()).step_by(2) {
+        for field in pair.into_inner() {
+            let mut iter = field.into_inner().peekable();
+            let attributes = check!(
+                Attribute::parse_leading(&mut iter, AttributeTarget::StructField, config),
+                Vec::new(),
+                warnings,
+                errors
+            );
+            let name_pair = iter.next().unwrap();
             let span = Span {
-                span: fields[i].as_span(),
+                span: name_pair.as_span(),
                 path: path.clone(),
             };
             let name = check!(
-                Ident::parse_from_pair(fields[i].clone(), config),
+                Ident::parse_from_pair(name_pair, config),
                 return err(warnings, errors),
                 warnings,
                 errors
@@ -138,13 +155,13 @@ impl StructField {
                     field_name: name.clone(),
                 }
             );
-            let type_pair = fields[i + 1].clone();
+            let type_pair = iter.next().unwrap();
             let type_span = Span {
                 span: type_pair.as_span(),
                 path: path.clone(),
             };
             let r#type = check!(
-                TypeInfo::parse_from_pair(fields[i + 1].clone(), config),
+                TypeInfo::parse_from_pair(type_pair, config),
                 TypeInfo::Tuple(Vec::new()),
                 warnings,
                 errors
@@ -154,6 +171,7 @@ impl StructField {
                 r#type,
                 span,
                 type_span,
+                attributes,
             });
         }
         ok(fields_buf, warnings, errors)
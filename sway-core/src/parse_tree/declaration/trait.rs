@@ -3,9 +3,9 @@ use crate::build_config::BuildConfig;
 use crate::parse_tree::TypeParameter;
 use crate::parser::Rule;
 use crate::span::Span;
-use crate::style::{is_snake_case, is_upper_camel_case};
+use crate::style::{is_screaming_snake_case, is_snake_case, is_upper_camel_case};
 use crate::type_engine::TypeInfo;
-use crate::{error::*, Ident};
+use crate::{error::*, CallPath, Ident};
 use pest::iterators::Pair;
 
 #[derive(Debug, Clone)]
@@ -14,6 +14,13 @@ pub struct TraitDeclaration {
     pub(crate) interface_surface: Vec<TraitFn>,
     pub(crate) methods: Vec<FunctionDeclaration>,
     pub(crate) type_parameters: Vec<TypeParameter>,
+    /// The traits each type implementing the trait must also implement, like the `B` and `C` in
+    /// `trait A: B + C`.
+    pub(crate) supertraits: Vec<CallPath>,
+    /// The constants each implementation of the trait must give a value, like `const DECIMALS: u8;`.
+    pub(crate) associated_consts: Vec<TraitConst>,
+    /// The types each implementation of the trait must define, like `type Item;`.
+    pub(crate) associated_types: Vec<Ident>,
     pub visibility: Visibility,
 }
 
@@ -51,11 +58,22 @@ impl TraitDeclaration {
         );
         let mut type_params_pair = None;
         let mut where_clause_pair = None;
+        let mut supertraits = Vec::new();
         let mut methods = Vec::new();
         let mut interface = Vec::new();
+        let mut associated_consts = Vec::new();
+        let mut associated_types = Vec::new();
 
-        for _ in 0..2 {
+        for _ in 0..3 {
             match trait_parts.peek().map(|x| x.as_rule()) {
+                Some(Rule::supertraits) => {
+                    supertraits = check!(
+                        parse_supertraits(trait_parts.next().unwrap(), config),
+                        Vec::new(),
+                        warnings,
+                        errors
+                    );
+                }
                 Some(Rule::trait_bounds) => {
                     where_clause_pair = Some(trait_parts.next().unwrap());
                 }
@@ -85,6 +103,22 @@ impl TraitDeclaration {
                             errors
                         ));
                     }
+                    Rule::trait_const => {
+                        associated_consts.push(check!(
+                            TraitConst::parse_from_pair(fn_sig_or_decl, config),
+                            continue,
+                            warnings,
+                            errors
+                        ));
+                    }
+                    Rule::trait_type => {
+                        associated_types.push(check!(
+                            parse_associated_type_name(fn_sig_or_decl, config),
+                            continue,
+                            warnings,
+                            errors
+                        ));
+                    }
                     a => unreachable!("{:?}", a),
                 }
             }
@@ -102,6 +136,9 @@ impl TraitDeclaration {
                 name,
                 interface_surface: interface,
                 methods,
+                supertraits,
+                associated_consts,
+                associated_types,
                 visibility,
             },
             warnings,
@@ -126,11 +163,22 @@ impl TraitFn {
         let path = config.map(|c| c.path());
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
-        let mut signature = pair.clone().into_inner();
+        let mut signature = pair.clone().into_inner().peekable();
         let whole_fn_sig_span = Span {
             span: pair.as_span(),
             path: path.clone(),
         };
+        // the storage access of a trait or abi method is declared by its implementation, so an
+        // `impure` keyword here has no effect
+        if let Some(keyword) = signature.next_if(|pair| pair.as_rule() == Rule::impurity_keyword) {
+            warnings.push(CompileWarning {
+                warning_content: Warning::IgnoredSignatureImpurity,
+                span: Span {
+                    span: keyword.as_span(),
+                    path: path.clone(),
+                },
+            });
+        }
         let _fn_keyword = signature.next().unwrap();
         let name = signature.next().unwrap();
         let name_span = Span {
@@ -189,3 +237,91 @@ impl TraitFn {
         )
     }
 }
+
+/// An associated constant declared by a trait or abi, like `const DECIMALS: u8;`, whose value is
+/// given by each implementation.
+#[derive(Debug, Clone)]
+pub(crate) struct TraitConst {
+    pub(crate) name: Ident,
+    pub(crate) r#type: TypeInfo,
+    pub(crate) type_span: Span,
+}
+
+impl TraitConst {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let path = config.map(|c| c.path());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut parts = pair.into_inner();
+        let _const_keyword = parts.next();
+        let name_pair = parts.next().unwrap();
+        let name = check!(
+            Ident::parse_from_pair(name_pair.clone(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        assert_or_warn!(
+            is_screaming_snake_case(name.as_str()),
+            warnings,
+            Span {
+                span: name_pair.as_span(),
+                path: path.clone(),
+            },
+            Warning::NonScreamingSnakeCaseConstName { name: name.clone() }
+        );
+        let type_pair = parts.next().unwrap().into_inner().next().unwrap();
+        let type_span = Span {
+            span: type_pair.as_span(),
+            path,
+        };
+        let r#type = check!(
+            TypeInfo::parse_from_pair(type_pair, config),
+            TypeInfo::ErrorRecovery,
+            warnings,
+            errors
+        );
+        ok(
+            TraitConst {
+                name,
+                r#type,
+                type_span,
+            },
+            warnings,
+            errors,
+        )
+    }
+}
+
+/// Parses the name of an associated type declared by a trait or abi, like `type Item;`.
+pub(crate) fn parse_associated_type_name(
+    pair: Pair<Rule>,
+    config: Option<&BuildConfig>,
+) -> CompileResult<Ident> {
+    let mut parts = pair.into_inner();
+    let _type_keyword = parts.next();
+    Ident::parse_from_pair(parts.next().unwrap(), config)
+}
+
+/// Parses the traits or abis extended by a trait or abi declaration, like the `B + C` in
+/// `trait A: B + C`.
+pub(crate) fn parse_supertraits(
+    pair: Pair<Rule>,
+    config: Option<&BuildConfig>,
+) -> CompileResult<Vec<CallPath>> {
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    let mut supertraits = Vec::new();
+    for trait_name in pair.into_inner() {
+        supertraits.push(check!(
+            CallPath::parse_from_pair(trait_name, config),
+            continue,
+            warnings,
+            errors
+        ));
+    }
+    ok(supertraits, warnings, errors)
+}
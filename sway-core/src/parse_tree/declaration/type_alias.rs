@@ -0,0 +1,86 @@
+use crate::build_config::BuildConfig;
+use crate::parse_tree::{declaration::TypeParameter, Visibility};
+use crate::parser::Rule;
+use crate::span::Span;
+use crate::style::is_upper_camel_case;
+use crate::type_engine::TypeInfo;
+use crate::{error::*, Ident};
+use pest::iterators::Pair;
+
+/// A type alias, like `pub type Pair<T> = (T, T);`, which gives another name to a type. Wherever
+/// the alias is used, its type arguments take the place of its type parameters in the type it is
+/// an alias for.
+#[derive(Debug, Clone)]
+pub struct TypeAliasDeclaration {
+    pub name: Ident,
+    pub(crate) type_parameters: Vec<TypeParameter>,
+    pub(crate) r#type: TypeInfo,
+    pub(crate) type_span: Span,
+    pub visibility: Visibility,
+    pub span: Span,
+}
+
+impl TypeAliasDeclaration {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let path = config.map(|c| c.path());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let span = Span {
+            span: pair.as_span(),
+            path: path.clone(),
+        };
+        let mut parts = pair.into_inner().peekable();
+        let visibility = Visibility::parse_from_pair(parts.next().unwrap());
+        let _type_keyword = parts.next();
+        let name_pair = parts.next().unwrap();
+        let type_params_pair = if parts.peek().unwrap().as_rule() == Rule::type_params {
+            parts.next()
+        } else {
+            None
+        };
+        let type_parameters =
+            TypeParameter::parse_from_type_params_and_where_clause(type_params_pair, None, config)
+                .unwrap_or_else(&mut warnings, &mut errors, Vec::new);
+        let type_pair = parts.next().unwrap();
+        let type_span = Span {
+            span: type_pair.as_span(),
+            path: path.clone(),
+        };
+        let r#type = check!(
+            TypeInfo::parse_from_pair(type_pair, config),
+            TypeInfo::ErrorRecovery,
+            warnings,
+            errors
+        );
+        let name = check!(
+            Ident::parse_from_pair(name_pair.clone(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        assert_or_warn!(
+            is_upper_camel_case(name.as_str()),
+            warnings,
+            Span {
+                span: name_pair.as_span(),
+                path,
+            },
+            Warning::NonClassCaseTypeAliasName { name: name.clone() }
+        );
+        ok(
+            TypeAliasDeclaration {
+                name,
+                type_parameters,
+                r#type,
+                type_span,
+                visibility,
+                span,
+            },
+            warnings,
+            errors,
+        )
+    }
+}
@@ -1,6 +1,13 @@
-use crate::parse_tree::Expression;
-use crate::Span;
-use crate::{type_engine::TypeInfo, Ident};
+use crate::build_config::BuildConfig;
+use crate::error::*;
+use crate::parse_tree::{
+    Declaration, DelayedResolutionVariant, DelayedStructNameResolution,
+    DelayedTupleVariantResolution, Expression,
+};
+use crate::parser::Rule;
+use crate::type_engine::{insert_type, TypeInfo};
+use crate::{hidden_ident, AstNodeContent, HiddenVariable, Ident, Span};
+use pest::iterators::Pair;
 
 #[derive(Debug, Clone)]
 pub struct VariableDeclaration {
@@ -10,3 +17,260 @@ pub struct VariableDeclaration {
     pub body: Expression, // will be codeblock variant
     pub is_mutable: bool,
 }
+
+/// The left hand side of a `let` which destructures a tuple or a struct, like `(a, mut b, _)` or
+/// `Point { x, y: (mut y0, y1) }`.
+#[derive(Debug, Clone)]
+pub(crate) enum VariablePattern {
+    Binding {
+        name: Ident,
+        is_mutable: bool,
+    },
+    Placeholder,
+    Tuple {
+        elems: Vec<VariablePattern>,
+        span: Span,
+    },
+    Struct {
+        struct_name: Ident,
+        fields: Vec<(Ident, VariablePattern)>,
+        span: Span,
+    },
+}
+
+impl VariablePattern {
+    /// Parses a `let` which destructures a tuple or a struct into a declaration of each of the
+    /// variables which it binds, along with a check of the name of each struct pattern. The value
+    /// being destructured is evaluated once, into a hidden variable which the bindings then read
+    /// their parts out of.
+    pub(crate) fn parse_declarations_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Vec<AstNodeContent>> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let path = config.map(|c| c.path());
+        let mut parts = pair.into_inner();
+        let _let_keyword = parts.next();
+        let pattern = check!(
+            VariablePattern::parse_from_pair(parts.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let mut maybe_body = parts.next().unwrap();
+        let (type_ascription, type_ascription_span) = match maybe_body.as_rule() {
+            Rule::type_ascription => {
+                let type_name = maybe_body.into_inner().next().unwrap();
+                let type_ascription_span = Span {
+                    span: type_name.as_span(),
+                    path,
+                };
+                maybe_body = parts.next().unwrap();
+                (
+                    check!(
+                        TypeInfo::parse_from_pair(type_name, config),
+                        TypeInfo::ErrorRecovery,
+                        warnings,
+                        errors
+                    ),
+                    Some(type_ascription_span),
+                )
+            }
+            // without an ascription, tuples are still checked to have the right number of
+            // elements, while structs are checked by their names
+            _ => (pattern.shape(), None),
+        };
+        let body = check!(
+            Expression::parse_from_pair(maybe_body, config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let destructured_name = hidden_ident(HiddenVariable::Destructured, &body.span());
+        let mut declarations = vec![AstNodeContent::Declaration(
+            Declaration::VariableDeclaration(VariableDeclaration {
+                name: destructured_name.clone(),
+                type_ascription,
+                type_ascription_span,
+                body,
+                is_mutable: false,
+            }),
+        )];
+        pattern.declare(
+            Expression::VariableExpression {
+                span: destructured_name.span().clone(),
+                name: destructured_name,
+            },
+            &mut declarations,
+        );
+        ok(declarations, warnings, errors)
+    }
+
+    fn parse_from_pair(pair: Pair<Rule>, config: Option<&BuildConfig>) -> CompileResult<Self> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let span = Span {
+            span: pair.as_span(),
+            path: config.map(|c| c.path()),
+        };
+        let pattern = match pair.as_rule() {
+            Rule::catch_all => VariablePattern::Placeholder,
+            Rule::var_binding => {
+                let mut parts = pair.into_inner();
+                let maybe_mut_keyword = parts.next().unwrap();
+                let is_mutable = maybe_mut_keyword.as_rule() == Rule::mut_keyword;
+                let name_pair = if is_mutable {
+                    parts.next().unwrap()
+                } else {
+                    maybe_mut_keyword
+                };
+                VariablePattern::Binding {
+                    name: check!(
+                        Ident::parse_from_pair(name_pair, config),
+                        return err(warnings, errors),
+                        warnings,
+                        errors
+                    ),
+                    is_mutable,
+                }
+            }
+            Rule::tuple_var_pattern => {
+                let mut elems = vec![];
+                for elem in pair.into_inner() {
+                    elems.push(check!(
+                        VariablePattern::parse_from_pair(elem, config),
+                        return err(warnings, errors),
+                        warnings,
+                        errors
+                    ));
+                }
+                VariablePattern::Tuple { elems, span }
+            }
+            Rule::struct_var_pattern => {
+                let mut parts = pair.into_inner();
+                let struct_name = check!(
+                    Ident::parse_from_pair(parts.next().unwrap(), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let mut fields = vec![];
+                for field in parts {
+                    let mut field_parts = field.into_inner();
+                    let first = field_parts.next().unwrap();
+                    let field = match first.as_rule() {
+                        // a field which is bound to a variable of the same name
+                        Rule::var_binding => {
+                            let binding = check!(
+                                VariablePattern::parse_from_pair(first, config),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            let name = match &binding {
+                                VariablePattern::Binding { name, .. } => name.clone(),
+                                _ => unreachable!("a binding is parsed from a var_binding"),
+                            };
+                            (name, binding)
+                        }
+                        _ => {
+                            let name = check!(
+                                Ident::parse_from_pair(first, config),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            let pattern = check!(
+                                VariablePattern::parse_from_pair(
+                                    field_parts.next().unwrap(),
+                                    config
+                                ),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            (name, pattern)
+                        }
+                    };
+                    fields.push(field);
+                }
+                VariablePattern::Struct {
+                    struct_name,
+                    fields,
+                    span,
+                }
+            }
+            a => unreachable!("variable patterns don't have any other sub-types: {:?}", a),
+        };
+        ok(pattern, warnings, errors)
+    }
+
+    /// The type of the values which this pattern can destructure, as far as it can tell.
+    fn shape(&self) -> TypeInfo {
+        match self {
+            VariablePattern::Tuple { elems, .. } => {
+                TypeInfo::Tuple(elems.iter().map(|elem| insert_type(elem.shape())).collect())
+            }
+            _ => TypeInfo::Unknown,
+        }
+    }
+
+    /// Declares the variables which this pattern binds, reading each of them out of `exp`.
+    fn declare(self, exp: Expression, declarations: &mut Vec<AstNodeContent>) {
+        match self {
+            VariablePattern::Binding { name, is_mutable } => {
+                declarations.push(AstNodeContent::Declaration(
+                    Declaration::VariableDeclaration(VariableDeclaration {
+                        name,
+                        type_ascription: TypeInfo::Unknown,
+                        type_ascription_span: None,
+                        body: exp,
+                        is_mutable,
+                    }),
+                ));
+            }
+            VariablePattern::Placeholder => (),
+            VariablePattern::Tuple { elems, span } => {
+                for (elem_num, elem) in elems.into_iter().enumerate() {
+                    let elem_exp = Expression::DelayedMatchTypeResolution {
+                        variant: DelayedResolutionVariant::TupleVariant(
+                            DelayedTupleVariantResolution {
+                                exp: Box::new(exp.clone()),
+                                elem_num,
+                            },
+                        ),
+                        span: span.clone(),
+                    };
+                    elem.declare(elem_exp, declarations);
+                }
+            }
+            VariablePattern::Struct {
+                struct_name,
+                fields,
+                span,
+            } => {
+                // the name is checked on its own, as a struct pattern may not have any fields
+                declarations.push(AstNodeContent::Expression(
+                    Expression::DelayedMatchTypeResolution {
+                        variant: DelayedResolutionVariant::StructName(
+                            DelayedStructNameResolution {
+                                exp: Box::new(exp.clone()),
+                                struct_name,
+                            },
+                        ),
+                        span: span.clone(),
+                    },
+                ));
+                for (field, pattern) in fields {
+                    let field_exp = Expression::SubfieldExpression {
+                        prefix: Box::new(exp.clone()),
+                        span: span.clone(),
+                        field_to_access: field,
+                    };
+                    pattern.declare(field_exp, declarations);
+                }
+            }
+        }
+    }
+}
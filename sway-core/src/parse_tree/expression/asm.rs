@@ -1,5 +1,6 @@
 use crate::build_config::BuildConfig;
 use crate::error::*;
+use crate::parse_tree::Purity;
 use crate::parser::Rule;
 use crate::span::Span;
 use crate::{Ident, TypeInfo};
@@ -175,6 +176,15 @@ impl AsmOp {
             errors,
         )
     }
+
+    /// The access of this op to contract storage.
+    pub(crate) fn storage_access(&self) -> Purity {
+        match self.op_name.as_str().to_lowercase().as_str() {
+            "srw" | "srwq" => Purity::Reads,
+            "sww" | "swwq" => Purity::Writes,
+            _ => Purity::Pure,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
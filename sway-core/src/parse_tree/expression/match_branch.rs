@@ -12,6 +12,8 @@ use super::{Expression, MatchCondition};
 #[derive(Debug, Clone)]
 pub struct MatchBranch {
     pub(crate) condition: MatchCondition,
+    /// An extra condition which has to hold for the branch to be taken, written after `if`.
+    pub(crate) guard: Option<Expression>,
     pub(crate) result: Expression,
     pub(crate) span: span::Span,
 }
@@ -92,7 +94,20 @@ impl MatchBranch {
                 return err(warnings, errors);
             }
         };
-        let result = match branch.next() {
+        let mut result = branch.next();
+        let guard = match result.clone() {
+            Some(guard) if guard.as_rule() == Rule::match_guard => {
+                result = branch.next();
+                Some(check!(
+                    Expression::parse_from_pair(guard.into_inner().next().unwrap(), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ))
+            }
+            _ => None,
+        };
+        let result = match result {
             Some(o) => o,
             None => {
                 errors.push(CompileError::Internal(
@@ -141,6 +156,7 @@ impl MatchBranch {
         ok(
             MatchBranch {
                 condition,
+                guard,
                 result,
                 span,
             },
@@ -1,12 +1,97 @@
 use crate::{
     error::{err, ok},
+    utils::join_spans,
     CallPath, CompileError, CompileResult, DelayedEnumVariantResolution, DelayedResolutionVariant,
-    DelayedStructFieldResolution, DelayedTupleVariantResolution, Expression, Ident, Literal,
-    Scrutinee, Span, StructScrutineeField,
+    DelayedStructFieldResolution, DelayedTupleVariantResolution, Expression, Ident, LazyOp,
+    Literal, Scrutinee, Span, StructScrutineeField,
 };
 
+use super::{Op, OpVariant};
+
+/// One of the requirements in a [MatchReqMap].
+#[derive(Debug, Clone)]
+pub enum MatchReq {
+    /// The expression on the left must equal the literal on the right.
+    Eq(Expression, Expression),
+    /// The expression must lie within a range of literals, which includes both ends.
+    Range {
+        exp: Expression,
+        start: Expression,
+        end: Expression,
+    },
+    /// The requirements of at least one of the alternatives must be met.
+    Or {
+        alternatives: Vec<MatchReqMap>,
+        span: Span,
+    },
+}
+
+impl MatchReq {
+    fn to_condition(&self) -> Expression {
+        match self {
+            MatchReq::Eq(left, right) => {
+                let span = join_spans(left.span(), right.span());
+                Expression::core_ops_eq(vec![left.to_owned(), right.to_owned()], span)
+            }
+            MatchReq::Range { exp, start, end } => {
+                let span = join_spans(start.span(), end.span());
+                let comparison = |op_variant, bound: &Expression| {
+                    let op = Op {
+                        span: span.clone(),
+                        op_variant,
+                    };
+                    Expression::core_ops(op, vec![exp.to_owned(), bound.to_owned()], span.clone())
+                };
+                lazy_operator(
+                    LazyOp::And,
+                    comparison(OpVariant::GreaterThanOrEqualTo, start),
+                    comparison(OpVariant::LessThanOrEqualTo, end),
+                )
+            }
+            MatchReq::Or { alternatives, span } => {
+                let mut conditions = vec![];
+                for alternative in alternatives {
+                    match match_req_map_to_condition(alternative) {
+                        Some(condition) => conditions.push(condition),
+                        // an alternative without requirements always matches
+                        None => {
+                            return Expression::Literal {
+                                value: Literal::Boolean(true),
+                                span: span.clone(),
+                            }
+                        }
+                    }
+                }
+                conditions
+                    .into_iter()
+                    .reduce(|lhs, rhs| lazy_operator(LazyOp::Or, lhs, rhs))
+                    .expect("an or-pattern has alternatives")
+            }
+        }
+    }
+}
+
+/// Joins the requirements of `match_req_map` into the conditional of a desugared if expression,
+/// or returns `None` if there aren't any.
+pub fn match_req_map_to_condition(match_req_map: &[MatchReq]) -> Option<Expression> {
+    match_req_map
+        .iter()
+        .map(MatchReq::to_condition)
+        .reduce(|lhs, rhs| lazy_operator(LazyOp::And, lhs, rhs))
+}
+
+fn lazy_operator(op: LazyOp, lhs: Expression, rhs: Expression) -> Expression {
+    let span = join_spans(lhs.span(), rhs.span());
+    Expression::LazyOperator {
+        op,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+        span,
+    }
+}
+
 /// List of requirements that a desugared if expression must include in the conditional.
-pub type MatchReqMap = Vec<(Expression, Expression)>;
+pub type MatchReqMap = Vec<MatchReq>;
 /// List of variable declarations that must be placed inside of the body of the if expression.
 pub type MatchImplMap = Vec<(Ident, Expression)>;
 /// This is the result type given back by the matcher.
@@ -41,7 +126,7 @@ pub type MatcherResult = Option<(MatchReqMap, MatchImplMap)>;
 ///
 /// ```ignore
 /// [
-///     (y, 5) // y must equal 5 to trigger this case
+///     Eq(y, 5) // y must equal 5 to trigger this case
 /// ]
 /// ```
 ///
@@ -69,6 +154,8 @@ pub fn matcher(exp: &Expression, scrutinee: &Scrutinee) -> CompileResult<Matcher
             span,
         } => match_enum(exp, call_path, args, span),
         Scrutinee::Tuple { elems, span } => match_tuple(exp, elems, span),
+        Scrutinee::Range { start, end, span } => match_range(exp, start, end, span),
+        Scrutinee::Or { alternatives, span } => match_or(exp, alternatives, span),
         scrutinee => {
             eprintln!("Unimplemented scrutinee: {:?}", scrutinee,);
             errors.push(CompileError::Unimplemented(
@@ -85,7 +172,7 @@ fn match_literal(
     scrutinee: &Literal,
     scrutinee_span: &Span,
 ) -> CompileResult<MatcherResult> {
-    let match_req_map = vec![(
+    let match_req_map = vec![MatchReq::Eq(
         exp.to_owned(),
         Expression::Literal {
             value: scrutinee.clone(),
@@ -96,6 +183,101 @@ fn match_literal(
     ok(Some((match_req_map, match_impl_map)), vec![], vec![])
 }
 
+fn match_range(
+    exp: &Expression,
+    start: &Literal,
+    end: &Literal,
+    span: &Span,
+) -> CompileResult<MatcherResult> {
+    let match_req_map = vec![MatchReq::Range {
+        exp: exp.to_owned(),
+        start: Expression::Literal {
+            value: start.clone(),
+            span: span.clone(),
+        },
+        end: Expression::Literal {
+            value: end.clone(),
+            span: span.clone(),
+        },
+    }];
+    let match_impl_map = vec![];
+    ok(Some((match_req_map, match_impl_map)), vec![], vec![])
+}
+
+/// Every alternative of an or-pattern has to bind the same variables. Each variable takes its
+/// value from the first alternative which matches, so it is declared with an if expression which
+/// tests the requirements of the alternatives in turn.
+fn match_or(
+    exp: &Expression,
+    alternatives: &[Scrutinee],
+    span: &Span,
+) -> CompileResult<MatcherResult> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut matched_alternatives = vec![];
+    for alternative in alternatives.iter() {
+        let new_matches = check!(
+            matcher(exp, alternative),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        match new_matches {
+            Some(new_matches) => matched_alternatives.push(new_matches),
+            None => return ok(None, warnings, errors),
+        }
+    }
+    let (_, first_match_impl_map) = &matched_alternatives[0];
+    for (_, match_impl_map) in matched_alternatives.iter() {
+        for (name, _) in first_match_impl_map.iter().chain(match_impl_map.iter()) {
+            let bound_in_both = [first_match_impl_map, match_impl_map]
+                .iter()
+                .all(|match_impl_map| match_impl_map.iter().any(|(other, _)| other == name));
+            if !bound_in_both {
+                errors.push(CompileError::MatchVariableNotBoundInAllPatterns {
+                    var_name: name.as_str().to_string(),
+                    span: span.clone(),
+                });
+                return err(warnings, errors);
+            }
+        }
+    }
+    let mut match_impl_map = vec![];
+    for (name, _) in first_match_impl_map.iter() {
+        let value_in = |match_impl_map: &MatchImplMap| {
+            match_impl_map
+                .iter()
+                .find(|(other, _)| other == name)
+                .map(|(_, value)| value.clone())
+                .expect("every alternative binds the variable")
+        };
+        let (_, last_match_impl_map) = matched_alternatives.last().unwrap();
+        let mut value = value_in(last_match_impl_map);
+        for (match_req_map, alternative_match_impl_map) in matched_alternatives.iter().rev().skip(1)
+        {
+            let alternative_value = value_in(alternative_match_impl_map);
+            value = match match_req_map_to_condition(match_req_map) {
+                Some(condition) => Expression::IfExp {
+                    condition: Box::new(condition),
+                    then: Box::new(alternative_value),
+                    r#else: Some(Box::new(value)),
+                    span: span.clone(),
+                },
+                None => alternative_value,
+            };
+        }
+        match_impl_map.push((name.clone(), value));
+    }
+    let match_req_map = vec![MatchReq::Or {
+        alternatives: matched_alternatives
+            .into_iter()
+            .map(|(match_req_map, _)| match_req_map)
+            .collect(),
+        span: span.clone(),
+    }];
+    ok(Some((match_req_map, match_impl_map)), warnings, errors)
+}
+
 fn match_variable(
     exp: &Expression,
     scrutinee_name: &Ident,
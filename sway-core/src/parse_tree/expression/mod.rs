@@ -2,8 +2,8 @@ use crate::build_config::BuildConfig;
 use crate::parse_tree::{CallPath, Literal};
 use crate::Span;
 use crate::{error::*, AstNode, AstNodeContent, Declaration, VariableDeclaration};
+use crate::{hidden_ident, CodeBlock, HiddenVariable, Ident};
 use crate::{parser::Rule, type_engine::TypeInfo};
-use crate::{CodeBlock, Ident};
 
 use either::Either;
 use pest;
@@ -22,7 +22,7 @@ pub(crate) use asm::*;
 pub(crate) use match_branch::MatchBranch;
 pub(crate) use match_condition::CatchAll;
 pub(crate) use match_condition::MatchCondition;
-use matcher::matcher;
+use matcher::{match_req_map_to_condition, matcher, MatchImplMap, MatchReqMap};
 pub(crate) use method_name::MethodName;
 pub(crate) use scrutinee::{Scrutinee, StructScrutineeField};
 pub(crate) use unary_op::UnaryOp;
@@ -58,6 +58,12 @@ pub enum Expression {
         contents: Vec<Expression>,
         span: Span,
     },
+    /// An array of `count` copies of `value`, both of which are constant expressions.
+    ArrayRepeat {
+        value: Box<Expression>,
+        count: Box<Expression>,
+        span: Span,
+    },
     MatchExpression {
         primary_expression: Box<Expression>,
         branches: Vec<MatchBranch>,
@@ -136,6 +142,17 @@ pub enum Expression {
         index: Box<Expression>,
         span: Span,
     },
+    /// A _try expression_ is anything of the form:
+    /// ```ignore
+    /// <exp>?
+    /// ```
+    /// Where `exp` is a result-like enum, like an `Option` or a `Result`. It evaluates to the value
+    /// held by `exp`, or returns the error from the enclosing function. As the enum can only be
+    /// known once `exp` is type checked, it is desugared during type checking.
+    TryExpression {
+        expr: Box<Expression>,
+        span: Span,
+    },
     /// This variant serves as a stand-in for parsing-level match expression desugaring.
     /// Because types cannot be known at parsing-time, a desugared struct or enum gets
     /// special cased into this variant. During type checking, this variant is removed
@@ -153,6 +170,8 @@ pub enum DelayedResolutionVariant {
     StructField(DelayedStructFieldResolution),
     EnumVariant(DelayedEnumVariantResolution),
     TupleVariant(DelayedTupleVariantResolution),
+    StructName(DelayedStructNameResolution),
+    EnumTag(DelayedEnumTagResolution),
 }
 
 /// During type checking, this gets replaced with struct field access.
@@ -163,6 +182,14 @@ pub struct DelayedStructFieldResolution {
     pub field: Ident,
 }
 
+/// During type checking, this checks that `exp` is of the struct `struct_name`, for a struct
+/// pattern of a destructuring `let`, and gets replaced with a block which evaluates `exp`.
+#[derive(Debug, Clone)]
+pub struct DelayedStructNameResolution {
+    pub exp: Box<Expression>,
+    pub struct_name: Ident,
+}
+
 /// During type checking, this gets replaced with enum arg access.
 #[derive(Debug, Clone)]
 pub struct DelayedEnumVariantResolution {
@@ -171,6 +198,13 @@ pub struct DelayedEnumVariantResolution {
     pub arg_num: usize,
 }
 
+/// During type checking, this gets replaced with a read of the tag of the enum `exp`, which tells
+/// which of its variants it is.
+#[derive(Debug, Clone)]
+pub struct DelayedEnumTagResolution {
+    pub exp: Box<Expression>,
+}
+
 /// During type checking, this gets replaced with tuple arg access.
 #[derive(Debug, Clone)]
 pub struct DelayedTupleVariantResolution {
@@ -251,6 +285,7 @@ impl Expression {
             VariableExpression { span, .. } => span,
             Tuple { span, .. } => span,
             Array { span, .. } => span,
+            ArrayRepeat { span, .. } => span,
             MatchExpression { span, .. } => span,
             StructExpression { span, .. } => span,
             CodeBlock { span, .. } => span,
@@ -261,6 +296,7 @@ impl Expression {
             DelineatedPath { span, .. } => span,
             AbiCast { span, .. } => span,
             ArrayIndex { span, .. } => span,
+            TryExpression { span, .. } => span,
             DelayedMatchTypeResolution { span, .. } => span,
         })
         .clone()
@@ -273,7 +309,7 @@ impl Expression {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
         let expr_for_debug = expr.clone();
-        let mut expr_iter = expr.into_inner();
+        let mut expr_iter = expr.into_inner().peekable();
         // first expr is always here
         let first_expr = expr_iter.next().unwrap();
         let first_expr = check!(
@@ -288,6 +324,7 @@ impl Expression {
             warnings,
             errors
         );
+        let first_expr = parse_try_ops(first_expr, &mut expr_iter, config);
         let mut expr_or_op_buf: Vec<Either<Op, Expression>> =
             vec![Either::Right(first_expr.clone())];
         // sometimes exprs are followed by ops in the same expr
@@ -333,6 +370,7 @@ impl Expression {
                     }
                 }
             };
+            let next_expr = parse_try_ops(next_expr, &mut expr_iter, config);
             // pushing these into a vec in this manner so we can re-associate according to order of
             // operations later
             expr_or_op_buf.push(Either::Left(op));
@@ -500,6 +538,7 @@ impl Expression {
                         MatchBranch::parse_from_pair(exp, config),
                         MatchBranch {
                             condition: MatchCondition::CatchAll(CatchAll { span: span.clone() }),
+                            guard: None,
                             result: Expression::Tuple {
                                 fields: vec![],
                                 span: span.clone(),
@@ -511,12 +550,11 @@ impl Expression {
                     );
                     branches.push(res);
                 }
-                check!(
-                    desugar_match_expression(primary_expression, branches, span),
-                    return err(warnings, errors),
-                    warnings,
-                    errors
-                )
+                Expression::MatchExpression {
+                    primary_expression: Box::new(primary_expression),
+                    branches,
+                    span,
+                }
             }
             Rule::struct_expression => {
                 let mut expr_iter = expr.into_inner();
@@ -600,16 +638,36 @@ impl Expression {
                 let condition_pair = if_exp_pairs.next().unwrap();
                 let then_pair = if_exp_pairs.next().unwrap();
                 let else_pair = if_exp_pairs.next();
-                let condition = Box::new(check!(
-                    Expression::parse_from_pair(condition_pair, config),
-                    Expression::Tuple {
-                        fields: vec![],
-                        span: span.clone()
-                    },
-                    warnings,
-                    errors
-                ));
-                let then = Box::new(check!(
+                let (value, condition, declarations) = match condition_pair.as_rule() {
+                    Rule::let_condition => {
+                        let LetCondition {
+                            value,
+                            condition,
+                            declarations,
+                        } = check!(
+                            parse_let_condition(condition_pair, config),
+                            return err(warnings, errors),
+                            warnings,
+                            errors
+                        );
+                        (Some(value), condition, declarations)
+                    }
+                    _ => (
+                        None,
+                        check!(
+                            Expression::parse_from_pair(condition_pair, config),
+                            Expression::Tuple {
+                                fields: vec![],
+                                span: span.clone()
+                            },
+                            warnings,
+                            errors
+                        ),
+                        vec![],
+                    ),
+                };
+                let condition = Box::new(condition);
+                let mut then = check!(
                     Expression::parse_from_pair_inner(then_pair, config),
                     Expression::Tuple {
                         fields: vec![],
@@ -617,7 +675,14 @@ impl Expression {
                     },
                     warnings,
                     errors
-                ));
+                );
+                // the variables which an `if let` binds are only in scope in its `then` block
+                if let Expression::CodeBlock { contents, .. } = &mut then {
+                    let mut then_contents = declarations;
+                    then_contents.append(&mut contents.contents);
+                    contents.contents = then_contents;
+                }
+                let then = Box::new(then);
                 let r#else = else_pair.map(|else_pair| {
                     Box::new(check!(
                         Expression::parse_from_pair_inner(else_pair, config),
@@ -629,11 +694,28 @@ impl Expression {
                         errors
                     ))
                 });
-                Expression::IfExp {
+                let if_exp = Expression::IfExp {
                     condition,
                     then,
                     r#else,
-                    span,
+                    span: span.clone(),
+                };
+                match value {
+                    // the value tested by an `if let` is declared in a block around the `if`
+                    Some(value) => Expression::CodeBlock {
+                        contents: CodeBlock {
+                            contents: vec![
+                                value,
+                                AstNode {
+                                    span: span.clone(),
+                                    content: AstNodeContent::ImplicitReturnExpression(if_exp),
+                                },
+                            ],
+                            whole_block_span: span.clone(),
+                        },
+                        span,
+                    },
+                    None => if_exp,
                 }
             }
             Rule::asm_expression => {
@@ -982,6 +1064,28 @@ impl Expression {
     }
 }
 
+/// Applies the `?` operators which follow `expr` in `pairs`, if there are any.
+fn parse_try_ops(
+    mut expr: Expression,
+    pairs: &mut std::iter::Peekable<pest::iterators::Pairs<Rule>>,
+    config: Option<&BuildConfig>,
+) -> Expression {
+    while let Some(try_op) = pairs.next_if(|pair| pair.as_rule() == Rule::try_op) {
+        let span = join_spans(
+            expr.span(),
+            Span {
+                span: try_op.as_span(),
+                path: config.map(|c| c.path()),
+            },
+        );
+        expr = Expression::TryExpression {
+            expr: Box::new(expr),
+            span,
+        };
+    }
+    expr
+}
+
 fn convert_unary_to_fn_calls(
     item: Pair<Rule>,
     config: Option<&BuildConfig>,
@@ -1005,6 +1109,23 @@ fn convert_unary_to_fn_calls(
                     errors
                 ),
             )),
+            // a negated integer literal is a literal itself, so that it can be of a signed type
+            Rule::literal_value
+                if matches!(unary_stack.last(), Some((_, UnaryOp::Neg)))
+                    && is_integer_literal(&item) =>
+            {
+                let (op_span, _) = unary_stack.pop().expect("checked above");
+                let (value, span) = check!(
+                    Literal::parse_negated_from_pair(item, config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                expr = Some(Expression::Literal {
+                    value,
+                    span: join_spans(op_span, span),
+                })
+            }
             _ => {
                 expr = Some(check!(
                     Expression::parse_from_pair_inner(item, config),
@@ -1017,7 +1138,6 @@ fn convert_unary_to_fn_calls(
     }
 
     let mut expr = expr.expect("guaranteed by grammar");
-    assert!(!unary_stack.is_empty(), "guaranteed by grammar");
     while let Some((op_span, unary_op)) = unary_stack.pop() {
         expr = unary_op.to_fn_application(
             expr.clone(),
@@ -1028,6 +1148,15 @@ fn convert_unary_to_fn_calls(
     ok(expr, warnings, errors)
 }
 
+fn is_integer_literal(literal: &Pair<Rule>) -> bool {
+    literal
+        .clone()
+        .into_inner()
+        .next()
+        .map(|inner| inner.as_rule() == Rule::integer)
+        .unwrap_or(false)
+}
+
 pub(crate) fn parse_array_index(
     item: Pair<Rule>,
     config: Option<&BuildConfig>,
@@ -1156,64 +1285,64 @@ fn parse_array_elems(elems: Pair<Rule>, config: Option<&BuildConfig>) -> Compile
 
     let mut elem_iter = elems.into_inner();
     let first_elem = elem_iter.next().unwrap();
-    let contents = match first_elem.as_rule() {
-        Rule::literal_value => {
-            // The form [initialiser; count].
-            let span = first_elem.as_span();
-            let init = Literal::parse_from_pair(first_elem, config)
-                .map(|(value, span)| Expression::Literal { value, span })
-                .unwrap_or_else(&mut warnings, &mut errors, || Expression::Tuple {
-                    fields: vec![],
-                    span: Span { span, path },
-                });
+    let first_span = first_elem.as_span();
+    let first_elem_expr = check!(
+        Expression::parse_from_pair(first_elem, config),
+        Expression::Tuple {
+            fields: vec![],
+            span: Span {
+                span: first_span,
+                path: path.clone()
+            }
+        },
+        warnings,
+        errors
+    );
+    let mut elem_iter = elem_iter.peekable();
+    if let Some(Rule::array_length) = elem_iter.peek().map(|pair| pair.as_rule()) {
+        // The form [initialiser; count].
+        let count = elem_iter.next().unwrap().into_inner().next().unwrap();
+        let count_span = count.as_span();
+        let count = check!(
+            Expression::parse_from_pair(count, config),
+            Expression::Tuple {
+                fields: vec![],
+                span: Span {
+                    span: count_span,
+                    path
+                }
+            },
+            warnings,
+            errors
+        );
+        return ok(
+            Expression::ArrayRepeat {
+                value: Box::new(first_elem_expr),
+                count: Box::new(count),
+                span,
+            },
+            warnings,
+            errors,
+        );
+    }
 
-            // This is a constant integer expression we need to parse now into a count.  Currently
-            // assuming it's a `u64_integer` in the grammar, so we can just use the builtin
-            // `parse()` to get it.
-            let count = elem_iter
-                .next()
-                .unwrap()
-                .as_str()
-                .trim()
-                .parse::<usize>()
-                .unwrap();
-            let mut elems = Vec::with_capacity(count);
-            elems.resize(count as usize, init);
-            elems
-        }
-        _otherwise => {
-            // The simple form [elem0, elem1, ..., elemN].
-            let span = first_elem.as_span();
-            let first_elem_expr = check!(
-                Expression::parse_from_pair(first_elem, config),
-                Expression::Tuple {
-                    fields: vec![],
-                    span: Span {
-                        span,
-                        path: path.clone()
-                    }
-                },
-                warnings,
-                errors
-            );
-            elem_iter.fold(vec![first_elem_expr], |mut elems, pair| {
-                let span = pair.as_span();
-                elems.push(check!(
-                    Expression::parse_from_pair(pair, config),
-                    Expression::Tuple {
-                        fields: vec![],
-                        span: Span {
-                            span,
-                            path: path.clone()
-                        }
-                    },
-                    warnings,
-                    errors
-                ));
-                elems
-            })
-        }
-    };
+    // The simple form [elem0, elem1, ..., elemN].
+    let contents = elem_iter.fold(vec![first_elem_expr], |mut elems, pair| {
+        let span = pair.as_span();
+        elems.push(check!(
+            Expression::parse_from_pair(pair, config),
+            Expression::Tuple {
+                fields: vec![],
+                span: Span {
+                    span,
+                    path: path.clone()
+                }
+            },
+            warnings,
+            errors
+        ));
+        elems
+    });
 
     ok(Expression::Array { contents, span }, warnings, errors)
 }
@@ -1275,7 +1404,7 @@ impl Op {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum OpVariant {
     Add,
     Subtract,
@@ -1451,11 +1580,120 @@ fn arrange_by_order_of_operations(
 
 struct MatchedBranch {
     result: Expression,
-    match_req_map: Vec<(Expression, Expression)>,
+    guard: Option<Expression>,
+    match_req_map: MatchReqMap,
     match_impl_map: Vec<(Ident, Expression)>,
     branch_span: Span,
 }
 
+/// Declares each of the variables which a pattern binds, in the order in which the pattern binds
+/// them.
+fn match_impl_map_to_declarations(match_impl_map: &MatchImplMap) -> Vec<AstNode> {
+    match_impl_map
+        .iter()
+        .map(|(left_impl, right_impl)| AstNode {
+            content: AstNodeContent::Declaration(Declaration::VariableDeclaration(
+                VariableDeclaration {
+                    name: left_impl.clone(),
+                    is_mutable: false,
+                    body: right_impl.clone(),
+                    type_ascription: TypeInfo::Unknown,
+                    type_ascription_span: None,
+                },
+            )),
+            span: join_spans(left_impl.span().clone(), right_impl.span()),
+        })
+        .collect()
+}
+
+/// The `let Pattern = expr` condition of an `if let` or a `while let`, desugared so that `expr` is
+/// only evaluated once.
+pub(crate) struct LetCondition {
+    /// Declares the hidden variable which holds the value of `expr`.
+    pub(crate) value: AstNode,
+    /// Tests whether the hidden variable matches the pattern.
+    pub(crate) condition: Expression,
+    /// Declares the variables which the pattern binds. They belong at the start of the block which
+    /// runs when the pattern matches, so that they are only in scope there.
+    pub(crate) declarations: Vec<AstNode>,
+}
+
+/// Desugars the `let Pattern = expr` condition of an `if let` or a `while let` into the
+/// declaration of a hidden variable holding `expr`, a condition which tests whether it matches
+/// the pattern, and the declarations of the variables which the pattern binds.
+pub(crate) fn parse_let_condition(
+    pair: Pair<Rule>,
+    config: Option<&BuildConfig>,
+) -> CompileResult<LetCondition> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let span = Span {
+        span: pair.as_span(),
+        path: config.map(|c| c.path()),
+    };
+    let mut iter = pair.into_inner();
+    let _let_keyword = iter.next().unwrap();
+    let scrutinee = check!(
+        Scrutinee::parse_from_pair(iter.next().unwrap(), config),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let exp = check!(
+        Expression::parse_from_pair(iter.next().unwrap(), config),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let value_name = hidden_ident(HiddenVariable::LetValue, &exp.span());
+    let value_exp = Expression::VariableExpression {
+        name: value_name.clone(),
+        span: exp.span(),
+    };
+    let value = AstNode {
+        span: exp.span(),
+        content: AstNodeContent::Declaration(Declaration::VariableDeclaration(
+            VariableDeclaration {
+                name: value_name,
+                is_mutable: false,
+                body: exp,
+                type_ascription: TypeInfo::Unknown,
+                type_ascription_span: None,
+            },
+        )),
+    };
+    let matches = check!(
+        matcher(&value_exp, &scrutinee),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    let (match_req_map, match_impl_map) = match matches {
+        Some(matches) => matches,
+        None => {
+            errors.push(CompileError::PatternMatchingAlgorithmFailure(
+                "found None",
+                span,
+            ));
+            return err(warnings, errors);
+        }
+    };
+    // a pattern without requirements always matches
+    let condition = match_req_map_to_condition(&match_req_map).unwrap_or(Expression::Literal {
+        value: Literal::Boolean(true),
+        span,
+    });
+    ok(
+        LetCondition {
+            value,
+            condition,
+            declarations: match_impl_map_to_declarations(&match_impl_map),
+        },
+        warnings,
+        errors,
+    )
+}
+
 /// This algorithm desugars match expressions to if statements.
 ///
 /// Given the following example:
@@ -1500,10 +1738,22 @@ struct MatchedBranch {
 ///     2b. Assemble the statements that go inside of the body of the if expression
 ///     2c. Assemble the giant if statement.
 /// 3. Return!
+///
 pub fn desugar_match_expression(
     primary_expression: Expression,
     branches: Vec<MatchBranch>,
     _span: Span,
+) -> CompileResult<Expression> {
+    desugar_match_expression_with_exhaustiveness(primary_expression, branches, false)
+}
+
+/// Desugars a match expression like [desugar_match_expression] does. If the match is known to be
+/// `exhaustive`, the last branch doesn't need a conditional and becomes the final `else`, which
+/// gives the if statement a value on every path.
+pub(crate) fn desugar_match_expression_with_exhaustiveness(
+    primary_expression: Expression,
+    branches: Vec<MatchBranch>,
+    exhaustive: bool,
 ) -> CompileResult<Expression> {
     let mut errors = vec![];
     let mut warnings = vec![];
@@ -1512,6 +1762,7 @@ pub fn desugar_match_expression(
     let mut matched_branches = vec![];
     for MatchBranch {
         condition,
+        guard,
         result,
         span: branch_span,
     } in branches.iter()
@@ -1529,6 +1780,7 @@ pub fn desugar_match_expression(
             Some((match_req_map, match_impl_map)) => {
                 matched_branches.push(MatchedBranch {
                     result: result.to_owned(),
+                    guard: guard.to_owned(),
                     match_req_map,
                     match_impl_map,
                     branch_span: branch_span.to_owned(),
@@ -1552,54 +1804,45 @@ pub fn desugar_match_expression(
     let mut if_statement = None;
     for MatchedBranch {
         result,
+        guard,
         match_req_map,
         match_impl_map,
         branch_span,
     } in matched_branches.iter().rev()
     {
         // 2a. Assemble the conditional that goes in the if primary expression.
-        let mut conditional = None;
-        for (left_req, right_req) in match_req_map.iter() {
-            let joined_span = join_spans(left_req.clone().span(), right_req.clone().span());
-            let condition = Expression::core_ops_eq(
-                vec![left_req.to_owned(), right_req.to_owned()],
-                joined_span,
-            );
-            match conditional {
-                None => {
-                    conditional = Some(condition);
-                }
-                Some(the_conditional) => {
-                    conditional = Some(Expression::LazyOperator {
-                        op: crate::LazyOp::And,
-                        lhs: Box::new(the_conditional.clone()),
-                        rhs: Box::new(condition.clone()),
-                        span: join_spans(the_conditional.span(), condition.span()),
-                    });
-                }
-            }
-        }
+        let mut conditional = match_req_map_to_condition(match_req_map);
 
         // 2b. Assemble the statements that go inside of the body of the if expression
-        let mut code_block_stmts = vec![];
-        let mut code_block_stmts_span = None;
-        for (left_impl, right_impl) in match_impl_map.iter() {
-            let decl = Declaration::VariableDeclaration(VariableDeclaration {
-                name: left_impl.clone(),
-                is_mutable: false,
-                body: right_impl.clone(),
-                type_ascription: TypeInfo::Unknown,
-                type_ascription_span: None,
-            });
-            let new_span = join_spans(left_impl.span().clone(), right_impl.span());
-            code_block_stmts.push(AstNode {
-                content: AstNodeContent::Declaration(decl),
-                span: new_span.clone(),
+        let mut code_block_stmts = match_impl_map_to_declarations(match_impl_map);
+        let mut code_block_stmts_span = code_block_stmts
+            .iter()
+            .map(|stmt| stmt.span.clone())
+            .reduce(join_spans);
+        // the guard can refer to the variables which the pattern binds, so it is placed in a
+        // block after their declarations
+        if let Some(guard) = guard {
+            let mut guard_stmts = code_block_stmts.clone();
+            guard_stmts.push(AstNode {
+                content: AstNodeContent::ImplicitReturnExpression(guard.clone()),
+                span: guard.span(),
             });
-            code_block_stmts_span = match code_block_stmts_span {
-                None => Some(new_span),
-                Some(old_span) => Some(join_spans(old_span, new_span)),
+            let guard = Expression::CodeBlock {
+                contents: CodeBlock {
+                    contents: guard_stmts,
+                    whole_block_span: guard.span(),
+                },
+                span: guard.span(),
             };
+            conditional = Some(match conditional {
+                None => guard,
+                Some(the_conditional) => Expression::LazyOperator {
+                    op: crate::LazyOp::And,
+                    span: join_spans(the_conditional.span(), guard.span()),
+                    lhs: Box::new(the_conditional),
+                    rhs: Box::new(guard),
+                },
+            });
         }
         match result {
             Expression::CodeBlock {
@@ -1619,7 +1862,7 @@ pub fn desugar_match_expression(
             }
             result => {
                 code_block_stmts.push(AstNode {
-                    content: AstNodeContent::Expression(result.clone()),
+                    content: AstNodeContent::ImplicitReturnExpression(result.clone()),
                     span: result.span(),
                 });
                 code_block_stmts_span = match code_block_stmts_span {
@@ -1644,13 +1887,13 @@ pub fn desugar_match_expression(
         match if_statement {
             None => {
                 if_statement = match conditional {
-                    None => Some(code_block),
-                    Some(conditional) => Some(Expression::IfExp {
+                    Some(conditional) if !exhaustive => Some(Expression::IfExp {
                         condition: Box::new(conditional.clone()),
                         then: Box::new(code_block.clone()),
                         r#else: None,
                         span: join_spans(conditional.span(), code_block.span()),
                     }),
+                    _ => Some(code_block),
                 };
             }
             Some(Expression::CodeBlock {
@@ -1685,17 +1928,21 @@ pub fn desugar_match_expression(
                 r#else,
                 span: exp_span,
             }) => {
-                if_statement = Some(Expression::IfExp {
-                    condition: Box::new(conditional.unwrap()),
-                    then: Box::new(code_block.clone()),
-                    r#else: Some(Box::new(Expression::IfExp {
-                        condition,
-                        then,
-                        r#else,
-                        span: exp_span.clone(),
-                    })),
-                    span: join_spans(code_block.clone().span(), exp_span),
-                });
+                if_statement = match conditional {
+                    // the branches after a catch-all are unreachable
+                    None => Some(code_block),
+                    Some(conditional) => Some(Expression::IfExp {
+                        condition: Box::new(conditional),
+                        then: Box::new(code_block.clone()),
+                        r#else: Some(Box::new(Expression::IfExp {
+                            condition,
+                            then,
+                            r#else,
+                            span: exp_span.clone(),
+                        })),
+                        span: join_spans(code_block.clone().span(), exp_span),
+                    }),
+                };
             }
             Some(if_statement) => {
                 eprintln!("Unimplemented if_statement_pattern: {:?}", if_statement,);
@@ -38,6 +38,17 @@ pub enum Scrutinee {
         elems: Vec<Scrutinee>,
         span: Span,
     },
+    /// Matches values from `start` up to and including `end`.
+    Range {
+        start: Literal,
+        end: Literal,
+        span: Span,
+    },
+    /// Matches values which any of the `alternatives` match.
+    Or {
+        alternatives: Vec<Scrutinee>,
+        span: Span,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -56,20 +67,32 @@ impl Scrutinee {
             Scrutinee::StructScrutinee { span, .. } => span.clone(),
             Scrutinee::EnumScrutinee { span, .. } => span.clone(),
             Scrutinee::Tuple { span, .. } => span.clone(),
+            Scrutinee::Range { span, .. } => span.clone(),
+            Scrutinee::Or { span, .. } => span.clone(),
         }
     }
 
     pub fn parse_from_pair(pair: Pair<Rule>, config: Option<&BuildConfig>) -> CompileResult<Self> {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
-        let mut scrutinees = pair.into_inner();
-        let scrutinee = scrutinees.next().unwrap();
-        let scrutinee = check!(
-            Scrutinee::parse_from_pair_inner(scrutinee, config),
-            return err(warnings, errors),
-            warnings,
-            errors
-        );
+        let span = Span {
+            span: pair.as_span(),
+            path: config.map(|c| c.path()),
+        };
+        let mut alternatives = vec![];
+        for scrutinee in pair.into_inner() {
+            alternatives.push(check!(
+                Scrutinee::parse_from_pair_inner(scrutinee, config),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
+        }
+        let scrutinee = if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Scrutinee::Or { alternatives, span }
+        };
         ok(scrutinee, warnings, errors)
     }
 
@@ -115,6 +138,12 @@ impl Scrutinee {
                 warnings,
                 errors
             ),
+            Rule::range_scrutinee => check!(
+                Self::parse_from_pair_range(scrutinee, config, span),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ),
             a => {
                 eprintln!(
                     "Unimplemented scrutinee: {:?} ({:?}) ({:?})",
@@ -299,4 +328,28 @@ impl Scrutinee {
         let scrutinee = Scrutinee::Tuple { elems, span };
         ok(scrutinee, warnings, errors)
     }
+
+    fn parse_from_pair_range(
+        scrutinee: Pair<Rule>,
+        config: Option<&BuildConfig>,
+        span: Span,
+    ) -> CompileResult<Self> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let mut parts = scrutinee.into_inner();
+        let (start, _) = check!(
+            Literal::parse_from_pair(parts.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let (end, _) = check!(
+            Literal::parse_from_pair(parts.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let scrutinee = Scrutinee::Range { start, end, span };
+        ok(scrutinee, warnings, errors)
+    }
 }
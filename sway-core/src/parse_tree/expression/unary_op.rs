@@ -1,6 +1,6 @@
 use crate::build_config::BuildConfig;
 use crate::error::*;
-use crate::parse_tree::{CallPath, Expression};
+use crate::parse_tree::{CallPath, Expression, MethodName};
 use crate::parser::Rule;
 use crate::span::Span;
 use crate::Ident;
@@ -9,6 +9,7 @@ use pest::iterators::Pair;
 #[derive(Clone, Debug)]
 pub enum UnaryOp {
     Not,
+    Neg,
     Ref,
     Deref,
 }
@@ -18,6 +19,7 @@ impl UnaryOp {
         use UnaryOp::*;
         match pair.as_str() {
             "!" => ok(Not, Vec::new(), Vec::new()),
+            "-" => ok(Neg, Vec::new(), Vec::new()),
             "ref" => ok(Ref, Vec::new(), Vec::new()),
             "deref" => ok(Deref, Vec::new(), Vec::new()),
             _ => {
@@ -39,19 +41,33 @@ impl UnaryOp {
             Ref => "ref",
             Deref => "deref",
             Not => "not",
+            Neg => "neg",
         }
     }
 
     pub fn to_fn_application(&self, arg: Expression, span: Span, op_span: Span) -> Expression {
+        let call_path = CallPath {
+            prefixes: vec![
+                Ident::new_with_override("core", op_span.clone()),
+                Ident::new_with_override("ops", op_span.clone()),
+            ],
+            suffix: Ident::new_with_override(self.to_var_name(), op_span),
+        };
+        // negation is a method of the type being negated, like the binary operators
+        if let UnaryOp::Neg = self {
+            return Expression::MethodApplication {
+                method_name: MethodName::FromType {
+                    call_path,
+                    type_name: None,
+                    is_absolute: true,
+                },
+                arguments: vec![arg],
+                span,
+            };
+        }
         Expression::FunctionApplication {
             type_arguments: Default::default(),
-            name: CallPath {
-                prefixes: vec![
-                    Ident::new_with_override("core", op_span.clone()),
-                    Ident::new_with_override("ops", op_span.clone()),
-                ],
-                suffix: Ident::new_with_override(self.to_var_name(), op_span),
-            },
+            name: call_path,
             arguments: vec![arg],
             span,
         }
@@ -0,0 +1,107 @@
+use crate::build_config::BuildConfig;
+use crate::parser::Rule;
+use crate::span::Span;
+use crate::{
+    error::{err, ok, CompileResult},
+    CodeBlock, Expression, Ident,
+};
+use pest::iterators::Pair;
+
+/// A parsed for loop, which binds `variable` to each element of an array, or each integer of a
+/// range, in turn and runs the `body` for each of them.
+#[derive(Debug, Clone)]
+pub struct ForLoop {
+    pub(crate) variable: Ident,
+    pub(crate) iterable: ForLoopIterable,
+    pub(crate) body: CodeBlock,
+}
+
+/// What a [ForLoop] iterates over.
+#[derive(Debug, Clone)]
+pub enum ForLoopIterable {
+    /// The integers from `start` up to, but not including, `end`.
+    Range { start: Expression, end: Expression },
+    /// The elements of a static array. Its length is only known once it has been type checked.
+    Array(Expression),
+}
+
+impl ForLoop {
+    pub(crate) fn parse_from_pair(
+        pair: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let path = config.map(|c| c.path());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let mut iter = pair.into_inner();
+        let _for_keyword = iter.next().unwrap();
+        let variable = check!(
+            Ident::parse_from_pair(iter.next().unwrap(), config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let _in_keyword = iter.next().unwrap();
+        let iterable = iter.next().unwrap();
+        let body = iter.next().unwrap();
+        let whole_block_span = Span {
+            span: body.as_span(),
+            path: path.clone(),
+        };
+
+        // the unit expression stands in for any expression which fails to parse
+        let unit = |pair: &Pair<Rule>| Expression::Tuple {
+            fields: vec![],
+            span: Span {
+                span: pair.as_span(),
+                path: path.clone(),
+            },
+        };
+        let iterable = match iterable.as_rule() {
+            Rule::for_range => {
+                let mut bounds = iterable.into_inner();
+                let (start, end) = (bounds.next().unwrap(), bounds.next().unwrap());
+                ForLoopIterable::Range {
+                    start: check!(
+                        Expression::parse_from_pair(start.clone(), config),
+                        unit(&start),
+                        warnings,
+                        errors
+                    ),
+                    end: check!(
+                        Expression::parse_from_pair(end.clone(), config),
+                        unit(&end),
+                        warnings,
+                        errors
+                    ),
+                }
+            }
+            _ => ForLoopIterable::Array(check!(
+                Expression::parse_from_pair(iterable.clone(), config),
+                unit(&iterable),
+                warnings,
+                errors
+            )),
+        };
+
+        let body = check!(
+            CodeBlock::parse_from_pair(body, config),
+            CodeBlock {
+                contents: Default::default(),
+                whole_block_span,
+            },
+            warnings,
+            errors
+        );
+
+        ok(
+            ForLoop {
+                variable,
+                iterable,
+                body,
+            },
+            warnings,
+            errors,
+        )
+    }
+}
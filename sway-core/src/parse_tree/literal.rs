@@ -19,6 +19,10 @@ pub enum Literal {
     U16(u16),
     U32(u32),
     U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
     String(span::Span),
     Boolean(bool),
     Byte(u8),
@@ -34,6 +38,10 @@ impl Literal {
             U16(_) => ResolvedType::UnsignedInteger(IntegerBits::Sixteen),
             U32(_) => ResolvedType::UnsignedInteger(IntegerBits::ThirtyTwo),
             U64(_) => ResolvedType::UnsignedInteger(IntegerBits::SixtyFour),
+            I8(_) => ResolvedType::SignedInteger(IntegerBits::Eight),
+            I16(_) => ResolvedType::SignedInteger(IntegerBits::Sixteen),
+            I32(_) => ResolvedType::SignedInteger(IntegerBits::ThirtyTwo),
+            I64(_) => ResolvedType::SignedInteger(IntegerBits::SixtyFour),
             String(inner) => ResolvedType::Str(inner.as_str().len() as u64),
             Boolean(_) => ResolvedType::Boolean,
             Byte(_) => ResolvedType::Byte,
@@ -47,79 +55,7 @@ impl Literal {
         let path = config.map(|c| c.path());
         let lit_inner = lit.into_inner().next().unwrap();
         let (parsed, span): (Result<Literal, CompileError>, _) = match lit_inner.as_rule() {
-            Rule::integer => {
-                let mut int_inner = lit_inner.into_inner().next().unwrap();
-                let rule = int_inner.as_rule();
-                if int_inner.as_rule() != Rule::basic_integer {
-                    int_inner = int_inner.into_inner().next().unwrap()
-                }
-                let span = span::Span {
-                    span: int_inner.as_span(),
-                    path: path.clone(),
-                };
-                (
-                    match rule {
-                        Rule::u8_integer => int_inner
-                            .as_str()
-                            .trim()
-                            .replace("_", "")
-                            .parse()
-                            .map(Literal::U8)
-                            .map_err(|e| {
-                                handle_parse_int_error(
-                                    e,
-                                    TypeInfo::UnsignedInteger(IntegerBits::Eight),
-                                    int_inner.as_span(),
-                                    path.clone(),
-                                )
-                            }),
-                        Rule::u16_integer => int_inner
-                            .as_str()
-                            .trim()
-                            .replace("_", "")
-                            .parse()
-                            .map(Literal::U16)
-                            .map_err(|e| {
-                                handle_parse_int_error(
-                                    e,
-                                    TypeInfo::UnsignedInteger(IntegerBits::Sixteen),
-                                    int_inner.as_span(),
-                                    path.clone(),
-                                )
-                            }),
-                        Rule::u32_integer => int_inner
-                            .as_str()
-                            .trim()
-                            .replace("_", "")
-                            .parse()
-                            .map(Literal::U32)
-                            .map_err(|e| {
-                                handle_parse_int_error(
-                                    e,
-                                    TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
-                                    int_inner.as_span(),
-                                    path.clone(),
-                                )
-                            }),
-                        Rule::u64_integer => int_inner
-                            .as_str()
-                            .trim()
-                            .replace("_", "")
-                            .parse()
-                            .map(Literal::U64)
-                            .map_err(|e| {
-                                handle_parse_int_error(
-                                    e,
-                                    TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
-                                    int_inner.as_span(),
-                                    path.clone(),
-                                )
-                            }),
-                        _ => unreachable!(),
-                    },
-                    span,
-                )
-            }
+            Rule::integer => parse_integer(lit_inner, false, path),
             Rule::string => {
                 // remove opening and closing quotes
                 let lit_span = lit_inner.as_span();
@@ -194,6 +130,20 @@ impl Literal {
             Err(compile_err) => err(Vec::new(), vec![compile_err]),
         }
     }
+    /// Parses an integer literal which is preceded by a unary `-`. Integers without a suffix are
+    /// `i64`s when negated.
+    pub(crate) fn parse_negated_from_pair(
+        lit: Pair<Rule>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<(Self, span::Span)> {
+        let path = config.map(|c| c.path());
+        let lit_inner = lit.into_inner().next().unwrap();
+        match parse_integer(lit_inner, true, path) {
+            (Ok(lit), span) => ok((lit, span), Vec::new(), Vec::new()),
+            (Err(compile_err), _) => err(Vec::new(), vec![compile_err]),
+        }
+    }
+
     /// Converts a literal to a big-endian representation. This is padded to words.
     pub(crate) fn to_bytes(&self) -> Vec<u8> {
         use Literal::*;
@@ -208,6 +158,11 @@ impl Literal {
                 vec![0, 0, 0, 0, bytes[0], bytes[1], bytes[2], bytes[3]]
             }
             U64(val) => val.to_be_bytes().to_vec(),
+            // signed integers are sign extended to a whole word
+            I8(val) => (*val as i64).to_be_bytes().to_vec(),
+            I16(val) => (*val as i64).to_be_bytes().to_vec(),
+            I32(val) => (*val as i64).to_be_bytes().to_vec(),
+            I64(val) => val.to_be_bytes().to_vec(),
             Boolean(b) => {
                 vec![
                     0,
@@ -241,6 +196,89 @@ impl Literal {
     }
 }
 
+/// Parses the `integer` rule, with a leading `-` if `negated`. The sign is parsed along with the
+/// digits, so that the most negative value of each signed type can be written.
+fn parse_integer(
+    integer: Pair<Rule>,
+    negated: bool,
+    path: Option<Arc<PathBuf>>,
+) -> (Result<Literal, CompileError>, span::Span) {
+    let mut int_inner = integer.into_inner().next().unwrap();
+    let rule = int_inner.as_rule();
+    let suffixed = rule != Rule::u64_integer || int_inner.as_str().trim_end().ends_with("u64");
+    if int_inner.as_rule() != Rule::basic_integer {
+        int_inner = int_inner.into_inner().next().unwrap()
+    }
+    let span = span::Span {
+        span: int_inner.as_span(),
+        path: path.clone(),
+    };
+    let digits = int_inner.as_str().trim().replace("_", "");
+    let digits = if negated {
+        format!("-{}", digits)
+    } else {
+        digits
+    };
+    let parse_error = |e, ty| handle_parse_int_error(e, ty, int_inner.as_span(), path.clone());
+    let unsigned = TypeInfo::UnsignedInteger;
+    let signed = TypeInfo::SignedInteger;
+    let parsed = match rule {
+        // a negated unsigned integer always underflows, even if it's zero
+        Rule::u8_integer | Rule::u16_integer | Rule::u32_integer | Rule::u64_integer
+            if negated && suffixed =>
+        {
+            let bits = match rule {
+                Rule::u8_integer => IntegerBits::Eight,
+                Rule::u16_integer => IntegerBits::Sixteen,
+                Rule::u32_integer => IntegerBits::ThirtyTwo,
+                _ => IntegerBits::SixtyFour,
+            };
+            Err(CompileError::IntegerTooSmall {
+                ty: unsigned(bits).friendly_type_str(),
+                span: span.clone(),
+            })
+        }
+        Rule::u8_integer => digits
+            .parse()
+            .map(Literal::U8)
+            .map_err(|e| parse_error(e, unsigned(IntegerBits::Eight))),
+        Rule::u16_integer => digits
+            .parse()
+            .map(Literal::U16)
+            .map_err(|e| parse_error(e, unsigned(IntegerBits::Sixteen))),
+        Rule::u32_integer => digits
+            .parse()
+            .map(Literal::U32)
+            .map_err(|e| parse_error(e, unsigned(IntegerBits::ThirtyTwo))),
+        Rule::u64_integer if negated => digits
+            .parse()
+            .map(Literal::I64)
+            .map_err(|e| parse_error(e, signed(IntegerBits::SixtyFour))),
+        Rule::u64_integer => digits
+            .parse()
+            .map(Literal::U64)
+            .map_err(|e| parse_error(e, unsigned(IntegerBits::SixtyFour))),
+        Rule::i8_integer => digits
+            .parse()
+            .map(Literal::I8)
+            .map_err(|e| parse_error(e, signed(IntegerBits::Eight))),
+        Rule::i16_integer => digits
+            .parse()
+            .map(Literal::I16)
+            .map_err(|e| parse_error(e, signed(IntegerBits::Sixteen))),
+        Rule::i32_integer => digits
+            .parse()
+            .map(Literal::I32)
+            .map_err(|e| parse_error(e, signed(IntegerBits::ThirtyTwo))),
+        Rule::i64_integer => digits
+            .parse()
+            .map(Literal::I64)
+            .map_err(|e| parse_error(e, signed(IntegerBits::SixtyFour))),
+        _ => unreachable!(),
+    };
+    (parsed, span)
+}
+
 fn parse_hex_from_pair(
     pair: Pair<Rule>,
     config: Option<&BuildConfig>,
@@ -2,12 +2,18 @@ use crate::build_config::BuildConfig;
 use crate::parser::Rule;
 use crate::span::Span;
 use crate::{
-    error::{ok, CompileResult},
-    CodeBlock, Expression,
+    error::{err, ok, CompileResult},
+    AstNode, AstNodeContent, CodeBlock, Expression,
 };
 use pest::iterators::Pair;
 
+use super::expression::{parse_let_condition, LetCondition};
+use super::Literal;
+
 /// A parsed while loop. Contains the `condition`, which is defined from an [Expression], and the `body` from a [CodeBlock].
+/// A `while let` loop is desugared into a `while true` loop whose body starts by evaluating the
+/// value being tested, breaking out of the loop unless it matches the pattern, and declaring the
+/// variables which the pattern binds.
 #[derive(Debug, Clone)]
 pub struct WhileLoop {
     pub(crate) condition: Expression,
@@ -31,29 +37,81 @@ impl WhileLoop {
             path: path.clone(),
         };
 
-        let condition = check!(
-            Expression::parse_from_pair(condition.clone(), config),
-            Expression::Tuple {
-                fields: vec![],
-                span: Span {
-                    span: condition.as_span(),
-                    path,
-                }
-            },
-            warnings,
-            errors
-        );
-
-        let body = check!(
+        let mut body = check!(
             CodeBlock::parse_from_pair(body, config),
             CodeBlock {
                 contents: Default::default(),
-                whole_block_span,
+                whole_block_span: whole_block_span.clone(),
             },
             warnings,
             errors
         );
+        let condition = match condition.as_rule() {
+            Rule::let_condition => {
+                let condition_span = Span {
+                    span: condition.as_span(),
+                    path,
+                };
+                let LetCondition {
+                    value,
+                    condition,
+                    mut declarations,
+                } = check!(
+                    parse_let_condition(condition, config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                // the value is evaluated once on each iteration, and the variables which the
+                // pattern binds are declared afresh each time
+                let mut contents = vec![value, break_unless(condition, &condition_span)];
+                contents.append(&mut declarations);
+                contents.append(&mut body.contents);
+                body.contents = contents;
+                Expression::Literal {
+                    value: Literal::Boolean(true),
+                    span: condition_span,
+                }
+            }
+            _ => check!(
+                Expression::parse_from_pair(condition.clone(), config),
+                Expression::Tuple {
+                    fields: vec![],
+                    span: Span {
+                        span: condition.as_span(),
+                        path,
+                    }
+                },
+                warnings,
+                errors
+            ),
+        };
 
         ok(WhileLoop { condition, body }, warnings, errors)
     }
 }
+
+/// `if condition {} else { break; }`, which exits the loop it is in unless `condition` holds.
+fn break_unless(condition: Expression, span: &Span) -> AstNode {
+    let block = |contents| {
+        Box::new(Expression::CodeBlock {
+            contents: CodeBlock {
+                contents,
+                whole_block_span: span.clone(),
+            },
+            span: span.clone(),
+        })
+    };
+    AstNode {
+        content: AstNodeContent::Expression(Expression::IfExp {
+            condition: Box::new(condition),
+            then: block(vec![]),
+            r#else: Some(block(vec![AstNode {
+                content: AstNodeContent::Break,
+                span: span.clone(),
+            }])),
+            span: span.clone(),
+        }),
+        span: span.clone(),
+    }
+}
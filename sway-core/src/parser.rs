@@ -308,4 +308,29 @@ mod test {
         );
         parsed.unwrap();
     }
+
+    #[test]
+    fn test_attributes() {
+        let parsed = HllParser::parse(
+            Rule::fn_decl,
+            r#"#[inline(never)]
+            #[test]
+            pub fn myfunc(x: u64) -> u64 {
+                x
+            }"#
+            .into(),
+        );
+        parsed.unwrap();
+        let parsed = HllParser::parse(
+            Rule::struct_decl,
+            r#"#[derive(Eq, Ord)]
+            struct Point {
+                #[doc(x)]
+                x: u64,
+                y: u64,
+            }"#
+            .into(),
+        );
+        parsed.unwrap();
+    }
 }
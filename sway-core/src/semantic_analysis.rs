@@ -2,10 +2,13 @@
 pub mod ast_node;
 mod namespace;
 mod node_dependencies;
+mod revert_codes;
 mod syntax_tree;
 pub(crate) mod type_check_arguments;
+mod visit;
 pub(crate) use ast_node::{TypedAstNode, TypedAstNodeContent, TypedExpression};
 pub use ast_node::{TypedConstantDeclaration, TypedDeclaration, TypedFunctionDeclaration};
+pub(crate) use namespace::AssociatedItems;
 pub use namespace::Namespace;
 pub use syntax_tree::TreeType;
 pub use syntax_tree::TypedParseTree;
@@ -7,8 +7,10 @@ use crate::{error::*, Ident};
 use sway_types::Property;
 
 mod function;
+mod storage;
 mod variable;
 pub use function::*;
+pub use storage::*;
 pub use variable::*;
 
 #[derive(Clone, Debug)]
@@ -27,6 +29,9 @@ pub enum TypedDeclaration {
         type_implementing_for: TypeInfo,
     },
     AbiDeclaration(TypedAbiDeclaration),
+    StorageDeclaration(TypedStorageDeclaration),
+    StorageReassignment(TypedStorageReassignment),
+    TypeAliasDeclaration(TypedTypeAliasDeclaration),
     // If type parameters are defined for a function, they are put in the namespace just for
     // the body of that function.
     GenericTypeForFunctionScope {
@@ -48,6 +53,8 @@ impl TypedDeclaration {
             StructDeclaration(ref mut struct_decl) => struct_decl.copy_types(type_mapping),
             EnumDeclaration(ref mut enum_decl) => enum_decl.copy_types(type_mapping),
             Reassignment(ref mut reassignment) => reassignment.copy_types(type_mapping),
+            StorageReassignment(ref mut reassignment) => reassignment.copy_types(type_mapping),
+            TypeAliasDeclaration(ref mut type_alias) => type_alias.copy_types(type_mapping),
             ImplTrait {
                 ref mut methods, ..
             } => {
@@ -55,6 +62,8 @@ impl TypedDeclaration {
             }
             // generics in an ABI is unsupported by design
             AbiDeclaration(..) => (),
+            // storage is only declared at the top level of a contract, so is never generic
+            StorageDeclaration(..) => (),
             GenericTypeForFunctionScope { .. } | ErrorRecovery => (),
         }
     }
@@ -74,6 +83,9 @@ impl TypedDeclaration {
             Reassignment(_) => "reassignment",
             ImplTrait { .. } => "impl trait",
             AbiDeclaration(..) => "abi",
+            StorageDeclaration(..) => "contract storage",
+            StorageReassignment(..) => "storage reassignment",
+            TypeAliasDeclaration(..) => "type alias",
             GenericTypeForFunctionScope { .. } => "generic type parameter",
             ErrorRecovery => "error",
         }
@@ -104,7 +116,10 @@ impl TypedDeclaration {
                         .map(TypedStructField::as_owned_typed_struct_field)
                         .collect(),
                 }),
-                TypedDeclaration::Reassignment(TypedReassignment { rhs, .. }) => rhs.return_type,
+                TypedDeclaration::Reassignment(TypedReassignment { rhs, .. })
+                | TypedDeclaration::StorageReassignment(TypedStorageReassignment { rhs, .. }) => {
+                    rhs.return_type
+                }
                 TypedDeclaration::GenericTypeForFunctionScope { name } => {
                     insert_type(TypeInfo::UnknownGeneric {
                         name: name.as_str().to_string(),
@@ -141,6 +156,11 @@ impl TypedDeclaration {
                 })
             }
             AbiDeclaration(TypedAbiDeclaration { span, .. }) => span.clone(),
+            StorageDeclaration(TypedStorageDeclaration { span, .. }) => span.clone(),
+            StorageReassignment(TypedStorageReassignment { field_name, .. }) => {
+                field_name.span().clone()
+            }
+            TypeAliasDeclaration(TypedTypeAliasDeclaration { name, .. }) => name.span().clone(),
             ImplTrait { span, .. } => span.clone(),
             ErrorRecovery | GenericTypeForFunctionScope { .. } => {
                 unreachable!("No span exists for these ast node types")
@@ -169,11 +189,18 @@ impl TypedDeclaration {
                     name.as_str().into(),
                 TypedDeclaration::EnumDeclaration(TypedEnumDeclaration { name, .. }) =>
                     name.as_str().into(),
+                TypedDeclaration::TypeAliasDeclaration(TypedTypeAliasDeclaration {
+                    name, ..
+                }) => name.as_str().into(),
                 TypedDeclaration::Reassignment(TypedReassignment { lhs, .. }) => lhs
                     .iter()
                     .map(|x| x.name.as_str())
                     .collect::<Vec<_>>()
                     .join("."),
+                TypedDeclaration::StorageReassignment(TypedStorageReassignment {
+                    field_name,
+                    ..
+                }) => format!("storage.{}", field_name.as_str()),
                 _ => String::new(),
             }
         )
@@ -187,12 +214,15 @@ impl TypedDeclaration {
             | Reassignment(..)
             | ImplTrait { .. }
             | AbiDeclaration(..)
+            | StorageDeclaration(..)
+            | StorageReassignment(..)
             | ErrorRecovery => Visibility::Public,
             EnumDeclaration(TypedEnumDeclaration { visibility, .. })
             | ConstantDeclaration(TypedConstantDeclaration { visibility, .. })
             | FunctionDeclaration(TypedFunctionDeclaration { visibility, .. })
             | TraitDeclaration(TypedTraitDeclaration { visibility, .. })
-            | StructDeclaration(TypedStructDeclaration { visibility, .. }) => *visibility,
+            | StructDeclaration(TypedStructDeclaration { visibility, .. })
+            | TypeAliasDeclaration(TypedTypeAliasDeclaration { visibility, .. }) => *visibility,
         }
     }
 }
@@ -206,7 +236,15 @@ pub struct TypedAbiDeclaration {
     pub(crate) interface_surface: Vec<TypedTraitFn>,
     /// The methods provided to a contract "for free" upon opting in to this interface
     pub(crate) methods: Vec<FunctionDeclaration>,
+    /// The abis a contract must also implement in order to opt in to this interface, including
+    /// those they extend in turn
+    pub(crate) supertraits: Vec<TypedSupertrait>,
+    /// The constants a contract must give a value in order to opt in to this interface
+    pub(crate) associated_consts: Vec<TraitConst>,
+    /// The types a contract must define in order to opt in to this interface
+    pub(crate) associated_types: Vec<Ident>,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 #[derive(Clone, Debug)]
@@ -215,6 +253,7 @@ pub struct TypedStructDeclaration {
     pub(crate) fields: Vec<TypedStructField>,
     pub(crate) type_parameters: Vec<TypeParameter>,
     pub(crate) visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl TypedStructDeclaration {
@@ -230,6 +269,37 @@ impl TypedStructDeclaration {
             .iter_mut()
             .for_each(|x| x.copy_types(type_mapping));
     }
+
+    /// The type of this struct when it is named with `type_arguments`, like the `u64` in
+    /// `Point<u64>`, whose fields have the type arguments in place of the type parameters. If no
+    /// type arguments are given the fields keep the types they are declared with. Returns `None`
+    /// if the wrong number of type arguments is given.
+    pub(crate) fn instantiate(&self, type_arguments: Vec<TypeId>) -> Option<TypeId> {
+        let fields = if type_arguments.is_empty() {
+            self.fields.clone()
+        } else if type_arguments.len() == self.type_parameters.len() {
+            let type_mapping = self
+                .type_parameters
+                .iter()
+                .cloned()
+                .zip(type_arguments)
+                .collect::<Vec<_>>();
+            let mut fields = self.fields.clone();
+            fields
+                .iter_mut()
+                .for_each(|field| field.copy_types(&type_mapping));
+            fields
+        } else {
+            return None;
+        };
+        Some(insert_type(TypeInfo::Struct {
+            name: self.name.as_str().to_string(),
+            fields: fields
+                .iter()
+                .map(TypedStructField::as_owned_typed_struct_field)
+                .collect(),
+        }))
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -237,6 +307,7 @@ pub struct TypedStructField {
     pub(crate) name: Ident,
     pub(crate) r#type: TypeId,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 // TODO(Static span) -- remove this type and use TypedStructField
@@ -291,6 +362,7 @@ pub struct TypedEnumDeclaration {
     pub(crate) variants: Vec<TypedEnumVariant>,
     pub(crate) span: Span,
     pub(crate) visibility: Visibility,
+    pub(crate) attributes: Vec<Attribute>,
 }
 impl TypedEnumDeclaration {
     pub(crate) fn monomorphize(&self) -> Self {
@@ -304,6 +376,27 @@ impl TypedEnumDeclaration {
             .iter_mut()
             .for_each(|x| x.copy_types(type_mapping));
     }
+    /// The type of this enum when it is named with `type_arguments`, like the `u64` in
+    /// `Option<u64>`, whose variants have the type arguments in place of the type parameters. If no
+    /// type arguments are given the variants keep the types they are declared with. Returns `None`
+    /// if the wrong number of type arguments is given.
+    pub(crate) fn instantiate(&self, type_arguments: Vec<TypeId>) -> Option<TypeId> {
+        if type_arguments.is_empty() {
+            Some(self.as_type())
+        } else if type_arguments.len() == self.type_parameters.len() {
+            let type_mapping = self
+                .type_parameters
+                .iter()
+                .cloned()
+                .zip(type_arguments)
+                .collect::<Vec<_>>();
+            let mut decl = self.clone();
+            decl.copy_types(&type_mapping);
+            Some(decl.as_type())
+        } else {
+            None
+        }
+    }
     /// Returns the [ResolvedType] corresponding to this enum's type.
     pub(crate) fn as_type(&self) -> TypeId {
         crate::type_engine::insert_type(TypeInfo::Enum {
@@ -322,6 +415,7 @@ pub struct TypedEnumVariant {
     pub(crate) r#type: TypeId,
     pub(crate) tag: usize,
     pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl TypedEnumVariant {
@@ -374,12 +468,79 @@ impl TypedConstantDeclaration {
     }
 }
 
+/// A type alias, whose type is resolved where it is declared. The type may refer to the type
+/// parameters of the alias, which are replaced by the type arguments each use of the alias gives.
+#[derive(Clone, Debug)]
+pub struct TypedTypeAliasDeclaration {
+    pub(crate) name: Ident,
+    pub(crate) type_parameters: Vec<TypeParameter>,
+    pub(crate) r#type: TypeId,
+    pub(crate) visibility: Visibility,
+}
+
+impl TypedTypeAliasDeclaration {
+    /// The type this alias stands for when it is used with `type_arguments`, like the `u64` in
+    /// `Pair<u64>`. If no type arguments are given they are inferred, like those of a struct are.
+    /// Returns `None` if the wrong number of type arguments is given.
+    pub(crate) fn instantiate(&self, type_arguments: Vec<TypeId>) -> Option<TypeId> {
+        let (name, type_arguments) = if type_arguments.is_empty() {
+            let type_arguments = self
+                .type_parameters
+                .iter()
+                .map(|_| insert_type(TypeInfo::Unknown))
+                .collect::<Vec<_>>();
+            (self.name.as_str().to_string(), type_arguments)
+        } else if type_arguments.len() == self.type_parameters.len() {
+            let name = format!(
+                "{}<{}>",
+                self.name.as_str(),
+                type_arguments
+                    .iter()
+                    .map(|type_argument| type_argument.friendly_type_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            (name, type_arguments)
+        } else {
+            return None;
+        };
+        let type_mapping = self
+            .type_parameters
+            .iter()
+            .cloned()
+            .zip(type_arguments)
+            .collect::<Vec<_>>();
+        // each use gets its own copy of the type, so that inferring the type of one use doesn't
+        // affect the others
+        let ty = look_up_type_id(self.r#type)
+            .matches_type_parameter(&type_mapping)
+            .unwrap_or_else(|| insert_type(look_up_type_id_raw(self.r#type)));
+        Some(insert_type(TypeInfo::Alias { name, ty }))
+    }
+
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.r#type = if let Some(matching_id) =
+            look_up_type_id(self.r#type).matches_type_parameter(type_mapping)
+        {
+            insert_type(TypeInfo::Ref(matching_id))
+        } else {
+            insert_type(look_up_type_id_raw(self.r#type))
+        };
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TypedTraitDeclaration {
     pub(crate) name: Ident,
     pub(crate) interface_surface: Vec<TypedTraitFn>,
     pub(crate) methods: Vec<FunctionDeclaration>,
     pub(crate) type_parameters: Vec<TypeParameter>,
+    // the supertraits of the supertraits are included, so that they don't have to be looked up
+    // from wherever the trait is used
+    pub(crate) supertraits: Vec<TypedSupertrait>,
+    // like the methods, these are type checked in each implementation of the trait
+    pub(crate) associated_consts: Vec<TraitConst>,
+    pub(crate) associated_types: Vec<Ident>,
     pub(crate) visibility: Visibility,
 }
 impl TypedTraitDeclaration {
@@ -389,9 +550,22 @@ impl TypedTraitDeclaration {
         self.interface_surface
             .iter_mut()
             .for_each(|x| x.copy_types(&type_mapping[..]));
+        self.supertraits
+            .iter_mut()
+            .flat_map(|supertrait| supertrait.interface_surface.iter_mut())
+            .for_each(|x| x.copy_types(&type_mapping[..]));
         // we don't have to type check the methods because it hasn't been type checked yet
     }
 }
+
+/// A trait or abi which is extended by another, like the `B` in `trait A: B`.
+#[derive(Clone, Debug)]
+pub struct TypedSupertrait {
+    pub(crate) name: CallPath,
+    pub(crate) interface_surface: Vec<TypedTraitFn>,
+    pub(crate) methods: Vec<FunctionDeclaration>,
+}
+
 #[derive(Clone, Debug)]
 pub struct TypedTraitFn {
     pub(crate) name: Ident,
@@ -452,8 +626,12 @@ impl TypedTraitFn {
     }
     /// This function is used in trait declarations to insert "placeholder" functions
     /// in the methods. This allows the methods to use functions declared in the
-    /// interface surface.
-    pub(crate) fn to_dummy_func(&self, mode: Mode) -> TypedFunctionDeclaration {
+    /// interface surface. The placeholder is marked as one of the trait `trait_name`.
+    pub(crate) fn to_dummy_func(
+        &self,
+        mode: Mode,
+        trait_name: &CallPath,
+    ) -> TypedFunctionDeclaration {
         TypedFunctionDeclaration {
             purity: Default::default(),
             name: self.name.clone(),
@@ -468,6 +646,8 @@ impl TypedTraitFn {
             visibility: Visibility::Public,
             type_parameters: vec![],
             is_contract_call: mode == Mode::ImplAbiFn,
+            trait_placeholder: Some(trait_name.clone()),
+            attributes: vec![],
         }
     }
 }
@@ -2,9 +2,9 @@ use crate::parse_tree::*;
 use crate::semantic_analysis::{
     ast_node::{
         IsConstant, Mode, TypedCodeBlock, TypedDeclaration, TypedExpression,
-        TypedExpressionVariant, TypedReturnStatement, TypedVariableDeclaration,
+        TypedExpressionVariant, TypedTraitDeclaration, TypedVariableDeclaration,
     },
-    TypeCheckArguments,
+    Namespace, TypeCheckArguments,
 };
 use crate::span::Span;
 use crate::type_engine::*;
@@ -14,6 +14,8 @@ use sha2::{Digest, Sha256};
 use sway_types::{Function, Property};
 
 mod function_parameter;
+mod return_statements;
+mod storage_access;
 pub use function_parameter::*;
 
 #[derive(Clone, Debug)]
@@ -30,7 +32,11 @@ pub struct TypedFunctionDeclaration {
     pub(crate) visibility: Visibility,
     /// whether this function exists in another contract and requires a call to it or not
     pub(crate) is_contract_call: bool,
+    /// If this is a placeholder without a body for a method of a trait, the trait. See
+    /// `TypedTraitFn::to_dummy_func`.
+    pub(crate) trait_placeholder: Option<CallPath>,
     pub(crate) purity: Purity,
+    pub(crate) attributes: Vec<Attribute>,
 }
 
 impl TypedFunctionDeclaration {
@@ -61,9 +67,12 @@ impl TypedFunctionDeclaration {
             return_type_span,
             visibility,
             purity,
+            attributes,
             ..
         } = fn_decl.clone();
         opts.purity = purity;
+        // a loop around the declaration can't be broken out of from inside of the function
+        opts.in_loop = false;
         // insert type parameters as Unknown types
         let type_mapping = insert_type_parameters(&type_parameters);
         let return_type =
@@ -72,10 +81,8 @@ impl TypedFunctionDeclaration {
             } else {
                 namespace
                     .resolve_type_with_self(return_type, self_type)
-                    .unwrap_or_else(|_| {
-                        errors.push(CompileError::UnknownType {
-                            span: return_type_span.clone(),
-                        });
+                    .unwrap_or_else(|error| {
+                        errors.push(error.into_error(return_type_span.clone()));
                         insert_type(TypeInfo::ErrorRecovery)
                     })
             };
@@ -85,6 +92,46 @@ impl TypedFunctionDeclaration {
         type_parameters.iter().for_each(|param| {
             namespace.insert(param.name_ident.clone(), param.into());
         });
+        // insert placeholder functions representing the interface surfaces of the trait bounds,
+        // to allow the body to call them on values of the bounded types. The calls are
+        // dispatched to the actual methods once the function is instantiated.
+        for (type_parameter, type_id) in &type_mapping {
+            for TraitConstraint { name: trait_name } in &type_parameter.trait_constraints {
+                let (interface_surface, supertraits) = match namespace.get_symbol(trait_name).value
+                {
+                    Some(TypedDeclaration::TraitDeclaration(TypedTraitDeclaration {
+                        interface_surface,
+                        supertraits,
+                        ..
+                    })) => (interface_surface.clone(), supertraits.clone()),
+                    _ => continue,
+                };
+                // the methods of the supertraits can be called too
+                let trait_name = CallPath {
+                    prefixes: vec![],
+                    suffix: trait_name.clone(),
+                };
+                for (trait_name, interface_surface) in
+                    std::iter::once((trait_name, interface_surface)).chain(
+                        supertraits
+                            .into_iter()
+                            .map(|supertrait| (supertrait.name, supertrait.interface_surface)),
+                    )
+                {
+                    namespace.insert_trait_implementation(
+                        trait_name.clone(),
+                        look_up_type_id(*type_id),
+                        interface_surface
+                            .iter()
+                            .map(|x| {
+                                x.to_dummy_func(Mode::NonAbi, &trait_name)
+                                    .replace_self_types(*type_id)
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
         for FunctionParameter {
             name,
             r#type,
@@ -96,10 +143,8 @@ impl TypedFunctionDeclaration {
             } else {
                 namespace
                     .resolve_type_with_self(r#type, self_type)
-                    .unwrap_or_else(|_| {
-                        errors.push(CompileError::UnknownType {
-                            span: type_span.clone(),
-                        });
+                    .unwrap_or_else(|error| {
+                        errors.push(error.into_error(type_span.clone()));
                         insert_type(TypeInfo::ErrorRecovery)
                     })
             };
@@ -146,6 +191,19 @@ impl TypedFunctionDeclaration {
             warnings,
             errors
         );
+        // accessing storage the function isn't declared to is an error in the body, but it may
+        // also be declared with access it never uses
+        let inferred_purity = body.storage_access();
+        if inferred_purity != purity && purity.allows(inferred_purity) {
+            warnings.push(CompileWarning {
+                span: name.span().clone(),
+                warning_content: Warning::UnneededStorageAccess {
+                    fn_name: name.clone(),
+                    declared: purity,
+                    inferred: inferred_purity,
+                },
+            });
+        }
 
         let parameters = parameters
             .into_iter()
@@ -162,10 +220,8 @@ impl TypedFunctionDeclaration {
                     } else {
                         namespace
                             .resolve_type_with_self(r#type, self_type)
-                            .unwrap_or_else(|_| {
-                                errors.push(CompileError::UnknownType {
-                                    span: type_span.clone(),
-                                });
+                            .unwrap_or_else(|error| {
+                                errors.push(error.into_error(type_span.clone()));
                                 insert_type(TypeInfo::ErrorRecovery)
                             })
                     },
@@ -174,25 +230,7 @@ impl TypedFunctionDeclaration {
             )
             .collect::<Vec<_>>();
         // handle the return statement(s)
-        let return_statements: Vec<(&TypedExpression, &Span)> = body
-            .contents
-            .iter()
-            .filter_map(|x| {
-                if let crate::semantic_analysis::TypedAstNode {
-                    content:
-                        crate::semantic_analysis::TypedAstNodeContent::ReturnStatement(
-                            TypedReturnStatement { ref expr },
-                        ),
-                    span,
-                } = x
-                {
-                    Some((expr, span))
-                } else {
-                    None
-                }
-            })
-            .collect();
-        for (stmt, span) in return_statements {
+        for (stmt, span) in body.return_statements() {
             match crate::type_engine::unify_with_self(
                 stmt.return_type,
                 return_type,
@@ -261,12 +299,61 @@ impl TypedFunctionDeclaration {
                 visibility,
                 // if this is for a contract, then it is a contract call
                 is_contract_call: mode == Mode::ImplAbiFn,
+                trait_placeholder: None,
                 purity,
+                attributes,
             },
             warnings,
             errors,
         )
     }
+    /// Builds a declaration with the signature of `fn_decl` and an empty body. This is inserted
+    /// into the namespace used to type check the function's own body, and those of the functions
+    /// it is mutually recursive with, so that they may call it before it has finished type
+    /// checking. The body keeps the span of the real body, which is what code generation uses to
+    /// find the function being called.
+    pub(crate) fn recursion_stub(
+        fn_decl: &FunctionDeclaration,
+        namespace: &Namespace,
+        self_type: TypeId,
+    ) -> TypedFunctionDeclaration {
+        let type_mapping = insert_type_parameters(&fn_decl.type_parameters);
+        // errors are reported when the real declaration is type checked
+        let resolve = |r#type: &TypeInfo| {
+            if let Some(matching_id) = r#type.matches_type_parameter(&type_mapping) {
+                insert_type(TypeInfo::Ref(matching_id))
+            } else {
+                namespace
+                    .resolve_type_with_self(r#type.clone(), self_type)
+                    .unwrap_or_else(|_| insert_type(TypeInfo::ErrorRecovery))
+            }
+        };
+        TypedFunctionDeclaration {
+            name: fn_decl.name.clone(),
+            body: TypedCodeBlock {
+                contents: vec![],
+                whole_block_span: fn_decl.body.whole_block_span.clone(),
+            },
+            parameters: fn_decl
+                .parameters
+                .iter()
+                .map(|param| TypedFunctionParameter {
+                    name: param.name.clone(),
+                    r#type: resolve(&param.r#type),
+                    type_span: param.type_span.clone(),
+                })
+                .collect(),
+            span: fn_decl.span.clone(),
+            return_type: resolve(&fn_decl.return_type),
+            type_parameters: fn_decl.type_parameters.clone(),
+            return_type_span: fn_decl.return_type_span.clone(),
+            visibility: fn_decl.visibility,
+            is_contract_call: false,
+            trait_placeholder: None,
+            purity: fn_decl.purity,
+            attributes: fn_decl.attributes.clone(),
+        }
+    }
     pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
         self.body.copy_types(type_mapping);
         self.parameters
@@ -284,12 +371,13 @@ impl TypedFunctionDeclaration {
     /// Given a typed function declaration with type parameters, make a copy of it and update the
     /// type ids which refer to generic types to be fresh copies, maintaining their referential
     /// relationship. This is used so when this function is resolved, the types don't clobber the
-    /// generic type info.
+    /// generic type info. The mapping from type parameters to the fresh type ids is returned too,
+    /// so that trait constraints can be checked once the arguments have been unified.
     pub(crate) fn monomorphize(
         &self,
         type_arguments: Vec<(TypeInfo, Span)>,
         self_type: TypeId,
-    ) -> CompileResult<TypedFunctionDeclaration> {
+    ) -> CompileResult<(TypedFunctionDeclaration, Vec<(TypeParameter, TypeId)>)> {
         let mut warnings: Vec<CompileWarning> = vec![];
         let mut errors: Vec<CompileError> = vec![];
         debug_assert!(
@@ -347,7 +435,7 @@ impl TypedFunctionDeclaration {
             insert_type(look_up_type_id_raw(new_decl.return_type))
         };
 
-        ok(new_decl, warnings, errors)
+        ok((new_decl, type_mapping), warnings, errors)
     }
     /// If there are parameters, join their spans. Otherwise, use the fn name span.
     pub(crate) fn parameters_span(&self) -> Span {
@@ -461,6 +549,12 @@ impl TypedFunctionDeclaration {
                 type_field: self.return_type.json_abi_str(),
                 components: self.return_type.generate_json_abi(),
             }],
+            storage: self
+                .purity
+                .storage_access()
+                .into_iter()
+                .map(String::from)
+                .collect(),
         }
     }
 }
@@ -497,6 +591,8 @@ fn test_function_selector_behavior() {
         },
         visibility: Visibility::Public,
         is_contract_call: false,
+        trait_placeholder: None,
+        attributes: vec![],
     };
 
     let selector_text = match decl.to_selector_name().value {
@@ -564,6 +660,8 @@ fn test_function_selector_behavior() {
         },
         visibility: Visibility::Public,
         is_contract_call: false,
+        trait_placeholder: None,
+        attributes: vec![],
     };
 
     let selector_text = match decl.to_selector_name().value {
@@ -0,0 +1,35 @@
+//! The return statements of a function body, wherever they are within it, which all have to match
+//! the return type of the function. Besides those written at the top level of the body, there are
+//! those within blocks and loops, and those which `?` desugars to within expressions.
+
+use crate::semantic_analysis::ast_node::{
+    TypedAstNode, TypedAstNodeContent, TypedCodeBlock, TypedExpression, TypedReturnStatement,
+};
+use crate::semantic_analysis::visit::{walk_node, Visit};
+use crate::span::Span;
+
+/// The value returned by a return statement, and the span of the statement.
+type ReturnStatement<'a> = (&'a TypedExpression, &'a Span);
+
+impl TypedCodeBlock {
+    pub(crate) fn return_statements(&self) -> Vec<ReturnStatement<'_>> {
+        let mut gatherer = ReturnStatements { statements: vec![] };
+        gatherer.visit_code_block(self);
+        gatherer.statements
+    }
+}
+
+/// The return statements in the declarations of functions and other items, and in the bodies of
+/// called functions, return from those functions, so the walk doesn't go into them.
+struct ReturnStatements<'a> {
+    statements: Vec<ReturnStatement<'a>>,
+}
+
+impl<'a> Visit<'a> for ReturnStatements<'a> {
+    fn visit_node(&mut self, node: &'a TypedAstNode) {
+        walk_node(self, node);
+        if let TypedAstNodeContent::ReturnStatement(TypedReturnStatement { expr }) = &node.content {
+            self.statements.push((expr, &node.span));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Inference of the storage access a function body actually does, to tell when a function is
+//! declared with more access than it needs. Calls contribute the access their function is
+//! declared with, since that is what the callee is checked against, or else the access its body
+//! does, for functions like those of `std::storage` which only access storage in asm and don't
+//! declare it yet.
+
+use crate::parse_tree::Purity;
+use crate::semantic_analysis::ast_node::{
+    StorageMapMethod, TypedCodeBlock, TypedDeclaration, TypedExpression, TypedExpressionVariant,
+};
+use crate::semantic_analysis::visit::{walk_declaration, walk_expression, Visit};
+use crate::type_engine::{look_up_type_id, TypeInfo};
+
+impl TypedCodeBlock {
+    pub(crate) fn storage_access(&self) -> Purity {
+        let mut inference = AccessInference {
+            access: Purity::Pure,
+        };
+        inference.visit_code_block(self);
+        inference.access
+    }
+}
+
+/// The union of the storage access of the parts of a function body visited so far.
+struct AccessInference {
+    access: Purity,
+}
+
+impl AccessInference {
+    fn add(&mut self, access: Purity) {
+        self.access = self.access.union(access);
+    }
+}
+
+impl<'a> Visit<'a> for AccessInference {
+    fn visit_declaration(&mut self, decl: &'a TypedDeclaration) {
+        if let TypedDeclaration::StorageReassignment(_) = decl {
+            self.add(Purity::Writes);
+        }
+        walk_declaration(self, decl);
+    }
+
+    fn visit_expression(&mut self, expr: &'a TypedExpression) {
+        use TypedExpressionVariant::*;
+        match &expr.expression {
+            FunctionApplication {
+                function_body,
+                selector,
+                purity,
+                ..
+            } => {
+                // a contract call accesses the storage of the contract being called
+                let call = match selector {
+                    Some(metadata) => metadata.contract_address.storage_access(),
+                    // a function which doesn't declare storage access may still access it in
+                    // asm, which is only warned about for now
+                    None if *purity == Purity::Pure => function_body.storage_access(),
+                    None => *purity,
+                };
+                self.add(call);
+            }
+            AsmExpression { body, .. } => body.iter().for_each(|op| self.add(op.storage_access())),
+            // a map isn't read as a whole, only its entries are, by its methods
+            StorageAccess { .. } => match look_up_type_id(expr.return_type) {
+                TypeInfo::StorageMap { .. } => (),
+                _ => self.add(Purity::Reads),
+            },
+            StorageMapMethod(StorageMapMethod::Get { .. }) => self.add(Purity::Reads),
+            StorageMapMethod(StorageMapMethod::Insert { .. }) => self.add(Purity::Writes),
+            _ => (),
+        }
+        walk_expression(self, expr);
+    }
+}
+
+impl TypedExpression {
+    fn storage_access(&self) -> Purity {
+        let mut inference = AccessInference {
+            access: Purity::Pure,
+        };
+        inference.visit_expression(self);
+        inference.access
+    }
+}
@@ -0,0 +1,178 @@
+use crate::parse_tree::{Attribute, Purity, Visibility};
+use crate::semantic_analysis::ast_node::{
+    IsConstant, TypedCodeBlock, TypedExpressionVariant, TypedFunctionDeclaration,
+    TypedFunctionParameter,
+};
+use crate::semantic_analysis::{TypedAstNode, TypedAstNodeContent, TypedExpression};
+use crate::span::Span;
+use crate::type_engine::*;
+use crate::{Ident, TypeParameter};
+
+/// The type checked `storage { .. }` declaration of a contract.
+#[derive(Clone, Debug)]
+pub struct TypedStorageDeclaration {
+    pub(crate) fields: Vec<TypedStorageField>,
+    pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
+}
+
+impl TypedStorageDeclaration {
+    pub(crate) fn find_field(&self, name: &Ident) -> Option<&TypedStorageField> {
+        self.fields.iter().find(|field| field.name == *name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TypedStorageField {
+    pub(crate) name: Ident,
+    pub(crate) r#type: TypeId,
+    /// The value the field holds until it is first written to.
+    pub(crate) initializer: TypedExpression,
+    pub(crate) span: Span,
+    pub(crate) attributes: Vec<Attribute>,
+}
+
+/// A reassignment of a whole storage field, i.e. `storage.field = rhs;`.
+#[derive(Clone, Debug)]
+pub struct TypedStorageReassignment {
+    pub(crate) field_name: Ident,
+    pub(crate) r#type: TypeId,
+    pub(crate) rhs: TypedExpression,
+}
+
+impl TypedStorageReassignment {
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.rhs.copy_types(type_mapping)
+    }
+}
+
+/// The body of one of the methods of `StorageMap`, which are provided by the compiler. The `map`
+/// is the `self` parameter, which holds the key of the slot of the storage field.
+#[derive(Clone, Debug)]
+pub(crate) enum StorageMapMethod {
+    /// Reads the value of `key`, which is all zeroes if nothing has been inserted for it.
+    Get {
+        map: Box<TypedExpression>,
+        key: Box<TypedExpression>,
+    },
+    /// Writes the value of `key`, replacing whatever was inserted for it before.
+    Insert {
+        map: Box<TypedExpression>,
+        key: Box<TypedExpression>,
+        value: Box<TypedExpression>,
+    },
+}
+
+impl StorageMapMethod {
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        match self {
+            StorageMapMethod::Get { map, key } => {
+                map.copy_types(type_mapping);
+                key.copy_types(type_mapping);
+            }
+            StorageMapMethod::Insert { map, key, value } => {
+                map.copy_types(type_mapping);
+                key.copy_types(type_mapping);
+                value.copy_types(type_mapping);
+            }
+        }
+    }
+}
+
+/// The methods of the `StorageMap` type `map_type`, which maps `key` to `value`:
+///
+/// ```ignore
+/// fn new() -> StorageMap<K, V>;
+/// #[storage(read)] fn get(self, key: K) -> V;
+/// #[storage(write)] fn insert(self, key: K, value: V);
+/// ```
+///
+/// They aren't declared anywhere, so `span` stands in for their declarations.
+pub(crate) fn storage_map_methods(
+    map_type: TypeId,
+    key: TypeId,
+    value: TypeId,
+    span: &Span,
+) -> Vec<TypedFunctionDeclaration> {
+    let parameter = |name: &'static str, r#type: TypeId| TypedFunctionParameter {
+        name: Ident::new_with_override(name, span.clone()),
+        r#type,
+        type_span: span.clone(),
+    };
+    let variable = |name: &'static str, r#type: TypeId| {
+        Box::new(TypedExpression {
+            expression: TypedExpressionVariant::VariableExpression {
+                name: Ident::new_with_override(name, span.clone()),
+            },
+            return_type: r#type,
+            is_constant: IsConstant::No,
+            span: span.clone(),
+        })
+    };
+    let method = |name: &'static str,
+                  parameters: Vec<TypedFunctionParameter>,
+                  return_type: TypeId,
+                  body: Option<StorageMapMethod>,
+                  purity: Purity| {
+        let contents = body
+            .map(|body| {
+                let body = TypedExpression {
+                    expression: TypedExpressionVariant::StorageMapMethod(body),
+                    return_type,
+                    is_constant: IsConstant::No,
+                    span: span.clone(),
+                };
+                vec![TypedAstNode {
+                    content: TypedAstNodeContent::ImplicitReturnExpression(body),
+                    span: span.clone(),
+                }]
+            })
+            .unwrap_or_default();
+        TypedFunctionDeclaration {
+            name: Ident::new_with_override(name, span.clone()),
+            body: TypedCodeBlock {
+                contents,
+                whole_block_span: span.clone(),
+            },
+            parameters,
+            span: span.clone(),
+            return_type,
+            type_parameters: vec![],
+            return_type_span: span.clone(),
+            visibility: Visibility::Public,
+            is_contract_call: false,
+            trait_placeholder: None,
+            purity,
+            attributes: vec![],
+        }
+    };
+    vec![
+        // a new map holds nothing, as its entries are only ever in storage
+        method("new", vec![], map_type, None, Purity::Pure),
+        method(
+            "get",
+            vec![parameter("self", map_type), parameter("key", key)],
+            value,
+            Some(StorageMapMethod::Get {
+                map: variable("self", map_type),
+                key: variable("key", key),
+            }),
+            Purity::Reads,
+        ),
+        method(
+            "insert",
+            vec![
+                parameter("self", map_type),
+                parameter("key", key),
+                parameter("value", value),
+            ],
+            insert_type(TypeInfo::Tuple(vec![])),
+            Some(StorageMapMethod::Insert {
+                map: variable("self", map_type),
+                key: variable("key", key),
+                value: variable("value", value),
+            }),
+            Purity::Writes,
+        ),
+    ]
+}
@@ -0,0 +1,452 @@
+//! Evaluation of constant expressions at compile time, for the initializers of constants and of
+//! storage fields and for the lengths of arrays. An expression is folded down to a literal, or to
+//! a tuple, struct, array or enum made up of them. Besides those, it can use the operators of the
+//! built in types, other constants and calls of functions whose bodies are constant themselves.
+
+use super::{TypedExpression, TypedExpressionVariant, TypedStructExpressionField};
+use crate::error::*;
+use crate::semantic_analysis::ast_node::{
+    IsConstant, TypedAstNode, TypedAstNodeContent, TypedCodeBlock, TypedConstantDeclaration,
+    TypedDeclaration, TypedReturnStatement, TypedVariableDeclaration,
+};
+use crate::semantic_analysis::Namespace;
+use crate::span::Span;
+use crate::type_engine::*;
+use crate::{Ident, LazyOp, Literal};
+use std::convert::TryFrom;
+
+/// Evaluates `expr` at compile time.
+pub(crate) fn evaluate_constant(
+    expr: &TypedExpression,
+    namespace: &Namespace,
+) -> CompileResult<TypedExpression> {
+    let mut evaluator = Evaluator {
+        namespace,
+        variables: vec![],
+        returned: None,
+    };
+    match evaluator.evaluate(expr) {
+        Ok(value) => ok(value, vec![], vec![]),
+        Err(error) => err(vec![], vec![error]),
+    }
+}
+
+/// Evaluates `expr`, which is a `u64`, at compile time as the length of an array.
+pub(crate) fn evaluate_array_length(
+    expr: &TypedExpression,
+    namespace: &Namespace,
+) -> CompileResult<usize> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let value = check!(
+        evaluate_constant(expr, namespace),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    match value.expression {
+        TypedExpressionVariant::Literal(Literal::U64(length)) => {
+            ok(length as usize, warnings, errors)
+        }
+        _ => {
+            errors.push(CompileError::NotAConstant {
+                span: expr.span.clone(),
+            });
+            err(warnings, errors)
+        }
+    }
+}
+
+struct Evaluator<'n> {
+    namespace: &'n Namespace,
+    /// The values of the variables in scope, innermost last. Only the parameters and the
+    /// variables of the function being called are in scope within its body.
+    variables: Vec<(Ident, TypedExpression)>,
+    /// The value of the function being called, once it has reached a `return` statement.
+    returned: Option<TypedExpression>,
+}
+
+impl Evaluator<'_> {
+    fn evaluate(&mut self, expr: &TypedExpression) -> Result<TypedExpression, CompileError> {
+        let folded = |expression| TypedExpression {
+            expression,
+            return_type: expr.return_type,
+            is_constant: IsConstant::Yes,
+            span: expr.span.clone(),
+        };
+        Ok(match &expr.expression {
+            TypedExpressionVariant::Literal(_) => folded(expr.expression.clone()),
+            TypedExpressionVariant::VariableExpression { name } => {
+                self.variable(name, &expr.span)?
+            }
+            TypedExpressionVariant::Tuple { fields } => folded(TypedExpressionVariant::Tuple {
+                fields: self.evaluate_all(fields)?,
+            }),
+            TypedExpressionVariant::Array { contents } => folded(TypedExpressionVariant::Array {
+                contents: self.evaluate_all(contents)?,
+            }),
+            TypedExpressionVariant::StructExpression {
+                struct_name,
+                fields,
+            } => folded(TypedExpressionVariant::StructExpression {
+                struct_name: struct_name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|field| {
+                        Ok(TypedStructExpressionField {
+                            name: field.name.clone(),
+                            value: self.evaluate(&field.value)?,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            }),
+            TypedExpressionVariant::EnumInstantiation {
+                enum_decl,
+                variant_name,
+                tag,
+                contents,
+            } => folded(TypedExpressionVariant::EnumInstantiation {
+                enum_decl: enum_decl.clone(),
+                variant_name: variant_name.clone(),
+                tag: *tag,
+                contents: match contents {
+                    Some(contents) => Some(Box::new(self.evaluate(contents)?)),
+                    None => None,
+                },
+            }),
+            TypedExpressionVariant::StructFieldAccess {
+                prefix,
+                field_to_access,
+                ..
+            } => match self.evaluate(prefix)?.expression {
+                TypedExpressionVariant::StructExpression { fields, .. } => fields
+                    .into_iter()
+                    .find(|field| field.name.as_str() == field_to_access.name)
+                    .map(|field| field.value)
+                    .ok_or_else(|| not_a_constant(&expr.span))?,
+                _ => return Err(not_a_constant(&expr.span)),
+            },
+            TypedExpressionVariant::TupleElemAccess {
+                prefix,
+                elem_to_access_num,
+                ..
+            } => match self.evaluate(prefix)?.expression {
+                TypedExpressionVariant::Tuple { mut fields }
+                    if *elem_to_access_num < fields.len() =>
+                {
+                    fields.swap_remove(*elem_to_access_num)
+                }
+                _ => return Err(not_a_constant(&expr.span)),
+            },
+            TypedExpressionVariant::ArrayIndex { prefix, index } => {
+                let contents = match self.evaluate(prefix)?.expression {
+                    TypedExpressionVariant::Array { contents } => contents,
+                    _ => return Err(not_a_constant(&expr.span)),
+                };
+                let index = match self.evaluate(index)?.expression {
+                    TypedExpressionVariant::Literal(Literal::U64(index)) => index,
+                    _ => return Err(not_a_constant(&index.span)),
+                };
+                match contents.get(index as usize) {
+                    Some(elem) => elem.clone(),
+                    None => {
+                        return Err(CompileError::ArrayOutOfBounds {
+                            index,
+                            count: contents.len() as u64,
+                            span: expr.span.clone(),
+                        })
+                    }
+                }
+            }
+            TypedExpressionVariant::LazyOperator { op, lhs, rhs } => {
+                let lhs = self.evaluate_bool(lhs)?;
+                folded(TypedExpressionVariant::Literal(Literal::Boolean(
+                    match op {
+                        LazyOp::And => lhs && self.evaluate_bool(rhs)?,
+                        LazyOp::Or => lhs || self.evaluate_bool(rhs)?,
+                    },
+                )))
+            }
+            TypedExpressionVariant::IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                if self.evaluate_bool(condition)? {
+                    self.evaluate(then)?
+                } else if let Some(r#else) = r#else {
+                    self.evaluate(r#else)?
+                } else {
+                    folded(TypedExpressionVariant::Tuple { fields: vec![] })
+                }
+            }
+            TypedExpressionVariant::CodeBlock(block) => match self.evaluate_block(block)? {
+                Some(value) => value,
+                None => folded(TypedExpressionVariant::Tuple { fields: vec![] }),
+            },
+            TypedExpressionVariant::SignedIntegerOp(op) => {
+                let arguments = self.evaluate_all(&op.arguments)?;
+                folded(TypedExpressionVariant::Literal(apply_operator(
+                    op.op.method_name(),
+                    &arguments,
+                    expr.return_type,
+                    &expr.span,
+                )?))
+            }
+            TypedExpressionVariant::FunctionApplication {
+                name,
+                arguments,
+                function_body,
+                selector: None,
+                ..
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|(name, argument)| Ok((name.clone(), self.evaluate(argument)?)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let is_core_op = name.prefixes.len() == 2
+                    && name.prefixes[0].as_str() == "core"
+                    && name.prefixes[1].as_str() == "ops";
+                let operands = arguments
+                    .iter()
+                    .map(|(_, argument)| argument.clone())
+                    .collect::<Vec<_>>();
+                if is_core_op && operands.iter().all(is_primitive_literal) {
+                    folded(TypedExpressionVariant::Literal(apply_operator(
+                        name.suffix.as_str(),
+                        &operands,
+                        expr.return_type,
+                        &expr.span,
+                    )?))
+                } else {
+                    self.call(arguments, function_body, expr)?
+                }
+            }
+            _ => return Err(not_a_constant(&expr.span)),
+        })
+    }
+
+    fn evaluate_all(
+        &mut self,
+        exprs: &[TypedExpression],
+    ) -> Result<Vec<TypedExpression>, CompileError> {
+        exprs.iter().map(|expr| self.evaluate(expr)).collect()
+    }
+
+    fn evaluate_bool(&mut self, expr: &TypedExpression) -> Result<bool, CompileError> {
+        match self.evaluate(expr)?.expression {
+            TypedExpressionVariant::Literal(Literal::Boolean(value)) => Ok(value),
+            _ => Err(not_a_constant(&expr.span)),
+        }
+    }
+
+    fn variable(&mut self, name: &Ident, span: &Span) -> Result<TypedExpression, CompileError> {
+        if let Some((_, value)) = self
+            .variables
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == name)
+        {
+            return Ok(value.clone());
+        }
+        let namespace = self.namespace;
+        match namespace.get_symbol(name).value {
+            Some(TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
+                value, ..
+            })) => self.evaluate(value),
+            _ => Err(not_a_constant(span)),
+        }
+    }
+
+    /// Evaluates the statements of `block` in order, returning the value it evaluates to, or
+    /// `None` if it ends without one.
+    fn evaluate_block(
+        &mut self,
+        block: &TypedCodeBlock,
+    ) -> Result<Option<TypedExpression>, CompileError> {
+        let scope = self.variables.len();
+        let mut value = None;
+        for TypedAstNode { content, span } in &block.contents {
+            match content {
+                TypedAstNodeContent::Declaration(TypedDeclaration::VariableDeclaration(
+                    TypedVariableDeclaration { name, body, .. },
+                )) => {
+                    let body = self.evaluate(body)?;
+                    self.variables.push((name.clone(), body));
+                }
+                TypedAstNodeContent::Declaration(TypedDeclaration::ConstantDeclaration(
+                    TypedConstantDeclaration { name, value, .. },
+                )) => {
+                    let value = self.evaluate(value)?;
+                    self.variables.push((name.clone(), value));
+                }
+                TypedAstNodeContent::Expression(expr) => {
+                    self.evaluate(expr)?;
+                }
+                TypedAstNodeContent::ImplicitReturnExpression(expr) => {
+                    value = Some(self.evaluate(expr)?);
+                }
+                TypedAstNodeContent::ReturnStatement(TypedReturnStatement { expr }) => {
+                    let expr = self.evaluate(expr)?;
+                    self.returned = Some(expr);
+                }
+                TypedAstNodeContent::SideEffect => (),
+                _ => return Err(not_a_constant(span)),
+            }
+            if self.returned.is_some() {
+                break;
+            }
+        }
+        self.variables.truncate(scope);
+        Ok(value)
+    }
+
+    /// Evaluates the body of the function called by `call` with the values of its `arguments`.
+    fn call(
+        &mut self,
+        arguments: Vec<(Ident, TypedExpression)>,
+        body: &TypedCodeBlock,
+        call: &TypedExpression,
+    ) -> Result<TypedExpression, CompileError> {
+        let caller_variables = std::mem::replace(&mut self.variables, arguments);
+        let value = self.evaluate_block(body);
+        self.variables = caller_variables;
+        let value = match (self.returned.take(), value?) {
+            (Some(value), _) | (None, Some(value)) => value,
+            // the bodies of functions which are provided by the compiler, or which are called
+            // recursively, aren't available
+            (None, None) if look_up_type_id(call.return_type).is_unit() => TypedExpression {
+                expression: TypedExpressionVariant::Tuple { fields: vec![] },
+                return_type: call.return_type,
+                is_constant: IsConstant::Yes,
+                span: call.span.clone(),
+            },
+            (None, None) => return Err(not_a_constant(&call.span)),
+        };
+        Ok(value)
+    }
+}
+
+fn not_a_constant(span: &Span) -> CompileError {
+    CompileError::NotAConstant { span: span.clone() }
+}
+
+fn is_primitive_literal(expr: &TypedExpression) -> bool {
+    matches!(
+        expr.expression,
+        TypedExpressionVariant::Literal(
+            Literal::U8(_)
+                | Literal::U16(_)
+                | Literal::U32(_)
+                | Literal::U64(_)
+                | Literal::I8(_)
+                | Literal::I16(_)
+                | Literal::I32(_)
+                | Literal::I64(_)
+                | Literal::Boolean(_)
+                | Literal::Byte(_)
+                | Literal::B256(_)
+        )
+    )
+}
+
+fn integer_value(literal: &Literal) -> Option<i128> {
+    Some(match literal {
+        Literal::U8(value) => *value as i128,
+        Literal::U16(value) => *value as i128,
+        Literal::U32(value) => *value as i128,
+        Literal::U64(value) => *value as i128,
+        Literal::I8(value) => *value as i128,
+        Literal::I16(value) => *value as i128,
+        Literal::I32(value) => *value as i128,
+        Literal::I64(value) => *value as i128,
+        _ => return None,
+    })
+}
+
+/// The literal of type `r#type` holding `value`, or `None` if `value` doesn't fit in it.
+fn integer_literal(value: i128, r#type: &TypeInfo) -> Option<Literal> {
+    use IntegerBits::*;
+    match r#type {
+        TypeInfo::UnsignedInteger(Eight) => u8::try_from(value).ok().map(Literal::U8),
+        TypeInfo::UnsignedInteger(Sixteen) => u16::try_from(value).ok().map(Literal::U16),
+        TypeInfo::UnsignedInteger(ThirtyTwo) => u32::try_from(value).ok().map(Literal::U32),
+        TypeInfo::SignedInteger(Eight) => i8::try_from(value).ok().map(Literal::I8),
+        TypeInfo::SignedInteger(Sixteen) => i16::try_from(value).ok().map(Literal::I16),
+        TypeInfo::SignedInteger(ThirtyTwo) => i32::try_from(value).ok().map(Literal::I32),
+        TypeInfo::SignedInteger(SixtyFour) => i64::try_from(value).ok().map(Literal::I64),
+        _ => u64::try_from(value).ok().map(Literal::U64),
+    }
+}
+
+/// Applies the `core::ops` method `op` to the literals `operands`, giving a value of type
+/// `return_type`.
+fn apply_operator(
+    op: &str,
+    operands: &[TypedExpression],
+    return_type: TypeId,
+    span: &Span,
+) -> Result<Literal, CompileError> {
+    let literals = operands
+        .iter()
+        .map(|operand| match &operand.expression {
+            TypedExpressionVariant::Literal(literal) => Ok(literal),
+            _ => Err(not_a_constant(&operand.span)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let integers = literals
+        .iter()
+        .copied()
+        .map(integer_value)
+        .collect::<Option<Vec<_>>>();
+    let booleans = literals
+        .iter()
+        .map(|literal| match literal {
+            Literal::Boolean(value) => Some(*value),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+    let return_type = look_up_type_id(return_type);
+    let integer = |value: Option<i128>| {
+        let value = value.ok_or_else(|| CompileError::ConstantOverflow {
+            ty: return_type.friendly_type_str(),
+            span: span.clone(),
+        })?;
+        integer_literal(value, &return_type).ok_or_else(|| CompileError::ConstantOverflow {
+            ty: return_type.friendly_type_str(),
+            span: span.clone(),
+        })
+    };
+    let divisor = |value: i128| {
+        if value == 0 {
+            Err(CompileError::ConstantDivisionByZero { span: span.clone() })
+        } else {
+            Ok(value)
+        }
+    };
+    let boolean = |value: bool| Ok(Literal::Boolean(value));
+    match (op, integers.as_deref(), booleans.as_deref(), &literals[..]) {
+        ("add", Some([lhs, rhs]), ..) => integer(lhs.checked_add(*rhs)),
+        ("subtract", Some([lhs, rhs]), ..) => integer(lhs.checked_sub(*rhs)),
+        ("multiply", Some([lhs, rhs]), ..) => integer(lhs.checked_mul(*rhs)),
+        ("divide", Some([lhs, rhs]), ..) => integer(lhs.checked_div(divisor(*rhs)?)),
+        ("modulo", Some([lhs, rhs]), ..) => integer(lhs.checked_rem(divisor(*rhs)?)),
+        ("neg", Some([value]), ..) => integer(value.checked_neg()),
+        ("binary_and", Some([lhs, rhs]), ..) => integer(Some(lhs & rhs)),
+        ("binary_or", Some([lhs, rhs]), ..) => integer(Some(lhs | rhs)),
+        ("xor", Some([lhs, rhs]), ..) => integer(Some(lhs ^ rhs)),
+        ("eq", Some([lhs, rhs]), ..) => boolean(lhs == rhs),
+        ("neq", Some([lhs, rhs]), ..) => boolean(lhs != rhs),
+        ("lt", Some([lhs, rhs]), ..) => boolean(lhs < rhs),
+        ("gt", Some([lhs, rhs]), ..) => boolean(lhs > rhs),
+        ("le", Some([lhs, rhs]), ..) => boolean(lhs <= rhs),
+        ("ge", Some([lhs, rhs]), ..) => boolean(lhs >= rhs),
+        ("binary_and", _, Some([lhs, rhs]), _) => boolean(lhs & rhs),
+        ("binary_or", _, Some([lhs, rhs]), _) => boolean(lhs | rhs),
+        ("xor", _, Some([lhs, rhs]), _) => boolean(lhs ^ rhs),
+        ("not", _, Some([value]), _) => boolean(!value),
+        ("eq", _, _, [lhs, rhs]) => boolean(lhs == rhs),
+        ("neq", _, _, [lhs, rhs]) => boolean(lhs != rhs),
+        _ => Err(not_a_constant(span)),
+    }
+}
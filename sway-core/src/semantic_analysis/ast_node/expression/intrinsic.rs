@@ -0,0 +1,82 @@
+//! The functions which are built into the compiler, `revert`, `require` and `assert`, which make
+//! the program revert with a code. The codes are constants, so that the build output can list
+//! where in the source each of them comes from.
+
+use super::{TypedExpression, TypedExpressionVariant};
+use crate::parse_tree::CallPath;
+use crate::semantic_analysis::ast_node::{TypedCodeBlock, TypedVariableDeclaration};
+use crate::semantic_analysis::{Namespace, TypedAstNodeContent, TypedDeclaration};
+
+/// The code `assert` reverts with when its condition is false.
+pub(crate) const ASSERT_REVERT_CODE: u64 = 0xffff_ffff_ffff_0000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Intrinsic {
+    /// `revert(code)`, which reverts with `code`. As it never returns, it can be used wherever a
+    /// value of any type is expected.
+    Revert,
+    /// `require(condition, code)`, which reverts with `code` if `condition` is false.
+    Require,
+    /// `assert(condition)`, which reverts with [ASSERT_REVERT_CODE] if `condition` is false.
+    Assert,
+}
+
+impl Intrinsic {
+    /// The intrinsic which a call of `name` is, if it is one. They are only called by their bare
+    /// names, and a function of the same name which is in scope is called instead of them.
+    pub(crate) fn from_call_path(name: &CallPath, namespace: &Namespace) -> Option<Self> {
+        if !name.prefixes.is_empty() || namespace.get_call_path(name).value.is_some() {
+            return None;
+        }
+        match name.suffix.as_str() {
+            "revert" => Some(Intrinsic::Revert),
+            "require" => Some(Intrinsic::Require),
+            "assert" => Some(Intrinsic::Assert),
+            _ => None,
+        }
+    }
+
+    /// The number of arguments the intrinsic takes.
+    pub(crate) fn parameter_count(&self) -> usize {
+        match self {
+            Intrinsic::Revert | Intrinsic::Assert => 1,
+            Intrinsic::Require => 2,
+        }
+    }
+}
+
+impl TypedExpression {
+    /// Whether this never finishes being evaluated, as it reverts, so that what follows it can't
+    /// be reached. A block diverges if one of its statements reverts or returns, and an `if`
+    /// diverges if both of its branches do. The body of a loop may never run, so a loop doesn't.
+    pub(crate) fn diverges(&self) -> bool {
+        match &self.expression {
+            TypedExpressionVariant::Revert { .. } => true,
+            TypedExpressionVariant::CodeBlock(block) => block.diverges(),
+            TypedExpressionVariant::IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                condition.diverges()
+                    || (then.diverges()
+                        && r#else.as_ref().map_or(false, |r#else| r#else.diverges()))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TypedCodeBlock {
+    fn diverges(&self) -> bool {
+        self.contents.iter().any(|node| match &node.content {
+            TypedAstNodeContent::ReturnStatement(_) => true,
+            TypedAstNodeContent::Expression(exp)
+            | TypedAstNodeContent::ImplicitReturnExpression(exp) => exp.diverges(),
+            TypedAstNodeContent::Declaration(TypedDeclaration::VariableDeclaration(
+                TypedVariableDeclaration { body, .. },
+            )) => body.diverges(),
+            _ => false,
+        })
+    }
+}
@@ -1,8 +1,24 @@
+mod const_eval;
 mod enum_instantiation;
+mod intrinsic;
+mod operator;
+mod signed_integer;
 mod struct_expr_field;
+mod trait_dispatch;
+mod try_expression;
 mod typed_expression;
 mod typed_expression_variant;
+mod usefulness;
+pub(crate) use const_eval::{evaluate_array_length, evaluate_constant};
 pub(crate) use enum_instantiation::instantiate_enum;
+pub(crate) use intrinsic::{Intrinsic, ASSERT_REVERT_CODE};
+pub(crate) use operator::{
+    dispatches_to_operator_trait, overloadable_operator, OverloadableOperator,
+};
+pub(crate) use signed_integer::{signed_integer_methods, SignedIntegerOp, SignedIntegerOpKind};
 pub(crate) use struct_expr_field::TypedStructExpressionField;
-pub(crate) use typed_expression::{error_recovery_expr, TypedExpression};
+pub(crate) use trait_dispatch::dispatch_trait_methods;
+pub(crate) use try_expression::ResultLikeEnum;
+pub(crate) use typed_expression::{error_recovery_expr, is_storage, TypedExpression};
 pub(crate) use typed_expression_variant::*;
+pub(crate) use usefulness::check_match_expression;
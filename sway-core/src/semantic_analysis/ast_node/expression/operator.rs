@@ -0,0 +1,67 @@
+//! Overloading of operators. Applying an operator is a call of a method of `core::ops`, like `add`
+//! for `+`. The built-in types get these methods from the implementations in `core`, which lower
+//! directly to instructions like `ADD`, `LT` and `EQ`, or from the compiler in the case of the
+//! signed integers. Any other type has to implement the operator trait the method belongs to.
+
+use crate::type_engine::TypeInfo;
+use crate::Ident;
+
+/// An operator which can be overloaded by implementing one of the traits of `core::ops`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OverloadableOperator {
+    /// The operator as it is written, like `+`.
+    pub(crate) symbol: &'static str,
+    /// The traits which declare the method of the operator, like `Add` for `add`. The first is
+    /// the one to suggest implementing.
+    pub(crate) trait_names: &'static [&'static str],
+}
+
+/// The operator whose method in `core::ops` is `method_name`, if any.
+pub(crate) fn overloadable_operator(
+    method_path: &[Ident],
+    method_name: &Ident,
+) -> Option<OverloadableOperator> {
+    let is_core_ops = method_path.len() == 2
+        && method_path[0].as_str() == "core"
+        && method_path[1].as_str() == "ops";
+    if !is_core_ops {
+        return None;
+    }
+    let (symbol, trait_names): (_, &'static [_]) = match method_name.as_str() {
+        "add" => ("+", &["Add"]),
+        "subtract" => ("-", &["Subtract"]),
+        "multiply" => ("*", &["Multiply"]),
+        "divide" => ("/", &["Divide"]),
+        "modulo" => ("%", &["Mod"]),
+        "neg" => ("-", &["Neg"]),
+        // `Ord` declares `eq` as well, so that ordered types need only implement the one trait
+        "eq" => ("==", &["Eq", "Ord"]),
+        "neq" => ("!=", &["Eq", "Ord"]),
+        "lt" => ("<", &["Ord"]),
+        "gt" => (">", &["Ord"]),
+        "le" => ("<=", &["Ord"]),
+        "ge" => (">=", &["Ord"]),
+        "binary_and" => ("&", &["BitwiseAnd"]),
+        "binary_or" => ("|", &["BitwiseOr"]),
+        "xor" => ("^", &["BitwiseXor"]),
+        _ => return None,
+    };
+    Some(OverloadableOperator {
+        symbol,
+        trait_names,
+    })
+}
+
+/// Whether applying an operator to a value of type `r#type` has to dispatch to an implementation
+/// of an operator trait. The methods of the built-in types are found like any other method, as
+/// are those of types which aren't known yet.
+pub(crate) fn dispatches_to_operator_trait(r#type: &TypeInfo) -> bool {
+    matches!(
+        r#type,
+        TypeInfo::Struct { .. }
+            | TypeInfo::Enum { .. }
+            | TypeInfo::Tuple(_)
+            | TypeInfo::Array(..)
+            | TypeInfo::UnknownGeneric { .. }
+    )
+}
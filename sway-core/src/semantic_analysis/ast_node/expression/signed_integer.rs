@@ -0,0 +1,179 @@
+use crate::parse_tree::{Purity, Visibility};
+use crate::semantic_analysis::ast_node::{
+    IsConstant, TypedCodeBlock, TypedExpressionVariant, TypedFunctionDeclaration,
+    TypedFunctionParameter,
+};
+use crate::semantic_analysis::{TypedAstNode, TypedAstNodeContent, TypedExpression};
+use crate::span::Span;
+use crate::type_engine::*;
+use crate::{Ident, TypeParameter};
+
+/// The body of one of the operator methods of the signed integer types, which are provided by the
+/// compiler as FuelVM only has instructions for unsigned integers. The `arguments` are the
+/// parameters of the method, starting with `self`.
+#[derive(Clone, Debug)]
+pub(crate) struct SignedIntegerOp {
+    pub(crate) op: SignedIntegerOpKind,
+    pub(crate) bits: IntegerBits,
+    pub(crate) arguments: Vec<TypedExpression>,
+}
+
+impl SignedIntegerOp {
+    pub(crate) fn copy_types(&mut self, type_mapping: &[(TypeParameter, TypeId)]) {
+        self.arguments
+            .iter_mut()
+            .for_each(|argument| argument.copy_types(type_mapping));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SignedIntegerOpKind {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Negate,
+    Equals,
+    NotEquals,
+    LessThan,
+    GreaterThan,
+    LessThanOrEqualTo,
+    GreaterThanOrEqualTo,
+    BinaryAnd,
+    BinaryOr,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+impl SignedIntegerOpKind {
+    /// The name of the method in `core::ops` which this is the body of.
+    pub(crate) fn method_name(&self) -> &'static str {
+        use SignedIntegerOpKind::*;
+        match self {
+            Add => "add",
+            Subtract => "subtract",
+            Multiply => "multiply",
+            Divide => "divide",
+            Modulo => "modulo",
+            Negate => "neg",
+            Equals => "eq",
+            NotEquals => "neq",
+            LessThan => "lt",
+            GreaterThan => "gt",
+            LessThanOrEqualTo => "le",
+            GreaterThanOrEqualTo => "ge",
+            BinaryAnd => "binary_and",
+            BinaryOr => "binary_or",
+            Xor => "xor",
+            ShiftLeft => "lsh",
+            ShiftRight => "rsh",
+        }
+    }
+}
+
+/// The operator methods of the signed integer type `int_type`, which has `bits` bits:
+///
+/// ```ignore
+/// fn add(self, other: Self) -> Self;        // and subtract, multiply, divide, modulo,
+///                                           // binary_and, binary_or and xor
+/// fn eq(self, other: Self) -> bool;         // and neq, lt, gt, le and ge
+/// fn neg(self) -> Self;
+/// fn lsh(self, other: u64) -> Self;         // and rsh, which is arithmetic
+/// ```
+///
+/// They aren't declared anywhere, so `span` stands in for their declarations, and they are only
+/// told apart from each other by their names.
+pub(crate) fn signed_integer_methods(
+    int_type: TypeId,
+    bits: IntegerBits,
+    span: &Span,
+) -> Vec<TypedFunctionDeclaration> {
+    use SignedIntegerOpKind::*;
+    let boolean = insert_type(TypeInfo::Boolean);
+    let shift_amount = insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour));
+    let method = |op: SignedIntegerOpKind, parameters: &[(&'static str, TypeId)], return_type| {
+        let parameters = parameters
+            .iter()
+            .map(|(name, r#type)| TypedFunctionParameter {
+                name: Ident::new_with_override(*name, span.clone()),
+                r#type: *r#type,
+                type_span: span.clone(),
+            })
+            .collect::<Vec<_>>();
+        let arguments = parameters
+            .iter()
+            .map(|parameter| TypedExpression {
+                expression: TypedExpressionVariant::VariableExpression {
+                    name: parameter.name.clone(),
+                },
+                return_type: parameter.r#type,
+                is_constant: IsConstant::No,
+                span: span.clone(),
+            })
+            .collect();
+        let body = TypedExpression {
+            expression: TypedExpressionVariant::SignedIntegerOp(SignedIntegerOp {
+                op,
+                bits,
+                arguments,
+            }),
+            return_type,
+            is_constant: IsConstant::No,
+            span: span.clone(),
+        };
+        TypedFunctionDeclaration {
+            name: Ident::new_with_override(op.method_name(), span.clone()),
+            body: TypedCodeBlock {
+                contents: vec![TypedAstNode {
+                    content: TypedAstNodeContent::ImplicitReturnExpression(body),
+                    span: span.clone(),
+                }],
+                whole_block_span: span.clone(),
+            },
+            parameters,
+            span: span.clone(),
+            return_type,
+            type_parameters: vec![],
+            return_type_span: span.clone(),
+            visibility: Visibility::Public,
+            is_contract_call: false,
+            trait_placeholder: None,
+            purity: Purity::Pure,
+            attributes: vec![],
+        }
+    };
+    let mut methods = vec![method(Negate, &[("self", int_type)], int_type)];
+    for op in [
+        Add, Subtract, Multiply, Divide, Modulo, BinaryAnd, BinaryOr, Xor,
+    ] {
+        methods.push(method(
+            op,
+            &[("self", int_type), ("other", int_type)],
+            int_type,
+        ));
+    }
+    for op in [
+        Equals,
+        NotEquals,
+        LessThan,
+        GreaterThan,
+        LessThanOrEqualTo,
+        GreaterThanOrEqualTo,
+    ] {
+        methods.push(method(
+            op,
+            &[("self", int_type), ("other", int_type)],
+            boolean,
+        ));
+    }
+    for op in [ShiftLeft, ShiftRight] {
+        methods.push(method(
+            op,
+            &[("self", int_type), ("other", shift_amount)],
+            int_type,
+        ));
+    }
+    methods
+}
@@ -0,0 +1,179 @@
+//! Dispatch of the methods of trait bounds. Within a generic function, the methods of the traits
+//! bounding a type parameter can be called on values of that type, but which implementation is
+//! called isn't known until the function is instantiated. Until then, such a call is to a
+//! placeholder without a body. Once the type parameter has been unified with the type the
+//! function is instantiated with, the placeholder is replaced with the method of that type.
+
+use super::{TypedExpression, TypedExpressionVariant};
+use crate::error::*;
+use crate::semantic_analysis::ast_node::TypedCodeBlock;
+use crate::semantic_analysis::visit::{walk_expression_mut, VisitMut};
+use crate::semantic_analysis::Namespace;
+use crate::span::Span;
+use crate::type_engine::*;
+use crate::{CallPath, Ident, TypeParameter};
+
+/// Replaces the calls in `block` to the methods of the trait bounds of the type parameters in
+/// `type_mapping` with calls to the methods of the types the parameters were instantiated with.
+pub(crate) fn dispatch_trait_methods(
+    block: &mut TypedCodeBlock,
+    type_mapping: &[(TypeParameter, TypeId)],
+    namespace: &Namespace,
+    self_type: TypeId,
+) -> CompileResult<()> {
+    let mut dispatcher = Dispatcher {
+        type_mapping,
+        namespace,
+        self_type,
+        warnings: vec![],
+        errors: vec![],
+    };
+    if type_mapping
+        .iter()
+        .any(|(type_parameter, _)| !type_parameter.trait_constraints.is_empty())
+    {
+        dispatcher.visit_code_block_mut(block);
+    }
+    let Dispatcher {
+        warnings, errors, ..
+    } = dispatcher;
+    if errors.is_empty() {
+        ok((), warnings, errors)
+    } else {
+        err(warnings, errors)
+    }
+}
+
+struct Dispatcher<'a> {
+    type_mapping: &'a [(TypeParameter, TypeId)],
+    namespace: &'a Namespace,
+    self_type: TypeId,
+    warnings: Vec<CompileWarning>,
+    errors: Vec<CompileError>,
+}
+
+// nested functions and impls are generic over their own type parameters, if any, so the walk not
+// going into their declarations is what's wanted here
+impl VisitMut for Dispatcher<'_> {
+    fn visit_expression_mut(&mut self, expr: &mut TypedExpression) {
+        walk_expression_mut(self, expr);
+        let TypedExpression {
+            expression,
+            return_type,
+            span,
+            ..
+        } = expr;
+        if let TypedExpressionVariant::FunctionApplication {
+            name,
+            arguments,
+            function_body,
+            trait_placeholder,
+            ..
+        } = expression
+        {
+            self.visit_code_block_mut(function_body);
+            if let Some(trait_name) = trait_placeholder {
+                if let Some(body) = self.dispatch(trait_name, name, arguments, *return_type, span) {
+                    *function_body = body;
+                    *trait_placeholder = None;
+                }
+            }
+        }
+    }
+}
+
+impl Dispatcher<'_> {
+    /// If this is a call to the placeholder of a method of the trait bound `trait_name`, whose
+    /// receiver is of the bounded type parameter, finds the method of that trait's
+    /// implementation for the type the parameter was instantiated with. Returns its body, and
+    /// renames the arguments after its parameters.
+    fn dispatch(
+        &mut self,
+        trait_name: &CallPath,
+        name: &CallPath,
+        arguments: &mut [(Ident, TypedExpression)],
+        return_type: TypeId,
+        span: &Span,
+    ) -> Option<TypedCodeBlock> {
+        let (_, receiver) = arguments.first()?;
+        let receiver_type = match look_up_type_id_raw(receiver.return_type) {
+            TypeInfo::Ref(id) => id,
+            _ => return None,
+        };
+        let (type_parameter, _) = self
+            .type_mapping
+            .iter()
+            .find(|(_, type_id)| *type_id == receiver_type)?;
+        // if the type parameter was instantiated with another generic type, the call is
+        // dispatched when that one is instantiated
+        if type_parameter.trait_constraints.is_empty()
+            || matches!(
+                look_up_type_id(receiver_type),
+                TypeInfo::UnknownGeneric { .. }
+            )
+        {
+            return None;
+        }
+
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let method = check!(
+            self.namespace
+                .find_trait_method_for_type(receiver_type, trait_name, &name.suffix),
+            return self.fail(warnings, errors),
+            warnings,
+            errors
+        );
+        let (mut method, type_mapping) = if method.type_parameters.is_empty() {
+            (method, vec![])
+        } else {
+            check!(
+                method.monomorphize(vec![], self.self_type),
+                return self.fail(warnings, errors),
+                warnings,
+                errors
+            )
+        };
+        for ((argument_name, argument), param) in arguments.iter_mut().zip(&method.parameters) {
+            match unify_with_self(argument.return_type, param.r#type, self.self_type, span) {
+                Ok(mut ws) => warnings.append(&mut ws),
+                Err(e) => errors.push(e.into()),
+            }
+            *argument_name = param.name.clone();
+        }
+        match unify_with_self(method.return_type, return_type, self.self_type, span) {
+            Ok(mut ws) => warnings.append(&mut ws),
+            Err(e) => errors.push(e.into()),
+        }
+        check!(
+            self.namespace.check_trait_constraints(&type_mapping, span),
+            return self.fail(warnings, errors),
+            warnings,
+            errors
+        );
+        check!(
+            dispatch_trait_methods(
+                &mut method.body,
+                &type_mapping,
+                self.namespace,
+                self.self_type
+            ),
+            return self.fail(warnings, errors),
+            warnings,
+            errors
+        );
+        self.warnings.append(&mut warnings);
+        self.errors.append(&mut errors);
+        Some(method.body)
+    }
+
+    fn fail(
+        &mut self,
+        mut warnings: Vec<CompileWarning>,
+        mut errors: Vec<CompileError>,
+    ) -> Option<TypedCodeBlock> {
+        self.warnings.append(&mut warnings);
+        self.errors.append(&mut errors);
+        None
+    }
+}
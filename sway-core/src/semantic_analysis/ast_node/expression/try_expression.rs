@@ -0,0 +1,160 @@
+//! The `?` operator, which can be applied to a result-like enum, like an `Option` or a `Result`.
+//! `expr?` evaluates to the value held by `expr`, or returns the error held by `expr` from the
+//! enclosing function. The enum is only known once `expr` is type checked, so that is when `?` is
+//! desugared.
+
+use crate::parse_tree::{
+    CallPath, DelayedEnumTagResolution, DelayedEnumVariantResolution, DelayedResolutionVariant,
+    Expression, Literal, ReturnStatement,
+};
+use crate::span::Span;
+use crate::type_engine::{look_up_type_id, TypeId, TypeInfo};
+use crate::{AstNode, AstNodeContent, CodeBlock, Ident};
+
+/// The variants of the enums which `?` can be applied to, as pairs of the variant which holds a
+/// value and the variant which holds an error.
+const RESULT_LIKE_VARIANTS: [(&str, &str); 2] = [("Some", "None"), ("Ok", "Err")];
+
+/// An enum which `?` can be applied to, which has exactly two variants: one which holds a value
+/// and one which holds an error.
+pub(crate) struct ResultLikeEnum {
+    pub(crate) name: String,
+    value_variant: &'static str,
+    error_variant: &'static str,
+    error_tag: usize,
+    error_type: TypeId,
+}
+
+impl ResultLikeEnum {
+    /// The result-like enum which `r#type` is, if it is one.
+    pub(crate) fn from_type(r#type: TypeId) -> Option<Self> {
+        let (name, variant_types) = match look_up_type_id(r#type) {
+            TypeInfo::Enum {
+                name,
+                variant_types,
+            } => (name, variant_types),
+            _ => return None,
+        };
+        let (first, second) = match &variant_types[..] {
+            [first, second] => (first, second),
+            _ => return None,
+        };
+        RESULT_LIKE_VARIANTS
+            .iter()
+            .find_map(|&(value_name, error_name)| {
+                if first.name == value_name && second.name == error_name {
+                    Some((value_name, second, error_name))
+                } else if first.name == error_name && second.name == value_name {
+                    Some((value_name, first, error_name))
+                } else {
+                    None
+                }
+            })
+            .map(|(value_name, error_variant, error_name)| ResultLikeEnum {
+                name,
+                value_variant: value_name,
+                error_variant: error_name,
+                error_tag: error_variant.tag,
+                error_type: error_variant.r#type,
+            })
+    }
+
+    /// Desugars `expr?`, where `try_value` is a variable holding the value of `expr`, which is of
+    /// this enum, which is declared as `enum_name`, to:
+    ///
+    /// ```ignore
+    /// {
+    ///     if <try_value is the error variant> {
+    ///         return Result::Err(<the error held by try_value>);
+    ///     }
+    ///     <the value held by try_value>
+    /// }
+    /// ```
+    ///
+    /// The returned error is checked against the return type of the enclosing function along with
+    /// its other return statements.
+    pub(crate) fn desugar_try_expression(
+        &self,
+        try_value: Expression,
+        enum_name: Ident,
+        span: Span,
+    ) -> Expression {
+        let variant_data = |variant_name| Expression::DelayedMatchTypeResolution {
+            variant: DelayedResolutionVariant::EnumVariant(DelayedEnumVariantResolution {
+                exp: Box::new(try_value.clone()),
+                call_path: CallPath {
+                    prefixes: vec![enum_name.clone()],
+                    suffix: Ident::new_with_override(variant_name, span.clone()),
+                },
+                arg_num: 0,
+            }),
+            span: span.clone(),
+        };
+
+        let is_error = Expression::core_ops_eq(
+            vec![
+                Expression::DelayedMatchTypeResolution {
+                    variant: DelayedResolutionVariant::EnumTag(DelayedEnumTagResolution {
+                        exp: Box::new(try_value.clone()),
+                    }),
+                    span: span.clone(),
+                },
+                Expression::Literal {
+                    value: Literal::U64(self.error_tag as u64),
+                    span: span.clone(),
+                },
+            ],
+            span.clone(),
+        );
+        let error = if look_up_type_id(self.error_type).is_unit() {
+            vec![]
+        } else {
+            vec![variant_data(self.error_variant)]
+        };
+        let return_error = AstNode {
+            content: AstNodeContent::ReturnStatement(ReturnStatement {
+                expr: Expression::DelineatedPath {
+                    call_path: CallPath {
+                        prefixes: vec![enum_name.clone()],
+                        suffix: Ident::new_with_override(self.error_variant, span.clone()),
+                    },
+                    args: error,
+                    span: span.clone(),
+                    type_arguments: vec![],
+                },
+            }),
+            span: span.clone(),
+        };
+        let check_error = Expression::IfExp {
+            condition: Box::new(is_error),
+            then: Box::new(Expression::CodeBlock {
+                contents: CodeBlock {
+                    contents: vec![return_error],
+                    whole_block_span: span.clone(),
+                },
+                span: span.clone(),
+            }),
+            r#else: None,
+            span: span.clone(),
+        };
+        let value = variant_data(self.value_variant);
+
+        let contents = vec![
+            AstNodeContent::Expression(check_error),
+            AstNodeContent::ImplicitReturnExpression(value),
+        ]
+        .into_iter()
+        .map(|content| AstNode {
+            content,
+            span: span.clone(),
+        })
+        .collect();
+        Expression::CodeBlock {
+            contents: CodeBlock {
+                contents,
+                whole_block_span: span.clone(),
+            },
+            span,
+        }
+    }
+}
@@ -1,8 +1,13 @@
 use super::*;
 use crate::build_config::BuildConfig;
 use crate::control_flow_analysis::ControlFlowGraph;
+use crate::parse_tree::Purity;
+use crate::semantic_analysis::ast_node::for_loop::{
+    declaration, declared_type, variable_expression,
+};
 use crate::semantic_analysis::{ast_node::*, Namespace, TypeCheckArguments};
 use crate::type_engine::{insert_type, IntegerBits};
+use crate::{hidden_ident, HiddenVariable};
 
 use either::Either;
 use std::cmp::Ordering;
@@ -22,6 +27,16 @@ pub struct TypedExpression {
     pub(crate) span: Span,
 }
 
+/// Whether `expr` is the `storage` in `storage.field`, rather than a variable which happens to be
+/// called `storage`.
+pub(crate) fn is_storage(expr: &Expression, namespace: &Namespace) -> bool {
+    matches!(
+        expr,
+        Expression::VariableExpression { name, .. }
+            if name.as_str() == "storage" && namespace.get_symbol_by_str("storage").is_none()
+    )
+}
+
 pub(crate) fn error_recovery_expr(span: Span) -> TypedExpression {
     TypedExpression {
         expression: TypedExpressionVariant::Tuple { fields: vec![] },
@@ -53,6 +68,32 @@ impl TypedExpression {
             Expression::VariableExpression { name, span, .. } => {
                 Self::type_check_variable_expression(name, span, namespace)
             }
+            Expression::FunctionApplication {
+                name,
+                arguments,
+                span,
+                type_arguments,
+                ..
+            } if type_arguments.is_empty()
+                && Intrinsic::from_call_path(&name, namespace).is_some() =>
+            {
+                Self::type_check_intrinsic(
+                    TypeCheckArguments {
+                        checkee: (name, arguments),
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: insert_type(TypeInfo::Unknown),
+                        help_text: Default::default(),
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    },
+                    span,
+                )
+            }
             Expression::FunctionApplication {
                 name,
                 arguments,
@@ -127,6 +168,42 @@ impl TypedExpression {
                 },
                 span,
             ),
+            Expression::MatchExpression {
+                primary_expression,
+                branches,
+                span,
+            } => Self::type_check_match_expression(
+                TypeCheckArguments {
+                    checkee: (*primary_expression, branches),
+                    return_type_annotation: type_annotation,
+                    namespace,
+                    crate_namespace,
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    mode: Mode::NonAbi,
+                    help_text: Default::default(),
+                    opts,
+                },
+                span,
+            ),
+            Expression::TryExpression { expr, span } => Self::type_check_try_expression(
+                TypeCheckArguments {
+                    checkee: *expr,
+                    return_type_annotation: type_annotation,
+                    namespace,
+                    crate_namespace,
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    mode: Mode::NonAbi,
+                    help_text: Default::default(),
+                    opts,
+                },
+                span,
+            ),
             Expression::AsmExpression { asm, span, .. } => Self::type_check_asm_expression(
                 asm,
                 span,
@@ -154,6 +231,13 @@ impl TypedExpression {
                 dependency_graph,
                 opts,
             ),
+            Expression::SubfieldExpression {
+                prefix,
+                span,
+                field_to_access,
+            } if is_storage(&prefix, namespace) => {
+                Self::type_check_storage_access(field_to_access, span, namespace, opts)
+            }
             Expression::SubfieldExpression {
                 prefix,
                 span,
@@ -243,6 +327,22 @@ impl TypedExpression {
                 dependency_graph,
                 opts,
             ),
+            Expression::ArrayRepeat { value, count, span } => Self::type_check_array_repeat(
+                TypeCheckArguments {
+                    checkee: (*value, *count),
+                    namespace,
+                    crate_namespace,
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    opts,
+                    return_type_annotation: insert_type(TypeInfo::Unknown),
+                    mode: Default::default(),
+                    help_text: Default::default(),
+                },
+                span,
+            ),
             Expression::ArrayIndex {
                 prefix,
                 index,
@@ -310,8 +410,8 @@ impl TypedExpression {
 
         typed_expression.return_type = namespace
             .resolve_type_with_self(look_up_type_id(typed_expression.return_type), self_type)
-            .unwrap_or_else(|_| {
-                errors.push(CompileError::UnknownType { span: expr_span });
+            .unwrap_or_else(|error| {
+                errors.push(error.into_error(expr_span));
                 insert_type(TypeInfo::ErrorRecovery)
             });
 
@@ -339,6 +439,10 @@ impl TypedExpression {
 
             Literal::U32(_) => TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
             Literal::U64(_) => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+            Literal::I8(_) => TypeInfo::SignedInteger(IntegerBits::Eight),
+            Literal::I16(_) => TypeInfo::SignedInteger(IntegerBits::Sixteen),
+            Literal::I32(_) => TypeInfo::SignedInteger(IntegerBits::ThirtyTwo),
+            Literal::I64(_) => TypeInfo::SignedInteger(IntegerBits::SixtyFour),
             Literal::Boolean(_) => TypeInfo::Boolean,
             Literal::Byte(_) => TypeInfo::Byte,
             Literal::B256(_) => TypeInfo::B256,
@@ -421,18 +525,21 @@ impl TypedExpression {
             warnings,
             errors
         );
-        let TypedFunctionDeclaration {
-            parameters,
-            return_type,
-            body,
-            span,
-            purity,
-            ..
-        } = if let TypedDeclaration::FunctionDeclaration(decl) = function_declaration {
+        let (
+            TypedFunctionDeclaration {
+                parameters,
+                return_type,
+                mut body,
+                span,
+                purity,
+                ..
+            },
+            type_mapping,
+        ) = if let TypedDeclaration::FunctionDeclaration(decl) = function_declaration {
             // if this is a generic function, monomorphize its internal types and insert the resulting
             // declaration into the namespace. Then, use that instead.
             if decl.type_parameters.is_empty() {
-                decl
+                (decl, vec![])
             } else {
                 check!(
                     decl.monomorphize(type_arguments, self_type),
@@ -450,8 +557,8 @@ impl TypedExpression {
             return err(warnings, errors);
         };
 
-        if opts.purity != purity {
-            errors.push(CompileError::PureCalledImpure { span: name.span() });
+        if let Err(error) = opts.purity.check_access(purity, &name.span()) {
+            errors.push(error);
         }
 
         match arguments.len().cmp(&parameters.len()) {
@@ -520,6 +627,21 @@ impl TypedExpression {
             })
             .collect();
 
+        // now that the type parameters are unified with the types of the arguments, check their
+        // trait bounds and call the methods of those traits on the actual types
+        check!(
+            namespace.check_trait_constraints(&type_mapping, &name.span()),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        check!(
+            dispatch_trait_methods(&mut body, &type_mapping, namespace, self_type),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+
         ok(
             TypedExpression {
                 return_type,
@@ -534,6 +656,8 @@ impl TypedExpression {
                     name,
                     function_body: body,
                     selector: None, // regular functions cannot be in a contract call; only methods
+                    purity,
+                    trait_placeholder: None,
                 },
                 span,
             },
@@ -542,6 +666,154 @@ impl TypedExpression {
         )
     }
 
+    /// Type checks a call of one of the intrinsics. A `revert` is of a type which is only known
+    /// from where it is used, as it never returns. `require` and `assert` revert if their
+    /// condition is false, as though they were `if condition { } else { revert(code) }`.
+    fn type_check_intrinsic(
+        arguments: TypeCheckArguments<'_, (CallPath, Vec<Expression>)>,
+        span: Span,
+    ) -> CompileResult<TypedExpression> {
+        let TypeCheckArguments {
+            checkee: (name, arguments),
+            namespace,
+            crate_namespace,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            opts,
+            ..
+        } = arguments;
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let intrinsic = match Intrinsic::from_call_path(&name, namespace) {
+            Some(intrinsic) => intrinsic,
+            None => {
+                errors.push(CompileError::Internal(
+                    "Attempted to type check a function call as an intrinsic.",
+                    span,
+                ));
+                return err(warnings, errors);
+            }
+        };
+        let expected = intrinsic.parameter_count();
+        if arguments.len() != expected {
+            let arguments_span = arguments.iter().fold(
+                arguments
+                    .get(0)
+                    .map(|x| x.span())
+                    .unwrap_or_else(|| name.span()),
+                |acc, arg| crate::utils::join_spans(acc, arg.span()),
+            );
+            errors.push(if arguments.len() > expected {
+                CompileError::TooManyArgumentsForFunction {
+                    span: arguments_span,
+                    method_name: name.suffix,
+                    expected,
+                    received: arguments.len(),
+                }
+            } else {
+                CompileError::TooFewArgumentsForFunction {
+                    span: arguments_span,
+                    method_name: name.suffix,
+                    expected,
+                    received: arguments.len(),
+                }
+            });
+            return err(warnings, errors);
+        }
+        let mut arguments = arguments.into_iter();
+        let mut type_check_argument = |argument: Expression, r#type: TypeInfo, help_text| {
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: argument,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(r#type),
+                help_text,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            })
+        };
+        let condition = match intrinsic {
+            Intrinsic::Revert => None,
+            Intrinsic::Require | Intrinsic::Assert => Some(check!(
+                type_check_argument(
+                    arguments.next().unwrap(),
+                    TypeInfo::Boolean,
+                    "The condition of this must be a boolean.",
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            )),
+        };
+        let code = match intrinsic {
+            Intrinsic::Revert | Intrinsic::Require => {
+                let code = check!(
+                    type_check_argument(
+                        arguments.next().unwrap(),
+                        TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+                        "A revert code must be a u64.",
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                // the code has to be known to be listed in the build output
+                match check!(
+                    evaluate_constant(&code, namespace),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                )
+                .expression
+                {
+                    TypedExpressionVariant::Literal(Literal::U64(code)) => code,
+                    _ => {
+                        errors.push(CompileError::NotAConstant { span: code.span });
+                        return err(warnings, errors);
+                    }
+                }
+            }
+            Intrinsic::Assert => ASSERT_REVERT_CODE,
+        };
+        let revert = TypedExpression {
+            expression: TypedExpressionVariant::Revert { code },
+            return_type: insert_type(TypeInfo::Unknown),
+            is_constant: IsConstant::No,
+            span: span.clone(),
+        };
+        let typed_expression = match condition {
+            None => revert,
+            Some(condition) => {
+                let unit = insert_type(TypeInfo::Tuple(vec![]));
+                TypedExpression {
+                    expression: TypedExpressionVariant::IfExp {
+                        condition: Box::new(condition),
+                        then: Box::new(TypedExpression {
+                            expression: TypedExpressionVariant::CodeBlock(TypedCodeBlock {
+                                contents: vec![],
+                                whole_block_span: span.clone(),
+                            }),
+                            return_type: unit,
+                            is_constant: IsConstant::No,
+                            span: span.clone(),
+                        }),
+                        r#else: Some(Box::new(revert)),
+                    },
+                    return_type: unit,
+                    is_constant: IsConstant::No,
+                    span,
+                }
+            }
+        };
+        ok(typed_expression, warnings, errors)
+    }
+
     fn type_check_lazy_operator(
         arguments: TypeCheckArguments<'_, (LazyOp, Expression, Expression)>,
         span: Span,
@@ -789,6 +1061,234 @@ impl TypedExpression {
         ok(exp, warnings, errors)
     }
 
+    /// Checks the branches of a match against the type of its primary expression, and then type
+    /// checks the if statement which the match desugars to. The primary expression is evaluated
+    /// once, into a hidden variable which the if statement matches against:
+    ///
+    /// ```ignore
+    /// {
+    ///     let __match_value = primary_expression;
+    ///     if <__match_value matches the first branch> { .. } else if ..
+    /// }
+    /// ```
+    fn type_check_match_expression(
+        arguments: TypeCheckArguments<'_, (Expression, Vec<MatchBranch>)>,
+        span: Span,
+    ) -> CompileResult<TypedExpression> {
+        let TypeCheckArguments {
+            checkee: (primary_expression, branches),
+            namespace,
+            crate_namespace,
+            return_type_annotation: type_annotation,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            opts,
+            ..
+        } = arguments;
+        let mut warnings = vec![];
+        let mut errors = vec![];
+
+        // the hidden variable is only in scope in the match
+        let mut local_namespace = namespace.clone();
+        let match_value = check!(
+            TypedAstNode::type_check(TypeCheckArguments {
+                checkee: declaration(
+                    hidden_ident(HiddenVariable::MatchValue, &span),
+                    primary_expression,
+                    false
+                ),
+                namespace: &mut local_namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Unknown),
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let exhaustive = match declared_type(&match_value) {
+            Some(scrutinee_type) => check!(
+                check_match_expression(scrutinee_type, &branches, &span),
+                false,
+                warnings,
+                errors
+            ),
+            None => false,
+        };
+        let desugared = check!(
+            desugar_match_expression_with_exhaustiveness(
+                variable_expression(HiddenVariable::MatchValue, &span),
+                branches,
+                exhaustive
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let typed_if = check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: desugared,
+                namespace: &mut local_namespace,
+                crate_namespace,
+                return_type_annotation: type_annotation,
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            error_recovery_expr(span),
+            warnings,
+            errors
+        );
+        let return_type = typed_if.return_type;
+        let contents = vec![
+            match_value,
+            TypedAstNode {
+                span: typed_if.span.clone(),
+                content: TypedAstNodeContent::ImplicitReturnExpression(typed_if),
+            },
+        ];
+        ok(
+            TypedExpression {
+                expression: TypedExpressionVariant::CodeBlock(TypedCodeBlock {
+                    contents,
+                    whole_block_span: span.clone(),
+                }),
+                return_type,
+                is_constant: IsConstant::No,
+                span,
+            },
+            warnings,
+            errors,
+        )
+    }
+
+    /// Checks that the operand of `?` is a result-like enum, and then type checks the expression
+    /// which `?` desugars to. The operand is type checked once, as the declaration of a hidden
+    /// variable, which the desugared expression reads the tag and the contents of.
+    fn type_check_try_expression(
+        arguments: TypeCheckArguments<'_, Expression>,
+        span: Span,
+    ) -> CompileResult<TypedExpression> {
+        let TypeCheckArguments {
+            checkee: expr,
+            namespace,
+            crate_namespace,
+            return_type_annotation: type_annotation,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            opts,
+            ..
+        } = arguments;
+        let mut warnings = vec![];
+        let mut errors = vec![];
+
+        // the hidden variable is only in scope in the desugared expression
+        let mut local_namespace = namespace.clone();
+        let try_value = check!(
+            TypedAstNode::type_check(TypeCheckArguments {
+                checkee: declaration(hidden_ident(HiddenVariable::TryValue, &span), expr, false),
+                namespace: &mut local_namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Unknown),
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let try_value_type = match declared_type(&try_value) {
+            Some(try_value_type) => try_value_type,
+            None => return err(warnings, errors),
+        };
+        let result_like = match ResultLikeEnum::from_type(try_value_type) {
+            Some(result_like) => result_like,
+            None => {
+                if !matches!(look_up_type_id(try_value_type), TypeInfo::ErrorRecovery) {
+                    errors.push(CompileError::TryOnNonResultLikeType {
+                        r#type: look_up_type_id(try_value_type).friendly_type_str(),
+                        span,
+                    });
+                }
+                return err(warnings, errors);
+            }
+        };
+        // the error is returned by naming the enum, which has to be in scope for that
+        let enum_name = match namespace.get_symbol_by_str(&result_like.name) {
+            Some(TypedDeclaration::EnumDeclaration(decl)) => decl.name.clone(),
+            _ => {
+                errors.push(CompileError::SymbolNotFound {
+                    name: result_like.name,
+                    span,
+                });
+                return err(warnings, errors);
+            }
+        };
+        let desugared = result_like.desugar_try_expression(
+            variable_expression(HiddenVariable::TryValue, &span),
+            enum_name,
+            span.clone(),
+        );
+        let typed_desugared = check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: desugared,
+                namespace: &mut local_namespace,
+                crate_namespace,
+                return_type_annotation: type_annotation,
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            error_recovery_expr(span.clone()),
+            warnings,
+            errors
+        );
+        let return_type = typed_desugared.return_type;
+        let contents = vec![
+            try_value,
+            TypedAstNode {
+                span: typed_desugared.span.clone(),
+                content: TypedAstNodeContent::ImplicitReturnExpression(typed_desugared),
+            },
+        ];
+        ok(
+            TypedExpression {
+                expression: TypedExpressionVariant::CodeBlock(TypedCodeBlock {
+                    contents,
+                    whole_block_span: span.clone(),
+                }),
+                return_type,
+                is_constant: IsConstant::No,
+                span,
+            },
+            warnings,
+            errors,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn type_check_asm_expression<'n>(
         asm: AsmExpression,
@@ -805,16 +1305,34 @@ impl TypedExpression {
         let mut errors = vec![];
         let return_type = namespace
             .resolve_type_with_self(asm.return_type.clone(), self_type)
-            .unwrap_or_else(|_| {
-                errors.push(CompileError::UnknownType {
-                    span: asm
-                        .returns
-                        .clone()
-                        .map(|x| x.1)
-                        .unwrap_or_else(|| asm.whole_block_span.clone()),
-                });
+            .unwrap_or_else(|error| {
+                errors.push(
+                    error.into_error(
+                        asm.returns
+                            .clone()
+                            .map(|x| x.1)
+                            .unwrap_or_else(|| asm.whole_block_span.clone()),
+                    ),
+                );
                 insert_type(TypeInfo::ErrorRecovery)
             });
+        for op in &asm.body {
+            let access = op.storage_access();
+            if opts.purity == Purity::Pure && access != Purity::Pure {
+                // functions which access storage only in asm, like those of `std::storage`,
+                // weren't required to declare it before the `#[storage(..)]` attribute, so
+                // until they are migrated this is only a warning
+                warnings.push(CompileWarning {
+                    warning_content: Warning::UndeclaredAsmStorageAccess {
+                        access: access.access_description(),
+                        attribute: access.to_attribute_syntax(),
+                    },
+                    span: op.span.clone(),
+                });
+            } else if let Err(error) = opts.purity.check_access(access, &op.span) {
+                errors.push(error);
+            }
+        }
         // type check the initializers
         let typed_registers = asm
             .registers
@@ -983,6 +1501,37 @@ impl TypedExpression {
         ok(exp, warnings, errors)
     }
 
+    fn type_check_storage_access(
+        field_name: Ident,
+        span: Span,
+        namespace: &Namespace,
+        opts: TCOpts,
+    ) -> CompileResult<TypedExpression> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let field = check!(
+            namespace.get_storage_field(&field_name, &span),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        // a map isn't read as a whole, only its entries are, by its methods
+        if !matches!(look_up_type_id(field.r#type), TypeInfo::StorageMap { .. }) {
+            if let Err(error) = opts.purity.check_access(Purity::Reads, &span) {
+                errors.push(error);
+            }
+        }
+        let exp = TypedExpression {
+            expression: TypedExpressionVariant::StorageAccess {
+                field_name: field_name.clone(),
+            },
+            return_type: field.r#type,
+            is_constant: IsConstant::No,
+            span,
+        };
+        ok(exp, warnings, errors)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn type_check_subfield_expression<'n>(
         prefix: Box<Expression>,
@@ -1140,6 +1689,41 @@ impl TypedExpression {
     ) -> CompileResult<TypedExpression> {
         let mut warnings = vec![];
         let mut errors = vec![];
+        // An associated constant of a trait implementation, like `Self::DECIMALS` or
+        // `MyToken::DECIMALS`, is accessed through the type implementing the trait.
+        if let ([type_name], true) = (&call_path.prefixes[..], args.is_empty()) {
+            let r#type = match type_name.as_str() {
+                "Self" => TypeInfo::SelfType,
+                name => TypeInfo::Custom {
+                    name: name.to_string(),
+                    type_arguments: vec![],
+                },
+            };
+            if let Ok(r#type) = namespace.resolve_type_with_self(r#type, self_type) {
+                match namespace.get_associated_const(&look_up_type_id(r#type), &call_path.suffix) {
+                    Ok(Some(decl)) => {
+                        return ok(
+                            TypedExpression {
+                                span,
+                                ..decl.value.clone()
+                            },
+                            warnings,
+                            errors,
+                        )
+                    }
+                    Ok(None) => (),
+                    Err(traits) => {
+                        errors.push(CompileError::AmbiguousAssociatedItem {
+                            name: call_path.suffix.as_str().to_string(),
+                            ty: r#type.friendly_type_str(),
+                            traits,
+                            span,
+                        });
+                        return err(warnings, errors);
+                    }
+                }
+            }
+        }
         // The first step is to determine if the call path refers to a module or an enum.
         // We could rely on the capitalization convention, where modules are lowercase
         // and enums are uppercase, but this is not robust in the long term.
@@ -1280,15 +1864,30 @@ impl TypedExpression {
             abi_name: abi_name.to_owned_call_path(),
             address: address_str,
         });
+        // the methods of the abis it extends can be called too
         let mut functions_buf = abi
             .interface_surface
             .iter()
-            .map(|x| x.to_dummy_func(Mode::ImplAbiFn))
+            .chain(
+                abi.supertraits
+                    .iter()
+                    .flat_map(|supertrait| supertrait.interface_surface.iter()),
+            )
+            .map(|x| x.to_dummy_func(Mode::ImplAbiFn, &abi_name))
+            .collect::<Vec<_>>();
+        let methods = abi
+            .methods
+            .iter()
+            .chain(
+                abi.supertraits
+                    .iter()
+                    .flat_map(|supertrait| supertrait.methods.iter()),
+            )
             .collect::<Vec<_>>();
         // calls of ABI methods do not result in any codegen of the ABI method block
         // they instead just use the CALL opcode and the return type
-        let mut type_checked_fn_buf = Vec::with_capacity(abi.methods.len());
-        for method in &abi.methods {
+        let mut type_checked_fn_buf = Vec::with_capacity(methods.len());
+        for method in methods {
             type_checked_fn_buf.push(check!(
                 TypedFunctionDeclaration::type_check(TypeCheckArguments {
                     checkee: method.clone(),
@@ -1421,6 +2020,92 @@ impl TypedExpression {
         )
     }
 
+    fn type_check_array_repeat(
+        arguments: TypeCheckArguments<'_, (Expression, Expression)>,
+        span: Span,
+    ) -> CompileResult<TypedExpression> {
+        let TypeCheckArguments {
+            checkee: (value, count),
+            namespace,
+            crate_namespace,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            opts,
+            ..
+        } = arguments;
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+
+        let value_span = value.span();
+        let value = check!(
+            Self::type_check(TypeCheckArguments {
+                checkee: value,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Unknown),
+                help_text: Default::default(),
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let count = check!(
+            Self::type_check(TypeCheckArguments {
+                checkee: count,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::UnsignedInteger(
+                    IntegerBits::SixtyFour
+                )),
+                help_text: "The length of an array must be a u64.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+
+        // the value is copied rather than evaluated once for each element, so it must be a
+        // constant as well as the count
+        let value = check!(
+            evaluate_constant(&value, namespace),
+            error_recovery_expr(value_span),
+            warnings,
+            errors
+        );
+        let count = check!(
+            evaluate_array_length(&count, namespace),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        ok(
+            TypedExpression {
+                return_type: insert_type(TypeInfo::Array(value.return_type, count)),
+                expression: TypedExpressionVariant::Array {
+                    contents: vec![value; count],
+                },
+                is_constant: IsConstant::Yes,
+                span,
+            },
+            warnings,
+            errors,
+        )
+    }
+
     fn type_check_array_index(
         arguments: TypeCheckArguments<'_, (Expression, Expression)>,
         span: Span,
@@ -1632,52 +2317,19 @@ impl TypedExpression {
                     warnings,
                     errors
                 );
-                let enum_name = call_path.prefixes.first().unwrap().clone();
+                let enum_name = call_path.prefixes.last().unwrap().clone();
                 let variant_name = call_path.suffix.clone();
-                let enum_module_combined_result = {
-                    let (module_path, enum_name) =
-                        call_path.prefixes.split_at(call_path.prefixes.len() - 1);
-                    let enum_name = enum_name[0].clone();
-                    let namespace = namespace.find_module_relative(module_path);
-                    let namespace = namespace.ok(&mut warnings, &mut errors);
-                    namespace.map(|ns| ns.find_enum(&enum_name)).flatten()
-                };
-                let mut return_type = None;
-                let mut owned_enum_variant = None;
-                match enum_module_combined_result {
-                    None => todo!(),
-                    Some(enum_decl) => {
-                        if enum_name != enum_decl.name {
-                            errors.push(CompileError::MatchWrongType {
-                                expected: parent.return_type,
-                                span: enum_name.span().clone(),
-                            });
-                            let exp = error_recovery_expr(span);
-                            return ok(exp, warnings, errors);
-                        }
-                        for (pos, variant) in enum_decl.variants.into_iter().enumerate() {
-                            match (pos == arg_num, variant.name == variant_name) {
-                                (true, true) => {
-                                    return_type = Some(variant.r#type);
-                                    owned_enum_variant = Some(variant);
-                                }
-                                (true, false) => {
-                                    errors.push(CompileError::MatchWrongType {
-                                        expected: parent.return_type,
-                                        span: variant_name.span().clone(),
-                                    });
-                                    let exp = error_recovery_expr(span);
-                                    return ok(exp, warnings, errors);
-                                }
-                                _ => (),
-                            }
-                        }
-                    }
-                }
-                let (return_type, _owned_enum_variant) = match (return_type, owned_enum_variant) {
-                    (Some(return_type), Some(owned_enum_variant)) => {
-                        (return_type, owned_enum_variant)
-                    }
+                // the variant is looked up in the type of the value, rather than in the
+                // declaration of the enum, so that the types of generic variants are those the
+                // value was instantiated with
+                let variant = match look_up_type_id(parent.return_type) {
+                    TypeInfo::Enum {
+                        name,
+                        variant_types,
+                    } if name == enum_name.as_str() => variant_types
+                        .into_iter()
+                        .find(|variant| variant.name == variant_name.as_str()),
+                    TypeInfo::ErrorRecovery => return err(warnings, errors),
                     _ => {
                         errors.push(CompileError::MatchWrongType {
                             expected: parent.return_type,
@@ -1687,15 +2339,111 @@ impl TypedExpression {
                         return ok(exp, warnings, errors);
                     }
                 };
+                let variant = match variant {
+                    Some(variant) => variant,
+                    None => {
+                        errors.push(CompileError::MatchWrongType {
+                            expected: parent.return_type,
+                            span: variant_name.span().clone(),
+                        });
+                        let exp = error_recovery_expr(span);
+                        return ok(exp, warnings, errors);
+                    }
+                };
 
                 let exp = TypedExpression {
                     expression: TypedExpressionVariant::EnumArgAccess {
                         resolved_type_of_parent: parent.return_type,
                         prefix: Box::new(parent),
-                        //variant_to_access: owned_enum_variant.to_owned(),
+                        tag: variant.tag,
                         arg_num_to_access: arg_num,
                     },
-                    return_type,
+                    return_type: variant.r#type,
+                    is_constant: IsConstant::No,
+                    span,
+                };
+                ok(exp, warnings, errors)
+            }
+            DelayedResolutionVariant::EnumTag(DelayedEnumTagResolution { exp }) => {
+                let parent = check!(
+                    TypedExpression::type_check(TypeCheckArguments {
+                        checkee: *exp,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: insert_type(TypeInfo::Unknown),
+                        help_text: "",
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                match look_up_type_id(parent.return_type) {
+                    TypeInfo::Enum { .. } => (),
+                    TypeInfo::ErrorRecovery => return err(warnings, errors),
+                    _ => {
+                        errors.push(CompileError::Internal(
+                            "Only the tag of an enum can be read.",
+                            span,
+                        ));
+                        return err(warnings, errors);
+                    }
+                }
+                let exp = TypedExpression {
+                    expression: TypedExpressionVariant::EnumTag {
+                        exp: Box::new(parent),
+                    },
+                    return_type: insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour)),
+                    is_constant: IsConstant::No,
+                    span,
+                };
+                ok(exp, warnings, errors)
+            }
+            DelayedResolutionVariant::StructName(DelayedStructNameResolution {
+                exp,
+                struct_name,
+            }) => {
+                let parent = check!(
+                    TypedExpression::type_check(TypeCheckArguments {
+                        checkee: *exp,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: insert_type(TypeInfo::Unknown),
+                        help_text: "",
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        dependency_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                match look_up_type_id(parent.return_type) {
+                    TypeInfo::Struct { name, .. } if name == struct_name.as_str() => (),
+                    TypeInfo::ErrorRecovery => (),
+                    actually => errors.push(CompileError::DestructuredWrongType {
+                        struct_name: struct_name.as_str().to_string(),
+                        actually: actually.friendly_type_str(),
+                        span: struct_name.span().clone(),
+                    }),
+                }
+                let exp = TypedExpression {
+                    expression: TypedExpressionVariant::CodeBlock(TypedCodeBlock {
+                        contents: vec![TypedAstNode {
+                            span: parent.span.clone(),
+                            content: TypedAstNodeContent::Expression(parent),
+                        }],
+                        whole_block_span: span.clone(),
+                    }),
+                    return_type: insert_type(TypeInfo::Tuple(Vec::new())),
                     is_constant: IsConstant::No,
                     span,
                 };
@@ -1798,6 +2546,9 @@ mod tests {
             manifest_path: Arc::new("".into()),
             print_intermediate_asm: false,
             print_finalized_asm: false,
+            use_ir: false,
+            print_ir: false,
+            optimization_passes: crate::OptimizationPass::all(),
         };
         let mut dead_code_graph: ControlFlowGraph = Default::default();
         let mut dependency_graph = HashMap::new();
@@ -64,19 +64,44 @@ pub(crate) fn type_check_method_application<'n>(
                     .unwrap_or_else(|| insert_type(TypeInfo::Unknown)),
             };
             let from_module = if is_absolute { crate_namespace } else { None };
-            check!(
-                namespace.find_method_for_type(
-                    ty,
-                    &call_path.suffix,
-                    &call_path.prefixes[..],
-                    from_module,
-                    self_type,
-                    &args_buf,
+            // an operator applied to a type which isn't built in dispatches to the type's
+            // implementation of the operator's trait
+            match overloadable_operator(&call_path.prefixes, &call_path.suffix) {
+                Some(operator)
+                    if is_absolute
+                        && type_name.is_none()
+                        && dispatches_to_operator_trait(&crate::type_engine::look_up_type_id(
+                            ty,
+                        )) =>
+                {
+                    check!(
+                        namespace.find_operator_method_for_type(
+                            ty,
+                            &call_path.suffix,
+                            &call_path.prefixes[..],
+                            operator,
+                            from_module,
+                            &args_buf,
+                        ),
+                        return err(warnings, errors),
+                        warnings,
+                        errors
+                    )
+                }
+                _ => check!(
+                    namespace.find_method_for_type(
+                        ty,
+                        &call_path.suffix,
+                        &call_path.prefixes[..],
+                        from_module,
+                        self_type,
+                        &args_buf,
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
                 ),
-                return err(warnings, errors),
-                warnings,
-                errors
-            )
+            }
         }
         MethodName::FromModule { ref method_name } => {
             let ty = args_buf
@@ -91,9 +116,39 @@ pub(crate) fn type_check_method_application<'n>(
             )
         }
     };
+    // methods of generic impl blocks are generic over the impl's type parameters, so they get
+    // fresh copies of their generic types for this call
+    let (mut method, type_mapping) = if method.type_parameters.is_empty() {
+        (method, vec![])
+    } else {
+        check!(
+            method.monomorphize(vec![], self_type),
+            return err(warnings, errors),
+            warnings,
+            errors
+        )
+    };
+    // the entries of a `StorageMap` are found through the key of the storage field which holds it
+    if let Some(receiver) = args_buf.get(0) {
+        if matches!(
+            crate::type_engine::look_up_type_id(receiver.return_type),
+            TypeInfo::StorageMap { .. }
+        ) && !matches!(
+            receiver.expression,
+            TypedExpressionVariant::StorageAccess { .. }
+        ) {
+            errors.push(CompileError::StorageMapOutsideOfStorage {
+                span: receiver.span.clone(),
+            });
+        }
+    }
+    // a call to another contract accesses its storage, not that of the caller
     let contract_caller = if method.is_contract_call {
         args_buf.pop_front()
     } else {
+        if let Err(error) = opts.purity.check_access(method.purity, &span) {
+            errors.push(error);
+        }
         None
     };
 
@@ -119,6 +174,20 @@ pub(crate) fn type_check_method_application<'n>(
         }
         // The annotation may result in a cast, which is handled in the type engine.
     }
+    // now that the arguments have been unified, the types the impl block was instantiated with
+    // must satisfy its `where` clause, and the methods of those traits are called on them
+    check!(
+        namespace.check_trait_constraints(&type_mapping, &span),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    check!(
+        dispatch_trait_methods(&mut method.body, &type_mapping, namespace, self_type),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
     let exp = match method_name {
         // something like a.b(c)
         MethodName::FromModule { method_name } => {
@@ -155,6 +224,8 @@ pub(crate) fn type_check_method_application<'n>(
                     },
                     arguments: args_and_names,
                     function_body: method.body.clone(),
+                    purity: method.purity,
+                    trait_placeholder: method.trait_placeholder.clone(),
                     selector: if method.is_contract_call {
                         let contract_address = match contract_caller
                             .map(|x| crate::type_engine::look_up_type_id(x.return_type))
@@ -232,6 +303,8 @@ pub(crate) fn type_check_method_application<'n>(
                     name: call_path.clone(),
                     arguments: args_and_names,
                     function_body: method.body.clone(),
+                    purity: method.purity,
+                    trait_placeholder: method.trait_placeholder.clone(),
                     selector: if method.is_contract_call {
                         let contract_address = match contract_caller
                             .map(|x| crate::type_engine::look_up_type_id(x.return_type))
@@ -1,5 +1,5 @@
 use super::*;
-use crate::parse_tree::AsmOp;
+use crate::parse_tree::{AsmOp, Purity};
 use crate::semantic_analysis::ast_node::*;
 use crate::type_engine::*;
 use crate::Ident;
@@ -20,6 +20,11 @@ pub(crate) enum TypedExpressionVariant {
         /// If this is `Some(val)` then `val` is the metadata. If this is `None`, then
         /// there is no selector.
         selector: Option<ContractCallMetadata>,
+        /// The storage access the function is declared with.
+        purity: Purity,
+        /// If this is a call to a placeholder for a method of a trait, the trait. See
+        /// `TypedTraitFn::to_dummy_func`.
+        trait_placeholder: Option<CallPath>,
     },
     LazyOperator {
         op: LazyOp,
@@ -67,10 +72,16 @@ pub(crate) enum TypedExpressionVariant {
     },
     EnumArgAccess {
         prefix: Box<TypedExpression>,
-        //variant_to_access: TypedEnumVariant,
+        /// The tag of the variant whose contents are accessed.
+        tag: usize,
         arg_num_to_access: usize,
         resolved_type_of_parent: TypeId,
     },
+    /// The tag of the enum `exp` evaluates to, which is the position of its variant in the
+    /// declaration of the enum.
+    EnumTag {
+        exp: Box<TypedExpression>,
+    },
     TupleElemAccess {
         prefix: Box<TypedExpression>,
         elem_to_access_num: usize,
@@ -92,6 +103,19 @@ pub(crate) enum TypedExpressionVariant {
         // this span may be used for errors in the future, although it is not right now.
         span: Span,
     },
+    /// A read of a whole field of contract storage, `storage.field_name`.
+    StorageAccess {
+        field_name: Ident,
+    },
+    /// The body of one of the methods of `StorageMap`, which are provided by the compiler.
+    StorageMapMethod(StorageMapMethod),
+    /// The body of one of the operator methods of the signed integer types, which are provided by
+    /// the compiler.
+    SignedIntegerOp(SignedIntegerOp),
+    /// A revert with a `code` which is known at compile time, by one of the intrinsics.
+    Revert {
+        code: u64,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -118,6 +142,10 @@ impl TypedExpressionVariant {
                     Literal::U16(content) => content.to_string(),
                     Literal::U32(content) => content.to_string(),
                     Literal::U64(content) => content.to_string(),
+                    Literal::I8(content) => content.to_string(),
+                    Literal::I16(content) => content.to_string(),
+                    Literal::I32(content) => content.to_string(),
+                    Literal::I64(content) => content.to_string(),
                     Literal::String(content) => content.as_str().to_string(),
                     Literal::Boolean(content) => content.to_string(),
                     Literal::Byte(content) => content.to_string(),
@@ -177,6 +205,12 @@ impl TypedExpressionVariant {
                     arg_num_to_access
                 )
             }
+            TypedExpressionVariant::EnumTag { exp } => {
+                format!(
+                    "\"{}\" enum tag",
+                    look_up_type_id(exp.return_type).friendly_type_str()
+                )
+            }
             TypedExpressionVariant::TupleElemAccess {
                 resolved_type_of_parent,
                 elem_to_access_num,
@@ -191,6 +225,17 @@ impl TypedExpressionVariant {
             TypedExpressionVariant::VariableExpression { name, .. } => {
                 format!("\"{}\" variable exp", name.as_str())
             }
+            TypedExpressionVariant::StorageAccess { field_name } => {
+                format!("\"storage.{}\" storage access", field_name.as_str())
+            }
+            TypedExpressionVariant::StorageMapMethod(method) => match method {
+                StorageMapMethod::Get { .. } => "storage map get".into(),
+                StorageMapMethod::Insert { .. } => "storage map insert".into(),
+            },
+            TypedExpressionVariant::SignedIntegerOp(SignedIntegerOp { op, .. }) => {
+                format!("\"{}\" signed integer op", op.method_name())
+            }
+            TypedExpressionVariant::Revert { code } => format!("revert {}", code),
             TypedExpressionVariant::EnumInstantiation {
                 tag,
                 enum_decl,
@@ -225,7 +270,7 @@ impl TypedExpressionVariant {
                 (*lhs).copy_types(type_mapping);
                 (*rhs).copy_types(type_mapping);
             }
-            VariableExpression { .. } => (),
+            VariableExpression { .. } | StorageAccess { .. } => (),
             Tuple { fields } => fields.iter_mut().for_each(|x| x.copy_types(type_mapping)),
             Array { contents } => contents.iter_mut().for_each(|x| x.copy_types(type_mapping)),
             ArrayIndex { prefix, index } => {
@@ -292,6 +337,7 @@ impl TypedExpressionVariant {
 
                 prefix.copy_types(type_mapping);
             }
+            EnumTag { exp } => exp.copy_types(type_mapping),
             TupleElemAccess {
                 prefix,
                 ref mut resolved_type_of_parent,
@@ -318,6 +364,9 @@ impl TypedExpressionVariant {
                 };
             }
             AbiCast { address, .. } => address.copy_types(type_mapping),
+            StorageMapMethod(method) => method.copy_types(type_mapping),
+            SignedIntegerOp(op) => op.copy_types(type_mapping),
+            Revert { .. } => (),
         }
     }
 }
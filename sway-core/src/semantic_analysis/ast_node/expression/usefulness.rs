@@ -0,0 +1,404 @@
+//! Exhaustiveness and reachability checking of match expressions, using the usefulness algorithm
+//! from Maranget's "Warnings for pattern matching". A pattern is _useful_ with respect to a list
+//! of patterns if it matches some value which none of them do. An arm is unreachable if its
+//! pattern isn't useful with respect to the arms before it, and a match is exhaustive if a
+//! catch-all pattern isn't useful with respect to all of its arms.
+
+use crate::error::*;
+use crate::parse_tree::{Literal, MatchBranch, MatchCondition, Scrutinee, StructScrutineeField};
+use crate::span::Span;
+use crate::type_engine::{look_up_type_id, TypeId, TypeInfo};
+
+/// The most example patterns reported as missing from a match which isn't exhaustive.
+const MAX_MISSING_PATTERNS: usize = 3;
+
+/// A pattern, deconstructed into the constructor of the values it matches and the patterns of
+/// their fields.
+#[derive(Clone, Debug)]
+enum Pattern {
+    Wildcard,
+    Constructor(Constructor, Vec<Pattern>),
+    Or(Vec<Pattern>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Constructor {
+    /// The only constructor of tuples and structs.
+    Single,
+    /// The variant of an enum with this tag, which has a single field.
+    Variant(usize),
+    /// A literal, which has no fields.
+    Literal(Literal),
+    /// A range of literals, which includes both ends and has no fields. Ranges are only compared
+    /// to each other and to literals by equality, so they can make an arm seem reachable when it
+    /// isn't, but never make a match seem exhaustive when it isn't.
+    Range(Literal, Literal),
+}
+
+/// Checks that the `branches` of a match on a value of type `scrutinee_type` cover every value,
+/// and warns about branches which are unreachable. Returns whether the match is exhaustive.
+///
+/// Branches with a guard aren't taken to cover anything, as the guard may not hold. Patterns which
+/// don't fit `scrutinee_type` are left for type checking to report, and the match is taken not to
+/// be exhaustive.
+pub(crate) fn check_match_expression(
+    scrutinee_type: TypeId,
+    branches: &[MatchBranch],
+    span: &Span,
+) -> CompileResult<bool> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut rows: Vec<Vec<Pattern>> = vec![];
+    for branch in branches {
+        let pattern = match &branch.condition {
+            MatchCondition::CatchAll(_) => Some(Pattern::Wildcard),
+            MatchCondition::Scrutinee(scrutinee) => to_pattern(scrutinee, scrutinee_type),
+        };
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => return ok(false, warnings, errors),
+        };
+        let row = vec![pattern];
+        if useful(&rows, &row, &[scrutinee_type]).is_empty() {
+            warnings.push(CompileWarning {
+                span: branch.span.clone(),
+                warning_content: Warning::UnreachableMatchArm,
+            });
+        }
+        if branch.guard.is_none() {
+            rows.push(row);
+        }
+    }
+    let missing = useful(&rows, &[Pattern::Wildcard], &[scrutinee_type]);
+    if !missing.is_empty() {
+        errors.push(CompileError::MatchNonExhaustive {
+            missing_patterns: missing
+                .iter()
+                .map(|witness| display(&witness[0], scrutinee_type))
+                .collect::<Vec<_>>()
+                .join(", "),
+            span: span.clone(),
+        });
+    }
+    ok(missing.is_empty(), warnings, errors)
+}
+
+/// Deconstructs `scrutinee`, or returns `None` if it doesn't fit `r#type`.
+fn to_pattern(scrutinee: &Scrutinee, r#type: TypeId) -> Option<Pattern> {
+    let type_info = look_up_type_id(r#type);
+    Some(match (scrutinee, type_info) {
+        (Scrutinee::Variable { .. }, _) | (Scrutinee::Unit { .. }, _) => Pattern::Wildcard,
+        (Scrutinee::Literal { value, .. }, _) => {
+            Pattern::Constructor(Constructor::Literal(value.clone()), vec![])
+        }
+        (Scrutinee::Range { start, end, .. }, _) => {
+            Pattern::Constructor(Constructor::Range(start.clone(), end.clone()), vec![])
+        }
+        (Scrutinee::Or { alternatives, .. }, _) => Pattern::Or(
+            alternatives
+                .iter()
+                .map(|alternative| to_pattern(alternative, r#type))
+                .collect::<Option<_>>()?,
+        ),
+        (Scrutinee::Tuple { elems, .. }, TypeInfo::Tuple(elem_types))
+            if elems.len() == elem_types.len() =>
+        {
+            let elems = elems
+                .iter()
+                .zip(elem_types)
+                .map(|(elem, elem_type)| to_pattern(elem, elem_type))
+                .collect::<Option<_>>()?;
+            Pattern::Constructor(Constructor::Single, elems)
+        }
+        (
+            Scrutinee::StructScrutinee {
+                struct_name,
+                fields,
+                ..
+            },
+            TypeInfo::Struct {
+                name,
+                fields: field_types,
+            },
+        ) if struct_name.as_str() == name => {
+            // fields which are left out, or only bound to a variable, match anything
+            let fields = field_types
+                .iter()
+                .map(|field_type| {
+                    let field = fields
+                        .iter()
+                        .find(|field| field.field.as_str() == field_type.name);
+                    match field {
+                        Some(StructScrutineeField {
+                            scrutinee: Some(scrutinee),
+                            ..
+                        }) => to_pattern(scrutinee, field_type.r#type),
+                        _ => Some(Pattern::Wildcard),
+                    }
+                })
+                .collect::<Option<_>>()?;
+            Pattern::Constructor(Constructor::Single, fields)
+        }
+        (
+            Scrutinee::EnumScrutinee {
+                call_path, args, ..
+            },
+            TypeInfo::Enum { variant_types, .. },
+        ) => {
+            let variant = variant_types
+                .iter()
+                .find(|variant| variant.name == call_path.suffix.as_str())?;
+            let field = match &args[..] {
+                [] => Pattern::Wildcard,
+                [arg] => to_pattern(arg, variant.r#type)?,
+                _ => return None,
+            };
+            Pattern::Constructor(Constructor::Variant(variant.tag), vec![field])
+        }
+        _ => return None,
+    })
+}
+
+/// All of the constructors of values of `r#type`, or `None` if there are too many to list.
+fn all_constructors(r#type: TypeId) -> Option<Vec<Constructor>> {
+    match look_up_type_id(r#type) {
+        TypeInfo::Tuple(_) | TypeInfo::Struct { .. } => Some(vec![Constructor::Single]),
+        TypeInfo::Enum { variant_types, .. } => Some(
+            variant_types
+                .iter()
+                .map(|variant| Constructor::Variant(variant.tag))
+                .collect(),
+        ),
+        TypeInfo::Boolean => Some(vec![
+            Constructor::Literal(Literal::Boolean(true)),
+            Constructor::Literal(Literal::Boolean(false)),
+        ]),
+        _ => None,
+    }
+}
+
+/// The types of the fields of values of `r#type` which are built by `constructor`.
+fn field_types(r#type: TypeId, constructor: &Constructor) -> Vec<TypeId> {
+    match (look_up_type_id(r#type), constructor) {
+        (TypeInfo::Tuple(elem_types), Constructor::Single) => elem_types,
+        (TypeInfo::Struct { fields, .. }, Constructor::Single) => {
+            fields.iter().map(|field| field.r#type).collect()
+        }
+        (TypeInfo::Enum { variant_types, .. }, Constructor::Variant(tag)) => variant_types
+            .iter()
+            .filter(|variant| variant.tag == *tag)
+            .map(|variant| variant.r#type)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns examples of the values which `row` matches, but none of the rows of `matrix` do. The
+/// columns of both have the types `types`, and the examples are rows of patterns themselves.
+fn useful(matrix: &[Vec<Pattern>], row: &[Pattern], types: &[TypeId]) -> Vec<Vec<Pattern>> {
+    let (head, tail) = match row.split_first() {
+        Some(split) => split,
+        None if matrix.is_empty() => return vec![vec![]],
+        None => return vec![],
+    };
+    let head_type = types[0];
+    if let Pattern::Or(alternatives) = head {
+        let mut witnesses = alternatives
+            .iter()
+            .flat_map(|alternative| {
+                let mut row = vec![alternative.clone()];
+                row.extend_from_slice(tail);
+                useful(matrix, &row, types)
+            })
+            .collect::<Vec<_>>();
+        witnesses.truncate(MAX_MISSING_PATTERNS);
+        return witnesses;
+    }
+    let matrix = &expand_or_patterns(matrix);
+    let mut witnesses = match head {
+        Pattern::Constructor(constructor, _) => useful_specialized(matrix, row, types, constructor),
+        Pattern::Wildcard => {
+            let used = matrix
+                .iter()
+                .filter_map(|matrix_row| match &matrix_row[0] {
+                    Pattern::Constructor(constructor, _) => Some(constructor.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let all = all_constructors(head_type);
+            match all {
+                // every constructor is matched by some row, so each has to be checked in turn
+                Some(all) if all.iter().all(|constructor| used.contains(constructor)) => all
+                    .iter()
+                    .flat_map(|constructor| useful_specialized(matrix, row, types, constructor))
+                    .collect(),
+                // otherwise only the rows which match anything in this column can cover the rest
+                all => {
+                    let default = matrix
+                        .iter()
+                        .filter(|matrix_row| matches!(matrix_row[0], Pattern::Wildcard))
+                        .map(|matrix_row| matrix_row[1..].to_vec())
+                        .collect::<Vec<_>>();
+                    let tail_witnesses = useful(&default, tail, &types[1..]);
+                    let heads = match all {
+                        Some(all) if !used.is_empty() => all
+                            .into_iter()
+                            .filter(|constructor| !used.contains(constructor))
+                            .map(|constructor| {
+                                let arity = field_types(head_type, &constructor).len();
+                                Pattern::Constructor(constructor, vec![Pattern::Wildcard; arity])
+                            })
+                            .collect(),
+                        _ => vec![Pattern::Wildcard],
+                    };
+                    tail_witnesses
+                        .iter()
+                        .flat_map(|tail| {
+                            heads.iter().map(move |head| {
+                                let mut witness = vec![head.clone()];
+                                witness.extend(tail.iter().cloned());
+                                witness
+                            })
+                        })
+                        .collect()
+                }
+            }
+        }
+    };
+    witnesses.truncate(MAX_MISSING_PATTERNS);
+    witnesses
+}
+
+/// Replaces each row of `matrix` which starts with an or-pattern with a row for each of its
+/// alternatives.
+fn expand_or_patterns(matrix: &[Vec<Pattern>]) -> Vec<Vec<Pattern>> {
+    let mut expanded = vec![];
+    for row in matrix {
+        match &row[0] {
+            Pattern::Or(alternatives) => {
+                let alternative_rows = alternatives
+                    .iter()
+                    .map(|alternative| {
+                        let mut alternative_row = vec![alternative.clone()];
+                        alternative_row.extend_from_slice(&row[1..]);
+                        alternative_row
+                    })
+                    .collect::<Vec<_>>();
+                // alternatives can be or-patterns themselves
+                expanded.append(&mut expand_or_patterns(&alternative_rows));
+            }
+            _ => expanded.push(row.clone()),
+        }
+    }
+    expanded
+}
+
+/// Checks the usefulness of `row` among only the values built by `constructor`, whose fields
+/// take the place of the first column.
+fn useful_specialized(
+    matrix: &[Vec<Pattern>],
+    row: &[Pattern],
+    types: &[TypeId],
+    constructor: &Constructor,
+) -> Vec<Vec<Pattern>> {
+    let mut specialized_types = field_types(types[0], constructor);
+    let arity = specialized_types.len();
+    specialized_types.extend_from_slice(&types[1..]);
+    let specialized_matrix = matrix
+        .iter()
+        .filter_map(|matrix_row| specialize(matrix_row, constructor, arity))
+        .collect::<Vec<_>>();
+    let specialized_row = match specialize(row, constructor, arity) {
+        Some(specialized_row) => specialized_row,
+        None => return vec![],
+    };
+    useful(&specialized_matrix, &specialized_row, &specialized_types)
+        .into_iter()
+        .map(|mut witness| {
+            let tail = witness.split_off(arity);
+            let mut witness = vec![Pattern::Constructor(constructor.clone(), witness)];
+            witness.extend(tail);
+            witness
+        })
+        .collect()
+}
+
+/// Replaces the first pattern of `row` with its fields if it matches values built by
+/// `constructor`, or returns `None` if it doesn't.
+fn specialize(row: &[Pattern], constructor: &Constructor, arity: usize) -> Option<Vec<Pattern>> {
+    let mut specialized = match &row[0] {
+        Pattern::Wildcard => vec![Pattern::Wildcard; arity],
+        Pattern::Constructor(other, fields) if other == constructor => fields.clone(),
+        Pattern::Constructor(..) => return None,
+        Pattern::Or(_) => unreachable!("or-patterns are expanded before specializing"),
+    };
+    specialized.extend_from_slice(&row[1..]);
+    Some(specialized)
+}
+
+/// Writes `pattern` as it would be written in a match arm.
+fn display(pattern: &Pattern, r#type: TypeId) -> String {
+    let (constructor, fields) = match pattern {
+        Pattern::Constructor(constructor, fields) => (constructor, fields),
+        // or-patterns are only ever matched against, never reported
+        Pattern::Wildcard | Pattern::Or(_) => return "_".into(),
+    };
+    match (look_up_type_id(r#type), constructor) {
+        (TypeInfo::Tuple(elem_types), Constructor::Single) => format!(
+            "({})",
+            fields
+                .iter()
+                .zip(elem_types)
+                .map(|(field, elem_type)| display(field, elem_type))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (
+            TypeInfo::Struct {
+                name,
+                fields: field_types,
+            },
+            Constructor::Single,
+        ) => format!(
+            "{} {{ {} }}",
+            name,
+            fields
+                .iter()
+                .zip(field_types)
+                .map(|(field, field_type)| format!(
+                    "{}: {}",
+                    field_type.name,
+                    display(field, field_type.r#type)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (
+            TypeInfo::Enum {
+                name,
+                variant_types,
+            },
+            Constructor::Variant(tag),
+        ) => {
+            let variant = variant_types
+                .iter()
+                .find(|variant| variant.tag == *tag)
+                .expect("the tag was taken from the enum");
+            // variants which hold nothing are written without arguments
+            match look_up_type_id(variant.r#type) {
+                TypeInfo::Tuple(elem_types) if elem_types.is_empty() => {
+                    format!("{}::{}", name, variant.name)
+                }
+                _ => format!(
+                    "{}::{}({})",
+                    name,
+                    variant.name,
+                    display(&fields[0], variant.r#type)
+                ),
+            }
+        }
+        // only booleans are listed by their literals
+        (_, Constructor::Literal(Literal::Boolean(value))) => value.to_string(),
+        _ => "_".into(),
+    }
+}
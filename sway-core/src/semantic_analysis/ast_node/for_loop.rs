@@ -0,0 +1,293 @@
+use super::{
+    TypedAstNode, TypedAstNodeContent, TypedDeclaration, TypedVariableDeclaration, TypedWhileLoop,
+};
+use crate::error::*;
+use crate::parse_tree::{
+    Declaration, Expression, ForLoop, ForLoopIterable, Literal, Op, OpVariant, Reassignment,
+    VariableDeclaration,
+};
+use crate::semantic_analysis::{ast_node::Mode, Namespace, TypeCheckArguments};
+use crate::span::Span;
+use crate::type_engine::{look_up_type_id, IntegerBits, TypeId, TypeInfo};
+use crate::{hidden_ident, AstNode, AstNodeContent, CodeBlock, HiddenVariable, Ident, WhileLoop};
+
+/// A for loop, lowered to the declarations of the state it needs followed by a while loop which
+/// steps through it.
+#[derive(Clone, Debug)]
+pub(crate) struct TypedForLoop {
+    /// Declares the counter, along with the array or the end of the range being iterated over, so
+    /// that they are only evaluated once.
+    pub(crate) setup: Vec<TypedAstNode>,
+    pub(crate) r#loop: TypedWhileLoop,
+}
+
+impl TypedForLoop {
+    /// Checks `for x in iterable { body }` as if it were
+    ///
+    /// ```ignore
+    /// let mut __for_counter = start;
+    /// let __for_end = end;
+    /// while __for_counter < __for_end {
+    ///     let x = __for_counter;
+    ///     __for_counter = __for_counter + 1;
+    ///     body
+    /// }
+    /// ```
+    ///
+    /// for a range, or with `x` bound to `__for_array[__for_counter]` for an array, whose length is
+    /// the end. The counter is stepped before the body so that nothing in the body can skip it.
+    pub(crate) fn type_check(
+        arguments: TypeCheckArguments<'_, ForLoop>,
+        span: Span,
+    ) -> CompileResult<TypedForLoop> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let TypeCheckArguments {
+            checkee:
+                ForLoop {
+                    variable,
+                    iterable,
+                    body,
+                },
+            namespace,
+            crate_namespace,
+            return_type_annotation,
+            help_text,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            opts,
+            ..
+        } = arguments;
+
+        // the hidden variables are only in scope in the loop
+        let mut local_namespace = namespace.clone();
+        let mut type_check_node = |node: AstNode, namespace: &mut Namespace| {
+            TypedAstNode::type_check(TypeCheckArguments {
+                checkee: node,
+                namespace,
+                crate_namespace,
+                return_type_annotation,
+                help_text,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            })
+        };
+
+        let mut setup = vec![];
+        let (end, element, counter_type) = match iterable {
+            ForLoopIterable::Range { start, end } => {
+                let start_span = start.span();
+                let counter = check!(
+                    type_check_node(
+                        declaration(hidden_ident(HiddenVariable::ForCounter, &span), start, true),
+                        &mut local_namespace
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let counter_type = match declared_type(&counter).map(look_up_type_id) {
+                    Some(TypeInfo::UnsignedInteger(bits)) => (false, bits),
+                    Some(TypeInfo::SignedInteger(bits)) => (true, bits),
+                    Some(TypeInfo::ErrorRecovery) => return err(warnings, errors),
+                    ty => {
+                        errors.push(CompileError::NotIterable {
+                            r#type: format!(
+                                "a range of {}",
+                                ty.map(|ty| ty.friendly_type_str())
+                                    .unwrap_or_else(|| "unknown".into())
+                            ),
+                            span: start_span,
+                        });
+                        return err(warnings, errors);
+                    }
+                };
+                setup.push(counter);
+                setup.push(check!(
+                    type_check_node(
+                        declaration(hidden_ident(HiddenVariable::ForEnd, &span), end, false),
+                        &mut local_namespace
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ));
+                (
+                    variable_expression(HiddenVariable::ForEnd, &span),
+                    counter_expression(&span),
+                    counter_type,
+                )
+            }
+            ForLoopIterable::Array(array) => {
+                let array_span = array.span();
+                let array = check!(
+                    type_check_node(
+                        declaration(hidden_ident(HiddenVariable::ForArray, &span), array, false),
+                        &mut local_namespace
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let length = match declared_type(&array).map(look_up_type_id) {
+                    Some(TypeInfo::Array(_, length)) => length,
+                    Some(TypeInfo::ErrorRecovery) => return err(warnings, errors),
+                    ty => {
+                        errors.push(CompileError::NotIterable {
+                            r#type: ty
+                                .map(|ty| ty.friendly_type_str())
+                                .unwrap_or_else(|| "unknown".into()),
+                            span: array_span,
+                        });
+                        return err(warnings, errors);
+                    }
+                };
+                setup.push(array);
+                setup.push(check!(
+                    type_check_node(
+                        declaration(
+                            hidden_ident(HiddenVariable::ForCounter, &span),
+                            Expression::Literal {
+                                value: Literal::U64(0),
+                                span: span.clone(),
+                            },
+                            true
+                        ),
+                        &mut local_namespace
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ));
+                (
+                    Expression::Literal {
+                        value: Literal::U64(length as u64),
+                        span: span.clone(),
+                    },
+                    Expression::ArrayIndex {
+                        prefix: Box::new(variable_expression(HiddenVariable::ForArray, &span)),
+                        index: Box::new(counter_expression(&span)),
+                        span: span.clone(),
+                    },
+                    (false, IntegerBits::SixtyFour),
+                )
+            }
+        };
+
+        let one = match counter_type {
+            (false, IntegerBits::Eight) => Literal::U8(1),
+            (false, IntegerBits::Sixteen) => Literal::U16(1),
+            (false, IntegerBits::ThirtyTwo) => Literal::U32(1),
+            (false, IntegerBits::SixtyFour) => Literal::U64(1),
+            (true, IntegerBits::Eight) => Literal::I8(1),
+            (true, IntegerBits::Sixteen) => Literal::I16(1),
+            (true, IntegerBits::ThirtyTwo) => Literal::I32(1),
+            (true, IntegerBits::SixtyFour) => Literal::I64(1),
+        };
+        let step = AstNode {
+            content: AstNodeContent::Declaration(Declaration::Reassignment(Reassignment {
+                lhs: Box::new(counter_expression(&span)),
+                rhs: Expression::core_ops(
+                    Op {
+                        span: span.clone(),
+                        op_variant: OpVariant::Add,
+                    },
+                    vec![
+                        counter_expression(&span),
+                        Expression::Literal {
+                            value: one,
+                            span: span.clone(),
+                        },
+                    ],
+                    span.clone(),
+                ),
+                span: span.clone(),
+            })),
+            span: span.clone(),
+        };
+        let condition = Expression::core_ops(
+            Op {
+                span: span.clone(),
+                op_variant: OpVariant::LessThan,
+            },
+            vec![counter_expression(&span), end],
+            span.clone(),
+        );
+        let mut contents = vec![declaration(variable, element, false), step];
+        contents.extend(body.contents);
+
+        let r#loop = check!(
+            TypedWhileLoop::type_check(TypeCheckArguments {
+                checkee: WhileLoop {
+                    condition,
+                    body: CodeBlock {
+                        contents,
+                        whole_block_span: body.whole_block_span,
+                    },
+                },
+                namespace: &mut local_namespace,
+                crate_namespace,
+                return_type_annotation,
+                help_text,
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+
+        ok(TypedForLoop { setup, r#loop }, warnings, errors)
+    }
+
+    pub(crate) fn pretty_print(&self) -> String {
+        format!("for loop on {}", self.r#loop.condition.pretty_print())
+    }
+}
+
+pub(super) fn variable_expression(kind: HiddenVariable, span: &Span) -> Expression {
+    Expression::VariableExpression {
+        name: hidden_ident(kind, span),
+        span: span.clone(),
+    }
+}
+
+fn counter_expression(span: &Span) -> Expression {
+    variable_expression(HiddenVariable::ForCounter, span)
+}
+
+pub(super) fn declaration(name: Ident, body: Expression, is_mutable: bool) -> AstNode {
+    let span = body.span();
+    AstNode {
+        content: AstNodeContent::Declaration(Declaration::VariableDeclaration(
+            VariableDeclaration {
+                name,
+                type_ascription: TypeInfo::Unknown,
+                type_ascription_span: None,
+                body,
+                is_mutable,
+            },
+        )),
+        span,
+    }
+}
+
+/// The type of the variable `node` declares, if it is a variable declaration.
+pub(super) fn declared_type(node: &TypedAstNode) -> Option<TypeId> {
+    match node.content {
+        TypedAstNodeContent::Declaration(TypedDeclaration::VariableDeclaration(
+            TypedVariableDeclaration { ref body, .. },
+        )) => Some(body.return_type),
+        _ => None,
+    }
+}
@@ -1,11 +1,18 @@
-use super::{declaration::TypedTraitFn, ERROR_RECOVERY_DECLARATION};
-use crate::parse_tree::{FunctionDeclaration, ImplTrait, TypeParameter};
+use super::{
+    declaration::{TypedSupertrait, TypedTraitFn},
+    error_recovery_expr, evaluate_constant, TypedExpression, ERROR_RECOVERY_DECLARATION,
+};
+use crate::parse_tree::{
+    AssociatedTypeDefinition, ConstantDeclaration, FunctionDeclaration, ImplTrait, TraitConst,
+    TypeParameter, Visibility,
+};
 use crate::semantic_analysis::{
-    Namespace, TCOpts, TypeCheckArguments, TypedDeclaration, TypedFunctionDeclaration,
+    AssociatedItems, Namespace, TCOpts, TypeCheckArguments, TypedConstantDeclaration,
+    TypedDeclaration, TypedFunctionDeclaration,
 };
 use crate::span::Span;
 use crate::type_engine::{
-    insert_type, look_up_type_id, resolve_type, FriendlyTypeString, TypeInfo,
+    insert_type, look_up_type_id, resolve_type, unify_with_self, FriendlyTypeString, TypeInfo,
 };
 use crate::{
     build_config::BuildConfig, control_flow_analysis::ControlFlowGraph, error::*,
@@ -26,22 +33,30 @@ pub(crate) fn implementation_of_trait(
     let mut warnings = vec![];
     let ImplTrait {
         trait_name,
+        type_parameters,
         type_arguments,
         functions,
         type_implementing_for,
         type_implementing_for_span,
+        type_implementing_for_arguments,
+        consts,
+        associated_types,
         type_arguments_span,
         block_span,
     } = impl_trait;
-    let type_implementing_for = namespace.resolve_type_without_self(&type_implementing_for);
+    let type_implementing_for = check!(
+        namespace.resolve_impl_type(
+            &type_implementing_for,
+            &type_implementing_for_arguments,
+            &type_parameters,
+            &type_implementing_for_span,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
     let type_implementing_for = look_up_type_id(type_implementing_for);
     let type_implementing_for_id = insert_type(type_implementing_for.clone());
-    if !type_arguments.is_empty() {
-        errors.push(CompileError::Internal(
-            "Where clauses are not supported yet.",
-            type_arguments[0].clone().name_ident.span().clone(),
-        ));
-    }
     match namespace
         .get_call_path(&trait_name)
         .ok(&mut warnings, &mut errors)
@@ -55,6 +70,38 @@ pub(crate) fn implementation_of_trait(
                 })
             }
 
+            check!(
+                check_supertraits_implemented(
+                    &tr.supertraits,
+                    &trait_name,
+                    &type_implementing_for,
+                    namespace,
+                    &type_implementing_for_span,
+                ),
+                (),
+                warnings,
+                errors
+            );
+            check!(
+                type_check_associated_items(
+                    &trait_name,
+                    &tr.associated_consts,
+                    &tr.associated_types,
+                    consts,
+                    associated_types,
+                    namespace,
+                    crate_namespace,
+                    type_implementing_for_id,
+                    build_config,
+                    dead_code_graph,
+                    &block_span,
+                    dependency_graph,
+                    opts,
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
             let functions_buf = check!(
                 type_check_trait_implementation(
                     &tr.interface_surface,
@@ -62,6 +109,7 @@ pub(crate) fn implementation_of_trait(
                     &tr.methods,
                     &tr.name,
                     &tr.type_parameters,
+                    &type_parameters,
                     namespace,
                     crate_namespace,
                     type_implementing_for_id,
@@ -115,6 +163,38 @@ pub(crate) fn implementation_of_trait(
                 });
             }
 
+            check!(
+                check_supertraits_implemented(
+                    &abi.supertraits,
+                    &trait_name,
+                    &type_implementing_for,
+                    namespace,
+                    &type_implementing_for_span,
+                ),
+                (),
+                warnings,
+                errors
+            );
+            check!(
+                type_check_associated_items(
+                    &trait_name,
+                    &abi.associated_consts,
+                    &abi.associated_types,
+                    consts,
+                    associated_types,
+                    namespace,
+                    crate_namespace,
+                    type_implementing_for_id,
+                    build_config,
+                    dead_code_graph,
+                    &block_span,
+                    dependency_graph,
+                    opts,
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
             let functions_buf = check!(
                 type_check_trait_implementation(
                     &abi.interface_surface,
@@ -123,6 +203,7 @@ pub(crate) fn implementation_of_trait(
                     &abi.name,
                     // ABIs don't have type parameters
                     &[],
+                    &type_parameters,
                     namespace,
                     crate_namespace,
                     type_implementing_for_id,
@@ -187,6 +268,7 @@ fn type_check_trait_implementation(
     methods: &[FunctionDeclaration],
     trait_name: &Ident,
     type_arguments: &[TypeParameter],
+    impl_type_parameters: &[TypeParameter],
     namespace: &mut Namespace,
     crate_namespace: Option<&Namespace>,
     _self_type: TypeId,
@@ -215,9 +297,14 @@ fn type_check_trait_implementation(
         // i.e. fn add(self, other: u64) -> Self becomes fn
         // add(self: u64, other: u64) -> u64
 
+        // the generic parameters of the impl block are in scope in each of its functions
+        let mut fn_decl = fn_decl.clone();
+        fn_decl
+            .type_parameters
+            .append(&mut impl_type_parameters.to_vec());
         let fn_decl = check!(
             TypedFunctionDeclaration::type_check(TypeCheckArguments {
-                checkee: fn_decl.clone(),
+                checkee: fn_decl,
                 namespace,
                 crate_namespace,
                 return_type_annotation: insert_type(TypeInfo::Unknown),
@@ -284,7 +371,11 @@ fn type_check_trait_implementation(
                             let mut errors = vec![];
                             // TODO use trait constraints as part of the type here to
                             // implement trait constraint solver */
-                            let fn_decl_param_type = fn_decl_param.r#type;
+                            let fn_decl_param_type = resolve_associated_types(
+                                fn_decl_param.r#type,
+                                namespace,
+                                self_type_id,
+                            );
                             let trait_param_type = trait_param.r#type;
 
                             match crate::type_engine::unify_with_self(
@@ -314,8 +405,10 @@ fn type_check_trait_implementation(
                         errors.append(&mut maybe_err);
                     }
 
+                    let return_type =
+                        resolve_associated_types(*return_type, namespace, self_type_id);
                     match crate::type_engine::unify_with_self(
-                        *return_type,
+                        return_type,
                         fn_decl.return_type,
                         self_type_id,
                         &fn_decl.return_type_span,
@@ -369,11 +462,15 @@ fn type_check_trait_implementation(
         // type check the method now that the interface
         // it depends upon has been implemented
 
+        let mut method = method.clone();
+        method
+            .type_parameters
+            .append(&mut impl_type_parameters.to_vec());
         // use a local namespace which has the above interface inserted
         // into it as a trait implementation for this
         let method = check!(
             TypedFunctionDeclaration::type_check(TypeCheckArguments {
-                checkee: method.clone(),
+                checkee: method,
                 namespace: &mut local_namespace,
                 crate_namespace,
                 return_type_annotation: insert_type(TypeInfo::Unknown),
@@ -406,3 +503,195 @@ fn type_check_trait_implementation(
     }
     ok(functions_buf, warnings, errors)
 }
+
+/// A type implementing a trait must implement its supertraits too.
+fn check_supertraits_implemented(
+    supertraits: &[TypedSupertrait],
+    trait_name: &CallPath,
+    type_implementing_for: &TypeInfo,
+    namespace: &Namespace,
+    span: &Span,
+) -> CompileResult<()> {
+    let warnings = vec![];
+    let errors = supertraits
+        .iter()
+        .filter(|supertrait| !namespace.implements_trait(type_implementing_for, &supertrait.name))
+        .map(|supertrait| CompileError::SupertraitNotImplemented {
+            supertrait: supertrait.name.suffix.clone(),
+            trait_name: trait_name.suffix.clone(),
+            ty: type_implementing_for.friendly_type_str(),
+            span: span.clone(),
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        ok((), warnings, errors)
+    } else {
+        err(warnings, errors)
+    }
+}
+
+/// The types in a trait's interface surface may be associated types of `Self`, which are the
+/// types the implementation for `self_type` defined.
+fn resolve_associated_types(r#type: TypeId, namespace: &Namespace, self_type: TypeId) -> TypeId {
+    match look_up_type_id(r#type) {
+        associated_type @ TypeInfo::AssociatedType { .. } => namespace
+            .resolve_type_with_self(associated_type, self_type)
+            .unwrap_or(r#type),
+        _ => r#type,
+    }
+}
+
+/// Type checks the values an implementation gives the associated constants of a trait, and the
+/// definitions of its associated types, and inserts them into the namespace so that they can be
+/// referred to through the type implementing the trait, like `Self::DECIMALS` or `Self::Item`.
+#[allow(clippy::too_many_arguments)]
+fn type_check_associated_items(
+    trait_name: &CallPath,
+    associated_consts: &[TraitConst],
+    associated_types: &[Ident],
+    consts: Vec<ConstantDeclaration>,
+    type_definitions: Vec<AssociatedTypeDefinition>,
+    namespace: &mut Namespace,
+    crate_namespace: Option<&Namespace>,
+    type_implementing_for: TypeId,
+    build_config: &BuildConfig,
+    dead_code_graph: &mut ControlFlowGraph,
+    block_span: &Span,
+    dependency_graph: &mut HashMap<String, HashSet<String>>,
+    opts: TCOpts,
+) -> CompileResult<()> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let self_type = type_implementing_for;
+    let mut items = AssociatedItems::default();
+    for AssociatedTypeDefinition {
+        name,
+        r#type,
+        type_span,
+    } in type_definitions
+    {
+        if !associated_types.contains(&name) {
+            errors.push(CompileError::NotAnAssociatedItemOfTrait {
+                name: name.clone(),
+                trait_name: trait_name.suffix.clone(),
+                span: name.span().clone(),
+            });
+            continue;
+        }
+        let r#type = namespace
+            .resolve_type_with_self(r#type, self_type)
+            .unwrap_or_else(|error| {
+                errors.push(error.into_error(type_span));
+                insert_type(TypeInfo::ErrorRecovery)
+            });
+        items.types.push((name, r#type));
+    }
+    // the types of the constants may be associated types
+    namespace.insert_associated_items(
+        trait_name.clone(),
+        look_up_type_id(type_implementing_for),
+        items.clone(),
+    );
+
+    for ConstantDeclaration {
+        name,
+        type_ascription,
+        value,
+        ..
+    } in consts
+    {
+        let trait_const = match associated_consts
+            .iter()
+            .find(|trait_const| trait_const.name == name)
+        {
+            Some(trait_const) => trait_const,
+            None => {
+                errors.push(CompileError::NotAnAssociatedItemOfTrait {
+                    name: name.clone(),
+                    trait_name: trait_name.suffix.clone(),
+                    span: name.span().clone(),
+                });
+                continue;
+            }
+        };
+        let r#type = namespace
+            .resolve_type_with_self(trait_const.r#type.clone(), self_type)
+            .unwrap_or_else(|error| {
+                errors.push(error.into_error(trait_const.type_span.clone()));
+                insert_type(TypeInfo::ErrorRecovery)
+            });
+        if type_ascription != TypeInfo::Unknown {
+            let ascribed_type = namespace
+                .resolve_type_with_self(type_ascription, self_type)
+                .unwrap_or_else(|error| {
+                    errors.push(error.into_error(name.span().clone()));
+                    insert_type(TypeInfo::ErrorRecovery)
+                });
+            match unify_with_self(ascribed_type, r#type, self_type, name.span()) {
+                Ok(mut ws) => warnings.append(&mut ws),
+                Err(_e) => errors.push(CompileError::MismatchedTypeInTrait {
+                    span: name.span().clone(),
+                    given: ascribed_type.friendly_type_str(),
+                    expected: r#type.friendly_type_str(),
+                }),
+            }
+        }
+        let value = check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: value,
+                namespace,
+                crate_namespace,
+                return_type_annotation: r#type,
+                help_text: "This constant's value does not match up with its type in the trait \
+                    declaration.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                mode: Mode::NonAbi,
+                dependency_graph,
+                opts,
+            }),
+            error_recovery_expr(name.span().clone()),
+            warnings,
+            errors
+        );
+        let value = check!(
+            evaluate_constant(&value, namespace),
+            error_recovery_expr(value.span),
+            warnings,
+            errors
+        );
+        items.consts.push(TypedConstantDeclaration {
+            name,
+            value,
+            visibility: Visibility::Public,
+        });
+    }
+
+    // check that every associated constant and type has been given
+    let missing_items = associated_consts
+        .iter()
+        .map(|trait_const| &trait_const.name)
+        .filter(|name| !items.consts.iter().any(|decl| decl.name == **name))
+        .chain(
+            associated_types
+                .iter()
+                .filter(|name| !items.types.iter().any(|(type_name, _)| type_name == *name)),
+        )
+        .map(|name| name.as_str().to_string())
+        .collect::<Vec<_>>();
+    if !missing_items.is_empty() {
+        errors.push(CompileError::MissingAssociatedItems {
+            trait_name: trait_name.suffix.clone(),
+            missing_items: missing_items.join(", "),
+            span: block_span.clone(),
+        });
+    }
+
+    namespace.insert_associated_items(
+        trait_name.clone(),
+        look_up_type_id(type_implementing_for),
+        items,
+    );
+    ok((), warnings, errors)
+}
@@ -2,7 +2,7 @@ use crate::build_config::BuildConfig;
 use crate::error::*;
 use crate::semantic_analysis::ast_node::declaration::insert_type_parameters;
 pub(crate) use crate::semantic_analysis::ast_node::declaration::ReassignmentLhs;
-use crate::semantic_analysis::{Namespace, TCOpts, TypeCheckArguments};
+use crate::semantic_analysis::{AssociatedItems, Namespace, TCOpts, TypeCheckArguments};
 use crate::span::Span;
 
 use crate::{control_flow_analysis::ControlFlowGraph, parse_tree::*};
@@ -14,6 +14,7 @@ use std::sync::Arc;
 mod code_block;
 pub mod declaration;
 mod expression;
+mod for_loop;
 pub mod impl_trait;
 mod return_statement;
 mod while_loop;
@@ -22,15 +23,17 @@ use super::ERROR_RECOVERY_DECLARATION;
 use crate::type_engine::*;
 pub(crate) use code_block::TypedCodeBlock;
 pub(crate) use declaration::{
-    OwnedTypedEnumVariant, OwnedTypedStructField, TypedReassignment, TypedTraitDeclaration,
-    TypedVariableDeclaration,
+    storage_map_methods, OwnedTypedEnumVariant, OwnedTypedStructField, StorageMapMethod,
+    TypedReassignment, TypedStorageDeclaration, TypedStorageField, TypedStorageReassignment,
+    TypedSupertrait, TypedTraitDeclaration, TypedVariableDeclaration,
 };
 pub use declaration::{
     TypedAbiDeclaration, TypedConstantDeclaration, TypedDeclaration, TypedEnumDeclaration,
     TypedEnumVariant, TypedFunctionDeclaration, TypedFunctionParameter, TypedStructDeclaration,
-    TypedStructField,
+    TypedStructField, TypedTypeAliasDeclaration,
 };
 pub(crate) use expression::*;
+pub(crate) use for_loop::TypedForLoop;
 use impl_trait::implementation_of_trait;
 pub(crate) use return_statement::TypedReturnStatement;
 use std::collections::{HashMap, HashSet};
@@ -51,6 +54,9 @@ pub(crate) enum TypedAstNodeContent {
     Expression(TypedExpression),
     ImplicitReturnExpression(TypedExpression),
     WhileLoop(TypedWhileLoop),
+    ForLoop(TypedForLoop),
+    Break,
+    Continue,
     // a no-op node used for something that just issues a side effect, like an import statement.
     SideEffect,
 }
@@ -72,6 +78,9 @@ impl std::fmt::Debug for TypedAstNode {
             Expression(exp) => exp.pretty_print(),
             ImplicitReturnExpression(exp) => format!("return {}", exp.pretty_print()),
             WhileLoop(w_loop) => w_loop.pretty_print(),
+            ForLoop(f_loop) => f_loop.pretty_print(),
+            Break => "break".into(),
+            Continue => "continue".into(),
             SideEffect => "".into(),
         };
         f.write_str(&text)
@@ -96,7 +105,23 @@ impl TypedAstNode {
                 condition.copy_types(type_mapping);
                 body.copy_types(type_mapping);
             }
-            TypedAstNodeContent::SideEffect => (),
+            TypedAstNodeContent::ForLoop(TypedForLoop {
+                ref mut setup,
+                r#loop:
+                    TypedWhileLoop {
+                        ref mut condition,
+                        ref mut body,
+                    },
+            }) => {
+                setup
+                    .iter_mut()
+                    .for_each(|node| node.copy_types(type_mapping));
+                condition.copy_types(type_mapping);
+                body.copy_types(type_mapping);
+            }
+            TypedAstNodeContent::Break
+            | TypedAstNodeContent::Continue
+            | TypedAstNodeContent::SideEffect => (),
         }
     }
     fn type_info(&self) -> TypeInfo {
@@ -110,7 +135,9 @@ impl TypedAstNode {
             ImplicitReturnExpression(TypedExpression { return_type, .. }) => {
                 crate::type_engine::look_up_type_id(*return_type)
             }
-            WhileLoop(_) | SideEffect => TypeInfo::Tuple(Vec::new()),
+            WhileLoop(_) | ForLoop(_) | Break | Continue | SideEffect => {
+                TypeInfo::Tuple(Vec::new())
+            }
         }
     }
     pub(crate) fn type_check(
@@ -138,10 +165,8 @@ impl TypedAstNode {
                                             value| {
             let type_id = namespace
                 .resolve_type_with_self(type_ascription, self_type)
-                .unwrap_or_else(|_| {
-                    errors.push(CompileError::UnknownType {
-                        span: node.span.clone(),
-                    });
+                .unwrap_or_else(|error| {
+                    errors.push(error.into_error(node.span.clone()));
                     insert_type(TypeInfo::ErrorRecovery)
                 });
             TypedExpression::type_check(TypeCheckArguments {
@@ -202,10 +227,8 @@ impl TypedAstNode {
                         }) => {
                             let type_ascription = namespace
                                 .resolve_type_with_self(type_ascription, self_type)
-                                .unwrap_or_else(|_| {
-                                    errors.push(CompileError::UnknownType {
-                                        span: type_ascription_span.expect("Invariant violated: type checked an annotation that did not exist in the source"),
-                                    });
+                                .unwrap_or_else(|error| {
+                                    errors.push(error.into_error(type_ascription_span.expect("Invariant violated: type checked an annotation that did not exist in the source")));
                                     insert_type(TypeInfo::ErrorRecovery)
                                 });
 
@@ -259,6 +282,12 @@ impl TypedAstNode {
                                 warnings,
                                 errors
                             );
+                            let value = check!(
+                                evaluate_constant(&value, namespace),
+                                error_recovery_expr(value.span),
+                                warnings,
+                                errors
+                            );
                             let typed_const_decl =
                                 TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
                                     name: name.clone(),
@@ -282,10 +311,21 @@ impl TypedAstNode {
                             decl
                         }
                         Declaration::FunctionDeclaration(fn_decl) => {
+                            // the function's own signature is visible within its body, so that
+                            // it may call itself
+                            let mut fn_namespace = namespace.clone();
+                            fn_namespace.insert(
+                                fn_decl.name.clone(),
+                                TypedDeclaration::FunctionDeclaration(
+                                    TypedFunctionDeclaration::recursion_stub(
+                                        &fn_decl, namespace, self_type,
+                                    ),
+                                ),
+                            );
                             let decl = check!(
                                 TypedFunctionDeclaration::type_check(TypeCheckArguments {
                                     checkee: fn_decl.clone(),
-                                    namespace,
+                                    namespace: &mut fn_namespace,
                                     crate_namespace,
                                     return_type_annotation: insert_type(TypeInfo::Unknown),
                                     help_text,
@@ -311,6 +351,9 @@ impl TypedAstNode {
                             interface_surface,
                             methods,
                             type_parameters,
+                            supertraits,
+                            associated_consts,
+                            associated_types,
                             visibility,
                         }) => {
                             // type check the interface surface
@@ -320,20 +363,49 @@ impl TypedAstNode {
                                 warnings,
                                 errors
                             );
+                            let supertraits = check!(
+                                type_check_supertraits(supertraits, &name, false, namespace),
+                                vec![],
+                                warnings,
+                                errors
+                            );
                             let mut trait_namespace = namespace.clone();
+                            // the methods can also use the interface surfaces of the supertraits
+                            for supertrait in &supertraits {
+                                trait_namespace.insert_trait_implementation(
+                                    supertrait.name.clone(),
+                                    TypeInfo::SelfType,
+                                    supertrait
+                                        .interface_surface
+                                        .iter()
+                                        .map(|x| x.to_dummy_func(Mode::NonAbi, &supertrait.name))
+                                        .collect(),
+                                );
+                            }
                             // insert placeholder functions representing the interface surface
                             // to allow methods to use those functions
+                            let trait_name = CallPath {
+                                prefixes: vec![],
+                                suffix: name.clone(),
+                            };
                             trait_namespace.insert_trait_implementation(
-                                CallPath {
-                                    prefixes: vec![],
-                                    suffix: name.clone(),
-                                },
+                                trait_name.clone(),
                                 TypeInfo::SelfType,
                                 interface_surface
                                     .iter()
-                                    .map(|x| x.to_dummy_func(Mode::NonAbi))
+                                    .map(|x| x.to_dummy_func(Mode::NonAbi, &trait_name))
                                     .collect(),
                             );
+                            trait_namespace.insert_associated_items(
+                                trait_name,
+                                TypeInfo::SelfType,
+                                placeholder_associated_items(
+                                    &associated_consts,
+                                    &associated_types,
+                                    namespace,
+                                    insert_type(TypeInfo::SelfType),
+                                ),
+                            );
                             // check the methods for errors but throw them away and use vanilla [FunctionDeclaration]s
                             let _methods = check!(
                                 type_check_trait_methods(
@@ -355,6 +427,9 @@ impl TypedAstNode {
                                     interface_surface,
                                     methods,
                                     type_parameters,
+                                    supertraits,
+                                    associated_consts,
+                                    associated_types,
                                     visibility,
                                 });
                             namespace.insert(name, trait_decl.clone());
@@ -403,19 +478,24 @@ impl TypedAstNode {
                             type_arguments,
                             functions,
                             type_implementing_for,
+                            type_implementing_for_arguments,
                             block_span,
+                            type_name_span,
                             ..
                         }) => {
-                            let implementing_for_type_id =
-                                namespace.resolve_type_without_self(&type_implementing_for);
                             // check, if this is a custom type, if it is in scope or a generic.
+                            let implementing_for_type_id = check!(
+                                namespace.resolve_impl_type(
+                                    &type_implementing_for,
+                                    &type_implementing_for_arguments,
+                                    &type_arguments,
+                                    &type_name_span,
+                                ),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
                             let mut functions_buf: Vec<TypedFunctionDeclaration> = vec![];
-                            if !type_arguments.is_empty() {
-                                errors.push(CompileError::Internal(
-                                    "Where clauses are not supported yet.",
-                                    type_arguments[0].clone().name_ident.span().clone(),
-                                ));
-                            }
                             for mut fn_decl in functions.into_iter() {
                                 let mut type_arguments = type_arguments.clone();
                                 // add generic params from impl trait into function type params
@@ -429,12 +509,12 @@ impl TypedAstNode {
                                 fn_decl.parameters.iter_mut().for_each(
                                     |FunctionParameter { ref mut r#type, .. }| {
                                         if r#type == &TypeInfo::SelfType {
-                                            *r#type = type_implementing_for.clone();
+                                            *r#type = TypeInfo::Ref(implementing_for_type_id);
                                         }
                                     },
                                 );
                                 if fn_decl.return_type == TypeInfo::SelfType {
-                                    fn_decl.return_type = type_implementing_for.clone();
+                                    fn_decl.return_type = TypeInfo::Ref(implementing_for_type_id);
                                 }
 
                                 functions_buf.push(check!(
@@ -485,6 +565,7 @@ impl TypedAstNode {
                                          r#type,
                                          span,
                                          type_span,
+                                         attributes,
                                      }| TypedStructField {
                                         name,
                                         r#type: if let Some(matching_id) =
@@ -494,14 +575,14 @@ impl TypedAstNode {
                                         } else {
                                             namespace
                                                 .resolve_type_with_self(r#type, self_type)
-                                                .unwrap_or_else(|_| {
-                                                    errors.push(CompileError::UnknownType {
-                                                        span: type_span.clone(),
-                                                    });
+                                                .unwrap_or_else(|error| {
+                                                    errors
+                                                        .push(error.into_error(type_span.clone()));
                                                     insert_type(TypeInfo::ErrorRecovery)
                                                 })
                                         },
                                         span,
+                                        attributes,
                                     },
                                 )
                                 .collect::<Vec<_>>();
@@ -510,6 +591,7 @@ impl TypedAstNode {
                                 type_parameters: decl.type_parameters.clone(),
                                 fields,
                                 visibility: decl.visibility,
+                                attributes: decl.attributes.clone(),
                             };
 
                             // insert struct into namespace
@@ -529,7 +611,11 @@ impl TypedAstNode {
                             name,
                             interface_surface,
                             methods,
+                            supertraits,
+                            associated_consts,
+                            associated_types,
                             span,
+                            attributes,
                         }) => {
                             // type check the interface surface and methods
                             // We don't want the user to waste resources by contract calling
@@ -542,12 +628,32 @@ impl TypedAstNode {
                                 warnings,
                                 errors
                             );
+                            let supertraits = check!(
+                                type_check_supertraits(supertraits, &name, true, namespace),
+                                vec![],
+                                warnings,
+                                errors
+                            );
+                            let mut abi_namespace = namespace.clone();
+                            abi_namespace.insert_associated_items(
+                                CallPath {
+                                    prefixes: vec![],
+                                    suffix: name.clone(),
+                                },
+                                look_up_type_id(self_type),
+                                placeholder_associated_items(
+                                    &associated_consts,
+                                    &associated_types,
+                                    namespace,
+                                    self_type,
+                                ),
+                            );
                             // type check these for errors but don't actually use them yet -- the real
                             // ones will be type checked with proper symbols when the ABI is implemented
                             let _methods = check!(
                                 type_check_trait_methods(
                                     methods.clone(),
-                                    namespace,
+                                    &mut abi_namespace,
                                     crate_namespace,
                                     self_type,
                                     build_config,
@@ -562,18 +668,126 @@ impl TypedAstNode {
                             let decl = TypedDeclaration::AbiDeclaration(TypedAbiDeclaration {
                                 interface_surface,
                                 methods,
+                                supertraits,
+                                associated_consts,
+                                associated_types,
                                 name: name.clone(),
                                 span,
+                                attributes,
                             });
                             namespace.insert(name, decl.clone());
                             decl
                         }
-                        Declaration::StorageDeclaration(StorageDeclaration { span, .. }) => {
-                            errors.push(CompileError::Unimplemented(
-                                "Storage declarations are not supported yet. Coming soon!",
+                        Declaration::StorageDeclaration(StorageDeclaration {
+                            fields,
+                            span,
+                            attributes,
+                        }) => {
+                            let mut typed_fields = Vec::with_capacity(fields.len());
+                            for StorageField {
+                                name,
+                                r#type,
+                                initializer,
+                                attributes,
+                            } in fields
+                            {
+                                let field_span = crate::utils::join_spans(
+                                    name.span().clone(),
+                                    initializer.span(),
+                                );
+                                let r#type = namespace
+                                    .resolve_type_with_self(r#type, self_type)
+                                    .unwrap_or_else(|error| {
+                                        errors.push(error.into_error(field_span.clone()));
+                                        insert_type(TypeInfo::ErrorRecovery)
+                                    });
+                                let initializer = check!(
+                                    TypedExpression::type_check(TypeCheckArguments {
+                                        checkee: initializer,
+                                        namespace,
+                                        crate_namespace,
+                                        return_type_annotation: r#type,
+                                        help_text: "Storage field's type annotation does not \
+                                                    match up with its initial value's type.",
+                                        self_type,
+                                        build_config,
+                                        dead_code_graph,
+                                        dependency_graph,
+                                        mode: Mode::NonAbi,
+                                        opts,
+                                    }),
+                                    error_recovery_expr(field_span.clone()),
+                                    warnings,
+                                    errors
+                                );
+                                // a `StorageMap` holds nothing itself, so there is no value to
+                                // evaluate
+                                let initializer = match look_up_type_id(r#type) {
+                                    TypeInfo::StorageMap { .. } => initializer,
+                                    _ => check!(
+                                        evaluate_constant(&initializer, namespace),
+                                        error_recovery_expr(initializer.span),
+                                        warnings,
+                                        errors
+                                    ),
+                                };
+                                typed_fields.push(TypedStorageField {
+                                    name,
+                                    r#type,
+                                    initializer,
+                                    span: field_span,
+                                    attributes,
+                                });
+                            }
+                            let decl = TypedStorageDeclaration {
+                                fields: typed_fields,
                                 span,
-                            ));
-                            return err(warnings, errors);
+                                attributes,
+                            };
+                            check!(
+                                namespace.set_storage_declaration(decl.clone()),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            TypedDeclaration::StorageDeclaration(decl)
+                        }
+                        Declaration::TypeAliasDeclaration(TypeAliasDeclaration {
+                            name,
+                            type_parameters,
+                            r#type,
+                            type_span,
+                            visibility,
+                            ..
+                        }) => {
+                            // the type parameters are only in scope in the type of the alias
+                            let mut alias_namespace = namespace.clone();
+                            for type_parameter in &type_parameters {
+                                alias_namespace.insert(
+                                    type_parameter.name_ident.clone(),
+                                    type_parameter.into(),
+                                );
+                            }
+                            let r#type = alias_namespace
+                                .resolve_type_alias(r#type, self_type)
+                                .unwrap_or_else(|error| {
+                                    errors.push(error.into_error(type_span));
+                                    insert_type(TypeInfo::ErrorRecovery)
+                                });
+                            let decl =
+                                TypedDeclaration::TypeAliasDeclaration(TypedTypeAliasDeclaration {
+                                    name: name.clone(),
+                                    type_parameters,
+                                    r#type,
+                                    visibility,
+                                });
+                            check!(
+                                namespace.insert(name, decl.clone()),
+                                return err(warnings, errors),
+                                warnings,
+                                errors
+                            );
+                            decl
                         }
                     })
                 }
@@ -643,57 +857,66 @@ impl TypedAstNode {
                     );
                     TypedAstNodeContent::ImplicitReturnExpression(typed_expr)
                 }
-                AstNodeContent::WhileLoop(WhileLoop { condition, body }) => {
-                    let typed_condition = check!(
-                        TypedExpression::type_check(TypeCheckArguments {
-                            checkee: condition,
+                AstNodeContent::WhileLoop(while_loop) => {
+                    let typed_while_loop = check!(
+                        TypedWhileLoop::type_check(TypeCheckArguments {
+                            checkee: while_loop,
                             namespace,
                             crate_namespace,
-                            return_type_annotation: insert_type(TypeInfo::Boolean),
-                            help_text:
-                                "A while loop's loop condition must be a boolean expression.",
+                            return_type_annotation,
+                            help_text,
                             self_type,
                             build_config,
                             dead_code_graph,
                             dependency_graph,
                             mode: Mode::NonAbi,
-                            opts
+                            opts,
                         }),
                         return err(warnings, errors),
                         warnings,
                         errors
                     );
-                    let (typed_body, _block_implicit_return) = check!(
-                        TypedCodeBlock::type_check(TypeCheckArguments {
-                            checkee: body.clone(),
-                            namespace,
-                            crate_namespace,
-                            return_type_annotation: insert_type(TypeInfo::Tuple(Vec::new())),
-                            help_text:
-                                "A while loop's loop body cannot implicitly return a value.Try \
-                             assigning it to a mutable variable declared outside of the loop \
-                             instead.",
-                            self_type,
-                            build_config,
-                            dead_code_graph,
-                            dependency_graph,
-                            mode: Mode::NonAbi,
-                            opts,
-                        }),
-                        (
-                            TypedCodeBlock {
-                                contents: vec![],
-                                whole_block_span: body.whole_block_span,
+                    TypedAstNodeContent::WhileLoop(typed_while_loop)
+                }
+                AstNodeContent::ForLoop(for_loop) => {
+                    let typed_for_loop = check!(
+                        TypedForLoop::type_check(
+                            TypeCheckArguments {
+                                checkee: for_loop,
+                                namespace,
+                                crate_namespace,
+                                return_type_annotation,
+                                help_text,
+                                self_type,
+                                build_config,
+                                dead_code_graph,
+                                dependency_graph,
+                                mode: Mode::NonAbi,
+                                opts,
                             },
-                            crate::type_engine::insert_type(TypeInfo::Tuple(Vec::new()))
+                            node.span.clone()
                         ),
+                        return err(warnings, errors),
                         warnings,
                         errors
                     );
-                    TypedAstNodeContent::WhileLoop(TypedWhileLoop {
-                        condition: typed_condition,
-                        body: typed_body,
-                    })
+                    TypedAstNodeContent::ForLoop(typed_for_loop)
+                }
+                AstNodeContent::Break => {
+                    if !opts.in_loop {
+                        errors.push(CompileError::BreakOutsideLoop {
+                            span: node.span.clone(),
+                        });
+                    }
+                    TypedAstNodeContent::Break
+                }
+                AstNodeContent::Continue => {
+                    if !opts.in_loop {
+                        errors.push(CompileError::ContinueOutsideLoop {
+                            span: node.span.clone(),
+                        });
+                    }
+                    TypedAstNodeContent::Continue
                 }
             },
             span: node.span.clone(),
@@ -814,7 +1037,7 @@ fn reassignment(
     } = arguments;
     let mut errors = vec![];
     let mut warnings = vec![];
-    // ensure that the lhs is a variable expression or struct field access
+    // ensure that the lhs is a variable expression, struct field access or storage field
     match *lhs {
         Expression::VariableExpression { name, span } => {
             // check that the reassigned name exists
@@ -888,6 +1111,57 @@ fn reassignment(
                 errors,
             )
         }
+        Expression::SubfieldExpression {
+            prefix,
+            field_to_access,
+            span,
+        } if is_storage(&prefix, namespace) => {
+            if let Err(error) = opts.purity.check_access(Purity::Writes, &span) {
+                errors.push(error);
+            }
+            let r#type = check!(
+                namespace.get_storage_field(&field_to_access, &span),
+                return err(warnings, errors),
+                warnings,
+                errors
+            )
+            .r#type;
+            if let TypeInfo::StorageMap { .. } = look_up_type_id(r#type) {
+                errors.push(CompileError::StorageMapReassignment {
+                    field_name: field_to_access.as_str().to_string(),
+                    span: span.clone(),
+                });
+            }
+            let rhs = check!(
+                TypedExpression::type_check(TypeCheckArguments {
+                    checkee: rhs,
+                    namespace,
+                    crate_namespace,
+                    return_type_annotation: r#type,
+                    help_text: "You can only reassign a value of the same type to a storage \
+                                field.",
+                    self_type,
+                    build_config,
+                    dead_code_graph,
+                    dependency_graph,
+                    mode: Mode::NonAbi,
+                    opts,
+                }),
+                error_recovery_expr(span),
+                warnings,
+                errors
+            );
+
+            ok(
+                TypedDeclaration::StorageReassignment(TypedStorageReassignment {
+                    field_name: field_to_access,
+                    r#type,
+                    rhs,
+                }),
+                warnings,
+                errors,
+            )
+        }
         Expression::SubfieldExpression {
             prefix,
             field_to_access,
@@ -923,6 +1197,16 @@ fn reassignment(
                         });
                         break type_checked.return_type;
                     }
+                    Expression::SubfieldExpression { ref prefix, .. }
+                        if is_storage(prefix, namespace) =>
+                    {
+                        errors.push(CompileError::Unimplemented(
+                            "Reassigning part of a storage field is not supported yet. Try \
+                             reassigning the whole field instead.",
+                            span,
+                        ));
+                        return err(warnings, errors);
+                    }
                     Expression::SubfieldExpression {
                         field_to_access,
                         prefix,
@@ -995,6 +1279,101 @@ fn reassignment(
     }
 }
 
+/// Looks up the traits extended by a trait, or the abis extended by an abi, along with those they
+/// extend in turn.
+fn type_check_supertraits(
+    supertraits: Vec<CallPath>,
+    name: &Ident,
+    is_abi: bool,
+    namespace: &Namespace,
+) -> CompileResult<Vec<TypedSupertrait>> {
+    let warnings = vec![];
+    let mut errors = vec![];
+    let mut typed_supertraits: Vec<TypedSupertrait> = vec![];
+    for supertrait in supertraits {
+        let (interface_surface, methods, inherited) =
+            match namespace.get_call_path(&supertrait).value {
+                Some(TypedDeclaration::TraitDeclaration(TypedTraitDeclaration {
+                    interface_surface,
+                    methods,
+                    supertraits,
+                    ..
+                })) if !is_abi => (interface_surface, methods, supertraits),
+                Some(TypedDeclaration::AbiDeclaration(TypedAbiDeclaration {
+                    interface_surface,
+                    methods,
+                    supertraits,
+                    ..
+                })) if is_abi => (interface_surface, methods, supertraits),
+                Some(_) => {
+                    errors.push(CompileError::InvalidSupertrait {
+                        supertrait: supertrait.suffix.clone(),
+                        name: name.clone(),
+                        expected: if is_abi { "an abi" } else { "a trait" },
+                        span: supertrait.span(),
+                    });
+                    continue;
+                }
+                None => {
+                    errors.push(CompileError::UnknownTrait {
+                        name: supertrait.suffix.clone(),
+                        span: supertrait.span(),
+                    });
+                    continue;
+                }
+            };
+        let supertrait = TypedSupertrait {
+            name: supertrait,
+            interface_surface,
+            methods,
+        };
+        for supertrait in std::iter::once(supertrait).chain(inherited) {
+            if !typed_supertraits
+                .iter()
+                .any(|typed_supertrait| typed_supertrait.name.suffix == supertrait.name.suffix)
+            {
+                typed_supertraits.push(supertrait);
+            }
+        }
+    }
+    ok(typed_supertraits, warnings, errors)
+}
+
+/// Placeholders for the associated constants and types of a trait, which allow its methods to
+/// refer to them before any implementation has defined them.
+fn placeholder_associated_items(
+    associated_consts: &[TraitConst],
+    associated_types: &[Ident],
+    namespace: &Namespace,
+    self_type: TypeId,
+) -> AssociatedItems {
+    AssociatedItems {
+        consts: associated_consts
+            .iter()
+            .map(|TraitConst { name, r#type, .. }| TypedConstantDeclaration {
+                name: name.clone(),
+                value: TypedExpression {
+                    return_type: namespace
+                        .resolve_type_with_self(r#type.clone(), self_type)
+                        .unwrap_or_else(|_| insert_type(TypeInfo::ErrorRecovery)),
+                    ..error_recovery_expr(name.span().clone())
+                },
+                visibility: Visibility::Public,
+            })
+            .collect(),
+        types: associated_types
+            .iter()
+            .map(|name| {
+                let r#type = insert_type(TypeInfo::AssociatedType {
+                    parent: self_type,
+                    name: name.as_str().to_string(),
+                });
+                (name.clone(), r#type)
+            })
+            .collect(),
+    }
+}
+
 fn type_check_interface_surface(
     interface_surface: Vec<TraitFn>,
     namespace: &Namespace,
@@ -1026,10 +1405,8 @@ fn type_check_interface_surface(
                                         r#type,
                                         crate::type_engine::insert_type(TypeInfo::SelfType),
                                     )
-                                    .unwrap_or_else(|_| {
-                                        errors.push(CompileError::UnknownType {
-                                            span: type_span.clone(),
-                                        });
+                                    .unwrap_or_else(|error| {
+                                        errors.push(error.into_error(type_span.clone()));
                                         insert_type(TypeInfo::ErrorRecovery)
                                     }),
                                 type_span,
@@ -1041,10 +1418,8 @@ fn type_check_interface_surface(
                             return_type,
                             crate::type_engine::insert_type(TypeInfo::SelfType),
                         )
-                        .unwrap_or_else(|_| {
-                            errors.push(CompileError::UnknownType {
-                                span: return_type_span,
-                            });
+                        .unwrap_or_else(|error| {
+                            errors.push(error.into_error(return_type_span));
                             insert_type(TypeInfo::ErrorRecovery)
                         }),
                 },
@@ -1076,6 +1451,7 @@ fn type_check_trait_methods(
         type_parameters,
         return_type_span,
         purity,
+        attributes,
         ..
     } in methods
     {
@@ -1089,10 +1465,8 @@ fn type_check_trait_methods(
                         r#type.clone(),
                         crate::type_engine::insert_type(TypeInfo::SelfType),
                     )
-                    .unwrap_or_else(|_| {
-                        errors.push(CompileError::UnknownType {
-                            span: name.span().clone(),
-                        });
+                    .unwrap_or_else(|error| {
+                        errors.push(error.into_error(name.span().clone()));
                         insert_type(TypeInfo::ErrorRecovery)
                     });
                 function_namespace.insert(
@@ -1116,7 +1490,7 @@ fn type_check_trait_methods(
         // the type scope
         let mut generic_params_buf_for_error_message = Vec::new();
         for param in parameters.iter() {
-            if let TypeInfo::Custom { ref name } = param.r#type {
+            if let TypeInfo::Custom { ref name, .. } = param.r#type {
                 generic_params_buf_for_error_message.push(name.to_string());
             }
         }
@@ -1137,7 +1511,10 @@ fn type_check_trait_methods(
                     |TypeParameter {
                          name: this_name, ..
                      }| {
-                        if let TypeInfo::Custom { name: this_name } = this_name {
+                        if let TypeInfo::Custom {
+                            name: this_name, ..
+                        } = this_name
+                        {
                             this_name == name
                         } else {
                             false
@@ -1169,10 +1546,8 @@ fn type_check_trait_methods(
                                 r#type,
                                 crate::type_engine::insert_type(TypeInfo::SelfType),
                             )
-                            .unwrap_or_else(|_| {
-                                errors.push(CompileError::UnknownType {
-                                    span: type_span.clone(),
-                                });
+                            .unwrap_or_else(|error| {
+                                errors.push(error.into_error(type_span.clone()));
                                 insert_type(TypeInfo::ErrorRecovery)
                             }),
                         type_span,
@@ -1184,10 +1559,8 @@ fn type_check_trait_methods(
         // TODO check code block implicit return
         let return_type = function_namespace
             .resolve_type_with_self(return_type, self_type)
-            .unwrap_or_else(|_| {
-                errors.push(CompileError::UnknownType {
-                    span: return_type_span.clone(),
-                });
+            .unwrap_or_else(|error| {
+                errors.push(error.into_error(return_type_span.clone()));
                 insert_type(TypeInfo::ErrorRecovery)
             });
         let (body, _code_block_implicit_return) = check!(
@@ -1203,7 +1576,10 @@ fn type_check_trait_methods(
                 dead_code_graph,
                 dependency_graph,
                 mode: Mode::NonAbi,
-                opts: TCOpts { purity }
+                opts: TCOpts {
+                    purity,
+                    in_loop: false
+                }
             }),
             continue,
             warnings,
@@ -1222,7 +1598,9 @@ fn type_check_trait_methods(
             visibility: Visibility::Public,
             return_type_span,
             is_contract_call: false,
+            trait_placeholder: None,
             purity,
+            attributes,
         });
     }
     ok(methods_buf, warnings, errors)
@@ -1237,6 +1615,7 @@ fn error_recovery_function_declaration(decl: FunctionDeclaration) -> TypedFuncti
         span,
         return_type_span,
         visibility,
+        attributes,
         ..
     } = decl;
     TypedFunctionDeclaration {
@@ -1248,10 +1627,12 @@ fn error_recovery_function_declaration(decl: FunctionDeclaration) -> TypedFuncti
         },
         span,
         is_contract_call: false,
+        trait_placeholder: None,
         return_type_span,
         parameters: Default::default(),
         visibility,
         return_type: crate::type_engine::insert_type(return_type),
         type_parameters: Default::default(),
+        attributes,
     }
 }
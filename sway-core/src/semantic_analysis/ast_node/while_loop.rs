@@ -1,4 +1,8 @@
 use super::{TypedCodeBlock, TypedExpression};
+use crate::error::*;
+use crate::semantic_analysis::{ast_node::Mode, TypeCheckArguments};
+use crate::type_engine::{insert_type, TypeInfo};
+use crate::WhileLoop;
 
 #[derive(Clone, Debug)]
 pub(crate) struct TypedWhileLoop {
@@ -7,6 +11,78 @@ pub(crate) struct TypedWhileLoop {
 }
 
 impl TypedWhileLoop {
+    pub(crate) fn type_check(
+        arguments: TypeCheckArguments<'_, WhileLoop>,
+    ) -> CompileResult<TypedWhileLoop> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let TypeCheckArguments {
+            checkee: WhileLoop { condition, body },
+            namespace,
+            crate_namespace,
+            self_type,
+            build_config,
+            dead_code_graph,
+            dependency_graph,
+            mut opts,
+            ..
+        } = arguments;
+
+        let typed_condition = check!(
+            TypedExpression::type_check(TypeCheckArguments {
+                checkee: condition,
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Boolean),
+                help_text: "A while loop's loop condition must be a boolean expression.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts
+            }),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        opts.in_loop = true;
+        let (typed_body, _block_implicit_return) = check!(
+            TypedCodeBlock::type_check(TypeCheckArguments {
+                checkee: body.clone(),
+                namespace,
+                crate_namespace,
+                return_type_annotation: insert_type(TypeInfo::Tuple(Vec::new())),
+                help_text: "A while loop's loop body cannot implicitly return a value.Try \
+                            assigning it to a mutable variable declared outside of the loop \
+                            instead.",
+                self_type,
+                build_config,
+                dead_code_graph,
+                dependency_graph,
+                mode: Mode::NonAbi,
+                opts,
+            }),
+            (
+                TypedCodeBlock {
+                    contents: vec![],
+                    whole_block_span: body.whole_block_span,
+                },
+                insert_type(TypeInfo::Tuple(Vec::new()))
+            ),
+            warnings,
+            errors
+        );
+        ok(
+            TypedWhileLoop {
+                condition: typed_condition,
+                body: typed_body,
+            },
+            warnings,
+            errors,
+        )
+    }
+
     pub(crate) fn pretty_print(&self) -> String {
         format!("while loop on {}", self.condition.pretty_print())
     }
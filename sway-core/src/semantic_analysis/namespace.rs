@@ -1,6 +1,7 @@
 use super::ast_node::{
-    OwnedTypedStructField, TypedEnumDeclaration, TypedEnumVariant, TypedStructDeclaration,
-    TypedStructField,
+    evaluate_array_length, signed_integer_methods, storage_map_methods, OverloadableOperator,
+    OwnedTypedStructField, TypedConstantDeclaration, TypedEnumDeclaration, TypedStorageDeclaration,
+    TypedStorageField,
 };
 use crate::error::*;
 use crate::parse_tree::Visibility;
@@ -10,7 +11,7 @@ use crate::type_engine::*;
 
 use crate::CallPath;
 use crate::{CompileResult, TypeInfo};
-use crate::{Ident, TypedDeclaration, TypedFunctionDeclaration};
+use crate::{Ident, TypeParameter, TypedDeclaration, TypedFunctionDeclaration};
 use std::collections::{BTreeMap, HashMap, VecDeque};
 
 type ModuleName = String;
@@ -24,6 +25,8 @@ pub struct Namespace {
     // order.
     symbols: BTreeMap<Ident, TypedDeclaration>,
     implemented_traits: HashMap<(TraitName, TypeInfo), Vec<TypedFunctionDeclaration>>,
+    // The constants and types that trait implementations associate with the types they are for.
+    associated_items: HashMap<(TraitName, TypeInfo), AssociatedItems>,
     // Any other modules within this scope, where a module is a namespace associated with an identifier.
     // This is a BTreeMap because we rely on its ordering being consistent. See
     // [Namespace::get_all_imported_modules] -- we need that iterator to have a deterministic
@@ -32,6 +35,49 @@ pub struct Namespace {
     use_synonyms: HashMap<Ident, Vec<Ident>>,
     // Represents an alternative name for a symbol.
     use_aliases: HashMap<String, Ident>,
+    // The `storage { .. }` declaration of a contract, if it has one.
+    declared_storage: Option<TypedStorageDeclaration>,
+}
+
+/// Why a type couldn't be resolved.
+#[derive(Debug)]
+pub(crate) enum TypeResolutionError {
+    /// The type, or a type within it, isn't declared.
+    UnknownType,
+    /// A part of the type is invalid, which the error points at.
+    Invalid(CompileError),
+    /// The implementations of more than one trait for `ty` define the associated type `name`.
+    AmbiguousAssociatedType {
+        name: String,
+        ty: String,
+        traits: String,
+    },
+}
+
+impl TypeResolutionError {
+    /// The error to report for the type at `span`.
+    pub(crate) fn into_error(self, span: Span) -> CompileError {
+        match self {
+            TypeResolutionError::UnknownType => CompileError::UnknownType { span },
+            TypeResolutionError::Invalid(error) => error,
+            TypeResolutionError::AmbiguousAssociatedType { name, ty, traits } => {
+                CompileError::AmbiguousAssociatedItem {
+                    name,
+                    ty,
+                    traits,
+                    span,
+                }
+            }
+        }
+    }
+}
+
+/// The constants and types an implementation of a trait associates with the type it is for, like
+/// the `DECIMALS` in `impl Token for MyToken { const DECIMALS: u8 = 9; }`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AssociatedItems {
+    pub(crate) consts: Vec<TypedConstantDeclaration>,
+    pub(crate) types: Vec<(Ident, TypeId)>,
 }
 
 impl Namespace {
@@ -52,77 +98,301 @@ impl Namespace {
         &self,
         ty: TypeInfo,
         self_type: TypeId,
-    ) -> Result<TypeId, ()> {
+    ) -> Result<TypeId, TypeResolutionError> {
         Ok(match ty {
-            TypeInfo::Custom { ref name } => match self.get_symbol_by_str(name) {
-                Some(TypedDeclaration::StructDeclaration(TypedStructDeclaration {
-                    name,
-                    fields,
-                    ..
-                })) => crate::type_engine::insert_type(TypeInfo::Struct {
-                    name: name.as_str().to_string(),
-                    fields: fields
+            TypeInfo::Custom {
+                ref name,
+                ref type_arguments,
+            } => {
+                let resolve_type_arguments = || {
+                    type_arguments
                         .iter()
-                        .map(TypedStructField::as_owned_typed_struct_field)
-                        .collect::<Vec<_>>(),
-                }),
-                Some(TypedDeclaration::EnumDeclaration(TypedEnumDeclaration {
-                    name,
-                    variants,
-                    ..
-                })) => crate::type_engine::insert_type(TypeInfo::Enum {
-                    name: name.as_str().to_string(),
-                    variant_types: variants
+                        .map(|type_argument| {
+                            self.resolve_type_with_self(look_up_type_id(*type_argument), self_type)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                };
+                let resolve_generic_type_arguments = || {
+                    type_arguments
                         .iter()
-                        .map(TypedEnumVariant::as_owned_typed_enum_variant)
-                        .collect(),
-                }),
-                Some(TypedDeclaration::GenericTypeForFunctionScope { name, .. }) => {
-                    crate::type_engine::insert_type(TypeInfo::UnknownGeneric {
-                        name: name.as_str().to_string(),
-                    })
+                        .map(|type_argument| {
+                            match self.generic_type_argument(&look_up_type_id(*type_argument)) {
+                                Some(type_argument) => Ok(type_argument),
+                                None => self.resolve_type_with_self(
+                                    look_up_type_id(*type_argument),
+                                    self_type,
+                                ),
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                };
+                match self.get_symbol_by_str(name) {
+                    Some(TypedDeclaration::StructDeclaration(decl)) => decl
+                        .instantiate(resolve_generic_type_arguments()?)
+                        .ok_or(TypeResolutionError::UnknownType)?,
+                    Some(TypedDeclaration::EnumDeclaration(decl)) => decl
+                        .instantiate(resolve_generic_type_arguments()?)
+                        .ok_or(TypeResolutionError::UnknownType)?,
+                    Some(TypedDeclaration::GenericTypeForFunctionScope { name, .. }) => {
+                        crate::type_engine::insert_type(TypeInfo::UnknownGeneric {
+                            name: name.as_str().to_string(),
+                        })
+                    }
+                    Some(TypedDeclaration::TypeAliasDeclaration(decl)) => decl
+                        .instantiate(resolve_type_arguments()?)
+                        .ok_or(TypeResolutionError::UnknownType)?,
+                    _ => return Err(TypeResolutionError::UnknownType),
                 }
-                _ => return Err(()),
-            },
+            }
             TypeInfo::SelfType => self_type,
             TypeInfo::Ref(id) => id,
+            TypeInfo::StorageMap { key, value } => insert_type(TypeInfo::StorageMap {
+                key: self.resolve_type_with_self(look_up_type_id(key), self_type)?,
+                value: self.resolve_type_with_self(look_up_type_id(value), self_type)?,
+            }),
+            TypeInfo::UnresolvedArray { elem_type, length } => {
+                let elem_type =
+                    self.resolve_type_with_self(look_up_type_id(elem_type), self_type)?;
+                let length = self
+                    .array_length(&length)
+                    .map_err(TypeResolutionError::Invalid)?;
+                insert_type(TypeInfo::Array(elem_type, length))
+            }
+            TypeInfo::AssociatedType { parent, name } => {
+                let parent = self.resolve_type_with_self(look_up_type_id(parent), self_type)?;
+                match self.get_associated_type(&look_up_type_id(parent), &name) {
+                    Ok(Some(r#type)) => r#type,
+                    // within a trait, or a function generic over a type bounded by a trait, the
+                    // implementation which defines the type isn't known
+                    Ok(None)
+                        if matches!(
+                            look_up_type_id(parent),
+                            TypeInfo::SelfType | TypeInfo::UnknownGeneric { .. }
+                        ) =>
+                    {
+                        insert_type(TypeInfo::AssociatedType { parent, name })
+                    }
+                    Ok(None) => return Err(TypeResolutionError::UnknownType),
+                    Err(traits) => {
+                        return Err(TypeResolutionError::AmbiguousAssociatedType {
+                            name,
+                            ty: parent.friendly_type_str(),
+                            traits,
+                        })
+                    }
+                }
+            }
             o => insert_type(o),
         })
     }
 
     /// Used to resolve a type when there is no known self type. This is needed
-    /// when declaring new self types.
-    pub(crate) fn resolve_type_without_self(&self, ty: &TypeInfo) -> TypeId {
+    /// when declaring new self types. Errors are reported at `span`.
+    /// The type parameters of structs, enums and impls aren't declared in the namespace, so a
+    /// type argument like the `T` in a field of type `Option<T>` of `struct Wrapper<T>`, which
+    /// names nothing, is taken to be a generic type named by one of them.
+    fn generic_type_argument(&self, type_argument: &TypeInfo) -> Option<TypeId> {
+        match type_argument {
+            TypeInfo::Custom {
+                name,
+                type_arguments,
+            } if type_arguments.is_empty() && self.get_symbol_by_str(name).is_none() => {
+                Some(insert_type(TypeInfo::UnknownGeneric { name: name.clone() }))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn resolve_type_without_self(
+        &self,
+        ty: &TypeInfo,
+        span: &Span,
+    ) -> CompileResult<TypeId> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
         let ty = ty.clone();
-        match ty {
-            TypeInfo::Custom { name } => match self.get_symbol_by_str(&name) {
-                Some(TypedDeclaration::StructDeclaration(TypedStructDeclaration {
-                    name,
-                    fields,
-                    ..
-                })) => crate::type_engine::insert_type(TypeInfo::Struct {
-                    name: name.as_str().to_string(),
-                    fields: fields
-                        .iter()
-                        .map(TypedStructField::as_owned_typed_struct_field)
-                        .collect::<Vec<_>>(),
-                }),
-                Some(TypedDeclaration::EnumDeclaration(TypedEnumDeclaration {
-                    name,
-                    variants,
-                    ..
-                })) => crate::type_engine::insert_type(TypeInfo::Enum {
-                    name: name.as_str().to_string(),
-                    variant_types: variants
-                        .iter()
-                        .map(TypedEnumVariant::as_owned_typed_enum_variant)
-                        .collect(),
-                }),
-                _ => crate::type_engine::insert_type(TypeInfo::Unknown),
+        let type_id = match ty {
+            TypeInfo::Custom {
+                name,
+                type_arguments,
+            } => {
+                let mut resolved_type_arguments = vec![];
+                for type_argument in type_arguments {
+                    let type_argument = look_up_type_id(type_argument);
+                    resolved_type_arguments.push(
+                        match self.generic_type_argument(&type_argument) {
+                            Some(type_argument) => type_argument,
+                            None => check!(
+                                self.resolve_type_without_self(&type_argument, span),
+                                insert_type(TypeInfo::ErrorRecovery),
+                                warnings,
+                                errors
+                            ),
+                        },
+                    );
+                }
+                let type_id = match self.get_symbol_by_str(&name) {
+                    Some(TypedDeclaration::StructDeclaration(decl)) => {
+                        decl.instantiate(resolved_type_arguments)
+                    }
+                    Some(TypedDeclaration::EnumDeclaration(decl)) => {
+                        decl.instantiate(resolved_type_arguments)
+                    }
+                    Some(TypedDeclaration::TypeAliasDeclaration(decl)) => {
+                        decl.instantiate(resolved_type_arguments)
+                    }
+                    _ => None,
+                };
+                type_id.unwrap_or_else(|| insert_type(TypeInfo::Unknown))
+            }
+            TypeInfo::UnresolvedArray { elem_type, length } => match self.array_length(&length) {
+                Ok(length) => insert_type(TypeInfo::Array(
+                    check!(
+                        self.resolve_type_without_self(&look_up_type_id(elem_type), span),
+                        insert_type(TypeInfo::ErrorRecovery),
+                        warnings,
+                        errors
+                    ),
+                    length,
+                )),
+                Err(error) => {
+                    errors.push(error);
+                    insert_type(TypeInfo::ErrorRecovery)
+                }
             },
+            TypeInfo::AssociatedType { parent, name } => {
+                let parent = check!(
+                    self.resolve_type_without_self(&look_up_type_id(parent), span),
+                    insert_type(TypeInfo::ErrorRecovery),
+                    warnings,
+                    errors
+                );
+                match self.get_associated_type(&look_up_type_id(parent), &name) {
+                    Ok(r#type) => r#type.unwrap_or_else(|| insert_type(TypeInfo::Unknown)),
+                    Err(traits) => {
+                        errors.push(CompileError::AmbiguousAssociatedItem {
+                            name,
+                            ty: parent.friendly_type_str(),
+                            traits,
+                            span: span.clone(),
+                        });
+                        insert_type(TypeInfo::ErrorRecovery)
+                    }
+                }
+            }
             TypeInfo::Ref(id) => id,
             o => insert_type(o),
+        };
+        ok(type_id, warnings, errors)
+    }
+
+    /// Resolves the type an impl block is for. Type arguments, like the `T` in `impl<T>
+    /// MyStruct<T>`, take the place of the type parameters of the struct or enum declaration, so
+    /// that the type refers to the generic parameters of the impl block rather than those of the
+    /// declaration.
+    pub(crate) fn resolve_impl_type(
+        &self,
+        type_implementing_for: &TypeInfo,
+        type_arguments: &[TypeInfo],
+        type_parameters: &[TypeParameter],
+        span: &Span,
+    ) -> CompileResult<TypeId> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let type_id = check!(
+            self.resolve_type_without_self(type_implementing_for, span),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        if type_arguments.is_empty() {
+            return ok(type_id, warnings, errors);
         }
+        let decl_type_parameters = match type_implementing_for {
+            TypeInfo::Custom { name, .. } => match self.get_symbol_by_str(name) {
+                Some(TypedDeclaration::StructDeclaration(decl)) => decl.type_parameters.clone(),
+                Some(TypedDeclaration::EnumDeclaration(decl)) => decl.type_parameters.clone(),
+                // the type arguments of an alias were given to it when it was resolved
+                Some(TypedDeclaration::TypeAliasDeclaration(_)) => {
+                    return ok(type_id, warnings, errors)
+                }
+                _ => vec![],
+            },
+            _ => vec![],
+        };
+        if decl_type_parameters.len() != type_arguments.len() {
+            errors.push(CompileError::IncorrectNumberOfTypeArguments {
+                given: type_arguments.len(),
+                expected: decl_type_parameters.len(),
+                span: span.clone(),
+            });
+            return err(warnings, errors);
+        }
+        let type_mapping = decl_type_parameters
+            .into_iter()
+            .zip(type_arguments.iter())
+            .map(|(decl_param, type_argument)| {
+                let type_argument = match type_parameters
+                    .iter()
+                    .find(|param| param.name == *type_argument)
+                {
+                    Some(param) => insert_type(TypeInfo::UnknownGeneric {
+                        name: param.name_ident.as_str().to_string(),
+                    }),
+                    None => check!(
+                        self.resolve_type_without_self(type_argument, span),
+                        insert_type(TypeInfo::ErrorRecovery),
+                        warnings,
+                        errors
+                    ),
+                };
+                (decl_param, type_argument)
+            })
+            .collect::<Vec<_>>();
+        let type_id = look_up_type_id(type_id)
+            .matches_type_parameter(&type_mapping)
+            .unwrap_or(type_id);
+        ok(type_id, warnings, errors)
+    }
+
+    /// Resolves the type a type alias is for, in the scope where the alias is declared. Unlike in
+    /// other types, the types within a tuple or an array are resolved too, as they can't be
+    /// resolved anywhere else.
+    pub(crate) fn resolve_type_alias(
+        &self,
+        ty: TypeInfo,
+        self_type: TypeId,
+    ) -> Result<TypeId, TypeResolutionError> {
+        let resolve = |ty: TypeId| self.resolve_type_alias(look_up_type_id(ty), self_type);
+        Ok(match ty {
+            TypeInfo::Tuple(fields) => insert_type(TypeInfo::Tuple(
+                fields.into_iter().map(resolve).collect::<Result<_, _>>()?,
+            )),
+            TypeInfo::Array(elem_type, length) => {
+                insert_type(TypeInfo::Array(resolve(elem_type)?, length))
+            }
+            TypeInfo::UnresolvedArray { elem_type, length } => {
+                let length = self
+                    .array_length(&length)
+                    .map_err(TypeResolutionError::Invalid)?;
+                insert_type(TypeInfo::Array(resolve(elem_type)?, length))
+            }
+            ty => self.resolve_type_with_self(ty, self_type)?,
+        })
+    }
+
+    /// The value of the constant `name` in this scope, if it is a valid length for an array.
+    fn array_length(&self, name: &Ident) -> Result<usize, CompileError> {
+        let length = match self.get_symbol_by_str(name.as_str()) {
+            Some(TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
+                value, ..
+            })) => evaluate_array_length(value, self).value,
+            _ => None,
+        };
+        length.ok_or_else(|| CompileError::ArrayLengthNotConstant {
+            name: name.as_str().to_string(),
+            span: name.span().clone(),
+        })
     }
 
     pub(crate) fn insert(&mut self, name: Ident, item: TypedDeclaration) -> CompileResult<()> {
@@ -131,13 +401,16 @@ impl Namespace {
         if self.symbols.get(&name).is_some() {
             match item {
                 TypedDeclaration::EnumDeclaration { .. }
-                | TypedDeclaration::StructDeclaration { .. } => {
+                | TypedDeclaration::StructDeclaration { .. }
+                | TypedDeclaration::TypeAliasDeclaration { .. } => {
                     errors.push(CompileError::ShadowsOtherSymbol {
                         span: name.span().clone(),
                         name: name.as_str().to_string(),
                     });
                     return err(warnings, errors);
                 }
+                // the hidden variables of nested constructs shadow each other
+                _ if name.is_hidden() => (),
                 _ => {
                     warnings.push(CompileWarning {
                         span: name.span().clone(),
@@ -152,6 +425,45 @@ impl Namespace {
         ok((), warnings, errors)
     }
 
+    /// Records the storage declaration of a contract, of which there may only be one.
+    pub(crate) fn set_storage_declaration(
+        &mut self,
+        decl: TypedStorageDeclaration,
+    ) -> CompileResult<()> {
+        if self.declared_storage.is_some() {
+            return err(
+                vec![],
+                vec![CompileError::MultipleStorageDeclarations { span: decl.span }],
+            );
+        }
+        self.declared_storage = Some(decl);
+        ok((), vec![], vec![])
+    }
+
+    /// Looks up the storage field accessed by `storage.field_name`.
+    pub(crate) fn get_storage_field(
+        &self,
+        field_name: &Ident,
+        span: &Span,
+    ) -> CompileResult<&TypedStorageField> {
+        match &self.declared_storage {
+            Some(storage) => match storage.find_field(field_name) {
+                Some(field) => ok(field, vec![], vec![]),
+                None => err(
+                    vec![],
+                    vec![CompileError::StorageFieldDoesNotExist {
+                        field_name: field_name.as_str().to_string(),
+                        span: field_name.span().clone(),
+                    }],
+                ),
+            },
+            None => err(
+                vec![],
+                vec![CompileError::NoDeclaredStorage { span: span.clone() }],
+            ),
+        }
+    }
+
     // TODO(static span) remove this and switch to spans when we have arena spans
     pub(crate) fn get_symbol_by_str(&self, symbol: &str) -> Option<&TypedDeclaration> {
         let empty = vec![];
@@ -295,18 +607,7 @@ impl Namespace {
     ) -> CompileResult<()> {
         let mut warnings = vec![];
         let errors = vec![];
-        let new_prefixes = if trait_name.prefixes.is_empty() {
-            self.use_synonyms
-                .get(&trait_name.suffix)
-                .unwrap_or(&trait_name.prefixes)
-                .clone()
-        } else {
-            trait_name.prefixes
-        };
-        let trait_name = CallPath {
-            suffix: trait_name.suffix,
-            prefixes: new_prefixes,
-        };
+        let trait_name = self.qualify_trait_name(trait_name);
         if self
             .implemented_traits
             .insert((trait_name.clone(), type_implementing_for), functions_buf)
@@ -320,6 +621,95 @@ impl Namespace {
         ok((), warnings, errors)
     }
 
+    /// Inserts the constants and types an implementation of the trait `trait_name` associates
+    /// with `type_implementing_for`, replacing any inserted before.
+    pub(crate) fn insert_associated_items(
+        &mut self,
+        trait_name: CallPath,
+        type_implementing_for: TypeInfo,
+        items: AssociatedItems,
+    ) {
+        let trait_name = self.qualify_trait_name(trait_name);
+        self.associated_items
+            .insert((trait_name, type_implementing_for), items);
+    }
+
+    /// The associated constant `name` of `r#type`, if an implementation of a trait for it gave
+    /// one a value. If the implementations of more than one trait did, which of them is meant is
+    /// ambiguous, and the names of those traits are returned instead.
+    pub(crate) fn get_associated_const(
+        &self,
+        r#type: &TypeInfo,
+        name: &Ident,
+    ) -> Result<Option<&TypedConstantDeclaration>, String> {
+        self.find_associated_item(r#type, |items| {
+            items.consts.iter().find(|decl| decl.name == *name)
+        })
+    }
+
+    /// The associated type `name` of `r#type`, if an implementation of a trait for it defined one.
+    /// If the implementations of more than one trait did, which of them is meant is ambiguous,
+    /// and the names of those traits are returned instead.
+    pub(crate) fn get_associated_type(
+        &self,
+        r#type: &TypeInfo,
+        name: &str,
+    ) -> Result<Option<TypeId>, String> {
+        self.find_associated_item(r#type, |items| {
+            items
+                .types
+                .iter()
+                .find(|(type_name, _)| type_name.as_str() == name)
+                .map(|(_, r#type)| *r#type)
+        })
+    }
+
+    fn find_associated_item<'a, T>(
+        &'a self,
+        r#type: &TypeInfo,
+        find: impl Fn(&'a AssociatedItems) -> Option<T>,
+    ) -> Result<Option<T>, String> {
+        let mut found = self
+            .associated_items
+            .iter()
+            .filter(|((_trait_name, type_info), _items)| is_implemented_for(r#type, type_info))
+            .filter_map(|((trait_name, _), items)| Some((trait_name, find(items)?)))
+            .collect::<Vec<_>>();
+        if found.len() > 1 {
+            let mut traits = found
+                .iter()
+                .map(|(trait_name, _)| {
+                    trait_name
+                        .prefixes
+                        .iter()
+                        .chain(std::iter::once(&trait_name.suffix))
+                        .map(|ident| ident.as_str())
+                        .collect::<Vec<_>>()
+                        .join("::")
+                })
+                .collect::<Vec<_>>();
+            traits.sort();
+            return Err(traits.join(", "));
+        }
+        Ok(found.pop().map(|(_, item)| item))
+    }
+
+    /// Trait names without a path are qualified with the path they were imported from, if any.
+    fn qualify_trait_name(&self, trait_name: CallPath) -> CallPath {
+        let prefixes = if trait_name.prefixes.is_empty() {
+            self.use_synonyms
+                .get(&trait_name.suffix)
+                .unwrap_or(&trait_name.prefixes)
+                .clone()
+        } else {
+            trait_name.prefixes
+        };
+        CallPath {
+            suffix: trait_name.suffix,
+            prefixes,
+        }
+    }
+
     pub fn insert_module(&mut self, module_name: String, module_contents: Namespace) {
         self.modules.insert(module_name, module_contents);
     }
@@ -424,17 +814,72 @@ impl Namespace {
         ok((symbol, parent_rover), warnings, errors)
     }
 
+    /// Returns the methods of every impl block for `r#type`, including generic ones like `impl<T>
+    /// MyStruct<T>` when `r#type` is an instantiation of them.
     pub(crate) fn get_methods_for_type(&self, r#type: TypeId) -> Vec<TypedFunctionDeclaration> {
         let mut methods = vec![];
         let r#type = crate::type_engine::look_up_type_id(r#type);
         for ((_trait_name, type_info), l_methods) in &self.implemented_traits {
-            if *type_info == r#type {
+            if is_implemented_for(&r#type, type_info) {
                 methods.append(&mut l_methods.clone());
             }
         }
         methods
     }
 
+    /// Whether or not the trait at `trait_name` is implemented for `r#type`. Traits with the same
+    /// name declared in different modules are different traits.
+    pub(crate) fn implements_trait(&self, r#type: &TypeInfo, trait_name: &CallPath) -> bool {
+        let trait_name = self.qualify_trait_name(trait_name.clone());
+        self.implemented_traits
+            .keys()
+            .any(|(implemented_trait, type_info)| {
+                *implemented_trait == trait_name && is_implemented_for(r#type, type_info)
+            })
+    }
+
+    /// Checks that the types the type parameters in `type_mapping` were instantiated with
+    /// implement the traits in the parameters' `where` clause bounds. Types which aren't known
+    /// yet can't be checked, but numeric literals whose type isn't known yet will be `u64`, so
+    /// they are checked as one. A type parameter instantiated with the type parameter of an
+    /// enclosing generic function must be bounded by the same traits there.
+    pub(crate) fn check_trait_constraints(
+        &self,
+        type_mapping: &[(TypeParameter, TypeId)],
+        span: &Span,
+    ) -> CompileResult<()> {
+        let warnings = vec![];
+        let mut errors = vec![];
+        for (type_parameter, type_id) in type_mapping {
+            let r#type = match look_up_type_id(*type_id) {
+                TypeInfo::Numeric => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+                r#type => r#type,
+            };
+            let is_uninstantiated = matches!(
+                look_up_type_id_raw(*type_id),
+                TypeInfo::UnknownGeneric { .. }
+            );
+            if is_uninstantiated || matches!(r#type, TypeInfo::Unknown | TypeInfo::ErrorRecovery) {
+                continue;
+            }
+            for constraint in &type_parameter.trait_constraints {
+                if !self.implements_trait(&r#type, &constraint.name.clone().into()) {
+                    errors.push(CompileError::TraitConstraintNotSatisfied {
+                        ty: r#type.friendly_type_str(),
+                        trait_name: constraint.name.clone(),
+                        type_parameter: type_parameter.name_ident.clone(),
+                        span: span.clone(),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            ok((), warnings, errors)
+        } else {
+            err(warnings, errors)
+        }
+    }
+
     /// given a declaration that may refer to a variable which contains a struct,
     /// find that struct's fields and name for use in determining if a subfield expression is valid
     /// e.g. foo.bar.baz
@@ -521,6 +966,8 @@ impl Namespace {
         let implemented_traits = namespace.implemented_traits.clone();
         self.implemented_traits
             .extend(&mut implemented_traits.into_iter());
+        let associated_items = namespace.associated_items.clone();
+        self.associated_items.extend(associated_items.into_iter());
         for symbol in symbols {
             self.use_synonyms.insert(symbol, path.clone());
         }
@@ -548,6 +995,7 @@ impl Namespace {
             errors
         );
         let mut impls_to_insert = vec![];
+        let mut associated_items_to_insert = vec![];
 
         match namespace.symbols.get(item) {
             Some(decl) => {
@@ -568,6 +1016,15 @@ impl Namespace {
                     .for_each(|(a, b)| {
                         impls_to_insert.push((a.clone(), b.to_vec()));
                     });
+                namespace
+                    .associated_items
+                    .iter()
+                    .filter(|((_trait_name, type_info), _items)| {
+                        a.map(look_up_type_id).as_ref() == Some(type_info)
+                    })
+                    .for_each(|(a, b)| {
+                        associated_items_to_insert.push((a.clone(), b.clone()));
+                    });
                 // no matter what, import it this way though.
                 match alias {
                     Some(alias) => {
@@ -592,6 +1049,8 @@ impl Namespace {
         impls_to_insert.into_iter().for_each(|(a, b)| {
             self.implemented_traits.insert(a, b);
         });
+        self.associated_items
+            .extend(associated_items_to_insert.into_iter());
 
         ok((), warnings, errors)
     }
@@ -627,10 +1086,8 @@ impl Namespace {
         // but if nothing turns up then we try the namespace where the type itself is declared.
         let r#type = namespace
             .resolve_type_with_self(look_up_type_id(r#type), self_type)
-            .unwrap_or_else(|_| {
-                errors.push(CompileError::UnknownType {
-                    span: method_name.span().clone(),
-                });
+            .unwrap_or_else(|error| {
+                errors.push(error.into_error(method_name.span().clone()));
                 insert_type(TypeInfo::ErrorRecovery)
             });
         let local_methods = self.get_methods_for_type(r#type);
@@ -638,6 +1095,21 @@ impl Namespace {
 
         let mut methods = local_methods;
         methods.append(&mut ns_methods);
+        if let TypeInfo::StorageMap { key, value } = look_up_type_id(r#type) {
+            methods.append(&mut storage_map_methods(
+                r#type,
+                key,
+                value,
+                method_name.span(),
+            ));
+        }
+        if let TypeInfo::SignedInteger(bits) = look_up_type_id(r#type) {
+            methods.append(&mut signed_integer_methods(
+                r#type,
+                bits,
+                method_name.span(),
+            ));
+        }
 
         match methods
             .into_iter()
@@ -658,4 +1130,100 @@ impl Namespace {
             }
         }
     }
+
+    /// Finds the method `method_name` of the implementation of the trait at `trait_name` for
+    /// `r#type`. Methods of the same name from other impl blocks aren't considered.
+    pub(crate) fn find_trait_method_for_type(
+        &self,
+        r#type: TypeId,
+        trait_name: &CallPath,
+        method_name: &Ident,
+    ) -> CompileResult<TypedFunctionDeclaration> {
+        let warnings = vec![];
+        let mut errors = vec![];
+        let trait_name = self.qualify_trait_name(trait_name.clone());
+        let type_info = look_up_type_id(r#type);
+        let method = self
+            .implemented_traits
+            .iter()
+            .filter(|((implemented_trait, type_info_implementing_for), _)| {
+                *implemented_trait == trait_name
+                    && is_implemented_for(&type_info, type_info_implementing_for)
+            })
+            .flat_map(|(_, methods)| methods.iter())
+            .find(|TypedFunctionDeclaration { name, .. }| name == method_name);
+        match method {
+            Some(method) => ok(method.clone(), warnings, errors),
+            None => {
+                errors.push(CompileError::MethodNotFound {
+                    method_name: method_name.as_str().to_string(),
+                    type_name: r#type.friendly_type_str(),
+                    span: method_name.span().clone(),
+                });
+                err(warnings, errors)
+            }
+        }
+    }
+
+    /// Finds the method an operator applied to a value of type `r#type` dispatches to, which is
+    /// the method `method_name` of the implementation of one of the operator's traits for the
+    /// type. Methods of the same name from other impl blocks aren't considered.
+    pub(crate) fn find_operator_method_for_type(
+        &self,
+        r#type: TypeId,
+        method_name: &Ident,
+        method_path: &[Ident],
+        operator: OverloadableOperator,
+        from_module: Option<&Namespace>,
+        args_buf: &VecDeque<TypedExpression>,
+    ) -> CompileResult<TypedFunctionDeclaration> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let base_module = match from_module {
+            Some(base_module) => base_module,
+            None => self,
+        };
+        let namespace = check!(
+            base_module.find_module_relative(method_path),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let type_info = look_up_type_id(r#type);
+        let method = [self, namespace]
+            .iter()
+            .flat_map(|namespace| namespace.implemented_traits.iter())
+            .filter(|((trait_name, type_info_implementing_for), _)| {
+                operator.trait_names.contains(&trait_name.suffix.as_str())
+                    && is_implemented_for(&type_info, type_info_implementing_for)
+            })
+            .flat_map(|(_, methods)| methods.iter())
+            .find(|TypedFunctionDeclaration { name, .. }| name == method_name);
+        match method {
+            Some(method) => ok(method.clone(), warnings, errors),
+            None => {
+                if args_buf.get(0).map(|x| look_up_type_id(x.return_type))
+                    != Some(TypeInfo::ErrorRecovery)
+                {
+                    errors.push(CompileError::OperatorNotImplemented {
+                        operator: operator.symbol,
+                        trait_name: operator.trait_names[0],
+                        ty: r#type.friendly_type_str(),
+                        span: method_name.span().clone(),
+                    });
+                }
+                err(warnings, errors)
+            }
+        }
+    }
+}
+
+/// Whether or not an implementation for `implementing_for` applies to `r#type`. The placeholder
+/// methods of the trait bounds of a generic type parameter are implemented for exactly that
+/// parameter, rather than for any type.
+fn is_implemented_for(r#type: &TypeInfo, implementing_for: &TypeInfo) -> bool {
+    match implementing_for {
+        TypeInfo::UnknownGeneric { .. } => r#type == implementing_for,
+        _ => r#type.is_instance_of(implementing_for),
+    }
 }
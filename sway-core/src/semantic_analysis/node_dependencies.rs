@@ -2,108 +2,210 @@ use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use crate::{
-    error::*, ident::Ident, parse_tree::Scrutinee, parse_tree::*, span::Span,
-    type_engine::IntegerBits, AstNode, AstNodeContent, CodeBlock, Declaration, Expression,
-    ReturnStatement, TypeInfo, WhileLoop,
+    error::*,
+    ident::Ident,
+    parse_tree::Scrutinee,
+    parse_tree::*,
+    span::Span,
+    type_engine::{look_up_type_id, IntegerBits},
+    AstNode, AstNodeContent, CodeBlock, Declaration, Expression, ReturnStatement, TypeInfo,
+    WhileLoop,
 };
 
 // -------------------------------------------------------------------------------------------------
 /// Take a list of nodes and reorder them so that they may be semantically analysed without any
 /// dependencies breaking.
 
-pub(crate) fn order_ast_nodes_by_dependency(nodes: Vec<AstNode>) -> CompileResult<Vec<AstNode>> {
-    let decl_dependencies =
+pub(crate) fn order_ast_nodes_by_dependency(
+    nodes: Vec<AstNode>,
+) -> CompileResult<(Vec<AstNode>, Vec<Vec<Ident>>)> {
+    let mut decl_dependencies =
         DependencyMap::from_iter(nodes.iter().filter_map(Dependencies::gather_from_decl_node));
+    gather_supertrait_impls(&nodes, &mut decl_dependencies);
 
     // Check here for recursive calls now that we have a nice map of the dependencies to help us.
-    let mut errors = find_recursive_calls(&decl_dependencies);
+    let recursive_fns = find_mutually_recursive_fns(&decl_dependencies);
+    let mut errors = find_unsupported_recursion(&nodes, &recursive_fns);
     if !errors.is_empty() {
         // Because we're pulling these errors out of a HashMap they'll probably be in a funny
         // order.  Here we'll sort them by span start.
         errors.sort_by(|lhs, rhs| lhs.span().0.cmp(&rhs.span().0));
         err(Vec::new(), errors)
     } else {
+        group_recursive_fn_dependencies(&mut decl_dependencies, &recursive_fns);
+
         // Reorder the parsed AstNodes based on dependency.  Includes first, then uses, then
         // reordered declarations, then anything else.  To keep the list stable and simple we can
         // use a basic insertion sort.
-        ok(
-            nodes
+        let ordered_nodes = nodes
+            .into_iter()
+            .fold(Vec::<AstNode>::new(), |ordered, node| {
+                insert_into_ordered_nodes(&decl_dependencies, ordered, node)
+            });
+        let recursive_fns = recursive_fns
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .filter_map(|fn_sym| match fn_sym {
+                        DependentSymbol::Fn(name, _) => Some(name),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        ok((ordered_nodes, recursive_fns), Vec::new(), Vec::new())
+    }
+}
+
+// An impl of a trait must come after the impls of its supertraits for the same type, as long as
+// the trait is declared in the same module.  The supertraits aren't known from the impl itself, so
+// these dependencies are added once all the declarations have been gathered.
+
+fn gather_supertrait_impls(nodes: &[AstNode], decl_dependencies: &mut DependencyMap) {
+    let supertraits =
+        HashMap::<&Ident, &Vec<CallPath>>::from_iter(nodes.iter().filter_map(|node| {
+            match &node.content {
+                AstNodeContent::Declaration(Declaration::TraitDeclaration(TraitDeclaration {
+                    name,
+                    supertraits,
+                    ..
+                }))
+                | AstNodeContent::Declaration(Declaration::AbiDeclaration(AbiDeclaration {
+                    name,
+                    supertraits,
+                    ..
+                })) => Some((name, supertraits)),
+                _ => None,
+            }
+        }));
+    for (dep_sym, deps) in decl_dependencies.iter_mut() {
+        if let DependentSymbol::Impl(trait_name, type_name) = dep_sym {
+            for supertrait in supertraits
+                .get(trait_name)
                 .into_iter()
-                .fold(Vec::<AstNode>::new(), |ordered, node| {
-                    insert_into_ordered_nodes(&decl_dependencies, ordered, node)
-                }),
-            Vec::new(),
-            Vec::new(),
-        )
+                .flat_map(|s| s.iter())
+            {
+                if supertrait.prefixes.is_empty() {
+                    deps.deps.insert(DependentSymbol::Impl(
+                        supertrait.suffix.clone(),
+                        type_name.clone(),
+                    ));
+                }
+            }
+        }
     }
 }
 
 // -------------------------------------------------------------------------------------------------
 // Recursion detection.
 
-fn find_recursive_calls(decl_dependencies: &DependencyMap) -> Vec<CompileError> {
-    decl_dependencies
-        .iter()
-        .filter_map(|(dep_sym, _)| find_recursive_call(decl_dependencies, dep_sym))
-        .collect()
-}
+// Functions which call each other, directly or indirectly, are grouped together.  Each group is
+// sorted by span start.  Immediate recursion doesn't form a group, as a function can see its own
+// signature while it's being type checked.
 
-fn find_recursive_call(
-    decl_dependencies: &DependencyMap,
-    fn_sym: &DependentSymbol,
-) -> Option<CompileError> {
-    if let DependentSymbol::Fn(_, Some(fn_span)) = fn_sym {
-        let mut chain = Vec::new();
-        find_recursive_call_chain(decl_dependencies, fn_sym, fn_span, &mut chain)
-    } else {
-        None
+fn find_mutually_recursive_fns(decl_dependencies: &DependencyMap) -> Vec<Vec<DependentSymbol>> {
+    let reachable = HashMap::<&DependentSymbol, HashSet<&DependentSymbol>>::from_iter(
+        decl_dependencies
+            .keys()
+            .filter(|dep_sym| matches!(dep_sym, DependentSymbol::Fn(_, Some(_))))
+            .map(|fn_sym| (fn_sym, find_reachable_symbols(decl_dependencies, fn_sym))),
+    );
+    let mut grouped = HashSet::new();
+    let mut groups = Vec::new();
+    for (&fn_sym, reached) in &reachable {
+        if grouped.contains(fn_sym) {
+            continue;
+        }
+        let mut group = reached
+            .iter()
+            .filter_map(|dep_sym| reachable.get_key_value(*dep_sym))
+            .filter(|(other_sym, other_reached)| {
+                ***other_sym != *fn_sym && other_reached.contains(fn_sym)
+            })
+            .map(|(other_sym, _)| *other_sym)
+            .collect::<Vec<_>>();
+        if group.is_empty() {
+            continue;
+        }
+        group.push(fn_sym);
+        group.sort_by_key(|fn_sym| match fn_sym {
+            DependentSymbol::Fn(_, Some(span)) => span.start(),
+            _ => 0,
+        });
+        grouped.extend(group.iter().cloned());
+        groups.push(group.into_iter().cloned().collect());
     }
+    groups
 }
 
-fn find_recursive_call_chain(
-    decl_dependencies: &DependencyMap,
-    fn_sym: &DependentSymbol,
-    fn_span: &Span,
-    chain: &mut Vec<Ident>,
-) -> Option<CompileError> {
-    if let DependentSymbol::Fn(fn_sym_ident, _) = fn_sym {
-        if chain.iter().any(|seen_sym| seen_sym == fn_sym_ident) {
-            // We've found a recursive loop, but it's possible this function is not actually in the
-            // loop, but is instead just calling into the loop.  Only if this function is at the
-            // start of the chain do we need to report it.
-            return if &chain[0] != fn_sym_ident {
-                None
-            } else {
-                Some(build_recursion_error(
-                    fn_sym_ident.clone(),
-                    fn_span.clone(),
-                    &chain[1..],
-                ))
-            };
+fn find_reachable_symbols<'a>(
+    decl_dependencies: &'a DependencyMap,
+    dep_sym: &DependentSymbol,
+) -> HashSet<&'a DependentSymbol> {
+    let mut reached = HashSet::new();
+    let mut to_visit = decl_dependencies
+        .get(dep_sym)
+        .map(|deps_set| deps_set.deps.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    while let Some(dep_sym) = to_visit.pop() {
+        if reached.insert(dep_sym) {
+            if let Some(deps_set) = decl_dependencies.get(dep_sym) {
+                to_visit.extend(deps_set.deps.iter());
+            }
         }
-        decl_dependencies
-            .get(fn_sym)
-            .map(|deps_set| {
-                chain.push(fn_sym_ident.clone());
-                let result = deps_set.deps.iter().find_map(|dep_sym| {
-                    find_recursive_call_chain(decl_dependencies, dep_sym, fn_span, chain)
-                });
-                chain.pop();
-                result
-            })
-            .flatten()
-    } else {
-        None
     }
+    reached
+}
+
+// A call to a generic function is type checked against a copy of the function with its type
+// parameters replaced, which can't be made until the function is declared, so generic functions
+// can't yet be mutually recursive.
+
+fn find_unsupported_recursion(
+    nodes: &[AstNode],
+    recursive_fns: &[Vec<DependentSymbol>],
+) -> Vec<CompileError> {
+    let generic_fns =
+        HashSet::<&Ident>::from_iter(nodes.iter().filter_map(|node| match &node.content {
+            AstNodeContent::Declaration(Declaration::FunctionDeclaration(fn_decl))
+                if !fn_decl.type_parameters.is_empty() =>
+            {
+                Some(&fn_decl.name)
+            }
+            _ => None,
+        }));
+    recursive_fns
+        .iter()
+        .filter(|group| {
+            group.iter().any(|fn_sym| {
+                matches!(fn_sym, DependentSymbol::Fn(name, _) if generic_fns.contains(name))
+            })
+        })
+        .flat_map(|group| {
+            group.iter().filter_map(move |fn_sym| match fn_sym {
+                DependentSymbol::Fn(name, Some(span)) => {
+                    let chain = group
+                        .iter()
+                        .filter_map(|other_sym| match other_sym {
+                            DependentSymbol::Fn(other_name, _) if other_name != name => {
+                                Some(other_name.clone())
+                            }
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    Some(build_recursion_error(name.clone(), span.clone(), &chain))
+                }
+                _ => None,
+            })
+        })
+        .collect()
 }
 
 fn build_recursion_error(fn_sym: Ident, span: Span, chain: &[Ident]) -> CompileError {
     match chain.len() {
-        // An empty chain indicates immediate recursion.
-        0 => CompileError::RecursiveCall {
-            fn_name: fn_sym,
-            span,
-        },
+        // An empty chain indicates immediate recursion, which is never grouped.
+        0 => unreachable!("immediate recursion is not an error"),
         // Chain entries indicate mutual recursion.
         1 => CompileError::RecursiveCallChain {
             fn_name: fn_sym,
@@ -127,6 +229,33 @@ fn build_recursion_error(fn_sym: Ident, span: Span, chain: &[Ident]) -> CompileE
     }
 }
 
+// The functions in a group are type checked against each other's signatures, which must all be
+// resolvable by the time the first of them is type checked.  So each function in a group depends
+// on whatever the others depend on, and anything which depends on one of them depends on all of
+// them.
+
+fn group_recursive_fn_dependencies(
+    decl_dependencies: &mut DependencyMap,
+    recursive_fns: &[Vec<DependentSymbol>],
+) {
+    for group in recursive_fns {
+        let group_deps = HashSet::<DependentSymbol>::from_iter(
+            group
+                .iter()
+                .filter_map(|fn_sym| decl_dependencies.get(fn_sym))
+                .flat_map(|deps_set| deps_set.deps.iter().cloned())
+                .chain(group.iter().cloned()),
+        );
+        for (dep_sym, deps_set) in decl_dependencies.iter_mut() {
+            if group.contains(dep_sym) {
+                deps_set.deps.extend(group_deps.iter().cloned());
+            } else if group.iter().any(|fn_sym| deps_set.deps.contains(fn_sym)) {
+                deps_set.deps.extend(group.iter().cloned());
+            }
+        }
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 // Dependency gathering.
 
@@ -253,8 +382,13 @@ impl Dependencies {
                 interface_surface,
                 methods,
                 type_parameters,
+                supertraits,
+                associated_consts,
                 ..
             }) => self
+                .gather_from_iter(supertraits.iter(), |deps, supertrait| {
+                    deps.gather_from_call_path(supertrait, false, false)
+                })
                 .gather_from_iter(interface_surface.iter(), |deps, sig| {
                     deps.gather_from_iter(sig.parameters.iter(), |deps, param| {
                         deps.gather_from_typeinfo(&param.r#type)
@@ -264,27 +398,51 @@ impl Dependencies {
                 .gather_from_iter(methods.iter(), |deps, fn_decl| {
                     deps.gather_from_fn_decl(fn_decl)
                 })
+                .gather_from_iter(associated_consts.iter(), |deps, trait_const| {
+                    deps.gather_from_typeinfo(&trait_const.r#type)
+                })
                 .gather_from_traits(type_parameters),
             Declaration::ImplTrait(ImplTrait {
                 trait_name,
                 type_implementing_for,
+                type_implementing_for_arguments,
+                type_parameters,
                 type_arguments,
                 functions,
+                consts,
+                associated_types,
                 ..
             }) => self
                 .gather_from_call_path(trait_name, false, false)
                 .gather_from_typeinfo(type_implementing_for)
+                .gather_from_iter(
+                    type_implementing_for_arguments.iter(),
+                    |deps, type_argument| deps.gather_from_typeinfo(type_argument),
+                )
+                .gather_from_traits(type_parameters)
                 .gather_from_traits(type_arguments)
                 .gather_from_iter(functions.iter(), |deps, fn_decl| {
                     deps.gather_from_fn_decl(fn_decl)
+                })
+                .gather_from_iter(consts.iter(), |deps, const_decl| {
+                    deps.gather_from_typeinfo(&const_decl.type_ascription)
+                        .gather_from_expr(&const_decl.value)
+                })
+                .gather_from_iter(associated_types.iter(), |deps, definition| {
+                    deps.gather_from_typeinfo(&definition.r#type)
                 }),
             Declaration::ImplSelf(ImplSelf {
                 type_implementing_for,
+                type_implementing_for_arguments,
                 type_arguments,
                 functions,
                 ..
             }) => self
                 .gather_from_typeinfo(type_implementing_for)
+                .gather_from_iter(
+                    type_implementing_for_arguments.iter(),
+                    |deps, type_argument| deps.gather_from_typeinfo(type_argument),
+                )
                 .gather_from_traits(type_arguments)
                 .gather_from_iter(functions.iter(), |deps, fn_decl| {
                     deps.gather_from_fn_decl(fn_decl)
@@ -292,8 +450,13 @@ impl Dependencies {
             Declaration::AbiDeclaration(AbiDeclaration {
                 interface_surface,
                 methods,
+                supertraits,
+                associated_consts,
                 ..
             }) => self
+                .gather_from_iter(supertraits.iter(), |deps, supertrait| {
+                    deps.gather_from_call_path(supertrait, false, false)
+                })
                 .gather_from_iter(interface_surface.iter(), |deps, sig| {
                     deps.gather_from_iter(sig.parameters.iter(), |deps, param| {
                         deps.gather_from_typeinfo(&param.r#type)
@@ -302,6 +465,9 @@ impl Dependencies {
                 })
                 .gather_from_iter(methods.iter(), |deps, fn_decl| {
                     deps.gather_from_fn_decl(fn_decl)
+                })
+                .gather_from_iter(associated_consts.iter(), |deps, trait_const| {
+                    deps.gather_from_typeinfo(&trait_const.r#type)
                 }),
             Declaration::StorageDeclaration(StorageDeclaration { fields, .. }) => self
                 .gather_from_iter(
@@ -316,6 +482,9 @@ impl Dependencies {
                             .gather_from_expr(initializer)
                     },
                 ),
+            Declaration::TypeAliasDeclaration(TypeAliasDeclaration { r#type, .. }) => {
+                self.gather_from_typeinfo(r#type)
+            }
         }
     }
 
@@ -337,7 +506,13 @@ impl Dependencies {
 
     fn gather_from_expr(mut self, expr: &Expression) -> Self {
         match expr {
-            Expression::VariableExpression { .. } => self,
+            Expression::VariableExpression { name, .. } => {
+                if name.as_str() == "storage" {
+                    self.deps
+                        .insert(DependentSymbol::Symbol("storage".to_string()));
+                }
+                self
+            }
             Expression::FunctionApplication {
                 name, arguments, ..
             } => self
@@ -362,6 +537,9 @@ impl Dependencies {
             Expression::Array { contents, .. } => {
                 self.gather_from_iter(contents.iter(), |deps, expr| deps.gather_from_expr(expr))
             }
+            Expression::ArrayRepeat { value, count, .. } => {
+                self.gather_from_expr(value).gather_from_expr(count)
+            }
             Expression::ArrayIndex { prefix, index, .. } => {
                 self.gather_from_expr(prefix).gather_from_expr(index)
             }
@@ -403,6 +581,7 @@ impl Dependencies {
                             deps.gather_from_scrutinee(scrutinee)
                         }
                     }
+                    .gather_from_opt_expr(&branch.guard)
                     .gather_from_expr(&branch.result)
                 },
             ),
@@ -414,6 +593,7 @@ impl Dependencies {
             Expression::Tuple { fields, .. } => {
                 self.gather_from_iter(fields.iter(), |deps, field| deps.gather_from_expr(field))
             }
+            Expression::TryExpression { expr, .. } => self.gather_from_expr(expr),
             Expression::DelayedMatchTypeResolution { .. } => self,
         }
     }
@@ -446,10 +626,20 @@ impl Dependencies {
             AstNodeContent::WhileLoop(WhileLoop { condition, body }) => {
                 self.gather_from_expr(condition).gather_from_block(body)
             }
+            AstNodeContent::ForLoop(ForLoop { iterable, body, .. }) => match iterable {
+                ForLoopIterable::Range { start, end } => self
+                    .gather_from_expr(start)
+                    .gather_from_expr(end)
+                    .gather_from_block(body),
+                ForLoopIterable::Array(array) => {
+                    self.gather_from_expr(array).gather_from_block(body)
+                }
+            },
 
             // No deps from these guys.
             AstNodeContent::UseStatement(_) => self,
             AstNodeContent::IncludeStatement(_) => self,
+            AstNodeContent::Break | AstNodeContent::Continue => self,
         }
     }
 
@@ -488,10 +678,35 @@ impl Dependencies {
     }
 
     fn gather_from_typeinfo(mut self, type_info: &TypeInfo) -> Self {
-        if let TypeInfo::Custom { name } = type_info {
-            self.deps.insert(DependentSymbol::Symbol(name.clone()));
+        match type_info {
+            TypeInfo::Custom {
+                name,
+                type_arguments,
+            } => {
+                self.deps.insert(DependentSymbol::Symbol(name.clone()));
+                self.gather_from_iter(type_arguments.iter(), |deps, type_argument| {
+                    deps.gather_from_typeinfo(&look_up_type_id(*type_argument))
+                })
+            }
+            TypeInfo::Tuple(fields) => self.gather_from_iter(fields.iter(), |deps, field| {
+                deps.gather_from_typeinfo(&look_up_type_id(*field))
+            }),
+            TypeInfo::Array(elem_type, _) => {
+                self.gather_from_typeinfo(&look_up_type_id(*elem_type))
+            }
+            TypeInfo::StorageMap { key, value } => self
+                .gather_from_typeinfo(&look_up_type_id(*key))
+                .gather_from_typeinfo(&look_up_type_id(*value)),
+            TypeInfo::UnresolvedArray { elem_type, length } => {
+                self.deps
+                    .insert(DependentSymbol::Symbol(length.as_str().to_string()));
+                self.gather_from_typeinfo(&look_up_type_id(*elem_type))
+            }
+            TypeInfo::AssociatedType { parent, .. } => {
+                self.gather_from_typeinfo(&look_up_type_id(*parent))
+            }
+            _ => self,
         }
-        self
     }
 
     fn gather_from_iter<I: Iterator, F: FnMut(Self, I::Item) -> Self>(self, iter: I, f: F) -> Self {
@@ -508,7 +723,7 @@ impl Dependencies {
 // they themselves depend on other declarations, no declarations depend on them.  This is
 // illustrated in DependentSymbol::is().
 
-#[derive(Debug, Eq)]
+#[derive(Clone, Debug, Eq)]
 enum DependentSymbol {
     Symbol(String),
     Fn(Ident, Option<Span>),
@@ -562,6 +777,7 @@ fn decl_name(decl: &Declaration) -> Option<DependentSymbol> {
         Declaration::EnumDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
         Declaration::TraitDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
         Declaration::AbiDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
+        Declaration::TypeAliasDeclaration(decl) => dep_sym(decl.name.as_str().to_string()),
 
         // These have the added complexity of converting CallPath and/or TypeInfo into a name.
         Declaration::ImplSelf(decl) => {
@@ -579,8 +795,9 @@ fn decl_name(decl: &Declaration) -> Option<DependentSymbol> {
         // These don't have declaration dependencies.
         Declaration::VariableDeclaration(_) => None,
         Declaration::Reassignment(_) => None,
-        // Storage cannot be depended upon or exported
-        Declaration::StorageDeclaration(_) => None,
+        // Storage is depended upon by anything which accesses `storage.field`, but can't be
+        // exported.
+        Declaration::StorageDeclaration(_) => dep_sym("storage".to_string()),
     }
 }
 
@@ -595,8 +812,14 @@ fn type_info_name(type_info: &TypeInfo) -> String {
             IntegerBits::ThirtyTwo => "uint32",
             IntegerBits::SixtyFour => "uint64",
         },
+        TypeInfo::SignedInteger(n) => match n {
+            IntegerBits::Eight => "int8",
+            IntegerBits::Sixteen => "int16",
+            IntegerBits::ThirtyTwo => "int32",
+            IntegerBits::SixtyFour => "int64",
+        },
         TypeInfo::Boolean => "bool",
-        TypeInfo::Custom { name } => name,
+        TypeInfo::Custom { name, .. } => name,
         TypeInfo::Tuple(fields) if fields.is_empty() => "unit",
         TypeInfo::Tuple(..) => "tuple",
         TypeInfo::SelfType => "self",
@@ -611,7 +834,10 @@ fn type_info_name(type_info: &TypeInfo) -> String {
         TypeInfo::ContractCaller { .. } => "contract caller",
         TypeInfo::Struct { .. } => "struct",
         TypeInfo::Enum { .. } => "enum",
-        TypeInfo::Array(..) => "array",
+        TypeInfo::Array(..) | TypeInfo::UnresolvedArray { .. } => "array",
+        TypeInfo::StorageMap { .. } => "storage map",
+        TypeInfo::AssociatedType { name, .. } => return format!("associated type {}", name),
+        TypeInfo::Alias { name, .. } => name,
     }
     .to_string()
 }
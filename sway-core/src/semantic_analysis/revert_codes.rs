@@ -0,0 +1,80 @@
+//! The places where a program reverts with a code, which are listed in the build output so that the
+//! code a transaction reverted with can be traced back to the source off-chain.
+
+use super::ast_node::TypedExpressionVariant;
+use super::visit::{walk_expression, Visit};
+use super::{TypedExpression, TypedParseTree};
+use crate::span::Span;
+use sway_types::{Position, Range, RevertCode};
+
+impl TypedParseTree {
+    /// The revert codes of the functions which the entry points of the program can call, wherever
+    /// those functions are declared. A library has no entry points, so it has none.
+    pub(crate) fn revert_codes(&self) -> Vec<RevertCode> {
+        let entry_points = match self {
+            TypedParseTree::Script { main_function, .. }
+            | TypedParseTree::Predicate { main_function, .. } => vec![main_function],
+            TypedParseTree::Contract { abi_entries, .. } => abi_entries.iter().collect(),
+            TypedParseTree::Library { .. } => vec![],
+        };
+        let mut gatherer = RevertCodes { codes: vec![] };
+        for function in entry_points {
+            gatherer.visit_code_block(&function.body);
+        }
+        gatherer.codes
+    }
+}
+
+/// Functions are gathered from where they are called, so the walk goes into the body of each
+/// called function.
+struct RevertCodes {
+    codes: Vec<RevertCode>,
+}
+
+impl<'a> Visit<'a> for RevertCodes {
+    fn visit_expression(&mut self, expr: &'a TypedExpression) {
+        walk_expression(self, expr);
+        match &expr.expression {
+            TypedExpressionVariant::FunctionApplication {
+                function_body,
+                selector,
+                ..
+            } => {
+                // the body of a contract call runs in the contract being called
+                match selector {
+                    Some(metadata) => self.visit_expression(&metadata.contract_address),
+                    None => self.visit_code_block(function_body),
+                }
+            }
+            TypedExpressionVariant::Revert { code } => {
+                // a function which is called more than once is gathered from each time
+                let code = revert_code(*code, &expr.span);
+                if !self.codes.contains(&code) {
+                    self.codes.push(code);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn revert_code(code: u64, span: &Span) -> RevertCode {
+    let (start_line, start_col) = span.start_pos().line_col();
+    // the end of a span is just past its last character, which the range ends at
+    let (end_line, end_col) = span.end_pos().line_col();
+    RevertCode {
+        code,
+        path: span.path.as_deref().cloned(),
+        range: Range {
+            start: Position {
+                line: start_line,
+                col: start_col,
+            },
+            end: Position {
+                line: end_line,
+                col: end_col - 1,
+            },
+        },
+        source: span.as_str().to_string(),
+    }
+}
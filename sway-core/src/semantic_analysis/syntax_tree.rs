@@ -7,7 +7,7 @@ use crate::parse_tree::Purity;
 use crate::semantic_analysis::{ast_node::Mode, Namespace, TypeCheckArguments};
 use crate::span::Span;
 use crate::{error::*, type_engine::*};
-use crate::{AstNode, ParseTree};
+use crate::{AstNode, AstNodeContent, Declaration, FunctionDeclaration, ParseTree};
 
 use std::collections::{HashMap, HashSet};
 
@@ -81,7 +81,7 @@ impl TypedParseTree {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
 
-        let ordered_nodes = check!(
+        let (ordered_nodes, recursive_fns) = check!(
             node_dependencies::order_ast_nodes_by_dependency(parsed.root_nodes),
             return err(warnings, errors),
             warnings,
@@ -90,6 +90,7 @@ impl TypedParseTree {
         let typed_nodes = check!(
             TypedParseTree::type_check_nodes(
                 ordered_nodes,
+                &recursive_fns,
                 &mut new_namespace,
                 build_config,
                 dead_code_graph,
@@ -110,8 +111,11 @@ impl TypedParseTree {
         )
     }
 
+    /// Type checks `nodes` in order. `recursive_fns` are the names of the groups of functions
+    /// which call each other, see [node_dependencies::order_ast_nodes_by_dependency].
     fn type_check_nodes(
         nodes: Vec<AstNode>,
+        recursive_fns: &[Vec<Ident>],
         namespace: &mut Namespace,
         build_config: &BuildConfig,
         dead_code_graph: &mut ControlFlowGraph,
@@ -119,9 +123,14 @@ impl TypedParseTree {
     ) -> CompileResult<Vec<TypedAstNode>> {
         let mut warnings = Vec::new();
         let mut errors = Vec::new();
+        let mut recursive_fn_decls = recursive_fns
+            .iter()
+            .map(|group| find_fn_decls(&nodes, group))
+            .collect();
         let typed_nodes = nodes
             .into_iter()
             .map(|node| {
+                declare_recursive_fn_group(&node, &mut recursive_fn_decls, namespace);
                 TypedAstNode::type_check(TypeCheckArguments {
                     checkee: node,
                     namespace,
@@ -181,9 +190,20 @@ impl TypedParseTree {
             };
         }
 
-        // impure functions are disallowed in non-contracts
+        // storage is disallowed in non-contracts, and functions which access it are disallowed in
+        // scripts and predicates. Libraries may declare them for the contracts which use them.
         if *tree_type != TreeType::Contract {
-            errors.append(&mut disallow_impure_functions(&declarations, &mains));
+            if !matches!(tree_type, TreeType::Library { .. }) {
+                errors.append(&mut disallow_impure_functions(&declarations, &mains));
+            }
+            errors.extend(declarations.iter().filter_map(|decl| match decl {
+                TypedDeclaration::StorageDeclaration(decl) => {
+                    Some(CompileError::StorageDeclarationInNonContract {
+                        span: decl.span.clone(),
+                    })
+                }
+                _ => None,
+            }));
         }
 
         // Perform other validation based on the tree type.
@@ -247,6 +267,44 @@ impl TypedParseTree {
     }
 }
 
+fn find_fn_decls(nodes: &[AstNode], names: &[Ident]) -> Vec<FunctionDeclaration> {
+    nodes
+        .iter()
+        .filter_map(|node| match &node.content {
+            AstNodeContent::Declaration(Declaration::FunctionDeclaration(fn_decl))
+                if names.contains(&fn_decl.name) =>
+            {
+                Some(fn_decl.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The functions of a group which call each other can't all be type checked before each other, so
+/// the signatures of all of them are declared once the first of them is reached. Their own
+/// declarations replace these as they are type checked in turn.
+fn declare_recursive_fn_group(
+    node: &AstNode,
+    recursive_fns: &mut Vec<Vec<FunctionDeclaration>>,
+    namespace: &mut Namespace,
+) {
+    let name = match &node.content {
+        AstNodeContent::Declaration(Declaration::FunctionDeclaration(fn_decl)) => &fn_decl.name,
+        _ => return,
+    };
+    if let Some(ix) = recursive_fns
+        .iter()
+        .position(|group| group.iter().any(|fn_decl| fn_decl.name == *name))
+    {
+        let self_type = insert_type(TypeInfo::Contract);
+        for fn_decl in recursive_fns.swap_remove(ix) {
+            let stub = TypedFunctionDeclaration::recursion_stub(&fn_decl, namespace, self_type);
+            namespace.insert(fn_decl.name, TypedDeclaration::FunctionDeclaration(stub));
+        }
+    }
+}
+
 fn disallow_impure_functions(
     declarations: &[TypedDeclaration],
     mains: &[TypedFunctionDeclaration],
@@ -260,7 +318,7 @@ fn disallow_impure_functions(
         .chain(mains);
     fn_decls
         .filter_map(|TypedFunctionDeclaration { purity, name, .. }| {
-            if *purity == Purity::Impure {
+            if *purity != Purity::Pure {
                 Some(CompileError::ImpureInNonContract {
                     span: name.span().clone(),
                 })
@@ -22,4 +22,6 @@ pub struct TypeCheckArguments<'a, T> {
 #[derive(Default, Clone, Copy)]
 pub struct TCOpts {
     pub(crate) purity: Purity,
+    /// Whether the node being checked is inside of a loop, and so may `break` or `continue`.
+    pub(crate) in_loop: bool,
 }
@@ -0,0 +1,305 @@
+//! Traversal of the typed AST, for the passes which look at or change the code within function
+//! bodies. A pass implements [Visit], or [VisitMut] to change the tree, and overrides the methods
+//! for the parts of the tree it is interested in. An override calls the matching `walk_*` function
+//! to go on into the children of that part.
+//!
+//! The walk doesn't go into the bodies of called functions, nor into the declarations of
+//! functions and other items, as those don't run where they are declared. It goes into the
+//! declarations of variables and constants and into reassignments, which do.
+
+use super::ast_node::{
+    StorageMapMethod, TypedCodeBlock, TypedConstantDeclaration, TypedDeclaration,
+    TypedExpressionVariant, TypedForLoop, TypedReassignment, TypedReturnStatement,
+    TypedStorageReassignment, TypedVariableDeclaration, TypedWhileLoop,
+};
+use super::{TypedAstNode, TypedAstNodeContent, TypedExpression};
+
+pub(crate) trait Visit<'a> {
+    fn visit_code_block(&mut self, block: &'a TypedCodeBlock) {
+        walk_code_block(self, block)
+    }
+
+    fn visit_node(&mut self, node: &'a TypedAstNode) {
+        walk_node(self, node)
+    }
+
+    fn visit_declaration(&mut self, decl: &'a TypedDeclaration) {
+        walk_declaration(self, decl)
+    }
+
+    fn visit_expression(&mut self, expr: &'a TypedExpression) {
+        walk_expression(self, expr)
+    }
+}
+
+pub(crate) fn walk_code_block<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    block: &'a TypedCodeBlock,
+) {
+    for node in &block.contents {
+        visitor.visit_node(node);
+    }
+}
+
+pub(crate) fn walk_node<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, node: &'a TypedAstNode) {
+    match &node.content {
+        TypedAstNodeContent::ReturnStatement(TypedReturnStatement { expr })
+        | TypedAstNodeContent::Expression(expr)
+        | TypedAstNodeContent::ImplicitReturnExpression(expr) => visitor.visit_expression(expr),
+        TypedAstNodeContent::Declaration(decl) => visitor.visit_declaration(decl),
+        TypedAstNodeContent::WhileLoop(while_loop) => walk_while_loop(visitor, while_loop),
+        TypedAstNodeContent::ForLoop(TypedForLoop { setup, r#loop }) => {
+            for node in setup {
+                visitor.visit_node(node);
+            }
+            walk_while_loop(visitor, r#loop);
+        }
+        TypedAstNodeContent::Break
+        | TypedAstNodeContent::Continue
+        | TypedAstNodeContent::SideEffect => (),
+    }
+}
+
+fn walk_while_loop<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, while_loop: &'a TypedWhileLoop) {
+    visitor.visit_expression(&while_loop.condition);
+    visitor.visit_code_block(&while_loop.body);
+}
+
+pub(crate) fn walk_declaration<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    decl: &'a TypedDeclaration,
+) {
+    match decl {
+        TypedDeclaration::VariableDeclaration(TypedVariableDeclaration { body, .. }) => {
+            visitor.visit_expression(body)
+        }
+        TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration { value, .. }) => {
+            visitor.visit_expression(value)
+        }
+        TypedDeclaration::Reassignment(TypedReassignment { rhs, .. })
+        | TypedDeclaration::StorageReassignment(TypedStorageReassignment { rhs, .. }) => {
+            visitor.visit_expression(rhs)
+        }
+        _ => (),
+    }
+}
+
+pub(crate) fn walk_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    expr: &'a TypedExpression,
+) {
+    use TypedExpressionVariant::*;
+    match &expr.expression {
+        FunctionApplication { arguments, .. } => {
+            for (_, argument) in arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        LazyOperator { lhs, rhs, .. } => {
+            visitor.visit_expression(lhs);
+            visitor.visit_expression(rhs);
+        }
+        Tuple { fields } => fields
+            .iter()
+            .for_each(|field| visitor.visit_expression(field)),
+        Array { contents } => contents
+            .iter()
+            .for_each(|elem| visitor.visit_expression(elem)),
+        ArrayIndex { prefix, index } => {
+            visitor.visit_expression(prefix);
+            visitor.visit_expression(index);
+        }
+        StructExpression { fields, .. } => fields
+            .iter()
+            .for_each(|field| visitor.visit_expression(&field.value)),
+        CodeBlock(block) => visitor.visit_code_block(block),
+        IfExp {
+            condition,
+            then,
+            r#else,
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(then);
+            if let Some(r#else) = r#else {
+                visitor.visit_expression(r#else);
+            }
+        }
+        AsmExpression { registers, .. } => registers
+            .iter()
+            .filter_map(|register| register.initializer.as_ref())
+            .for_each(|initializer| visitor.visit_expression(initializer)),
+        StructFieldAccess { prefix, .. }
+        | EnumArgAccess { prefix, .. }
+        | EnumTag { exp: prefix }
+        | TupleElemAccess { prefix, .. } => visitor.visit_expression(prefix),
+        EnumInstantiation { contents, .. } => {
+            if let Some(contents) = contents {
+                visitor.visit_expression(contents);
+            }
+        }
+        AbiCast { address, .. } => visitor.visit_expression(address),
+        StorageMapMethod(StorageMapMethod::Get { map, key }) => {
+            visitor.visit_expression(map);
+            visitor.visit_expression(key);
+        }
+        StorageMapMethod(StorageMapMethod::Insert { map, key, value }) => {
+            visitor.visit_expression(map);
+            visitor.visit_expression(key);
+            visitor.visit_expression(value);
+        }
+        SignedIntegerOp(op) => op
+            .arguments
+            .iter()
+            .for_each(|argument| visitor.visit_expression(argument)),
+        Literal(_)
+        | VariableExpression { .. }
+        | FunctionParameter
+        | StorageAccess { .. }
+        | Revert { .. } => (),
+    }
+}
+
+pub(crate) trait VisitMut {
+    fn visit_code_block_mut(&mut self, block: &mut TypedCodeBlock) {
+        walk_code_block_mut(self, block)
+    }
+
+    fn visit_node_mut(&mut self, node: &mut TypedAstNode) {
+        walk_node_mut(self, node)
+    }
+
+    fn visit_declaration_mut(&mut self, decl: &mut TypedDeclaration) {
+        walk_declaration_mut(self, decl)
+    }
+
+    fn visit_expression_mut(&mut self, expr: &mut TypedExpression) {
+        walk_expression_mut(self, expr)
+    }
+}
+
+pub(crate) fn walk_code_block_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    block: &mut TypedCodeBlock,
+) {
+    for node in &mut block.contents {
+        visitor.visit_node_mut(node);
+    }
+}
+
+pub(crate) fn walk_node_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut TypedAstNode) {
+    match &mut node.content {
+        TypedAstNodeContent::ReturnStatement(TypedReturnStatement { expr })
+        | TypedAstNodeContent::Expression(expr)
+        | TypedAstNodeContent::ImplicitReturnExpression(expr) => visitor.visit_expression_mut(expr),
+        TypedAstNodeContent::Declaration(decl) => visitor.visit_declaration_mut(decl),
+        TypedAstNodeContent::WhileLoop(while_loop) => walk_while_loop_mut(visitor, while_loop),
+        TypedAstNodeContent::ForLoop(TypedForLoop { setup, r#loop }) => {
+            for node in setup {
+                visitor.visit_node_mut(node);
+            }
+            walk_while_loop_mut(visitor, r#loop);
+        }
+        TypedAstNodeContent::Break
+        | TypedAstNodeContent::Continue
+        | TypedAstNodeContent::SideEffect => (),
+    }
+}
+
+fn walk_while_loop_mut<V: VisitMut + ?Sized>(visitor: &mut V, while_loop: &mut TypedWhileLoop) {
+    visitor.visit_expression_mut(&mut while_loop.condition);
+    visitor.visit_code_block_mut(&mut while_loop.body);
+}
+
+pub(crate) fn walk_declaration_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    decl: &mut TypedDeclaration,
+) {
+    match decl {
+        TypedDeclaration::VariableDeclaration(TypedVariableDeclaration { body, .. }) => {
+            visitor.visit_expression_mut(body)
+        }
+        TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration { value, .. }) => {
+            visitor.visit_expression_mut(value)
+        }
+        TypedDeclaration::Reassignment(TypedReassignment { rhs, .. })
+        | TypedDeclaration::StorageReassignment(TypedStorageReassignment { rhs, .. }) => {
+            visitor.visit_expression_mut(rhs)
+        }
+        _ => (),
+    }
+}
+
+pub(crate) fn walk_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expr: &mut TypedExpression,
+) {
+    use TypedExpressionVariant::*;
+    match &mut expr.expression {
+        FunctionApplication { arguments, .. } => {
+            for (_, argument) in arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        LazyOperator { lhs, rhs, .. } => {
+            visitor.visit_expression_mut(lhs);
+            visitor.visit_expression_mut(rhs);
+        }
+        Tuple { fields } => fields
+            .iter_mut()
+            .for_each(|field| visitor.visit_expression_mut(field)),
+        Array { contents } => contents
+            .iter_mut()
+            .for_each(|elem| visitor.visit_expression_mut(elem)),
+        ArrayIndex { prefix, index } => {
+            visitor.visit_expression_mut(prefix);
+            visitor.visit_expression_mut(index);
+        }
+        StructExpression { fields, .. } => fields
+            .iter_mut()
+            .for_each(|field| visitor.visit_expression_mut(&mut field.value)),
+        CodeBlock(block) => visitor.visit_code_block_mut(block),
+        IfExp {
+            condition,
+            then,
+            r#else,
+        } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_expression_mut(then);
+            if let Some(r#else) = r#else {
+                visitor.visit_expression_mut(r#else);
+            }
+        }
+        AsmExpression { registers, .. } => registers
+            .iter_mut()
+            .filter_map(|register| register.initializer.as_mut())
+            .for_each(|initializer| visitor.visit_expression_mut(initializer)),
+        StructFieldAccess { prefix, .. }
+        | EnumArgAccess { prefix, .. }
+        | EnumTag { exp: prefix }
+        | TupleElemAccess { prefix, .. } => visitor.visit_expression_mut(prefix),
+        EnumInstantiation { contents, .. } => {
+            if let Some(contents) = contents {
+                visitor.visit_expression_mut(contents);
+            }
+        }
+        AbiCast { address, .. } => visitor.visit_expression_mut(address),
+        StorageMapMethod(StorageMapMethod::Get { map, key }) => {
+            visitor.visit_expression_mut(map);
+            visitor.visit_expression_mut(key);
+        }
+        StorageMapMethod(StorageMapMethod::Insert { map, key, value }) => {
+            visitor.visit_expression_mut(map);
+            visitor.visit_expression_mut(key);
+            visitor.visit_expression_mut(value);
+        }
+        SignedIntegerOp(op) => op
+            .arguments
+            .iter_mut()
+            .for_each(|argument| visitor.visit_expression_mut(argument)),
+        Literal(_)
+        | VariableExpression { .. }
+        | FunctionParameter
+        | StorageAccess { .. }
+        | Revert { .. } => (),
+    }
+}
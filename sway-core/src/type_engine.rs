@@ -28,8 +28,13 @@ pub(crate) trait FriendlyTypeString {
 }
 
 impl FriendlyTypeString for TypeId {
+    /// Unlike looking up the type, which follows aliases to the types they are for, this stops at
+    /// the first alias so that it is referred to by its name.
     fn friendly_type_str(&self) -> String {
-        look_up_type_id(*self).friendly_type_str()
+        match look_up_type_id_raw(*self) {
+            TypeInfo::Ref(id) => id.friendly_type_str(),
+            ty => ty.friendly_type_str(),
+        }
     }
 }
 
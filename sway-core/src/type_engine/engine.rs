@@ -23,7 +23,7 @@ impl Engine {
 
     pub fn look_up_type_id(&self, id: TypeId) -> TypeInfo {
         match self.slab.get(id) {
-            TypeInfo::Ref(other) => self.look_up_type_id(other),
+            TypeInfo::Ref(other) | TypeInfo::Alias { ty: other, .. } => self.look_up_type_id(other),
             ty => ty,
         }
     }
@@ -65,6 +65,28 @@ impl Engine {
                 Some(_) => self.unify(received, expected, span),
             },
 
+            // An alias is unified as the type it is an alias for, but a mismatch is reported with
+            // the name of the alias, which is why it is only followed once unknown types have been
+            // made to refer to it.
+            (Alias { ty, .. }, _) => {
+                self.unify(ty, expected, span)
+                    .map_err(|_| TypeError::MismatchedType {
+                        expected,
+                        received,
+                        help_text: Default::default(),
+                        span: span.clone(),
+                    })
+            }
+            (_, Alias { ty, .. }) => {
+                self.unify(received, ty, span)
+                    .map_err(|_| TypeError::MismatchedType {
+                        expected,
+                        received,
+                        help_text: Default::default(),
+                        span: span.clone(),
+                    })
+            }
+
             (Tuple(fields_a), Tuple(fields_b)) if fields_a.len() == fields_b.len() => {
                 let mut warnings = vec![];
                 for (field_a, field_b) in fields_a.iter().zip(fields_b.iter()) {
@@ -98,6 +120,28 @@ impl Engine {
                 Ok(warn)
             }
 
+            // Signed integers cast between widths just like unsigned ones do, but never to or from
+            // unsigned integers.
+            (
+                ref received_info @ SignedInteger(recieved_width),
+                ref expected_info @ SignedInteger(expected_width),
+            ) => {
+                let warn = match numeric_cast_compat(expected_width, recieved_width) {
+                    NumericCastCompatResult::CastableWithWarning(warn) => {
+                        vec![CompileWarning {
+                            span: span.clone(),
+                            warning_content: warn,
+                        }]
+                    }
+                    NumericCastCompatResult::Compatible => {
+                        vec![]
+                    }
+                };
+                self.slab
+                    .replace(received, received_info, expected_info.clone());
+                Ok(warn)
+            }
+
             (ref received_info @ UnknownGeneric { .. }, _) => {
                 self.slab
                     .replace(received, received_info, TypeInfo::Ref(expected));
@@ -148,13 +192,15 @@ impl Engine {
                 Ok(vec![])
             }
 
-            (Numeric, expected_info @ UnsignedInteger(_)) => {
+            (Numeric, expected_info @ UnsignedInteger(_))
+            | (Numeric, expected_info @ SignedInteger(_)) => {
                 match self.slab.replace(received, &Numeric, expected_info) {
                     None => Ok(vec![]),
                     Some(_) => self.unify(received, expected, span),
                 }
             }
-            (received_info @ UnsignedInteger(_), Numeric) => {
+            (received_info @ UnsignedInteger(_), Numeric)
+            | (received_info @ SignedInteger(_), Numeric) => {
                 match self.slab.replace(expected, &Numeric, received_info) {
                     None => Ok(vec![]),
                     Some(_) => self.unify(received, expected, span),
@@ -172,6 +218,29 @@ impl Engine {
                     span: span.clone(),
                 }),
 
+            (
+                StorageMap {
+                    key: a_key,
+                    value: a_value,
+                },
+                StorageMap {
+                    key: b_key,
+                    value: b_value,
+                },
+            ) => self
+                .unify(a_key, b_key, span)
+                .and_then(|mut warnings| {
+                    warnings.append(&mut self.unify(a_value, b_value, span)?);
+                    Ok(warnings)
+                })
+                // As with arrays, report the map types as mismatching rather than their contents.
+                .map_err(|_| TypeError::MismatchedType {
+                    expected,
+                    received,
+                    help_text: Default::default(),
+                    span: span.clone(),
+                }),
+
             // When unifying complex types, we must check their sub-types. This
             // can be trivially implemented for tuples, sum types, etc.
             // (List(a_item), List(b_item)) => self.unify(a_item, b_item),
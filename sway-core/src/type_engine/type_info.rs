@@ -3,7 +3,7 @@ use crate::{
     build_config::BuildConfig,
     parse_tree::OwnedCallPath,
     semantic_analysis::ast_node::{OwnedTypedEnumVariant, OwnedTypedStructField},
-    Rule, Span, TypeParameter,
+    Ident, Rule, Span, TypeParameter,
 };
 use derivative::Derivative;
 
@@ -19,6 +19,8 @@ pub enum TypeInfo {
     },
     Str(u64),
     UnsignedInteger(IntegerBits),
+    /// A two's complement integer, which is held sign extended to a whole word.
+    SignedInteger(IntegerBits),
     Enum {
         name: String,
         variant_types: Vec<OwnedTypedEnumVariant>,
@@ -48,6 +50,10 @@ pub enum TypeInfo {
     /// until the semantic analysis stage.
     Custom {
         name: String,
+        /// The type arguments given to the name, like the `u64` in `Pair<u64>`. Only type aliases
+        /// make use of them so far, so they don't tell one custom type from another.
+        #[derivative(PartialEq = "ignore", Hash = "ignore")]
+        type_arguments: Vec<TypeId>,
     },
     SelfType,
     Byte,
@@ -60,6 +66,33 @@ pub enum TypeInfo {
     ErrorRecovery,
     // Static, constant size arrays.
     Array(TypeId, usize),
+    /// A static array whose size is the constant `length`, which isn't known until the constant
+    /// has been type checked. Resolving the type turns it into an `Array`.
+    UnresolvedArray {
+        elem_type: TypeId,
+        length: Ident,
+    },
+    /// A map from `key`s to `value`s, which can only be the type of a field of contract storage.
+    /// Each entry is kept in slots of its own, whose keys are derived from the key of the
+    /// field's slot and the entry's key.
+    StorageMap {
+        key: TypeId,
+        value: TypeId,
+    },
+    /// An associated type of a trait, like `Self::Item`, which the trait declares and each of its
+    /// implementations defines. Resolving the type through the `parent` type it is associated
+    /// with turns it into the type the implementation for that type defined.
+    AssociatedType {
+        parent: TypeId,
+        name: String,
+    },
+    /// A type alias, like `Pair<u64>` for `type Pair<T> = (T, T);`, which is the type `ty` by
+    /// another `name`. Like a `Ref` it is followed when a type is looked up, but messages refer to
+    /// the type by the name of the alias.
+    Alias {
+        name: String,
+        ty: TypeId,
+    },
 }
 
 impl Default for TypeInfo {
@@ -87,7 +120,94 @@ impl TypeInfo {
                 return err(vec![], errors);
             }
         }
-        Self::parse_from_pair_inner(input.into_inner().next().unwrap(), config)
+        let mut inner = input.into_inner();
+        let type_name = inner.next().unwrap();
+        if type_name.as_rule() == Rule::ident && type_name.as_str().trim() == "StorageMap" {
+            return Self::parse_storage_map(type_name, inner.next(), config);
+        }
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let type_info = check!(
+            Self::parse_from_pair_inner(type_name, config),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        let type_info = match (type_info, inner.next()) {
+            (TypeInfo::Custom { name, .. }, Some(type_params)) => {
+                let mut type_arguments = vec![];
+                for type_argument in type_params.into_inner() {
+                    let type_info = check!(
+                        TypeInfo::parse_from_pair(type_argument, config),
+                        TypeInfo::ErrorRecovery,
+                        warnings,
+                        errors
+                    );
+                    type_arguments.push(insert_type(type_info));
+                }
+                TypeInfo::Custom {
+                    name,
+                    type_arguments,
+                }
+            }
+            (type_info, _) => type_info,
+        };
+        ok(type_info, warnings, errors)
+    }
+
+    /// Parses `StorageMap<K, V>`. The type arguments may be left out, as in `~StorageMap::new()`,
+    /// in which case they are inferred.
+    fn parse_storage_map(
+        type_name: Pair<Rule>,
+        type_arguments: Option<Pair<Rule>>,
+        config: Option<&BuildConfig>,
+    ) -> CompileResult<Self> {
+        let mut warnings = vec![];
+        let mut errors = vec![];
+        let type_arguments = match type_arguments {
+            Some(type_arguments) => type_arguments,
+            None => {
+                return ok(
+                    TypeInfo::StorageMap {
+                        key: insert_type(TypeInfo::Unknown),
+                        value: insert_type(TypeInfo::Unknown),
+                    },
+                    warnings,
+                    errors,
+                )
+            }
+        };
+        let path = config.map(|config| config.dir_of_code.clone());
+        let span = crate::utils::join_spans(
+            Span {
+                span: type_name.as_span(),
+                path: path.clone(),
+            },
+            Span {
+                span: type_arguments.as_span(),
+                path,
+            },
+        );
+        let mut type_ids = vec![];
+        for type_argument in type_arguments.into_inner() {
+            let type_info = check!(
+                TypeInfo::parse_from_pair(type_argument, config),
+                TypeInfo::ErrorRecovery,
+                warnings,
+                errors
+            );
+            type_ids.push(insert_type(type_info));
+        }
+        match type_ids[..] {
+            [key, value] => ok(TypeInfo::StorageMap { key, value }, warnings, errors),
+            _ => {
+                errors.push(CompileError::StorageMapTypeArguments {
+                    received: type_ids.len(),
+                    span,
+                });
+                err(warnings, errors)
+            }
+        }
     }
 
     fn parse_from_pair_inner(
@@ -114,6 +234,10 @@ impl TypeInfo {
                 "u16" => TypeInfo::UnsignedInteger(IntegerBits::Sixteen),
                 "u32" => TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
                 "u64" => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+                "i8" => TypeInfo::SignedInteger(IntegerBits::Eight),
+                "i16" => TypeInfo::SignedInteger(IntegerBits::Sixteen),
+                "i32" => TypeInfo::SignedInteger(IntegerBits::ThirtyTwo),
+                "i64" => TypeInfo::SignedInteger(IntegerBits::SixtyFour),
                 "bool" => TypeInfo::Boolean,
                 "unit" => TypeInfo::Tuple(Vec::new()),
                 "byte" => TypeInfo::Byte,
@@ -122,8 +246,23 @@ impl TypeInfo {
                 "Contract" => TypeInfo::Contract,
                 _other => TypeInfo::Custom {
                     name: input.as_str().trim().to_string(),
+                    type_arguments: vec![],
                 },
             },
+            Rule::associated_type => {
+                let mut parts = input.into_inner();
+                let parent = check!(
+                    Self::parse_from_pair_inner(parts.next().unwrap(), config),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                let _path_separator = parts.next();
+                TypeInfo::AssociatedType {
+                    parent: insert_type(parent),
+                    name: parts.next().unwrap().as_str().trim().to_string(),
+                }
+            }
             Rule::array_type => {
                 let mut array_inner_iter = input.into_inner();
                 let elem_type_info = match array_inner_iter.next() {
@@ -180,6 +319,22 @@ impl TypeInfo {
                                     errors
                                 )
                             }
+                            Rule::ident => {
+                                let length = check!(
+                                    Ident::parse_from_pair(array_elem_count_pair, config),
+                                    return err(warnings, errors),
+                                    warnings,
+                                    errors
+                                );
+                                return ok(
+                                    TypeInfo::UnresolvedArray {
+                                        elem_type: insert_type(elem_type_info),
+                                        length,
+                                    },
+                                    warnings,
+                                    errors,
+                                );
+                            }
                             _otherwise => {
                                 errors.push(CompileError::Internal(
                                     "Unexpected token for array element count \
@@ -231,8 +386,15 @@ impl TypeInfo {
                 IntegerBits::SixtyFour => "u64",
             }
             .into(),
+            SignedInteger(x) => match x {
+                IntegerBits::Eight => "i8",
+                IntegerBits::Sixteen => "i16",
+                IntegerBits::ThirtyTwo => "i32",
+                IntegerBits::SixtyFour => "i64",
+            }
+            .into(),
             Boolean => "bool".into(),
-            Custom { name } => format!("unresolved {}", name),
+            Custom { name, .. } => format!("unresolved {}", name),
             Ref(id) => format!("T{} ({})", id, (*id).friendly_type_str()),
             Tuple(fields) => {
                 let field_strs = fields
@@ -261,6 +423,16 @@ impl TypeInfo {
                 format!("contract caller {}", abi_name.suffix)
             }
             Array(elem_ty, count) => format!("[{}; {}]", elem_ty.friendly_type_str(), count),
+            UnresolvedArray { elem_type, length } => {
+                format!("[{}; {}]", elem_type.friendly_type_str(), length)
+            }
+            StorageMap { key, value } => format!(
+                "StorageMap<{}, {}>",
+                key.friendly_type_str(),
+                value.friendly_type_str()
+            ),
+            AssociatedType { parent, name } => format!("{}::{}", parent.friendly_type_str(), name),
+            Alias { name, .. } => name.clone(),
         }
     }
 
@@ -277,8 +449,15 @@ impl TypeInfo {
                 IntegerBits::SixtyFour => "u64",
             }
             .into(),
+            SignedInteger(x) => match x {
+                IntegerBits::Eight => "i8",
+                IntegerBits::Sixteen => "i16",
+                IntegerBits::ThirtyTwo => "i32",
+                IntegerBits::SixtyFour => "i64",
+            }
+            .into(),
             Boolean => "bool".into(),
-            Custom { name } => format!("unresolved {}", name),
+            Custom { name, .. } => format!("unresolved {}", name),
             Ref(id) => format!("T{} ({})", id, (*id).json_abi_str()),
             Tuple(fields) => {
                 let field_strs = fields
@@ -303,6 +482,16 @@ impl TypeInfo {
                 format!("contract caller {}", abi_name.suffix)
             }
             Array(elem_ty, count) => format!("[{}; {}]", elem_ty.json_abi_str(), count),
+            UnresolvedArray { elem_type, length } => {
+                format!("[{}; {}]", elem_type.json_abi_str(), length)
+            }
+            StorageMap { key, value } => format!(
+                "StorageMap<{}, {}>",
+                key.json_abi_str(),
+                value.json_abi_str()
+            ),
+            AssociatedType { parent, name } => format!("{}::{}", parent.json_abi_str(), name),
+            Alias { ty, .. } => ty.json_abi_str(),
         }
     }
 
@@ -321,6 +510,16 @@ impl TypeInfo {
                 }
                 .into()
             }
+            SignedInteger(bits) => {
+                use IntegerBits::*;
+                match bits {
+                    Eight => "i8",
+                    Sixteen => "i16",
+                    ThirtyTwo => "i32",
+                    SixtyFour => "i64",
+                }
+                .into()
+            }
             Boolean => "bool".into(),
 
             Tuple(fields) => {
@@ -410,8 +609,8 @@ impl TypeInfo {
             // Each char is a byte, so the size is the num of characters / 8
             // rounded up to the nearest word
             TypeInfo::Str(len) => Ok((len + 7) / 8),
-            // Since things are unpacked, all unsigned integers are 64 bits.....for now
-            TypeInfo::UnsignedInteger(_) | TypeInfo::Numeric => Ok(1),
+            // Since things are unpacked, all integers are 64 bits.....for now
+            TypeInfo::UnsignedInteger(_) | TypeInfo::SignedInteger(_) | TypeInfo::Numeric => Ok(1),
             TypeInfo::Boolean => Ok(1),
             TypeInfo::Tuple(fields) => Ok(fields
                 .iter()
@@ -449,16 +648,22 @@ impl TypeInfo {
             // `ContractCaller` types are unsized and used only in the type system for
             // calling methods
             TypeInfo::ContractCaller { .. } => Ok(0),
+            // `StorageMap` types hold nothing themselves, their entries are all in storage
+            TypeInfo::StorageMap { .. } => Ok(0),
             TypeInfo::Contract => unreachable!("contract types are never instantiated"),
             TypeInfo::ErrorRecovery => unreachable!(),
             TypeInfo::Unknown
             | TypeInfo::Custom { .. }
+            | TypeInfo::UnresolvedArray { .. }
+            | TypeInfo::AssociatedType { .. }
             | TypeInfo::SelfType
             | TypeInfo::UnknownGeneric { .. } => Err(CompileError::TypeMustBeKnown {
                 ty: self.friendly_type_str(),
                 span: err_span.clone(),
             }),
-            TypeInfo::Ref(id) => look_up_type_id(*id).size_in_words(err_span),
+            TypeInfo::Ref(id) | TypeInfo::Alias { ty: id, .. } => {
+                look_up_type_id(*id).size_in_words(err_span)
+            }
             TypeInfo::Array(elem_ty, count) => {
                 Ok(look_up_type_id(*elem_ty).size_in_words(err_span)? * *count as u64)
             }
@@ -466,7 +671,10 @@ impl TypeInfo {
     }
     pub(crate) fn is_copy_type(&self) -> bool {
         match self {
-            TypeInfo::UnsignedInteger(_) | TypeInfo::Boolean | TypeInfo::Byte => true,
+            TypeInfo::UnsignedInteger(_)
+            | TypeInfo::SignedInteger(_)
+            | TypeInfo::Boolean
+            | TypeInfo::Byte => true,
             TypeInfo::Tuple(fields) => fields
                 .iter()
                 .all(|field_type| look_up_type_id(*field_type).is_copy_type()),
@@ -543,6 +751,66 @@ impl TypeInfo {
         }
     }
 
+    /// Whether or not this type is an instantiation of `generic`, the type of a possibly generic
+    /// impl block. Generic type parameters in `generic` match any type, so `MyStruct<u64>` is an
+    /// instance of `MyStruct<T>`, while all other types must be the same.
+    pub(crate) fn is_instance_of(&self, generic: &TypeInfo) -> bool {
+        use TypeInfo::*;
+        let is_instance = |ty: &TypeId, generic: &TypeId| {
+            look_up_type_id(*ty).is_instance_of(&look_up_type_id(*generic))
+        };
+        match (self, generic) {
+            (_, UnknownGeneric { .. }) => true,
+            (
+                Struct { name, fields },
+                Struct {
+                    name: generic_name,
+                    fields: generic_fields,
+                },
+            ) => {
+                name == generic_name
+                    && fields.len() == generic_fields.len()
+                    && fields
+                        .iter()
+                        .zip(generic_fields.iter())
+                        .all(|(field, generic_field)| {
+                            field.name == generic_field.name
+                                && is_instance(&field.r#type, &generic_field.r#type)
+                        })
+            }
+            (
+                Enum {
+                    name,
+                    variant_types,
+                },
+                Enum {
+                    name: generic_name,
+                    variant_types: generic_variant_types,
+                },
+            ) => {
+                name == generic_name
+                    && variant_types.len() == generic_variant_types.len()
+                    && variant_types.iter().zip(generic_variant_types.iter()).all(
+                        |(variant, generic_variant)| {
+                            variant.name == generic_variant.name
+                                && is_instance(&variant.r#type, &generic_variant.r#type)
+                        },
+                    )
+            }
+            (Tuple(fields), Tuple(generic_fields)) => {
+                fields.len() == generic_fields.len()
+                    && fields
+                        .iter()
+                        .zip(generic_fields.iter())
+                        .all(|(field, generic_field)| is_instance(field, generic_field))
+            }
+            (Array(elem_type, count), Array(generic_elem_type, generic_count)) => {
+                count == generic_count && is_instance(elem_type, generic_elem_type)
+            }
+            (ty, generic) => ty == generic,
+        }
+    }
+
     pub(crate) fn matches_type_parameter(
         &self,
         mapping: &[(TypeParameter, TypeId)],
@@ -562,6 +830,7 @@ impl TypeInfo {
                     if param.name
                         == (TypeInfo::Custom {
                             name: name.to_string(),
+                            type_arguments: vec![],
                         })
                     {
                         return Some(*ty_id);
@@ -633,12 +902,23 @@ impl TypeInfo {
                     Some(insert_type(TypeInfo::Tuple(new_fields)))
                 }
             }
+            TypeInfo::Alias { name, ty } => look_up_type_id(*ty)
+                .matches_type_parameter(mapping)
+                .map(|matching_id| {
+                    insert_type(TypeInfo::Alias {
+                        name: name.clone(),
+                        ty: matching_id,
+                    })
+                }),
             Unknown
             | Str(..)
             | UnsignedInteger(..)
+            | SignedInteger(..)
             | Boolean
             | Ref(..)
             | ContractCaller { .. }
+            | StorageMap { .. }
+            | AssociatedType { .. }
             | SelfType
             | Byte
             | B256
@@ -40,6 +40,7 @@ fn get_kind(token_type: &TokenType) -> Option<CompletionItemKind> {
         TokenType::Struct(_) => Some(CompletionItemKind::STRUCT),
         TokenType::Variable(_) => Some(CompletionItemKind::VARIABLE),
         TokenType::Trait(_) => Some(CompletionItemKind::INTERFACE),
+        TokenType::TypeAlias(_) => Some(CompletionItemKind::TYPE_PARAMETER),
         _ => None,
     }
 }
@@ -40,6 +40,7 @@ fn get_kind(token_type: &TokenType) -> SymbolKind {
         TokenType::Struct(_) => SymbolKind::STRUCT,
         TokenType::Variable(_) => SymbolKind::VARIABLE,
         TokenType::Trait(_) => SymbolKind::INTERFACE,
+        TokenType::TypeAlias(_) => SymbolKind::TYPE_PARAMETER,
         _ => SymbolKind::NULL, // TODO SymbolKind::UNKNOWN was removed in https://github.com/gluon-lang/lsp-types/pull/219
     }
 }
@@ -67,6 +67,7 @@ fn get_hover_format(token: &Token, documents: &Documents) -> Hover {
             &token.name
         ),
         TokenType::Enum => format!("enum {}", &token.name),
+        TokenType::TypeAlias(type_alias_details) => type_alias_details.declaration.clone(),
         _ => token.name.clone(),
     };
 
@@ -74,6 +74,7 @@ fn create_semantic_token(next_token: &Token, prev_token: Option<&Token>) -> Sema
 // these values should reflect indexes in `token_types`
 static FUNCTION: u32 = 1;
 static LIBRARY: u32 = 3;
+static TYPE: u32 = 7;
 static VARIABLE: u32 = 9;
 static ENUM: u32 = 10;
 static STRUCT: u32 = 11;
@@ -87,6 +88,7 @@ fn get_type(token_type: &TokenType) -> u32 {
         TokenType::Enum => ENUM,
         TokenType::Struct(_) => STRUCT,
         TokenType::Trait(_) => TRAIT,
+        TokenType::TypeAlias(_) => TYPE,
         // currently we return `variable` type as default
         _ => VARIABLE,
     }
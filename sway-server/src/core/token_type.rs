@@ -1,5 +1,7 @@
 use crate::utils::function::extract_fn_signature;
-use sway_core::{FunctionDeclaration, StructDeclaration, TraitDeclaration, Visibility};
+use sway_core::{
+    FunctionDeclaration, StructDeclaration, TraitDeclaration, TypeAliasDeclaration, Visibility,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
@@ -11,6 +13,7 @@ pub enum TokenType {
     Enum,
     Trait(TraitDetails),
     Struct(StructDetails),
+    TypeAlias(TypeAliasDetails),
 }
 
 pub fn get_function_details(func_dec: &FunctionDeclaration) -> FunctionDetails {
@@ -31,6 +34,17 @@ pub fn get_trait_details(trait_dec: &TraitDeclaration) -> TraitDetails {
         visibility: trait_dec.visibility,
     }
 }
+pub fn get_type_alias_details(type_alias_dec: &TypeAliasDeclaration) -> TypeAliasDetails {
+    TypeAliasDetails {
+        declaration: type_alias_dec
+            .span
+            .as_str()
+            .trim_end_matches(';')
+            .trim()
+            .to_string(),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionDetails {
     pub signature: String,
@@ -56,6 +70,11 @@ pub struct TraitDetails {
     pub visibility: Visibility,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeAliasDetails {
+    pub declaration: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VariableDetails {
     pub is_mutable: bool,
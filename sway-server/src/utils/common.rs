@@ -11,6 +11,10 @@ pub(crate) fn extract_visibility(visibility: &Visibility) -> String {
 }
 
 pub(crate) fn extract_var_body(var_dec: &VariableDeclaration) -> VarBody {
+    // the type is shown as it is written, so that an alias is shown by its name
+    if let Some(type_ascription_span) = &var_dec.type_ascription_span {
+        return VarBody::Type(type_ascription_span.as_str().trim().into());
+    }
     match &var_dec.body {
         Expression::FunctionApplication { name, .. } => {
             VarBody::FunctionCall(name.suffix.as_str().into())
@@ -345,6 +345,10 @@ pub struct Function {
     pub inputs: Vec<Property>,
     pub name: String,
     pub outputs: Vec<Property>,
+    /// The kinds of access to contract storage the function is declared with, `"read"` and
+    /// `"write"`.
+    #[serde(default)]
+    pub storage: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -355,3 +359,16 @@ pub struct Property {
     pub type_field: String,
     pub components: Option<Vec<Property>>, // Used for custom types
 }
+
+/// A place in the source where a program reverts with a code which is known at compile time, for
+/// tooling to tell which `revert`, `require` or `assert` a transaction reverted at from its code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertCode {
+    pub code: u64,
+    /// The path of the source file, if the source was read from one.
+    pub path: Option<PathBuf>,
+    pub range: Range,
+    /// The call which reverts, like `require(amount > 0, 3)`.
+    pub source: String,
+}
@@ -57,10 +57,10 @@ pub(crate) fn runs_on_node(file_name: &str) {
 
 /// Very basic check that code does indeed run in the VM.
 /// `true` if it does, `false` if not.
-pub(crate) fn runs_in_vm(file_name: &str) -> ProgramState {
+pub(crate) fn runs_in_vm(file_name: &str, use_ir: bool) -> ProgramState {
     let storage = MemoryStorage::default();
 
-    let script = compile_to_bytes(file_name).unwrap();
+    let script = compile_to_bytes(file_name, use_ir).unwrap();
     let gas_price = 10;
     let gas_limit = 100000;
     let maturity = 0;
@@ -88,7 +88,7 @@ pub(crate) fn runs_in_vm(file_name: &str) -> ProgramState {
 /// code should have been rejected by the compiler.
 pub(crate) fn does_not_compile(file_name: &str) {
     assert!(
-        compile_to_bytes(file_name).is_err(),
+        compile_to_bytes(file_name, false).is_err(),
         "{} should not have compiled.",
         file_name,
     )
@@ -96,8 +96,12 @@ pub(crate) fn does_not_compile(file_name: &str) {
 
 /// Returns `true` if a file compiled without any errors or warnings,
 /// and `false` if it did not.
-pub(crate) fn compile_to_bytes(file_name: &str) -> Result<Vec<u8>, String> {
-    println!(" Compiling {}", file_name);
+pub(crate) fn compile_to_bytes(file_name: &str, use_ir: bool) -> Result<Vec<u8>, String> {
+    println!(
+        " Compiling {}{}",
+        file_name,
+        if use_ir { " (IR)" } else { "" }
+    );
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     forc_build::build(BuildCommand {
         path: Some(format!(
@@ -106,12 +110,66 @@ pub(crate) fn compile_to_bytes(file_name: &str) -> Result<Vec<u8>, String> {
         )),
         print_finalized_asm: false,
         print_intermediate_asm: false,
+        use_ir,
+        print_ir: false,
+        no_optimize: false,
         binary_outfile: None,
+        revert_codes_outfile: None,
         offline_mode: false,
         silent_mode: true,
     })
 }
 
+/// Builds a project with `--revert-codes-outfile`, and returns the revert codes it lists, as
+/// tuples of the code, the start and the inclusive end of its range and the source it reverts at.
+pub(crate) fn compile_to_revert_codes(
+    file_name: &str,
+) -> Result<Vec<(u64, (u64, u64), (u64, u64), String)>, String> {
+    println!(" Listing revert codes of {}", file_name);
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let output_path = format!(
+        "{}/src/e2e_vm_tests/test_programs/{}/{}",
+        manifest_dir, file_name, "revert_codes_output.json"
+    );
+    forc_build::build(BuildCommand {
+        path: Some(format!(
+            "{}/src/e2e_vm_tests/test_programs/{}",
+            manifest_dir, file_name
+        )),
+        print_finalized_asm: false,
+        print_intermediate_asm: false,
+        use_ir: false,
+        print_ir: false,
+        no_optimize: false,
+        binary_outfile: None,
+        revert_codes_outfile: Some(output_path.clone()),
+        offline_mode: false,
+        silent_mode: true,
+    })?;
+    let output_contents =
+        fs::read_to_string(output_path).expect("Something went wrong reading the file.");
+    let output: Value = serde_json::from_str(&output_contents).map_err(|e| e.to_string())?;
+    let position = |position: &Value| {
+        (
+            position["line"].as_u64().unwrap(),
+            position["col"].as_u64().unwrap(),
+        )
+    };
+    Ok(output
+        .as_array()
+        .ok_or("The revert codes aren't a list.")?
+        .iter()
+        .map(|code| {
+            (
+                code["code"].as_u64().unwrap(),
+                position(&code["range"]["start"]),
+                position(&code["range"]["end"]),
+                code["source"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect())
+}
+
 pub(crate) fn test_json_abi(file_name: &str) -> Result<(), String> {
     let _script = compile_to_json_abi(file_name)?;
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
@@ -73,20 +73,68 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         ("b512_test", ProgramState::Return(1)),      // true
         ("block_height", ProgramState::Return(1)),   // true
         ("valid_impurity", ProgramState::Return(0)), // false
+        ("storage_access_declarations", ProgramState::Return(0)),
+        ("deprecated_impurity", ProgramState::Return(0)),
         ("trait_override_bug", ProgramState::Return(7)),
         ("if_implicit_unit", ProgramState::Return(0)),
         ("modulo_uint_test", ProgramState::Return(1)), // true
         ("trait_import_with_star", ProgramState::Return(0)),
         ("tuple_desugaring", ProgramState::Return(9)),
+        ("recursive_fns", ProgramState::Return(175)),
+        ("mutual_recursion", ProgramState::Return(141)),
+        ("register_spilling", ProgramState::Return(1830)),
+        ("const_folding", ProgramState::Return(4006)),
+        ("for_loops", ProgramState::Return(150)),
+        ("break_and_continue", ProgramState::Return(45)),
+        ("signed_integers", ProgramState::Return(1)), // true
+        ("match_expressions_exhaustive", ProgramState::Return(13)),
+        ("match_expressions_or_range_guard", ProgramState::Return(108)),
+        ("if_let_and_while_let", ProgramState::Return(59)),
+        ("destructuring_let", ProgramState::Return(146)),
+        ("const_expressions", ProgramState::Return(58)),
+        ("generic_impl", ProgramState::Return(23)),
+        ("generic_fn_trait_bounds", ProgramState::Return(25)),
+        ("associated_items", ProgramState::Return(310)),
+        ("supertraits", ProgramState::Return(121)),
+        ("abi_supertraits", ProgramState::Return(0)),
+        ("operator_overloading", ProgramState::Return(452)),
+        ("derive_eq_ord", ProgramState::Return(16383)),
+        ("attributes", ProgramState::Return(7)),
+        ("type_alias", ProgramState::Return(159)),
+        ("try_operator", ProgramState::Return(199)),
+        ("revert_intrinsics", ProgramState::Revert(42)),
     ];
 
     project_names.into_iter().for_each(|(name, res)| {
         if filter(name) {
-            assert_eq!(crate::e2e_vm_tests::harness::runs_in_vm(name), res);
+            assert_eq!(crate::e2e_vm_tests::harness::runs_in_vm(name, false), res);
             assert_eq!(crate::e2e_vm_tests::harness::test_json_abi(name), Ok(()));
         }
     });
 
+    // the subset of the above which the IR backend supports so far; these are run a second time
+    // through the IR to check that it produces the same result
+    let ir_project_names = vec![
+        ("asm_expr_basic", ProgramState::Return(6)),
+        ("basic_func_decl", ProgramState::Return(1)), // 1 == true
+        ("fix_opcode_bug", ProgramState::Return(30)),
+        ("struct_field_access", ProgramState::Return(43)),
+        ("bool_and_or", ProgramState::Return(42)),
+        ("const_decl", ProgramState::Return(100)),
+        ("recursive_fns", ProgramState::Return(175)),
+        ("mutual_recursion", ProgramState::Return(141)),
+        ("register_spilling", ProgramState::Return(1830)),
+        ("for_loops", ProgramState::Return(150)),
+        ("break_and_continue", ProgramState::Return(45)),
+        ("derive_eq_ord", ProgramState::Return(16383)),
+    ];
+
+    ir_project_names.into_iter().for_each(|(name, res)| {
+        if filter(name) {
+            assert_eq!(crate::e2e_vm_tests::harness::runs_in_vm(name, true), res);
+        }
+    });
+
     // source code that should _not_ compile
     let project_names = vec![
         "recursive_calls",
@@ -113,6 +161,29 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         "predicate_calls_impure",
         "script_calls_impure",
         "contract_pure_calls_impure",
+        "for_loop_not_iterable",
+        "break_outside_loop",
+        "storage_access_in_pure_fn",
+        "storage_in_script",
+        "storage_map_reassignment",
+        "storage_access_not_declared",
+        "match_expressions_non_exhaustive",
+        "match_expressions_or_unbound_var",
+        "destructuring_let_wrong_arity",
+        "destructuring_let_wrong_struct",
+        "const_expression_overflow",
+        "array_length_not_constant",
+        "generic_impl_unsatisfied_bound",
+        "generic_fn_unsatisfied_bound",
+        "associated_items_missing",
+        "associated_items_ambiguous",
+        "supertrait_not_implemented",
+        "operator_not_implemented",
+        "derive_not_implemented",
+        "type_alias_mismatched_args",
+        "type_alias_generic_struct_mismatch",
+        "try_operator_non_result",
+        "revert_code_not_constant",
     ];
     project_names.into_iter().for_each(|name| {
         if filter(name) {
@@ -120,6 +191,26 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         }
     });
 
+    // the revert codes a program can revert with are listed with where they are in the source,
+    // in the order they are reached from `main`
+    if filter("revert_intrinsics") {
+        let source = |source: &str| source.to_string();
+        assert_eq!(
+            harness::compile_to_revert_codes("revert_intrinsics"),
+            Ok(vec![
+                (1, (22, 5), (22, 20), source("require(true, 1)")),
+                (
+                    0xffff_ffff_ffff_0000,
+                    (23, 5),
+                    (23, 18),
+                    source("assert(1 == 1)")
+                ),
+                (2, (8, 9), (8, 30), source("revert(DIVIDE_BY_ZERO)")),
+                (42, (15, 9), (15, 28), source("revert(FOUND_ANSWER)")),
+            ])
+        );
+    }
+
     // ---- Tests paired with contracts upon which they depend which must be pre-deployed.
     // TODO validate that call output is correct
     let contract_and_project_names = &[
@@ -129,6 +220,7 @@ pub fn run(filter_regex: Option<regex::Regex>) {
         ("context_testing_contract", "caller_context_test"),
         ("contract_abi_impl", "contract_call"),
         ("balance_test_contract", "bal_opcode"),
+        ("storage_access_contract", "call_storage_access_contract"),
     ];
 
     // Filter them first.
@@ -0,0 +1,28 @@
+contract;
+// this file tests that the methods of an abi's supertraits are part of the contract's abi
+
+abi Ownable {
+    fn owner(gas: u64, coin: u64, asset_id: b256, input: bool) -> b256;
+}
+
+abi Token: Ownable {
+    fn total_supply(gas: u64, coin: u64, asset_id: b256, input: bool) -> u64;
+}
+
+impl Token for Contract {
+    fn total_supply(gas: u64, coin: u64, asset_id: b256, input: bool) -> u64 {
+        100
+    }
+}
+
+impl Ownable for Contract {
+    fn owner(gas: u64, coin: u64, asset_id: b256, input: bool) -> b256 {
+        0x0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0001
+    }
+}
+
+fn calls_inherited_method() -> b256 {
+    let token = abi(Token, 0x0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000);
+    let asset_id = 0x0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000;
+    token.owner(5, 5, asset_id, true)
+}
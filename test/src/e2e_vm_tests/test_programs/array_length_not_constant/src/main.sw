@@ -0,0 +1,7 @@
+script;
+
+fn main() -> u64 {
+    // `LENGTH` isn't declared anywhere, so this can't be the length of an array
+    let values: [u64; LENGTH] = [1, 2];
+    values[0]
+}
@@ -0,0 +1,68 @@
+script;
+
+trait Token {
+    const DECIMALS: u8;
+    type Amount;
+
+    fn amount(self) -> Self::Amount;
+    fn decimals(self) -> u8;
+} {
+    fn scale(self) -> u64 {
+        let mut scale = 1;
+        let mut i = 0;
+        while i < self.decimals() {
+            scale = scale * 10;
+            i = i + 1;
+        }
+        scale
+    }
+}
+
+struct Coin {
+    value: u64,
+}
+
+struct Point {
+    value: u8,
+}
+
+impl Token for Coin {
+    const DECIMALS: u8 = 2;
+    type Amount = u64;
+
+    fn amount(self) -> Self::Amount {
+        self.value
+    }
+    fn decimals(self) -> u8 {
+        Self::DECIMALS
+    }
+}
+
+impl Token for Point {
+    const DECIMALS: u8 = 1;
+    type Amount = u8;
+
+    fn amount(self) -> u8 {
+        self.value
+    }
+    fn decimals(self) -> u8 {
+        Point::DECIMALS
+    }
+}
+
+fn main() -> u64 {
+    let coin = Coin {
+        value: 3,
+    };
+    let point = Point {
+        value: 4,
+    };
+    let points: u8 = point.amount();
+    let decimals: u8 = Coin::DECIMALS;
+    if points == 4 && decimals == 2 {
+        // 3 * 100 + 10
+        coin.amount() * coin.scale() + point.scale()
+    } else {
+        0
+    }
+}
@@ -0,0 +1,39 @@
+script;
+
+trait Token {
+    const DECIMALS: u8;
+    type Amount;
+}
+
+trait Currency {
+    const DECIMALS: u8;
+    type Amount;
+}
+
+struct Coin {
+    value: u64,
+}
+
+impl Token for Coin {
+    const DECIMALS: u8 = 9;
+    type Amount = u64;
+}
+
+impl Currency for Coin {
+    const DECIMALS: u8 = 2;
+    type Amount = u32;
+}
+
+// both `Token` and `Currency` define `Coin::Amount`
+fn amount(coin: Coin) -> Coin::Amount {
+    coin.value
+}
+
+fn main() -> u64 {
+    let coin = Coin {
+        value: 3,
+    };
+    // both `Token` and `Currency` give `Coin::DECIMALS` a value
+    let decimals = Coin::DECIMALS;
+    amount(coin)
+}
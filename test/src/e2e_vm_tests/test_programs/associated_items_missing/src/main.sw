@@ -0,0 +1,28 @@
+script;
+
+trait Token {
+    const DECIMALS: u8;
+    type Amount;
+
+    fn amount(self) -> Self::Amount;
+}
+
+struct Coin {
+    value: u64,
+}
+
+// `DECIMALS` is not given a value
+impl Token for Coin {
+    type Amount = u64;
+
+    fn amount(self) -> u64 {
+        self.value
+    }
+}
+
+fn main() -> u64 {
+    let coin = Coin {
+        value: 3,
+    };
+    coin.amount()
+}
@@ -0,0 +1,39 @@
+script;
+
+use core::ops::*;
+
+#[derive(Eq)]
+struct Point {
+    // unknown attributes are ignored with a warning
+    #[unused]
+    x: u64,
+    y: u64,
+}
+
+#[derive(Eq, Ord)]
+enum Direction {
+    Up: (),
+    #[unused]
+    Down: (),
+}
+
+#[inline(never)]
+fn sum(p: Point) -> u64 {
+    p.x + p.y
+}
+
+fn main() -> u64 {
+    let p = Point {
+        x: 3,
+        y: 4,
+    };
+    let q = Point {
+        x: 3,
+        y: 4,
+    };
+    if p == q && Direction::Up < Direction::Down {
+        sum(p)
+    } else {
+        0
+    }
+}
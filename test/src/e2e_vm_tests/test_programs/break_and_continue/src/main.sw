@@ -0,0 +1,33 @@
+script;
+// This test breaks out of and continues while loops and for loops, including nested ones.
+
+fn main() -> u64 {
+    let mut i = 0;
+    let mut odd = false;
+    let mut sum = 0;
+    while true {
+        i = i + 1;
+        odd = !odd;
+        if i > 10 {
+            break;
+        }
+        if !odd {
+            continue;
+        }
+        sum = sum + i;
+    }
+    // 1 + 3 + 5 + 7 + 9 = 25
+    for x in 0..100 {
+        if x == 5 {
+            break;
+        }
+        for y in [1, 2, 3] {
+            if y == 2 {
+                continue;
+            }
+            sum = sum + y;
+        }
+    }
+    // 25 + 5 * 4 = 45
+    sum
+}
@@ -0,0 +1,14 @@
+script;
+
+fn main() -> u64 {
+    let mut sum = 0;
+    while sum < 10 {
+        sum = sum + f();
+    }
+    sum
+}
+
+fn f() -> u64 {
+    break;
+    1
+}
@@ -0,0 +1,78 @@
+script;
+// This test writes storage fields of every layout, and the entries of storage maps, and reads
+// them back in later calls.
+use storage_access_abi::*;
+
+const GAS: u64 = 10000;
+const ETH: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000;
+
+fn main() -> bool {
+    // the id of storage_access_contract, which changes along with its bytecode
+    let contract = abi(StorageAccess, 0x0000000000000000000000000000000000000000000000000000000000000000);
+
+    // a function which doesn't access storage doesn't initialize it either
+    assert(contract.double(GAS, 0, ETH, 21) == 42);
+
+    assert(contract.get_word(GAS, 0, ETH, ()) == 7);
+    contract.set_word(GAS, 0, ETH, 11);
+    assert(contract.get_word(GAS, 0, ETH, ()) == 11);
+
+    let hash = 0x0303030303030303030303030303030303030303030303030303030303030303;
+    assert(contract.get_hash(GAS, 0, ETH, ()) == 0x0101010101010101010101010101010101010101010101010101010101010101);
+    contract.set_hash(GAS, 0, ETH, hash);
+    assert(contract.get_hash(GAS, 0, ETH, ()) == hash);
+
+    let point = contract.get_point(GAS, 0, ETH, ());
+    assert(point.x == 1);
+    assert(point.y == 2);
+    assert(point.tag == 0x0202020202020202020202020202020202020202020202020202020202020202);
+    contract.set_point(GAS, 0, ETH, Point {
+        x: 5,
+        y: 6,
+        tag: hash,
+    });
+    let point = contract.get_point(GAS, 0, ETH, ());
+    assert(point.x == 5);
+    assert(point.y == 6);
+    assert(point.tag == hash);
+
+    // writing the other fields left this one alone, and storage was only initialized once
+    assert(contract.get_word(GAS, 0, ETH, ()) == 11);
+    assert(contract.get_untouched(GAS, 0, ETH, ()) == 42);
+
+    // entries of different keys, and of different maps, are kept apart
+    let other_hash = 0x0404040404040404040404040404040404040404040404040404040404040404;
+    contract.insert_by_id(GAS, 0, ETH, IdEntry {
+        id: 1,
+        value: 10,
+    });
+    contract.insert_by_id(GAS, 0, ETH, IdEntry {
+        id: 2,
+        value: 20,
+    });
+    contract.insert_by_hash(GAS, 0, ETH, HashEntry {
+        hash: hash,
+        value: 30,
+    });
+    contract.insert_by_hash(GAS, 0, ETH, HashEntry {
+        hash: other_hash,
+        value: 40,
+    });
+    contract.insert_by_hash(GAS, 0, ETH, HashEntry {
+        hash: ETH,
+        value: 50,
+    });
+    assert(contract.get_by_id(GAS, 0, ETH, 1) == 10);
+    assert(contract.get_by_id(GAS, 0, ETH, 2) == 20);
+    assert(contract.get_by_id(GAS, 0, ETH, 0) == 0);
+    assert(contract.get_by_id(GAS, 0, ETH, 3) == 0);
+    assert(contract.get_by_hash(GAS, 0, ETH, hash) == 30);
+    assert(contract.get_by_hash(GAS, 0, ETH, other_hash) == 40);
+    assert(contract.get_by_hash(GAS, 0, ETH, ETH) == 50);
+
+    // the sum of 0 to 199
+    assert(contract.fill_by_id(100000, 0, ETH, 200) == 19900);
+    assert(contract.get_by_id(GAS, 0, ETH, 1) == 10);
+    assert(contract.get_by_id(GAS, 0, ETH, 1199) == 199);
+    true
+}
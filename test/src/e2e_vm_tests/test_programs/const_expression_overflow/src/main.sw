@@ -0,0 +1,8 @@
+script;
+
+const LIMIT: u8 = 200u8;
+const TOO_LARGE: u8 = LIMIT + 100u8;
+
+fn main() -> u64 {
+    0
+}
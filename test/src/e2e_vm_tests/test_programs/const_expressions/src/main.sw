@@ -0,0 +1,46 @@
+script;
+// This test checks that constants, array lengths and repeat counts are evaluated at compile time.
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+const WIDTH: u64 = 4;
+const HEIGHT: u64 = WIDTH * 2 + 1;
+const LENGTH: u64 = WIDTH + 2;
+const AREA: u64 = area(WIDTH, HEIGHT);
+const ORIGIN: Point = Point {
+    x: 1,
+    y: HEIGHT - 7,
+};
+const PAIR: (u64, bool) = (AREA % 5, WIDTH < HEIGHT && !(WIDTH == 0));
+const PRIMES: [u64; 3] = [2, 3, 5];
+const THIRD_PRIME: u64 = PRIMES[2];
+const SMALL: u8 = 200u8 + 55u8;
+const NEGATIVE: i64 = -3 * 4i64;
+
+fn area(width: u64, height: u64) -> u64 {
+    let area = width * height;
+    if area > 100 {
+        return 100;
+    }
+    area
+}
+
+fn sum(values: [u64; WIDTH]) -> u64 {
+    values[0] + values[1] + values[2] + values[3]
+}
+
+fn main() -> u64 {
+    let ones = [1; WIDTH];
+    let twos = [ORIGIN.y; LENGTH];
+    // 4 + 2 * 6
+    let total = sum(ones) + twos[5] * LENGTH;
+    if PAIR.1 && SMALL == 255u8 && NEGATIVE == -12 {
+        // 16 + 36 + 1 + 5
+        total + AREA + PAIR.0 + THIRD_PRIME
+    } else {
+        0
+    }
+}
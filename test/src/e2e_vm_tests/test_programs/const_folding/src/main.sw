@@ -0,0 +1,19 @@
+script;
+// This test checks that arithmetic and branches on values known at compile time still compute the
+// right result once they are folded, including results too large for an immediate.
+
+fn main() -> u64 {
+    let a = 4000;
+    let b = a * 1000;
+    let c = if b > a {
+        b - a
+    } else {
+        0
+    };
+    let d = c / 1000 + 10;
+    if d == 4006 {
+        d
+    } else {
+        0
+    }
+}
@@ -0,0 +1,32 @@
+contract;
+
+abi ImpurityTest {
+    impure fn clear_and_check(gas: u64, coins: u64, asset_id: b256, input: b256) -> bool;
+}
+
+// `impure` still works, with a warning, as `#[storage(read, write)]`
+impure fn clear(key: b256) {
+    store_word(key, 0);
+}
+
+// storage accessed only in asm, without declaring it, is still a warning rather than an error,
+// like `std::storage` was written before `#[storage(..)]`
+fn store_word(key: b256, value: u64) {
+    asm(r1: key, r2: value) {
+        sww r1 r2;
+    }
+}
+
+fn load_word(key: b256) -> u64 {
+    asm(r1: key, r2) {
+        srw r2 r1;
+        r2: u64
+    }
+}
+
+impl ImpurityTest for Contract {
+    impure fn clear_and_check(gas: u64, coins: u64, asset_id: b256, input: b256) -> bool {
+        clear(input);
+        load_word(input) == 0
+    }
+}
@@ -0,0 +1,114 @@
+script;
+
+use core::ops::*;
+
+#[derive(Eq, Ord)]
+struct Version {
+    major: u64,
+    minor: u64,
+}
+
+#[derive(Eq, Ord)]
+enum Level {
+    Low: (),
+    Medium: (),
+    High: (),
+}
+
+// an Option-like enum, whose variants hold data of different sizes
+#[derive(Eq, Ord)]
+enum Amount {
+    Unknown: (),
+    Known: u64,
+    Exact: Version,
+}
+
+#[derive(Eq)]
+struct Release {
+    version: Version,
+    level: Level,
+}
+
+fn main() -> u64 {
+    let a = Version {
+        major: 1,
+        minor: 2,
+    };
+    let b = Version {
+        major: 1,
+        minor: 3,
+    };
+    let c = Version {
+        major: 2,
+        minor: 0,
+    };
+
+    let mut result = 0;
+    if a == a {
+        result = result + 1;
+    }
+    if a < b {
+        result = result + 2;
+    }
+    // the first field which differs decides the order
+    if b < c {
+        result = result + 4;
+    }
+    if c > a {
+        result = result + 8;
+    }
+    if a > b {
+        result = 0;
+    } else {
+        result = result + 16;
+    }
+
+    // variants are ordered as they are declared
+    if Level::Low < Level::High {
+        result = result + 32;
+    }
+    if Level::Medium == Level::Medium {
+        result = result + 64;
+    }
+    if Level::Medium == Level::High {
+        result = 0;
+    } else {
+        result = result + 128;
+    }
+
+    let x = Release {
+        version: a,
+        level: Level::Low,
+    };
+    let y = Release {
+        version: Version {
+            major: 1,
+            minor: 2,
+        },
+        level: Level::Low,
+    };
+    if x == y {
+        result = result + 256;
+    }
+
+    // values of the same variant are ordered by their data
+    if Amount::Known(3) == Amount::Known(3) {
+        result = result + 512;
+    }
+    if Amount::Known(3) == Amount::Known(4) || Amount::Known(3) == Amount::Unknown {
+        result = 0;
+    } else {
+        result = result + 1024;
+    }
+    if Amount::Known(3) < Amount::Known(4) {
+        result = result + 2048;
+    }
+    if Amount::Unknown < Amount::Known(1) && Amount::Known(100) < Amount::Exact(a) {
+        result = result + 4096;
+    }
+    if Amount::Exact(b) > Amount::Exact(a) && Amount::Exact(a) == Amount::Exact(y.version) {
+        result = result + 8192;
+    }
+    // 1 + 2 + 4 + 8 + 16 + 32 + 64 + 128 + 256 + 512 + 1024 + 2048 + 4096 + 8192
+    result
+}
@@ -0,0 +1,23 @@
+script;
+
+use core::ops::*;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+// `Point` doesn't implement `Eq`, so neither can `Line`
+#[derive(Eq)]
+struct Line {
+    start: Point,
+    end: Point,
+}
+
+fn main() -> bool {
+    let a = Line {
+        start: Point { x: 0, y: 0 },
+        end: Point { x: 1, y: 1 },
+    };
+    a == a
+}
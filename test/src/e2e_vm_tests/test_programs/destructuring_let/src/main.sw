@@ -0,0 +1,19 @@
+script;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+fn gimme_a_triple() -> (u64, u64, (u64, u64)) {
+    (1, 2, (3, 4))
+}
+
+fn main() -> u64 {
+    let (a, _, (mut c, d)) = gimme_a_triple();
+    c = c * 10;
+    let Point { x, y: mut height } = Point { x: 5, y: 6 };
+    height = height + 100;
+    // 1 + 30 + 4 + 5 + 106
+    a + c + d + x + height
+}
@@ -0,0 +1,7 @@
+script;
+
+fn main() -> u64 {
+    // the tuple has three elements, not two
+    let (a, b) = (1, 2, 3);
+    a + b
+}
@@ -0,0 +1,21 @@
+script;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+struct Size {
+    x: u64,
+    y: u64,
+}
+
+struct Empty {}
+
+fn main() -> u64 {
+    // the value is a `Point`, not a `Size`, even though they have the same fields
+    let Size { x, y } = Point { x: 1, y: 2 };
+    // nor is it `Empty`, even though no fields are read out of it
+    let Empty {} = Point { x: 3, y: 4 };
+    x + y
+}
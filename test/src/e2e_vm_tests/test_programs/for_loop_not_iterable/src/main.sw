@@ -0,0 +1,9 @@
+script;
+
+fn main() -> u64 {
+    let mut sum = 0;
+    for x in 42 {
+        sum = sum + x;
+    }
+    sum
+}
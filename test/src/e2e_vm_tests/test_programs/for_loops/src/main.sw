@@ -0,0 +1,31 @@
+script;
+// This test iterates over arrays and ranges, including nested and empty loops.
+
+fn main() -> u64 {
+    let arr = [1, 2, 3, 4];
+    let mut sum = 0;
+    for x in arr {
+        sum = sum + x;
+    }
+    // 10
+    for i in 0..5 {
+        sum = sum + i * 10;
+    }
+    // 110
+    for i in 1..3 {
+        for j in arr {
+            sum = sum + i * j;
+        }
+    }
+    // 140
+    for i in 5..5 {
+        sum = sum + 1000;
+    }
+    for b in [true, false, true] {
+        if b {
+            sum = sum + 5;
+        }
+    }
+    // 150
+    sum
+}
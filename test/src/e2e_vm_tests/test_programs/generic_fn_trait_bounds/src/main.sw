@@ -0,0 +1,59 @@
+script;
+
+trait Double {
+    fn double(self) -> u64;
+}
+
+impl Double for u64 {
+    fn double(self) -> u64 {
+        self * 2
+    }
+}
+
+impl Double for bool {
+    fn double(self) -> u64 {
+        if self {
+            2
+        } else {
+            0
+        }
+    }
+}
+
+// a method of the same name in another trait, which calls bounded by `Double` must not call
+trait Quadruple {
+    fn double(self) -> u64;
+}
+
+impl Quadruple for u64 {
+    fn double(self) -> u64 {
+        self * 4
+    }
+}
+
+struct Wrapper<T> {
+    value: T,
+}
+
+impl<T> Double for Wrapper<T> where T: Double {
+    fn double(self) -> u64 {
+        self.value.double() + 1
+    }
+}
+
+fn double_it<T>(x: T) -> u64 where T: Double {
+    x.double()
+}
+
+fn double_both<T>(x: T, y: T) -> u64 where T: Double {
+    double_it(x) + y.double()
+}
+
+fn main() -> u64 {
+    let six: u64 = 6;
+    let wrapped = Wrapper {
+        value: six,
+    };
+    // 10 + 2 + 13
+    double_it(5) + double_both(true, false) + double_it(wrapped)
+}
@@ -0,0 +1,36 @@
+script;
+
+trait Double {
+    fn double(self) -> u64;
+}
+
+impl Double for u64 {
+    fn double(self) -> u64 {
+        self * 2
+    }
+}
+
+trait Halve {
+    fn halve(self) -> u32;
+}
+
+impl Halve for u32 {
+    fn halve(self) -> u32 {
+        self / 2
+    }
+}
+
+fn double_it<T>(x: T) -> u64 where T: Double {
+    x.double()
+}
+
+fn halve_it<T>(x: T) -> u32 where T: Halve {
+    x.halve()
+}
+
+fn main() -> u64 {
+    // a numeric literal is a `u64`, which does not implement `Halve`
+    halve_it(4);
+    // `bool` does not implement `Double`
+    double_it(true)
+}
@@ -0,0 +1,56 @@
+script;
+
+trait Double {
+    fn double(self) -> u64;
+}
+
+impl Double for u64 {
+    fn double(self) -> u64 {
+        self * 2
+    }
+}
+
+struct Wrapper<T> {
+    value: T,
+    count: u64,
+}
+
+impl<T> Wrapper<T> {
+    fn value(self) -> T {
+        self.value
+    }
+
+    fn count(self) -> u64 {
+        self.count
+    }
+}
+
+impl<T> Double for Wrapper<T> where T: Double {
+    fn double(self) -> u64 {
+        self.count * 2
+    }
+}
+
+fn main() -> u64 {
+    let a = Wrapper {
+        value: 3,
+        count: 4,
+    };
+    let b = Wrapper {
+        value: true,
+        count: 5,
+    };
+    let c = Wrapper {
+        value: 7,
+        count: 10,
+    };
+
+    let x: u64 = a.value();
+    let flag: bool = b.value();
+    if flag {
+        // 3 + 8 + 7 + 5
+        x + a.double() + c.value() + b.count()
+    } else {
+        0
+    }
+}
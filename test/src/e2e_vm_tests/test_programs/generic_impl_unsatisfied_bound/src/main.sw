@@ -0,0 +1,31 @@
+script;
+
+trait Double {
+    fn double(self) -> u64;
+}
+
+impl Double for u64 {
+    fn double(self) -> u64 {
+        self * 2
+    }
+}
+
+struct Wrapper<T> {
+    value: T,
+    count: u64,
+}
+
+impl<T> Double for Wrapper<T> where T: Double {
+    fn double(self) -> u64 {
+        self.count * 2
+    }
+}
+
+fn main() -> u64 {
+    let a = Wrapper {
+        value: true,
+        count: 4,
+    };
+    // `bool` does not implement `Double`
+    a.double()
+}
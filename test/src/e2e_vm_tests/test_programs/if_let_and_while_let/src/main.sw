@@ -0,0 +1,35 @@
+script;
+
+fn main() -> u64 {
+    let pair = (1, 42);
+    let mut sum = 0;
+    if let (1, y) = pair {
+        sum = sum + y;
+    } else {
+        sum = 1000;
+    }
+    if let (2, y) = pair {
+        sum = y;
+    }
+    // the value being tested is evaluated once, however many of its parts the pattern tests
+    let mut evaluations = 0;
+    if let (1, 42) = {
+        evaluations = evaluations + 1;
+        pair
+    } {
+        sum = sum + evaluations;
+    }
+
+    let mut countdown = 3;
+    let mut total = 0;
+    let mut polls = 0;
+    while let (1..=10, step) = {
+        polls = polls + 1;
+        (countdown, 2)
+    } {
+        total = total + countdown * step;
+        countdown = countdown - 1;
+    }
+    // 43 + (3 + 2 + 1) * 2 + 4
+    sum + total + polls
+}
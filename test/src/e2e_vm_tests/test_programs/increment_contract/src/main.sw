@@ -7,10 +7,12 @@ use std::storage::get;
 const key = 0x0000000000000000000000000000000000000000000000000000000000000000;
 
 impl Incrementor for Contract {
+  #[storage(write)]
   fn initialize(gas: u64, amt: u64, asset_id: b256, initial_value: u64) -> u64 {
     store(key, initial_value);
     initial_value
   }
+  #[storage(read, write)]
   fn increment(gas: u64, amt: u64, asset_id: b256, increment_by: u64) -> u64 {
     let new_val = get::<u64>(key) + 1;
     // check that monomorphization doesn't overwrite the type of the above
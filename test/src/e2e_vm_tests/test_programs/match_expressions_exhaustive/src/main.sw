@@ -0,0 +1,16 @@
+script;
+
+fn main() -> u64 {
+    let flags = (true, false);
+    // every value is covered without a catch-all, so the match has a value on every path
+    let a = match flags {
+        (true, true) => { 1 },
+        (false, _) => { 2 },
+        (true, false) => { 3 },
+    };
+    let b = match a == 3 {
+        true => { 10 },
+        false => { 20 },
+    };
+    a + b
+}
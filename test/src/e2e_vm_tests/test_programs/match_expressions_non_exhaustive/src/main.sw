@@ -0,0 +1,10 @@
+script;
+
+fn main() -> u64 {
+    let flags = (true, false);
+    // (false, false) is not covered
+    match flags {
+        (true, _) => { 1 },
+        (false, true) => { 2 },
+    }
+}
@@ -0,0 +1,20 @@
+script;
+
+fn classify(n: u64) -> u64 {
+    match n {
+        0 | 1 => { 10 },
+        2..=9 => { 20 },
+        x if x % 2 == 0 => { 30 },
+        _ => { 40 },
+    }
+}
+
+fn main() -> u64 {
+    let pair = (3, 8);
+    // y is bound by whichever alternative matches
+    let a = match pair {
+        (1, y) | (3, y) => { y },
+        _ => { 0 },
+    };
+    classify(0) + classify(5) + classify(12) + classify(13) + a
+}
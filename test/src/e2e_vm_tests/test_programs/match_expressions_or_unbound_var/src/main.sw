@@ -0,0 +1,10 @@
+script;
+
+fn main() -> u64 {
+    let pair = (3, 8);
+    // y is not bound when the second alternative matches
+    match pair {
+        (1, y) | (3, 8) => { y },
+        _ => { 0 },
+    }
+}
@@ -0,0 +1,58 @@
+script;
+// This test tests functions which call each other.
+
+fn is_even(n: u64) -> bool {
+    if n == 0 {
+        true
+    } else {
+        is_odd(n - 1)
+    }
+}
+
+fn is_odd(n: u64) -> bool {
+    if n == 0 {
+        false
+    } else {
+        is_even(n - 1)
+    }
+}
+
+// counts down by one, two and three in turn
+fn by_one(n: u64, steps: u64) -> u64 {
+    if n == 0 {
+        steps
+    } else {
+        by_two(n - 1, steps + 1)
+    }
+}
+
+fn by_two(n: u64, steps: u64) -> u64 {
+    if n < 2 {
+        by_one(0, steps + 1)
+    } else {
+        by_three(n - 2, steps + 1)
+    }
+}
+
+fn by_three(n: u64, steps: u64) -> u64 {
+    if n < 3 {
+        by_one(0, steps + 1)
+    } else {
+        by_one(n - 3, steps + 1)
+    }
+}
+
+fn main() -> u64 {
+    let mut result = 0;
+    if is_even(10) {
+        result = result + 1;
+    }
+    if is_odd(7) {
+        result = result + 10;
+    }
+    if is_even(5) == false {
+        result = result + 100;
+    }
+    // 60 -> 59 -> 57 -> 54 -> ... -> 0 takes 30 steps
+    result + by_one(60, 0)
+}
@@ -13,6 +13,7 @@ fn bar() {
     let z = baz();
 }
 
-impure fn baz() -> u64 {
+#[storage(read)]
+fn baz() -> u64 {
   5
 }
@@ -0,0 +1,22 @@
+script;
+
+struct Counter {
+    count: u64,
+}
+
+impl Counter {
+    fn add(self, other: Self) -> Self {
+        Counter {
+            count: self.count + other.count,
+        }
+    }
+}
+
+fn main() -> u64 {
+    let counter = Counter {
+        count: 1,
+    };
+    // `Counter` has an `add` method, but does not implement `Add`
+    let counted = counter + counter;
+    counted.count
+}
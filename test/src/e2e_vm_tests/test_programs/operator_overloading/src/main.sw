@@ -0,0 +1,77 @@
+script;
+
+use core::ops::*;
+
+// a fixed-point decimal with two decimal places
+struct Decimal {
+    value: u64,
+}
+
+impl Add for Decimal {
+    fn add(self, other: Self) -> Self {
+        Decimal {
+            value: self.value + other.value,
+        }
+    }
+}
+
+impl Multiply for Decimal {
+    fn multiply(self, other: Self) -> Self {
+        Decimal {
+            value: self.value * other.value / 100,
+        }
+    }
+}
+
+impl Ord for Decimal {
+    fn gt(self, other: Self) -> bool {
+        self.value > other.value
+    }
+    fn lt(self, other: Self) -> bool {
+        self.value < other.value
+    }
+    fn eq(self, other: Self) -> bool {
+        self.value == other.value
+    }
+}
+
+struct Counter {
+    count: u64,
+}
+
+impl Counter {
+    // not an implementation of `Add`, so `+` can't be applied to `Counter`s
+    fn add(self, other: Self) -> Self {
+        Counter {
+            count: self.count + other.count,
+        }
+    }
+}
+
+fn max<T>(a: T, b: T) -> T where T: Ord {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+fn main() -> u64 {
+    let a = Decimal {
+        value: 150,
+    };
+    let b = Decimal {
+        value: 200,
+    };
+    // 1.50 * 2.00 + 1.50 = 4.50
+    let c = a * b + a;
+    let counter = Counter {
+        count: 1,
+    };
+    let counted = counter.add(counter);
+    if c > b && !(c == a) && c == max(c, b) {
+        c.value + counted.count
+    } else {
+        0
+    }
+}
@@ -6,6 +6,7 @@ fn main() {
 }
 
 
-impure fn foo() {
+#[storage(read)]
+fn foo() {
   
 }
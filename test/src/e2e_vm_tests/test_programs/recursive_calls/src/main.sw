@@ -1,16 +1,11 @@
 script;
 
-// a -> a
-fn a(n: u64) -> u64 {
-    a(n)
-}
-
 // b -> c -> b
-fn b(n: u64) -> u64 {
+fn b<T>(n: T) -> T {
     c(n)
 }
 
-fn c(n: u64) -> u64 {
+fn c<T>(n: T) -> T {
     b(n)
 }
 
@@ -19,7 +14,7 @@ fn d(n: u64) -> u64 {
     e(n)
 }
 
-fn e(n: u64) -> u64 {
+fn e<T>(n: T) -> T {
     f(n)
 }
 
@@ -35,5 +30,5 @@ fn g(a: u64) -> u64 {
 
 // main
 fn main() -> u64 {
-    a(1)
+    b(1)
 }
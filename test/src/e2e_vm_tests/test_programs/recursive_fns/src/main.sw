@@ -0,0 +1,23 @@
+script;
+// This test tests functions which call themselves.
+
+fn factorial(n: u64) -> u64 {
+    if n == 0 {
+        1
+    } else {
+        n * factorial(n - 1)
+    }
+}
+
+fn fib(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+fn main() -> u64 {
+    // 120 + 55
+    factorial(5) + fib(10)
+}
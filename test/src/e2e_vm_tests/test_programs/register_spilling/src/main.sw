@@ -0,0 +1,73 @@
+script;
+// This test keeps more values live at once than there are registers, so some of them must be
+// spilled to the stack.
+
+fn main() -> u64 {
+    let a1 = 1;
+    let a2 = 2;
+    let a3 = 3;
+    let a4 = 4;
+    let a5 = 5;
+    let a6 = 6;
+    let a7 = 7;
+    let a8 = 8;
+    let a9 = 9;
+    let a10 = 10;
+    let a11 = 11;
+    let a12 = 12;
+    let a13 = 13;
+    let a14 = 14;
+    let a15 = 15;
+    let a16 = 16;
+    let a17 = 17;
+    let a18 = 18;
+    let a19 = 19;
+    let a20 = 20;
+    let a21 = 21;
+    let a22 = 22;
+    let a23 = 23;
+    let a24 = 24;
+    let a25 = 25;
+    let a26 = 26;
+    let a27 = 27;
+    let a28 = 28;
+    let a29 = 29;
+    let a30 = 30;
+    let a31 = 31;
+    let a32 = 32;
+    let a33 = 33;
+    let a34 = 34;
+    let a35 = 35;
+    let a36 = 36;
+    let a37 = 37;
+    let a38 = 38;
+    let a39 = 39;
+    let a40 = 40;
+    let a41 = 41;
+    let a42 = 42;
+    let a43 = 43;
+    let a44 = 44;
+    let a45 = 45;
+    let a46 = 46;
+    let a47 = 47;
+    let a48 = 48;
+    let a49 = 49;
+    let a50 = 50;
+    let a51 = 51;
+    let a52 = 52;
+    let a53 = 53;
+    let a54 = 54;
+    let a55 = 55;
+    let a56 = 56;
+    let a57 = 57;
+    let a58 = 58;
+    let a59 = 59;
+    let a60 = 60;
+    // the sum of 1 to 60
+    a60 + a59 + a58 + a57 + a56 + a55 + a54 + a53 + a52 + a51
+        + a50 + a49 + a48 + a47 + a46 + a45 + a44 + a43 + a42 + a41
+        + a40 + a39 + a38 + a37 + a36 + a35 + a34 + a33 + a32 + a31
+        + a30 + a29 + a28 + a27 + a26 + a25 + a24 + a23 + a22 + a21
+        + a20 + a19 + a18 + a17 + a16 + a15 + a14 + a13 + a12 + a11
+        + a10 + a9 + a8 + a7 + a6 + a5 + a4 + a3 + a2 + a1
+}
@@ -0,0 +1,7 @@
+script;
+
+fn main() -> u64 {
+    let code = 1;
+    require(false, code);
+    0
+}
@@ -0,0 +1,26 @@
+script;
+
+const DIVIDE_BY_ZERO: u64 = 2;
+const FOUND_ANSWER: u64 = 42;
+
+fn checked_divide(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        revert(DIVIDE_BY_ZERO);
+    }
+    a / b
+}
+
+fn answer_or_revert(x: u64) -> u64 {
+    if x == 42 {
+        revert(FOUND_ANSWER)
+    } else {
+        x
+    }
+}
+
+fn main() -> u64 {
+    require(true, 1);
+    assert(1 == 1);
+    let x = checked_divide(84, 2);
+    answer_or_revert(x)
+}
@@ -1,4 +1,5 @@
 script;
 
 // In a script, there can be no impurity since storage is only available in contracts.
-impure fn main() {}
+#[storage(read)]
+fn main() {}
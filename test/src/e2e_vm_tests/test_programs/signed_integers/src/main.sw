@@ -0,0 +1,24 @@
+script;
+// This test exercises the operators of signed integers, which FuelVM has no instructions for.
+
+fn main() -> bool {
+    let a = -7;
+    let b = 2i64;
+    let mut result = a / b == -3 && a % b == -1 && -a % b == 1;
+    result = result && a * b == -14 && a + b == -5 && b - a == 9;
+    result = result && a < b && -a > b && a <= -7 && b >= -a / 3 && a != b;
+    result = result && a.rsh(1) == -4 && a.lsh(2) == -28;
+
+    // narrower integers wrap around at their own width
+    let max = 127i8;
+    result = result && max + 1i8 == -128i8 && -128i8 - 1i8 == max;
+    result = result && -(-128i8) == -128i8 && 100i16 * 400i16 == -25536i16;
+    result = result && -9223372036854775808 < 9223372036854775807i64;
+
+    let mut sum = 0i32;
+    for i in -3i32..2i32 {
+        sum = sum + i;
+    }
+    // -3 + -2 + -1 + 0 + 1
+    result && sum == -5i32
+}
@@ -0,0 +1,33 @@
+library storage_access_abi;
+
+pub struct Point {
+    x: u64,
+    y: u64,
+    tag: b256,
+}
+
+pub struct IdEntry {
+    id: u64,
+    value: u64,
+}
+
+pub struct HashEntry {
+    hash: b256,
+    value: u64,
+}
+
+abi StorageAccess {
+    fn double(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64;
+    fn set_word(gas: u64, coins: u64, asset_id: b256, input: u64) -> ();
+    fn get_word(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+    fn set_hash(gas: u64, coins: u64, asset_id: b256, input: b256) -> ();
+    fn get_hash(gas: u64, coins: u64, asset_id: b256, input: ()) -> b256;
+    fn set_point(gas: u64, coins: u64, asset_id: b256, input: Point) -> ();
+    fn get_point(gas: u64, coins: u64, asset_id: b256, input: ()) -> Point;
+    fn get_untouched(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+    fn insert_by_id(gas: u64, coins: u64, asset_id: b256, input: IdEntry) -> ();
+    fn get_by_id(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64;
+    fn insert_by_hash(gas: u64, coins: u64, asset_id: b256, input: HashEntry) -> ();
+    fn get_by_hash(gas: u64, coins: u64, asset_id: b256, input: b256) -> u64;
+    fn fill_by_id(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64;
+}
@@ -0,0 +1,89 @@
+contract;
+use storage_access_abi::*;
+
+storage {
+    word: u64 = 7,
+    hash: b256 = 0x0101010101010101010101010101010101010101010101010101010101010101,
+    point: Point = Point {
+        x: 1,
+        y: 2,
+        tag: 0x0202020202020202020202020202020202020202020202020202020202020202,
+    },
+    untouched: u64 = 42,
+    by_id: StorageMap<u64, u64> = ~StorageMap::new(),
+    by_hash: StorageMap<b256, u64> = ~StorageMap::new(),
+}
+
+impl StorageAccess for Contract {
+    fn double(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64 {
+        input * 2
+    }
+
+    #[storage(write)]
+    fn set_word(gas: u64, coins: u64, asset_id: b256, input: u64) -> () {
+        storage.word = input;
+    }
+
+    #[storage(read)]
+    fn get_word(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+        storage.word
+    }
+
+    #[storage(write)]
+    fn set_hash(gas: u64, coins: u64, asset_id: b256, input: b256) -> () {
+        storage.hash = input;
+    }
+
+    #[storage(read)]
+    fn get_hash(gas: u64, coins: u64, asset_id: b256, input: ()) -> b256 {
+        storage.hash
+    }
+
+    #[storage(write)]
+    fn set_point(gas: u64, coins: u64, asset_id: b256, input: Point) -> () {
+        storage.point = input;
+    }
+
+    #[storage(read)]
+    fn get_point(gas: u64, coins: u64, asset_id: b256, input: ()) -> Point {
+        storage.point
+    }
+
+    #[storage(read)]
+    fn get_untouched(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+        storage.untouched
+    }
+
+    #[storage(write)]
+    fn insert_by_id(gas: u64, coins: u64, asset_id: b256, input: IdEntry) -> () {
+        storage.by_id.insert(input.id, input.value);
+    }
+
+    #[storage(read)]
+    fn get_by_id(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64 {
+        storage.by_id.get(input)
+    }
+
+    #[storage(write)]
+    fn insert_by_hash(gas: u64, coins: u64, asset_id: b256, input: HashEntry) -> () {
+        storage.by_hash.insert(input.hash, input.value);
+    }
+
+    #[storage(read)]
+    fn get_by_hash(gas: u64, coins: u64, asset_id: b256, input: b256) -> u64 {
+        storage.by_hash.get(input)
+    }
+
+    // accesses the map many times over, which mustn't grow the stack each time
+    #[storage(read, write)]
+    fn fill_by_id(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64 {
+        for i in 0..input {
+            storage.by_id.insert(1000 + i, i);
+        }
+        let mut sum = 0;
+        for i in 0..input {
+            sum = sum + storage.by_id.get(1000 + i);
+        }
+        sum
+    }
+}
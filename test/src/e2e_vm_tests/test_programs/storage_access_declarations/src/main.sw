@@ -0,0 +1,50 @@
+contract;
+
+abi Counter {
+    fn get(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+    fn set(gas: u64, coins: u64, asset_id: b256, input: u64) -> ();
+    fn increment(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+    fn double(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64;
+}
+
+storage {
+    count: u64 = 0,
+}
+
+#[storage(read)]
+fn read_count() -> u64 {
+    storage.count
+}
+
+#[storage(write)]
+fn write_count(value: u64) {
+    storage.count = value;
+}
+
+// storage access through asm has to be declared as well
+#[storage(write)]
+fn clear(key: b256) {
+    asm(r1: key, r2: 0) {
+        sww r1 r2;
+    }
+}
+
+impl Counter for Contract {
+    #[storage(read)]
+    fn get(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+        read_count()
+    }
+    #[storage(write)]
+    fn set(gas: u64, coins: u64, asset_id: b256, input: u64) -> () {
+        clear(0x0000000000000000000000000000000000000000000000000000000000000000);
+        write_count(input);
+    }
+    #[storage(read, write)]
+    fn increment(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+        write_count(read_count() + 1);
+        read_count()
+    }
+    fn double(gas: u64, coins: u64, asset_id: b256, input: u64) -> u64 {
+        input * 2
+    }
+}
@@ -0,0 +1,22 @@
+contract;
+
+abi Counter {
+    fn increment(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+    fn get(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64;
+}
+
+storage {
+    count: u64 = 0,
+}
+
+impl Counter for Contract {
+    #[storage(read, write)]
+    fn increment(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+        storage.count = storage.count + 1;
+        storage.count
+    }
+    // storage may only be accessed from functions declared to access it
+    fn get(gas: u64, coins: u64, asset_id: b256, input: ()) -> u64 {
+        storage.count
+    }
+}
@@ -0,0 +1,33 @@
+contract;
+
+storage {
+    count: u64 = 0,
+}
+
+fn main() {
+}
+
+// this should fail because a function declared to only read storage can't write it
+#[storage(read)]
+fn reset() {
+    storage.count = 0;
+}
+
+// nor can one declared to only write storage call a function which reads it
+#[storage(write)]
+fn increment() {
+    storage.count = get() + 1;
+}
+
+#[storage(read)]
+fn get() -> u64 {
+    storage.count
+}
+
+// storage written through asm counts as well
+#[storage(read)]
+fn clear(key: b256) {
+    asm(r1: key, r2: 0) {
+        sww r1 r2;
+    }
+}
@@ -0,0 +1,9 @@
+script;
+
+storage {
+    count: u64 = 0,
+}
+
+fn main() -> u64 {
+    0
+}
@@ -0,0 +1,24 @@
+contract;
+
+abi Bank {
+    fn deposit(gas: u64, coins: u64, asset_id: b256, input: b256) -> u64;
+    fn reset(gas: u64, coins: u64, asset_id: b256, input: ()) -> ();
+}
+
+storage {
+    balances: StorageMap<b256, u64> = ~StorageMap::new(),
+}
+
+impl Bank for Contract {
+    #[storage(read, write)]
+    fn deposit(gas: u64, coins: u64, asset_id: b256, input: b256) -> u64 {
+        let balance = storage.balances.get(input) + coins;
+        storage.balances.insert(input, balance);
+        balance
+    }
+    // the entries of a map can't all be replaced at once
+    #[storage(write)]
+    fn reset(gas: u64, coins: u64, asset_id: b256, input: ()) -> () {
+        storage.balances = ~StorageMap::new();
+    }
+}
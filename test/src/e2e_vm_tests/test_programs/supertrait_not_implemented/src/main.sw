@@ -0,0 +1,27 @@
+script;
+
+trait Named {
+    fn id(self) -> u64;
+}
+
+trait Greeter: Named {
+    fn greeting(self) -> u64;
+}
+
+struct Person {
+    id: u64,
+}
+
+// `Named` is not implemented for `Person`
+impl Greeter for Person {
+    fn greeting(self) -> u64 {
+        100
+    }
+}
+
+fn main() -> u64 {
+    let person = Person {
+        id: 7,
+    };
+    person.greeting()
+}
@@ -0,0 +1,43 @@
+script;
+
+trait Named {
+    fn id(self) -> u64;
+}
+
+trait Greeter: Named {
+    fn greeting(self) -> u64;
+} {
+    fn greet(self) -> u64 {
+        self.greeting() + self.id()
+    }
+}
+
+struct Person {
+    id: u64,
+}
+
+// implemented before its supertrait, which is type checked first regardless
+impl Greeter for Person {
+    fn greeting(self) -> u64 {
+        100
+    }
+}
+
+impl Named for Person {
+    fn id(self) -> u64 {
+        self.id
+    }
+}
+
+fn id_of<T>(x: T) -> u64 where T: Greeter {
+    // the methods of `Named` are available through the `Greeter` bound
+    x.id()
+}
+
+fn main() -> u64 {
+    let person = Person {
+        id: 7,
+    };
+    // 107 + 7 + 7
+    person.greet() + id_of(person) + person.id()
+}
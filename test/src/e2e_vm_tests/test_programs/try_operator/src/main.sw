@@ -0,0 +1,106 @@
+script;
+
+enum Option<T> {
+    Some: T,
+    None: (),
+}
+
+enum Result<T, E> {
+    Ok: T,
+    Err: E,
+}
+
+fn checked_sub(a: u64, b: u64) -> Option<u64> {
+    if a < b {
+        Option::None
+    } else {
+        Option::Some(a - b)
+    }
+}
+
+fn divide(a: u64, b: u64) -> Result<u64, u64> {
+    if b == 0 {
+        Result::Err(a)
+    } else {
+        Result::Ok(a / b)
+    }
+}
+
+fn sub_twice(a: u64, b: u64) -> Option<u64> {
+    let once = checked_sub(a, b)?;
+    Option::Some(checked_sub(once, b)?)
+}
+
+fn divide_all(a: u64, divisors: [u64; 3]) -> Result<u64, u64> {
+    let mut result = a;
+    let mut i = 0;
+    while i < 3 {
+        result = divide(result, divisors[i])?;
+        i = i + 1;
+    }
+    Result::Ok(result)
+}
+
+fn first_zero(values: [u64; 3]) -> u64 {
+    let mut i = 0;
+    while i < 3 {
+        if values[i] == 0 {
+            return i;
+        }
+        i = i + 1;
+    }
+    3
+}
+
+// the tag of the first variant is 0, and the value of either variant is the second word
+fn is_first_variant(value: Result<u64, u64>) -> bool {
+    asm(r1: value, r2) {
+        lw r2 r1 i0;
+        eq r2 r2 zero;
+        r2: bool
+    }
+}
+
+fn held_value(value: Result<u64, u64>) -> u64 {
+    asm(r1: value, r2) {
+        lw r2 r1 i1;
+        r2: u64
+    }
+}
+
+fn unwrap_or(value: Option<u64>, default: u64) -> u64 {
+    let is_some = asm(r1: value, r2) {
+        lw r2 r1 i0;
+        eq r2 r2 zero;
+        r2: bool
+    };
+    if is_some {
+        asm(r1: value, r2) {
+            lw r2 r1 i1;
+            r2: u64
+        }
+    } else {
+        default
+    }
+}
+
+fn main() -> u64 {
+    let mut result = unwrap_or(sub_twice(10, 3), 0);
+    // the second subtraction fails
+    result = result + unwrap_or(sub_twice(5, 3), 10);
+    // the first subtraction fails
+    result = result + unwrap_or(sub_twice(1, 3), 20);
+
+    let divided = divide_all(120, [2, 3, 4]);
+    if is_first_variant(divided) {
+        result = result + held_value(divided);
+    }
+    // the error is returned from within the loop
+    let failed = divide_all(120, [2, 0, 4]);
+    if !is_first_variant(failed) {
+        result = result + held_value(failed);
+    }
+
+    // 4 + 10 + 20 + 5 + 60 + 100
+    result + first_zero([3, 0, 1]) * 100
+}
@@ -0,0 +1,9 @@
+script;
+
+fn double(value: u64) -> u64 {
+    value? * 2
+}
+
+fn main() -> u64 {
+    double(21)
+}
@@ -0,0 +1,3 @@
+library aliases;
+
+pub type Hashes = [b256; 8];
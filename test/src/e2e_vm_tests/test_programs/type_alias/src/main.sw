@@ -0,0 +1,37 @@
+script;
+
+dep aliases;
+
+use aliases::Hashes;
+
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+type Pair<T> = (T, T);
+type Coordinate = Point<u64>;
+type Pairs<T> = [Pair<T>; 2];
+
+fn swap(pair: Pair<u64>) -> Pair<u64> {
+    (pair.1, pair.0)
+}
+
+fn sum(pairs: Pairs<u64>) -> u64 {
+    pairs[0].0 + pairs[0].1 + pairs[1].0 + pairs[1].1
+}
+
+fn main() -> u64 {
+    let swapped = swap((1, 2));
+    let point: Coordinate = Point {
+        x: 3,
+        y: 4,
+    };
+    let pairs: Pairs<u64> = [(5, 6), (7, 8)];
+    let hashes: Hashes = [0x0000000000000000000000000000000000000000000000000000000000000000; 8];
+    let mut result = swapped.0 * 10 + swapped.1 + point.x * point.y + sum(pairs);
+    if hashes[7] == 0x0000000000000000000000000000000000000000000000000000000000000000 {
+        result = result + 100;
+    }
+    result
+}
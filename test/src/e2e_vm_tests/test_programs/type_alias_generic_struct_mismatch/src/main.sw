@@ -0,0 +1,16 @@
+script;
+
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+type Coordinate = Point<u64>;
+
+fn main() -> u64 {
+    let point: Coordinate = Point {
+        x: true,
+        y: false,
+    };
+    0
+}
@@ -0,0 +1,8 @@
+script;
+
+type Pair<T> = (T, T);
+
+fn main() -> u64 {
+    let pair: Pair<u64, bool> = (1, true);
+    pair.0
+}